@@ -48,8 +48,7 @@ pub fn handle(
         if let Some(mut typed) = model.downcast_ref::<TypedModel>().cloned() {
             rename_outputs(&mut typed, sub_matches)?;
             let file = std::fs::File::create(path)?;
-            let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
-            nnef.write_to_tar(&typed, encoder)?;
+            nnef.write_to_tar_gz(&typed, file)?;
         } else {
             bail!("Only typed model can be dumped")
         }
@@ -102,6 +101,13 @@ pub fn handle(
         }
     }
 
+    if sub_matches.is_present("pulse-report") {
+        #[cfg(feature = "pulse")]
+        pulse_report(params, matches)?;
+        #[cfg(not(feature = "pulse"))]
+        bail!("tract is built without pulse support");
+    }
+
     if options.cost {
         let total = annotations.tags.values().sum::<NodeTags>();
         let assert =
@@ -127,6 +133,32 @@ pub fn handle(
     Ok(())
 }
 
+#[cfg(feature = "pulse")]
+fn pulse_report(params: &Parameters, matches: &clap::ArgMatches) -> CliResult<()> {
+    use tract_pulse::internal::*;
+    let pulsed =
+        params.pulsed_model.as_ref().context("Pulsed model not generated (using --pulse ?)")?;
+    for input in pulsed.input_outlets()? {
+        let pulse = pulsed.outlet_fact(*input)?.pulse();
+        println!("Input {}: pulse {}", pulsed.node(input.node).name, pulse);
+    }
+    println!("Latency: {} frames", pulsed.latency()?);
+    let buffers = pulsed.buffered_bytes()?;
+    let total = buffers.iter().fold(0.to_dim(), |acc, (_, bytes)| acc + bytes);
+    println!("Buffered state: {} bytes", total);
+    for (node, bytes) in buffers {
+        let node = pulsed.node(node);
+        println!("  {} {}: {} bytes", node.op.name(), node.name, bytes);
+    }
+    if let Some(source) =
+        params.reference_model.as_ref().and_then(|m| m.downcast_ref::<TypedModel>())
+    {
+        let pulse = matches.value_of("pulse").context("Pulse not given")?.parse()?;
+        println!("Minimal pulse: {}", PulsedModel::minimal_pulse(source, pulse)?);
+    }
+    Ok(())
+}
+
 fn rename_outputs(typed: &mut TypedModel, sub_matches: &clap::ArgMatches) -> TractResult<()> {
     if let Some(renamed) = sub_matches.values_of("nnef-override-output-name") {
         for (ix, name) in renamed.into_iter().enumerate() {
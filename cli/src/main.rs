@@ -104,6 +104,9 @@ fn main() -> tract_core::anyhow::Result<()> {
     (@arg kaldi_adjust_final_offset: --("kaldi-adjust-final-offset") +takes_value
      "Adjust value of final offset in network (for reproducibility)")
 
+    (@arg kaldi_subtract_log_priors: --("kaldi-subtract-log-priors")
+     "Subtract the log priors of an acoustic model (final.mdl) from its output")
+
     (@arg kaldi_downsample: --("kaldi-downsample") +takes_value
      "Add a subsampling to output on axis 0")
 
@@ -122,6 +125,9 @@ fn main() -> tract_core::anyhow::Result<()> {
     (@arg tf_initializer_output_node: --("tf-initializer-output-node") +takes_value +multiple number_of_values(1)
      "Set an initializer node")
 
+    (@arg tf_signature: --("tf-signature") +takes_value
+     "Signature to use for a SavedModel directory (defaults to serving_default).")
+
     (@arg output_node: --("output-node") +takes_value +multiple number_of_values(1)
      "Override output nodes name (auto-detects otherwise).")
 
@@ -248,6 +254,11 @@ fn main() -> tract_core::anyhow::Result<()> {
             .long("nnef-graph")
             .help("Dump the network definition (without the weights) as a graph.nnef-like file"),
             )
+        .arg(
+            Arg::with_name("pulse-report")
+            .long("pulse-report")
+            .help("Report latency, buffered state and minimal pulse of the pulsed network (with --pulse)"),
+            )
         .arg(
             Arg::with_name("assert-output")
             .takes_value(true)
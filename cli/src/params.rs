@@ -108,8 +108,14 @@ impl Parameters {
         let format = matches.value_of("format").unwrap_or(
             if filename.extension().map(|s| s == "onnx").unwrap_or(false) {
                 "onnx"
-            } else if filename.extension().map(|s| s == "raw" || s == "txt").unwrap_or(false) {
+            } else if filename
+                .extension()
+                .map(|s| s == "raw" || s == "txt" || s == "mdl")
+                .unwrap_or(false)
+            {
                 "kaldi"
+            } else if filename.is_dir() && filename.join("saved_model.pb").exists() {
+                "tf"
             } else if filename.is_dir()
                 || filename.to_string_lossy().ends_with(".tar")
                 || filename.to_string_lossy().ends_with(".tar.gz")
@@ -130,6 +136,9 @@ impl Parameters {
                 if let Some(i) = matches.value_of("kaldi_adjust_final_offset") {
                     graph.adjust_final_offset = i.parse()?;
                 }
+                if matches.is_present("kaldi_subtract_log_priors") {
+                    graph.subtract_log_priors = true;
+                }
                 let parsed = kaldi.model_for_proto_model(&graph)?;
                 if need_graph {
                     (SomeGraphDef::Kaldi(graph), Box::new(parsed), Option::<TfExt>::None)
@@ -139,12 +148,7 @@ impl Parameters {
             }
             "nnef" => {
                 let nnef = super::nnef(&matches);
-                let mut file = std::fs::File::open(&filename)?;
-                let proto_model = if filename.to_string_lossy().ends_with("gz") {
-                    nnef.proto_model_for_read(&mut flate2::read::GzDecoder::new(file))?
-                } else {
-                    nnef.proto_model_for_read(&mut file)?
-                };
+                let proto_model = nnef.proto_model_for_path(&filename)?;
                 info_usage("proto model loaded", probe);
                 if need_graph {
                     (
@@ -187,12 +191,18 @@ impl Parameters {
             "tf" => {
                 let tf = tract_tensorflow::tensorflow();
                 info_usage("loaded framework (tf)", probe);
-                let mut graph = tf.proto_model_for_path(&filename)?;
+                let mut saved = if filename.is_dir() {
+                    tf.read_saved_model_dir(&filename, matches.value_of("tf_signature"))?
+                } else {
+                    let graph = tf.proto_model_for_path(&filename)?;
+                    tract_tensorflow::saved_model::FrozenSavedModel { graph, signature: None }
+                };
                 info_usage("proto model loaded", probe);
                 if matches.is_present("determinize") {
-                    tract_tensorflow::Tensorflow::determinize(&mut graph)?;
+                    tract_tensorflow::Tensorflow::determinize(&mut saved.graph)?;
                 }
-                let mut model_and_ext = tf.parse_graph(&graph)?;
+                let mut model_and_ext = tf.parse_saved_model(&saved)?;
+                let graph = saved.graph;
                 model_and_ext.1.initializing_nodes = matches
                     .values_of("tf_initializer_output_node")
                     .map(|values| {
@@ -602,7 +612,12 @@ impl Parameters {
                 stage!("concretize-stream-dim-declutter", typed_model -> typed_model, |m:TypedModel| Ok(m.declutter()?));
             } else if let Some(pulse) = pulse {
                 stage!("pulse", typed_model -> pulsed_model, |m:TypedModel| Ok(PulsedModel::new(&m, pulse)?));
+                // keeping the pulsed model makes the next stage clone it
+                let report =
+                    matches.subcommand_matches("dump").map(|sm| sm.is_present("pulse-report"));
+                let pulsed = if report == Some(true) { pulsed_model.clone() } else { None };
                 stage!("pulse-to-type", pulsed_model -> typed_model, |m:PulsedModel| Ok(m.into_typed()?));
+                pulsed_model = pulsed;
                 stage!("pulse-declutter", typed_model -> typed_model, |m:TypedModel| Ok(m.declutter()?));
             }
         }
@@ -651,6 +666,8 @@ impl Parameters {
                     (true, None)
                 }
             }
+            // the minimal pulse is searched on the model as it was before pulsing
+            ("dump", Some(sm)) if sm.is_present("pulse-report") => (false, Some("declutter")),
             _ => (false, None),
         };
 
@@ -103,7 +103,10 @@ pub mod internal {
     pub use crate::ops::change_axes::*;
     pub use crate::ops::element_wise::ElementWiseMiniOp;
     pub use crate::ops::invariants::*;
-    pub use crate::ops::{AttrOrInput, AxisInfo, Cost, EvalOp, Invariants, Op, OpState, Validation};
+    pub use crate::ops::{
+        batch_axis, take_state_tensor, zero_batch_slot, AttrOrInput, AxisInfo, Cost, EvalOp,
+        Invariants, Op, OpState, Validation,
+    };
     pub use crate::plan::SessionState;
     pub use crate::prelude::*;
     pub use anyhow::{bail, format_err, Context as TractErrorContext};
@@ -118,7 +121,7 @@ pub mod internal {
     };
     pub use tvec;
     pub use {args_1, args_2, args_3, args_4, args_5, args_6, args_7, args_8};
-    pub use {as_op, impl_op_same_as, not_a_typed_op, op_as_typed_op};
+    pub use {as_op, impl_op_same_as, not_a_typed_op, op_as_typed_op, op_state_without_data};
     pub use {bin_to_super_type, element_wise, element_wise_oop};
 }
 
@@ -87,4 +87,6 @@ impl OpState for ConstantOfShapeState {
             .collect::<TractResult<TVec<_>>>()?;
         Ok(tvec!(op.scalar.broadcast_scalar_to_shape(&*shape)?.into_arc_tensor()))
     }
+
+    op_state_without_data!();
 }
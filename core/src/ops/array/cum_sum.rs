@@ -0,0 +1,105 @@
+use crate::internal::*;
+use ndarray::*;
+
+/// Cumulative sum along an axis.
+///
+/// With `exclusive`, each element is the sum of the elements strictly before
+/// it. With `reverse`, the sum runs from the end of the axis.
+#[derive(Debug, Clone, new, Hash)]
+pub struct CumSum {
+    pub axis: usize,
+    pub exclusive: bool,
+    pub reverse: bool,
+}
+
+impl_dyn_hash!(CumSum);
+
+impl CumSum {
+    fn eval_t<T>(&self, input: &Tensor) -> TractResult<Tensor>
+    where
+        T: Datum + num_traits::Zero + Copy,
+    {
+        let mut output = input.to_array_view::<T>()?.to_owned();
+        for mut lane in output.lanes_mut(Axis(self.axis)) {
+            let len = lane.len();
+            let mut acc = T::zero();
+            for i in 0..len {
+                let ix = if self.reverse { len - 1 - i } else { i };
+                let x = lane[ix];
+                if self.exclusive {
+                    lane[ix] = acc;
+                    acc = acc + x;
+                } else {
+                    acc = acc + x;
+                    lane[ix] = acc;
+                }
+            }
+        }
+        Ok(output.into_tensor())
+    }
+}
+
+impl Op for CumSum {
+    fn name(&self) -> Cow<str> {
+        "CumSum".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "axis: {}, exclusive: {}, reverse: {}",
+            self.axis, self.exclusive, self.reverse
+        )])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for CumSum {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = &inputs[0];
+        if self.axis >= input.rank() {
+            bail!("CumSum on axis {} for an input of shape {:?}", self.axis, input.shape());
+        }
+        let output = dispatch_numbers!(Self::eval_t(input.datum_type())(self, input))?;
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for CumSum {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if self.axis >= inputs[0].rank() {
+            bail!("CumSum on axis {} for an input of rank {}", self.axis, inputs[0].rank());
+        }
+        Ok(tvec!(inputs[0].without_value()))
+    }
+
+    fn invariants(&self, model: &TypedModel, node: &TypedNode) -> TractResult<Invariants> {
+        let rank = model.outlet_fact(node.inputs[0])?.rank();
+        Ok((0..rank).filter(|&ax| ax != self.axis).map(AxisInfo::simple).collect())
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cum_sum_variants() {
+        let input = rctensor2(&[[1i32, 2, 3], [4, 5, 6]]);
+        let run = |axis, exclusive, reverse| {
+            CumSum::new(axis, exclusive, reverse).eval(tvec!(input.clone())).unwrap().remove(0)
+        };
+        assert_eq!(run(1, false, false), rctensor2(&[[1i32, 3, 6], [4, 9, 15]]));
+        assert_eq!(run(0, false, false), rctensor2(&[[1i32, 2, 3], [5, 7, 9]]));
+        assert_eq!(run(1, true, false), rctensor2(&[[0i32, 1, 3], [0, 4, 9]]));
+        assert_eq!(run(1, false, true), rctensor2(&[[6i32, 5, 3], [15, 11, 6]]));
+        assert_eq!(run(1, true, true), rctensor2(&[[5i32, 3, 0], [11, 6, 0]]));
+    }
+}
@@ -7,6 +7,8 @@ pub enum PadMode {
     Constant(Arc<Tensor>),
     Reflect,
     Edge,
+    /// Like Reflect, but the border element is repeated.
+    Symmetric,
 }
 
 impl Default for PadMode {
@@ -46,7 +48,7 @@ impl Pad {
             .collect();
         let slice_info = SliceInfo::<_, IxDyn, IxDyn>::try_from(slice_spec).unwrap();
         output.slice_mut(slice_info.as_ref()).assign(&input);
-        if self.mode == PadMode::Reflect || self.mode == PadMode::Edge {
+        if !matches!(self.mode, PadMode::Constant(_)) {
             for (ax, &(bef, aft)) in self.pads.iter().enumerate() {
                 let axis = Axis(ax);
                 let dim = output.shape()[ax];
@@ -57,6 +59,7 @@ impl Pad {
                         let source_slice = match self.mode {
                             PadMode::Edge => 0,
                             PadMode::Reflect => bef - i,
+                            PadMode::Symmetric => bef - 1 - i,
                             _ => panic!(),
                         };
                         let source =
@@ -71,6 +74,7 @@ impl Pad {
                         let source_slice = match self.mode {
                             PadMode::Edge => dim - aft - 1,
                             PadMode::Reflect => dim - aft - 2 - i,
+                            PadMode::Symmetric => dim - aft - 1 - i,
                             _ => panic!(),
                         };
                         let source =
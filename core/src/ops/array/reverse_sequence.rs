@@ -0,0 +1,90 @@
+use crate::internal::*;
+use tract_ndarray::prelude::*;
+
+/// Reverses, for each batch entry, the first `sequence_lens[b]` elements
+/// along the time axis. Elements past the sequence length are copied as is.
+#[derive(Debug, Clone, new, Hash)]
+pub struct ReverseSequence {
+    pub batch_axis: usize,
+    pub time_axis: usize,
+}
+
+impl_dyn_hash!(ReverseSequence);
+
+impl ReverseSequence {
+    fn eval_t<T: Datum>(&self, input: &Tensor, lens: &[i64]) -> TractResult<Tensor> {
+        let input = input.to_array_view::<T>()?;
+        let mut output = input.to_owned();
+        let time_len = input.shape()[self.time_axis];
+        for (b, &len) in lens.iter().enumerate() {
+            if len < 0 || len as usize > time_len {
+                bail!("Invalid sequence length {} for a time axis of {}", len, time_len);
+            }
+            let len = len as usize;
+            let input = input.index_axis(Axis(self.batch_axis), b);
+            let mut output = output.index_axis_mut(Axis(self.batch_axis), b);
+            // the time axis shifts down by one if the batch axis was before it
+            let time_axis = self.time_axis - (self.batch_axis < self.time_axis) as usize;
+            for t in 0..len {
+                output
+                    .index_axis_mut(Axis(time_axis), t)
+                    .assign(&input.index_axis(Axis(time_axis), len - 1 - t));
+            }
+        }
+        Ok(output.into_tensor())
+    }
+}
+
+impl Op for ReverseSequence {
+    fn name(&self) -> Cow<str> {
+        "ReverseSequence".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("batch_axis: {}, time_axis: {}", self.batch_axis, self.time_axis)])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for ReverseSequence {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (input, lens) = args_2!(inputs);
+        let lens = lens.cast_to::<i64>()?;
+        let lens = lens.as_slice::<i64>()?;
+        if lens.len() != input.shape()[self.batch_axis] {
+            bail!("Expected {} sequence lengths, got {:?}", input.shape()[self.batch_axis], lens);
+        }
+        let output = dispatch_datum!(Self::eval_t(input.datum_type())(self, &input, lens))?;
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for ReverseSequence {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if inputs[0].rank() < 2 || self.batch_axis == self.time_axis {
+            bail!("Invalid ReverseSequence axes for {:?}", inputs[0]);
+        }
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, inputs[0].shape.to_tvec())))
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_major() {
+        let op = ReverseSequence::new(0, 1);
+        let input = rctensor2(&[[0, 1, 2, 3], [4, 5, 6, 7], [8, 9, 10, 11]]);
+        let output = op.eval(tvec!(input, rctensor1(&[1i64, 2, 4]))).unwrap();
+        assert_eq!(*output[0], tensor2(&[[0, 1, 2, 3], [5, 4, 6, 7], [11, 10, 9, 8]]));
+    }
+}
@@ -99,6 +99,20 @@ impl TypedOp for Slice {
         }
     }
 
+    fn concretize_dims(
+        &self,
+        _source: &TypedModel,
+        node: &TypedNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+        values: &SymbolValues,
+    ) -> TractResult<TVec<OutletId>> {
+        let op =
+            Slice { axis: self.axis, start: self.start.eval(&values), end: self.end.eval(&values) };
+        let inputs = node.inputs.iter().map(|i| mapping[i]).collect::<TVec<_>>();
+        target.wire_node(&node.name, op, &inputs)
+    }
+
     fn declutter(
         &self,
         model: &TypedModel,
@@ -129,7 +143,11 @@ impl TypedOp for Slice {
                 return Ok(None);
             } else if patch.model.nodes.len() == 3 {
                 let other = model.node(node.inputs[0].node);
-                if other.op_is::<Self>() {
+                if let Some(other_op) = other.op_as::<Self>() {
+                    // only swap slices one way, or two of them keep trading places
+                    if other_op.axis > self.axis {
+                        return Ok(None);
+                    }
                     patch.dont_apply_twice = Some(format!("Swap {} and {}", node.name, other.name));
                 }
             }
@@ -0,0 +1,116 @@
+use crate::internal::*;
+
+/// Keeps the upper (or lower) triangular part of the two innermost axes of
+/// its first input, zeroing the rest.
+///
+/// The optional second input is the scalar diagonal offset `k`: with `upper`,
+/// elements at (i, j) are kept when j - i >= k, otherwise when j - i <= k.
+#[derive(Debug, Clone, new, Hash)]
+pub struct Trilu {
+    pub upper: bool,
+}
+
+impl_dyn_hash!(Trilu);
+
+impl Trilu {
+    fn eval_t<T: Datum>(&self, input: &Tensor, k: i64) -> TractResult<Tensor> {
+        let mut output = input.to_array_view::<T>()?.to_owned();
+        let rank = output.ndim();
+        for (coords, value) in output.indexed_iter_mut() {
+            let (i, j) = (coords[rank - 2] as i64, coords[rank - 1] as i64);
+            let keep = if self.upper { j - i >= k } else { j - i <= k };
+            if !keep {
+                *value = T::default();
+            }
+        }
+        Ok(output.into_tensor())
+    }
+}
+
+impl Op for Trilu {
+    fn name(&self) -> Cow<str> {
+        "Trilu".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("upper: {}", self.upper)])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Trilu {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let k = if let Some(k) = inputs.get(1) { k.cast_to_scalar::<i64>()? } else { 0 };
+        let input = &inputs[0];
+        if input.rank() < 2 {
+            bail!("Trilu expects an input of rank at least 2, got {:?}", input.shape());
+        }
+        let output = dispatch_datum!(Self::eval_t(input.datum_type())(self, input, k))?;
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for Trilu {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if inputs[0].rank() < 2 {
+            bail!("Trilu expects an input of rank at least 2, got {:?}", inputs[0]);
+        }
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, inputs[0].shape.to_tvec())))
+    }
+
+    fn declutter(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        let inputs = model.node_input_facts(node.id)?;
+        let konsts = inputs.iter().map(|f| f.konst.clone()).collect::<Option<TVec<_>>>();
+        if let Some(konsts) = konsts {
+            let mut patch = TypedModelPatch::default();
+            let output = self.eval(konsts)?.remove(0);
+            let wire = patch.add_const(&node.name, output)?;
+            patch.shunt_outside(model, node.id.into(), wire)?;
+            return Ok(Some(patch));
+        }
+        Ok(None)
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::konst::Const;
+
+    #[test]
+    fn upper_and_lower() {
+        let input = rctensor2(&[[1, 2, 3], [4, 5, 6], [7, 8, 9]]);
+        let upper = Trilu::new(true).eval(tvec!(input.clone(), rctensor0(1i64))).unwrap();
+        assert_eq!(*upper[0], tensor2(&[[0, 2, 3], [0, 0, 6], [0, 0, 0]]));
+        let lower = Trilu::new(false).eval(tvec!(input, rctensor0(-1i64))).unwrap();
+        assert_eq!(*lower[0], tensor2(&[[0, 0, 0], [4, 0, 0], [7, 8, 0]]));
+    }
+
+    #[test]
+    fn declutter_const() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let ones = model.add_const("ones", tensor2(&[[true; 3]; 3]))?;
+        let mask = model.wire_node("mask", Trilu::new(false), &[ones])?;
+        model.set_output_outlets(&mask)?;
+        let model = model.declutter()?;
+        assert_eq!(model.nodes().len(), 1);
+        let konst = model.nodes()[0].op_as::<Const>().unwrap();
+        assert_eq!(
+            *konst.0,
+            tensor2(&[[true, false, false], [true, true, false], [true, true, true]])
+        );
+        Ok(())
+    }
+}
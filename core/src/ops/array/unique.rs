@@ -0,0 +1,152 @@
+use crate::internal::*;
+use std::cmp::Ordering;
+use tract_ndarray::prelude::*;
+
+/// Finds the unique elements of a tensor (or unique slices along an axis).
+///
+/// Outputs are the unique values, the index of their first occurrence in the
+/// input, the index of each input element in the unique values, and the
+/// number of occurrences of each unique value. The number of unique values is
+/// data-dependent, and is represented by a dedicated symbol.
+#[derive(Debug, Clone, Hash)]
+pub struct Unique {
+    pub axis: Option<usize>,
+    pub sorted: bool,
+    pub len: Symbol,
+}
+
+impl_dyn_hash!(Unique);
+
+impl Unique {
+    pub fn new(axis: Option<usize>, sorted: bool) -> Unique {
+        Unique { axis, sorted, len: Symbol::new('u') }
+    }
+
+    fn eval_t<T: Datum + PartialOrd>(&self, input: &Tensor) -> TractResult<TVec<Tensor>> {
+        let input = input.to_array_view::<T>()?;
+        let (outer, len, inner) = if let Some(axis) = self.axis {
+            let shape = input.shape();
+            (shape[..axis].iter().product(), shape[axis], shape[axis + 1..].iter().product())
+        } else {
+            (1, input.len(), 1)
+        };
+        let view = input.to_shape((outer, len, inner))?;
+        let compare = |a: usize, b: usize| -> Ordering {
+            view.index_axis(Axis(1), a)
+                .iter()
+                .zip(view.index_axis(Axis(1), b).iter())
+                .map(|(a, b)| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+                .find(|o| *o != Ordering::Equal)
+                .unwrap_or(Ordering::Equal)
+        };
+        // stable sort: the first index of each group is its first occurrence
+        let mut order: Vec<usize> = (0..len).collect();
+        order.sort_by(|&a, &b| compare(a, b));
+        let mut groups: Vec<Vec<usize>> = vec![];
+        for ix in order {
+            match groups.last_mut() {
+                Some(group) if compare(group[0], ix) == Ordering::Equal => group.push(ix),
+                _ => groups.push(vec![ix]),
+            }
+        }
+        if !self.sorted {
+            groups.sort_by_key(|group| group[0]);
+        }
+        let first: Vec<usize> = groups.iter().map(|g| g[0]).collect();
+        let mut inverse = vec![0i64; len];
+        for (unique, group) in groups.iter().enumerate() {
+            for &ix in group {
+                inverse[ix] = unique as i64;
+            }
+        }
+        let counts: Vec<i64> = groups.iter().map(|g| g.len() as i64).collect();
+        let mut shape: TVec<usize> =
+            if self.axis.is_some() { input.shape().into() } else { tvec!(len) };
+        shape[self.axis.unwrap_or(0)] = groups.len();
+        let unique = view.select(Axis(1), &first).into_shape(&*shape)?;
+        Ok(tvec!(
+            unique.into_tensor(),
+            tensor1(&first.iter().map(|&i| i as i64).collect::<Vec<_>>()),
+            tensor1(&inverse),
+            tensor1(&counts)
+        ))
+    }
+}
+
+impl Op for Unique {
+    fn name(&self) -> Cow<str> {
+        "Unique".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("axis: {:?}, sorted: {}", self.axis, self.sorted)])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Unique {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let outputs = match input.datum_type() {
+            DatumType::Bool => self.eval_t::<bool>(&input)?,
+            DatumType::String => self.eval_t::<String>(&input)?,
+            dt => dispatch_numbers!(Self::eval_t(dt)(self, &input))?,
+        };
+        Ok(outputs.into_iter().map(|t| t.into_arc_tensor()).collect())
+    }
+}
+
+impl TypedOp for Unique {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let input = inputs[0];
+        let (shape, len) = if let Some(axis) = self.axis {
+            if axis >= input.rank() {
+                bail!("Invalid axis {} for Unique on {:?}", axis, input);
+            }
+            let mut shape = input.shape.to_tvec();
+            shape[axis] = self.len.to_dim();
+            (shape, input.shape[axis].clone())
+        } else {
+            (tvec!(self.len.to_dim()), input.shape.iter().maybe_product()?)
+        };
+        Ok(tvec!(
+            TypedFact::dt_shape(input.datum_type, shape),
+            TypedFact::dt_shape(i64::datum_type(), &[self.len.to_dim()]),
+            TypedFact::dt_shape(i64::datum_type(), &[len]),
+            TypedFact::dt_shape(i64::datum_type(), &[self.len.to_dim()])
+        ))
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn not_sorted_without_axis() {
+        let op = Unique::new(None, false);
+        let outputs = op.eval(tvec!(rctensor1(&[2f32, 1., 1., 3., 4., 3.]))).unwrap();
+        assert_eq!(*outputs[0], tensor1(&[2f32, 1., 3., 4.]));
+        assert_eq!(*outputs[1], tensor1(&[0i64, 1, 3, 4]));
+        assert_eq!(*outputs[2], tensor1(&[0i64, 1, 1, 2, 3, 2]));
+        assert_eq!(*outputs[3], tensor1(&[1i64, 2, 2, 1]));
+    }
+
+    #[test]
+    fn sorted_with_axis() {
+        let op = Unique::new(Some(0), true);
+        let outputs = op.eval(tvec!(rctensor2(&[[2, 3, 4], [1, 0, 0], [1, 0, 0]]))).unwrap();
+        assert_eq!(*outputs[0], tensor2(&[[1, 0, 0], [2, 3, 4]]));
+        assert_eq!(*outputs[1], tensor1(&[1i64, 0]));
+        assert_eq!(*outputs[2], tensor1(&[1i64, 0, 0]));
+        assert_eq!(*outputs[3], tensor1(&[2i64, 1]));
+    }
+}
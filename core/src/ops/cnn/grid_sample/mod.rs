@@ -0,0 +1,268 @@
+#[cfg(test)]
+mod proptest;
+
+use crate::internal::*;
+use num_traits::Float;
+use tract_ndarray::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InterpolationMode {
+    Nearest,
+    Bilinear,
+    Bicubic,
+}
+
+impl InterpolationMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InterpolationMode::Nearest => "nearest",
+            InterpolationMode::Bilinear => "bilinear",
+            InterpolationMode::Bicubic => "bicubic",
+        }
+    }
+}
+
+impl std::str::FromStr for InterpolationMode {
+    type Err = TractError;
+    fn from_str(s: &str) -> TractResult<InterpolationMode> {
+        match s {
+            "nearest" => Ok(InterpolationMode::Nearest),
+            "bilinear" | "linear" => Ok(InterpolationMode::Bilinear),
+            "bicubic" | "cubic" => Ok(InterpolationMode::Bicubic),
+            _ => bail!("Unsupported interpolation mode {}", s),
+        }
+    }
+}
+
+/// How samples falling outside of the input are computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GridPadding {
+    Zeros,
+    Border,
+    Reflection,
+}
+
+impl GridPadding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GridPadding::Zeros => "zeros",
+            GridPadding::Border => "border",
+            GridPadding::Reflection => "reflection",
+        }
+    }
+}
+
+impl std::str::FromStr for GridPadding {
+    type Err = TractError;
+    fn from_str(s: &str) -> TractResult<GridPadding> {
+        match s {
+            "zeros" => Ok(GridPadding::Zeros),
+            "border" => Ok(GridPadding::Border),
+            "reflection" => Ok(GridPadding::Reflection),
+            _ => bail!("Unsupported grid padding mode {}", s),
+        }
+    }
+}
+
+/// Samples a NCHW input at the locations given by a NHW2 grid.
+///
+/// Grid locations are (x, y) pairs, normalized in [-1, 1]. With
+/// `align_corners`, -1 and 1 are the centers of the corner pixels, otherwise
+/// they are the outer edges of the corner pixels.
+#[derive(Debug, Clone, new, PartialEq, Hash)]
+pub struct GridSample {
+    pub mode: InterpolationMode,
+    pub padding: GridPadding,
+    pub align_corners: bool,
+}
+
+impl_dyn_hash!(GridSample);
+
+impl GridSample {
+    fn unnormalize<T: Float>(&self, coord: T, size: usize) -> T {
+        let one = T::one();
+        let two = one + one;
+        let size = T::from(size).unwrap();
+        if self.align_corners {
+            (coord + one) / two * (size - one)
+        } else {
+            ((coord + one) * size - one) / two
+        }
+    }
+
+    fn reflect<T: Float>(&self, coord: T, size: usize) -> T {
+        let (low, high) = if self.align_corners {
+            (T::zero(), T::from(size - 1).unwrap())
+        } else {
+            (T::from(-0.5).unwrap(), T::from(size as f64 - 0.5).unwrap())
+        };
+        let span = high - low;
+        if span <= T::zero() {
+            return T::zero();
+        }
+        let coord = (coord - low).abs();
+        let flips = (coord / span).floor();
+        let extra = coord - flips * span;
+        if flips % (T::one() + T::one()) == T::zero() {
+            low + extra
+        } else {
+            high - extra
+        }
+    }
+
+    /// Maps a coordinate inside the input according to the padding mode. With
+    /// zeros padding, the coordinate is left untouched.
+    fn pad<T: Float>(&self, coord: T, size: usize) -> T {
+        let clip = |c: T| c.max(T::zero()).min(T::from(size - 1).unwrap());
+        match self.padding {
+            GridPadding::Zeros => coord,
+            GridPadding::Border => clip(coord),
+            GridPadding::Reflection => clip(self.reflect(coord, size)),
+        }
+    }
+
+    fn pixel<T: Datum + Float>(&self, plane: &ArrayView2<T>, y: T, x: T) -> T {
+        let (h, w) = plane.dim();
+        let (y, x) = (self.pad(y, h), self.pad(x, w));
+        if y < T::zero() || x < T::zero() {
+            return T::zero();
+        }
+        let (y, x) = (y.to_usize().unwrap(), x.to_usize().unwrap());
+        if y < h && x < w {
+            plane[(y, x)]
+        } else {
+            T::zero()
+        }
+    }
+
+    fn sample<T: Datum + Float>(&self, plane: &ArrayView2<T>, y: T, x: T) -> T {
+        let (h, w) = plane.dim();
+        let y = self.unnormalize(y, h);
+        let x = self.unnormalize(x, w);
+        match self.mode {
+            InterpolationMode::Nearest => {
+                let y = round_half_to_even(self.pad(y, h));
+                let x = round_half_to_even(self.pad(x, w));
+                self.pixel(plane, y, x)
+            }
+            InterpolationMode::Bilinear => {
+                let (y, x) = (self.pad(y, h), self.pad(x, w));
+                let (y0, x0) = (y.floor(), x.floor());
+                let (ty, tx) = (y - y0, x - x0);
+                let one = T::one();
+                self.pixel(plane, y0, x0) * (one - ty) * (one - tx)
+                    + self.pixel(plane, y0, x0 + one) * (one - ty) * tx
+                    + self.pixel(plane, y0 + one, x0) * ty * (one - tx)
+                    + self.pixel(plane, y0 + one, x0 + one) * ty * tx
+            }
+            InterpolationMode::Bicubic => {
+                let (y0, x0) = (y.floor(), x.floor());
+                let wy = cubic_coefficients(y - y0);
+                let wx = cubic_coefficients(x - x0);
+                let mut acc = T::zero();
+                for (i, wy) in wy.iter().enumerate() {
+                    let y = y0 + T::from(i as isize - 1).unwrap();
+                    for (j, wx) in wx.iter().enumerate() {
+                        let x = x0 + T::from(j as isize - 1).unwrap();
+                        acc = acc + self.pixel(plane, y, x) * *wy * *wx;
+                    }
+                }
+                acc
+            }
+        }
+    }
+
+    fn eval_t<T: Datum + Float>(&self, input: &Tensor, grid: &Tensor) -> TractResult<Tensor> {
+        let input = input.to_array_view::<T>()?.into_dimensionality::<Ix4>()?;
+        let grid = grid.to_array_view::<T>()?.into_dimensionality::<Ix4>()?;
+        let (n, c, _, _) = input.dim();
+        let (grid_n, h, w, coords) = grid.dim();
+        if grid_n != n || coords != 2 {
+            bail!("Inconsistent input {:?} and grid {:?}", input.shape(), grid.shape());
+        }
+        let mut output = Array4::<T>::zeros((n, c, h, w));
+        for n in 0..n {
+            for c in 0..c {
+                let plane = input.slice(s![n, c, .., ..]);
+                for y in 0..h {
+                    for x in 0..w {
+                        output[(n, c, y, x)] =
+                            self.sample(&plane, grid[(n, y, x, 1)], grid[(n, y, x, 0)]);
+                    }
+                }
+            }
+        }
+        Ok(output.into_tensor())
+    }
+}
+
+fn round_half_to_even<T: Float>(x: T) -> T {
+    let rounded = x.round();
+    let two = T::one() + T::one();
+    if (rounded - x).abs() == T::one() / two {
+        (x / two).round() * two
+    } else {
+        rounded
+    }
+}
+
+/// Cubic convolution weights of the four neighbours (A = -0.75).
+fn cubic_coefficients<T: Float>(t: T) -> [T; 4] {
+    let a = T::from(-0.75).unwrap();
+    let one = T::one();
+    let two = one + one;
+    let close = |x: T| ((a + two) * x - (a + two + one)) * x * x + one;
+    let far = |x: T| {
+        ((a * x - T::from(5).unwrap() * a) * x + T::from(8).unwrap() * a) * x
+            - T::from(4).unwrap() * a
+    };
+    [far(t + one), close(t), close(one - t), far(two - t)]
+}
+
+impl Op for GridSample {
+    fn name(&self) -> Cow<str> {
+        "GridSample".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "mode: {}, padding: {}, align_corners: {}",
+            self.mode.as_str(),
+            self.padding.as_str(),
+            self.align_corners
+        )])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for GridSample {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (input, grid) = args_2!(inputs);
+        let grid = grid.cast_to_dt(input.datum_type())?;
+        let output = dispatch_floatlike!(Self::eval_t(input.datum_type())(self, &input, &grid))?;
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for GridSample {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if inputs[0].rank() != 4 || inputs[1].rank() != 4 {
+            bail!("GridSample only supports 2D spatial inputs (NCHW input and NHW2 grid)")
+        }
+        let shape = tvec!(
+            inputs[0].shape[0].clone(),
+            inputs[0].shape[1].clone(),
+            inputs[1].shape[1].clone(),
+            inputs[1].shape[2].clone()
+        );
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, shape)))
+    }
+
+    as_op!();
+}
@@ -0,0 +1,222 @@
+use super::{GridPadding, GridSample, InterpolationMode};
+use crate::internal::*;
+use crate::setup_test_logger;
+use proptest::collection::vec;
+use proptest::prelude::*;
+use tract_ndarray::prelude::*;
+
+#[derive(Debug)]
+struct GridSampleProblem {
+    op: GridSample,
+    data: Array4<f32>,
+    grid: Array4<f32>,
+}
+
+impl GridSampleProblem {
+    fn unnormalize(&self, coord: f32, size: usize) -> f32 {
+        if self.op.align_corners {
+            (coord + 1.0) / 2.0 * (size as f32 - 1.0)
+        } else {
+            ((coord + 1.0) * size as f32 - 1.0) / 2.0
+        }
+    }
+
+    fn reflect(&self, coord: f32, size: usize) -> f32 {
+        let (low, high) = if self.op.align_corners {
+            (0.0, size as f32 - 1.0)
+        } else {
+            (-0.5, size as f32 - 0.5)
+        };
+        let span = high - low;
+        if span <= 0.0 {
+            return 0.0;
+        }
+        let mut coord = (coord - low).abs();
+        let mut flipped = false;
+        while coord > span {
+            coord -= span;
+            flipped = !flipped;
+        }
+        if coord == span {
+            flipped = !flipped;
+            coord = 0.0;
+        }
+        if flipped {
+            high - coord
+        } else {
+            low + coord
+        }
+    }
+
+    fn pad(&self, coord: f32, size: usize) -> f32 {
+        match self.op.padding {
+            GridPadding::Zeros => coord,
+            GridPadding::Border => coord.max(0.0).min(size as f32 - 1.0),
+            GridPadding::Reflection => self.reflect(coord, size).max(0.0).min(size as f32 - 1.0),
+        }
+    }
+
+    fn kernel(&self, coord: f32, index: isize) -> f32 {
+        let a = -0.75;
+        let abs = (coord - index as f32).abs();
+        match self.op.mode {
+            InterpolationMode::Nearest => {
+                let floor = coord.floor();
+                let nearest = if coord - floor == 0.5 {
+                    if floor % 2.0 == 0.0 {
+                        floor
+                    } else {
+                        floor + 1.0
+                    }
+                } else {
+                    coord.round()
+                };
+                (index as f32 == nearest) as usize as f32
+            }
+            InterpolationMode::Bilinear => (1.0 - abs).max(0.0),
+            InterpolationMode::Bicubic if abs <= 1.0 => {
+                (a + 2.0) * abs * abs * abs - (a + 3.0) * abs * abs + 1.0
+            }
+            InterpolationMode::Bicubic if abs < 2.0 => {
+                a * abs * abs * abs - 5.0 * a * abs * abs + 8.0 * a * abs - 4.0 * a
+            }
+            InterpolationMode::Bicubic => 0.0,
+        }
+    }
+
+    fn value(&self, n: usize, c: usize, y: isize, x: isize) -> f32 {
+        let (_, _, h, w) = self.data.dim();
+        let y = self.pad(y as f32, h) as isize;
+        let x = self.pad(x as f32, w) as isize;
+        if y < 0 || x < 0 || y >= h as isize || x >= w as isize {
+            0.0
+        } else {
+            self.data[(n, c, y as usize, x as usize)]
+        }
+    }
+
+    fn reference(&self) -> Array4<f32> {
+        let (n, c, h, w) = self.data.dim();
+        let (_, ho, wo, _) = self.grid.dim();
+        let mut output = Array4::zeros((n, c, ho, wo));
+        for ((n, c, oy, ox), output) in output.indexed_iter_mut() {
+            let mut y = self.unnormalize(self.grid[(n, oy, ox, 1)], h);
+            let mut x = self.unnormalize(self.grid[(n, oy, ox, 0)], w);
+            if self.op.mode != InterpolationMode::Bicubic {
+                y = self.pad(y, h);
+                x = self.pad(x, w);
+            }
+            for iy in y.floor() as isize - 2..=y.floor() as isize + 2 {
+                for ix in x.floor() as isize - 2..=x.floor() as isize + 2 {
+                    let weight = self.kernel(y, iy) * self.kernel(x, ix);
+                    if weight != 0.0 {
+                        *output += weight * self.value(n, c, iy, ix);
+                    }
+                }
+            }
+        }
+        output
+    }
+
+    fn tract(&self) -> anyhow::Result<Array4<f32>> {
+        setup_test_logger();
+        let mut model = TypedModel::default();
+        let data =
+            model.add_source("data", TypedFact::dt_shape(f32::datum_type(), self.data.shape()))?;
+        let grid =
+            model.add_source("grid", TypedFact::dt_shape(f32::datum_type(), self.grid.shape()))?;
+        let wire = model.wire_node("grid_sample", self.op.clone(), &[data, grid])?;
+        model.set_output_outlets(&wire)?;
+        let mut output = model
+            .into_optimized()?
+            .into_runnable()?
+            .run(tvec![self.data.clone().into_tensor(), self.grid.clone().into_tensor()])?;
+        Ok(output.remove(0).into_tensor().into_array::<f32>()?.into_dimensionality()?)
+    }
+}
+
+impl Arbitrary for GridSampleProblem {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<GridSampleProblem>;
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        let mode = prop_oneof!(
+            Just(InterpolationMode::Nearest),
+            Just(InterpolationMode::Bilinear),
+            Just(InterpolationMode::Bicubic)
+        );
+        let padding = prop_oneof!(
+            Just(GridPadding::Zeros),
+            Just(GridPadding::Border),
+            Just(GridPadding::Reflection)
+        );
+        (
+            mode,
+            padding,
+            any::<bool>(),
+            1usize..3,
+            1usize..3,
+            1usize..5,
+            1usize..5,
+            1usize..4,
+            1usize..4,
+        )
+            .prop_flat_map(|(mode, padding, align_corners, n, c, h, w, ho, wo)| {
+                // values in [-16, 16[, grid coordinates in [-2, 2[
+                let data = vec(any::<i8>().prop_map(|i| i as f32 / 8.0), n * c * h * w);
+                let grid = vec(any::<i8>().prop_map(|i| i as f32 / 64.0), n * ho * wo * 2);
+                (
+                    Just(GridSample::new(mode, padding, align_corners)),
+                    Just((n, c, h, w, ho, wo)),
+                    data,
+                    grid,
+                )
+            })
+            .prop_map(|(op, (n, c, h, w, ho, wo), data, grid)| GridSampleProblem {
+                op,
+                data: Array4::from_shape_vec((n, c, h, w), data).unwrap(),
+                grid: Array4::from_shape_vec((n, ho, wo, 2), grid).unwrap(),
+            })
+            .boxed()
+    }
+}
+
+proptest::proptest! {
+    #[test]
+    fn prop(pb in any::<GridSampleProblem>()) {
+        pb.tract().unwrap().into_tensor().close_enough(&pb.reference().into_tensor(), true).unwrap()
+    }
+}
+
+#[test]
+fn bilinear_zeros_outside() -> anyhow::Result<()> {
+    let pb = GridSampleProblem {
+        op: GridSample::new(InterpolationMode::Bilinear, GridPadding::Zeros, false),
+        data: arr4(&[[[[1f32, 2.], [3., 4.]]]]),
+        grid: arr4(&[[[[-1f32, -1.], [0., 0.], [1.5, 0.]]]]),
+    };
+    assert_eq!(pb.tract()?, arr4(&[[[[0.25f32, 2.5, 0.]]]]));
+    assert_eq!(pb.tract()?, pb.reference());
+    Ok(())
+}
+
+#[test]
+fn nearest_border_align_corners() -> anyhow::Result<()> {
+    let pb = GridSampleProblem {
+        op: GridSample::new(InterpolationMode::Nearest, GridPadding::Border, true),
+        data: arr4(&[[[[1f32, 2., 3.]]]]),
+        grid: arr4(&[[[[-2f32, 0.], [-0.5, 0.], [0.5, 0.], [2., 0.]]]]),
+    };
+    assert_eq!(pb.tract()?, arr4(&[[[[1f32, 1., 3., 3.]]]]));
+    assert_eq!(pb.tract()?, pb.reference());
+    Ok(())
+}
+
+#[test]
+fn bicubic_reflection() -> anyhow::Result<()> {
+    let pb = GridSampleProblem {
+        op: GridSample::new(InterpolationMode::Bicubic, GridPadding::Reflection, false),
+        data: arr4(&[[[[1f32, -2., 0.5], [0.25, 3., -1.]]]]),
+        grid: arr4(&[[[[-1.25f32, 0.75], [0.3, -0.9]]]]),
+    };
+    pb.tract()?.into_tensor().close_enough(&pb.reference().into_tensor(), true)
+}
@@ -1,5 +1,6 @@
 pub mod conv;
 pub mod deconv;
+pub mod grid_sample;
 mod maxpool;
 mod padding;
 mod patch_axis;
@@ -9,6 +10,7 @@ mod sumpool;
 
 pub use self::conv::{ConvUnary, KernelFormat};
 pub use self::deconv::DeconvUnary;
+pub use self::grid_sample::{GridPadding, GridSample, InterpolationMode};
 pub use self::maxpool::MaxPool;
 pub use self::padding::PaddingSpec;
 pub use self::patch_axis::PatchAxis;
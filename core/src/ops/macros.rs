@@ -29,6 +29,41 @@ macro_rules! not_a_typed_op {
     };
 }
 
+/// Implements the state saving, loading and batch slot reset of an `OpState`
+/// holding no data of its own.
+#[macro_export]
+macro_rules! op_state_without_data {
+    () => {
+        fn save(
+            &self,
+            _op: &dyn Op,
+            _prefix: &str,
+            _tensors: &mut Vec<(String, Tensor)>,
+        ) -> TractResult<()> {
+            Ok(())
+        }
+
+        fn load(
+            &mut self,
+            _op: &dyn Op,
+            _prefix: &str,
+            _tensors: &mut HashMap<String, Tensor>,
+        ) -> TractResult<()> {
+            Ok(())
+        }
+
+        fn reset_batch_slot(
+            &mut self,
+            _op: &dyn Op,
+            _inputs: &[&TypedFact],
+            _batch: Symbol,
+            _slot: usize,
+        ) -> TractResult<()> {
+            Ok(())
+        }
+    };
+}
+
 #[macro_export]
 macro_rules! op_core {
     () => {
@@ -128,6 +128,8 @@ impl OpState for State {
             eval(op, scratch.as_mut(), &inputs, &shape, op.c_m_axis, op.c_n_axis, &final_shape)
         }
     }
+
+    op_state_without_data!();
 }
 
 impl EvalOp for LirMatMulUnary {
@@ -188,4 +188,49 @@ mod test {
         model.declutter()?.optimize()?.into_runnable()?.run(tvec!(input))?;
         Ok(())
     }
+
+    fn check_k_split(a_shape: &[usize], b_shape: &[usize]) -> TractResult<()> {
+        let rank = b_shape.len();
+        let (k1, k2) = (2, b_shape[rank - 1] - 2);
+        let mut model = TypedModel::default();
+        let mut halves = tvec!();
+        for (ix, k) in [k1, k2].iter().enumerate() {
+            let mut shape: TVec<usize> = b_shape.into();
+            shape[rank - 1] = *k;
+            let fact = TypedFact::dt_shape(f32::datum_type(), &*shape);
+            halves.push(model.add_source(format!("b{}", ix), fact)?);
+        }
+        let concat = crate::ops::array::TypedConcat::concat_vars(rank - 1, 2);
+        let b = model.wire_node("concat", concat, &halves)?;
+        let len = a_shape.iter().product::<usize>();
+        let a = tensor1(&(0..len).map(|x| x as f32).collect::<Vec<_>>()).into_shape(a_shape)?;
+        let op =
+            MatMulUnary { a: a.into_arc_tensor(), a_trans: false, b_trans: true, c_trans: false };
+        let c = model.wire_node("m", op, &b)?;
+        model.set_output_outlets(&c)?;
+        let inputs: TVec<Tensor> = model
+            .input_outlets()?
+            .iter()
+            .map(|o| {
+                let shape = model.outlet_fact(*o)?.shape.as_concrete().unwrap().to_vec();
+                let len = shape.iter().product::<usize>();
+                tensor1(&(0..len).map(|x| x as f32 / 10.0).collect::<Vec<_>>()).into_shape(&shape)
+            })
+            .collect::<TractResult<_>>()?;
+        let expected = model.clone().into_runnable()?.run(inputs.clone())?;
+        let decluttered = model.declutter()?;
+        assert!(decluttered.nodes().iter().any(|n| n.name == "m.k-0-2"));
+        let found = decluttered.into_runnable()?.run(inputs)?;
+        found[0].close_enough(&expected[0], true)
+    }
+
+    #[test]
+    fn k_split_single_row() -> TractResult<()> {
+        check_k_split(&[1, 5], &[3, 5])
+    }
+
+    #[test]
+    fn k_split_leading_unit_axis() -> TractResult<()> {
+        check_k_split(&[1, 2, 5], &[1, 3, 5])
+    }
 }
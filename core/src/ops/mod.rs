@@ -72,9 +72,79 @@ pub trait OpState: fmt::Debug + Send + dyn_clone::DynClone {
         op: &dyn Op,
         inputs: TVec<Arc<Tensor>>,
     ) -> TractResult<TVec<Arc<Tensor>>>;
+
+    /// Exports the state as named tensors, prefixing their names with `prefix`.
+    #[allow(unused_variables)]
+    fn save(
+        &self,
+        op: &dyn Op,
+        prefix: &str,
+        tensors: &mut Vec<(String, Tensor)>,
+    ) -> TractResult<()> {
+        bail!("{:?} can not be saved", self)
+    }
+
+    /// Restores the state from tensors exported by `save` with the same
+    /// `prefix`. Tensors that do not fit `op` must be rejected.
+    #[allow(unused_variables)]
+    fn load(
+        &mut self,
+        op: &dyn Op,
+        prefix: &str,
+        tensors: &mut HashMap<String, Tensor>,
+    ) -> TractResult<()> {
+        bail!("{:?} can not be loaded", self)
+    }
+
+    /// Resets the state of the stream in `slot` when running a batch of
+    /// independent streams, `batch` being the symbol of the batch size in
+    /// the `inputs` facts.
+    #[allow(unused_variables)]
+    fn reset_batch_slot(
+        &mut self,
+        op: &dyn Op,
+        inputs: &[&TypedFact],
+        batch: Symbol,
+        slot: usize,
+    ) -> TractResult<()> {
+        bail!("{:?} can not reset a single stream of a batch", self)
+    }
 }
 dyn_clone::clone_trait_object!(OpState);
 
+/// Removes from `tensors` the state tensor `name` saved under `prefix`.
+pub fn take_state_tensor(
+    tensors: &mut HashMap<String, Tensor>,
+    prefix: &str,
+    name: &str,
+) -> TractResult<Tensor> {
+    let name = format!("{}{}", prefix, name);
+    tensors.remove(&name).with_context(|| format!("Missing state tensor {}", name))
+}
+
+/// Axis of `fact` along which the batch of size `batch` goes.
+pub fn batch_axis(fact: &TypedFact, batch: Symbol) -> TractResult<usize> {
+    let axes: TVec<usize> = fact
+        .shape
+        .iter()
+        .enumerate()
+        .filter(|(_, d)| d.symbols().contains(&batch))
+        .map(|(ix, _)| ix)
+        .collect();
+    if axes.len() != 1 {
+        bail!("Expected exactly one batch axis (of size {:?}) in {:?}", batch, fact)
+    }
+    Ok(axes[0])
+}
+
+/// Zeroes the entry `slot` of `tensor` along `axis`.
+pub fn zero_batch_slot(tensor: &mut Tensor, axis: usize, slot: usize) -> TractResult<()> {
+    let mut shape: TVec<usize> = tensor.shape().into();
+    shape[axis] = 1;
+    let zero = Tensor::zero_dt(tensor.datum_type(), &shape)?;
+    tensor.assign_slice(slot..slot + 1, &zero, .., axis)
+}
+
 pub trait EvalOp {
     #[allow(unused_variables)]
     fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
@@ -71,6 +71,7 @@ impl EvalOp for LirScan {
             mutable: MutableState {
                 position: 0,
                 hidden_state: tvec!(),
+                pending_resets: tvec!(),
                 model_state: TypedSimpleState::new(Arc::clone(&self.plan))?,
             },
             op: Arc::clone(&self.0),
@@ -88,10 +89,35 @@ struct State {
 struct MutableState {
     position: usize,
     hidden_state: TVec<Tensor>,
+    /// (state, batch axis, slot, position) of the hidden states to
+    /// reinitialize before the iteration at position
+    pending_resets: TVec<(usize, usize, usize, usize)>,
     model_state: TypedSimpleState<TypedModel, Arc<TypedSimplePlan<TypedModel>>>,
 }
 
 impl MutableState {
+    fn apply_pending_resets(
+        &mut self,
+        op: &LirScanOpParams,
+        inputs: &[Arc<Tensor>],
+    ) -> TractResult<()> {
+        let position = self.position - 1;
+        let due: TVec<_> =
+            self.pending_resets.iter().filter(|reset| reset.3 == position).cloned().collect();
+        self.pending_resets.retain(|reset| reset.3 != position);
+        let initializers: TVec<&StateInitializer> =
+            op.input_mapping.iter().filter_map(|m| m.as_state()).collect();
+        for (state, axis, slot, _) in due {
+            let initial: &Tensor = match initializers[state] {
+                StateInitializer::FromInput(input) => &inputs[*input],
+                StateInitializer::Value(v) => v,
+            };
+            let from = if initial.shape()[axis] == 1 { 0 } else { slot };
+            self.hidden_state[state].assign_slice(slot..slot + 1, initial, from..from + 1, axis)?;
+        }
+        Ok(())
+    }
+
     pub(super) fn slice_input(
         &self,
         input: &Tensor,
@@ -152,11 +178,13 @@ impl MutableState {
 impl OpState for State {
     fn eval(
         &mut self,
-        _session: &mut SessionState,
+        session: &mut SessionState,
         _op: &dyn Op,
         inputs: TVec<Arc<Tensor>>,
     ) -> TractResult<TVec<Arc<Tensor>>> {
         let State { op, ref mut mutable } = self;
+        // the body shapes use the same symbols (batch size, ...)
+        mutable.model_state.session_state.resolved_symbols = session.resolved_symbols.clone();
         // initialize state at first pass
         if mutable.hidden_state.len() == 0 {
             for input in &op.input_mapping {
@@ -186,7 +214,8 @@ impl OpState for State {
         for (ix, output) in op.output_mapping.iter().enumerate() {
             if let Some(slot) = output.full_slot {
                 let fact = op.plan.model().output_fact(ix)?;
-                let mut shape: TVec<usize> = fact.shape.as_concrete().unwrap().into();
+                let mut shape: TVec<usize> =
+                    fact.shape.eval_to_usize(&session.resolved_symbols)?.into_owned();
                 let scanning_dim = output
                     .full_dim_hint
                     .as_ref()
@@ -208,6 +237,7 @@ impl OpState for State {
             if mutable.position <= op.skip {
                 continue;
             }
+            mutable.apply_pending_resets(op, &inputs)?;
             mutable.hidden_state.reverse();
 
             let iter_inputs: TVec<Tensor> = op
@@ -261,6 +291,66 @@ impl OpState for State {
 
         Ok(outputs.into_iter().map(Arc::new).collect())
     }
+
+    fn save(
+        &self,
+        _op: &dyn Op,
+        prefix: &str,
+        tensors: &mut Vec<(String, Tensor)>,
+    ) -> TractResult<()> {
+        if self.mutable.pending_resets.len() > 0 {
+            bail!("Can not save a scan state while some batch slots are being reset")
+        }
+        tensors.push((format!("{}position", prefix), tensor0(self.mutable.position as i64)));
+        for (ix, hidden) in self.mutable.hidden_state.iter().enumerate() {
+            tensors.push((format!("{}hidden.{}", prefix, ix), hidden.clone()));
+        }
+        self.mutable.model_state.save_states(&format!("{}body.", prefix), tensors)
+    }
+
+    fn load(
+        &mut self,
+        _op: &dyn Op,
+        prefix: &str,
+        tensors: &mut HashMap<String, Tensor>,
+    ) -> TractResult<()> {
+        let State { op, ref mut mutable } = self;
+        mutable.position =
+            *take_state_tensor(tensors, prefix, "position")?.to_scalar::<i64>()? as usize;
+        // hidden states are only there once the first pass has initialized them
+        let states = op.input_mapping.iter().filter(|m| m.as_state().is_some()).count();
+        mutable.hidden_state = (0..states)
+            .filter_map(|ix| tensors.remove(&format!("{}hidden.{}", prefix, ix)))
+            .collect();
+        if mutable.hidden_state.len() != 0 && mutable.hidden_state.len() != states {
+            bail!("Expected {} hidden states, found {}", states, mutable.hidden_state.len());
+        }
+        mutable.model_state.load_states(&format!("{}body.", prefix), tensors)
+    }
+
+    fn reset_batch_slot(
+        &mut self,
+        _op: &dyn Op,
+        _inputs: &[&TypedFact],
+        batch: Symbol,
+        slot: usize,
+    ) -> TractResult<()> {
+        let State { op, ref mut mutable } = self;
+        // hidden states are reinitialized when the first frame of the new
+        // stream comes in (after the skipped frames), where the initializer
+        // inputs are available
+        if mutable.hidden_state.len() > 0 {
+            let position = mutable.position + op.skip;
+            let body = op.plan.model();
+            let states =
+                op.input_mapping.iter().enumerate().filter(|(_, m)| m.as_state().is_some());
+            for (state, (ix, _)) in states.enumerate() {
+                let axis = batch_axis(body.input_fact(ix)?, batch)?;
+                mutable.pending_resets.push((state, axis, slot, position));
+            }
+        }
+        mutable.model_state.reset_batch_slot(batch, slot)
+    }
 }
 
 impl TypedOp for LirScan {
@@ -215,7 +215,13 @@ impl Scan {
     ) -> TractResult<Option<TypedModelPatch>> {
         for (inner_input_id, input) in self.body.input_outlets()?.iter().enumerate() {
             let source_node = self.body.node(input.node);
-            if source_node.outputs[0].successors.len() == 0 {
+            // the last scanning input gives the iteration count
+            let is_last_scan = self.input_mapping[inner_input_id].as_scan().is_some()
+                && self.input_mapping.iter().filter(|m| m.as_scan().is_some()).count() == 1;
+            if source_node.outputs[0].successors.len() == 0
+                && !self.body.output_outlets()?.contains(input)
+                && !is_last_scan
+            {
                 let mut new_inputs = node.inputs.clone();
                 let slot = match &self.input_mapping[inner_input_id] {
                     InputMapping::Full { slot } => Some(slot),
@@ -385,6 +391,10 @@ impl Scan {
     ) -> TractResult<Option<TypedModelPatch>> {
         for (model_ix, mapping) in self.output_mapping.iter().enumerate() {
             let slot = if let Some(slot) = mapping.full_slot { slot } else { continue };
+            // the body needs the op for states, and last values are taken inside
+            if mapping.state || mapping.last_value_slot.is_some() {
+                continue;
+            }
             let emitter_outlet = self.body.output_outlets()?[model_ix];
             let emitter_node = self.body.node(emitter_outlet.node);
             if emitter_node.outputs[emitter_outlet.slot].successors.len() > 1
@@ -12,6 +12,8 @@ impl OpState for SourceState {
     ) -> TractResult<TVec<Arc<Tensor>>> {
         Ok(tvec!(session.inputs[&self.0].clone()))
     }
+
+    op_state_without_data!();
 }
 
 #[derive(Debug, Clone, new, Hash)]
@@ -125,6 +125,30 @@ where
     _phantom: PhantomData<(M, F, O)>,
 }
 
+/// Checks a value against its fact, evaluating the symbols already resolved
+/// in the session. Facts that are not fully determined (as in inference
+/// models) are checked with `Fact::matches`.
+fn matches_with_symbols<F: Fact>(
+    fact: &F,
+    value: &Tensor,
+    symbols: &SymbolValues,
+) -> TractResult<bool> {
+    let fact = match fact.to_typed_fact() {
+        Ok(fact) => fact,
+        Err(_) => return fact.matches(value),
+    };
+    let shape = match fact.shape.eval_to_usize(symbols) {
+        Ok(shape) => shape,
+        Err(_) => return Ok(false),
+    };
+    Ok(fact.datum_type == value.datum_type() && &**shape == value.shape())
+}
+
+/// Prefix of the saved state tensors of the node `name`.
+fn node_state_prefix(prefix: &str, name: &str) -> String {
+    format!("{}{}.", prefix, name.replace('\\', "\\\\").replace('.', "\\."))
+}
+
 impl<F, O, M, P> SimpleState<F, O, M, P>
 where
     F: Fact + Hash + Clone + 'static,
@@ -163,6 +187,107 @@ where
         Ok(())
     }
 
+    /// Exports the states of the stateful ops and the session tensors as
+    /// named tensors.
+    ///
+    /// The snapshot can be restored with `restore` in any state built from
+    /// the same plan, for instance to resume a stream on another worker.
+    pub fn snapshot(&self) -> TractResult<Vec<(String, Tensor)>> {
+        let mut tensors = vec![];
+        self.save_states("", &mut tensors)?;
+        Ok(tensors)
+    }
+
+    /// Restores a snapshot taken by `snapshot`.
+    pub fn restore(&mut self, snapshot: Vec<(String, Tensor)>) -> TractResult<()> {
+        let mut tensors: HashMap<String, Tensor> = snapshot.into_iter().collect();
+        self.load_states("", &mut tensors)?;
+        if tensors.len() > 0 {
+            let mut names: Vec<&String> = tensors.keys().collect();
+            names.sort();
+            bail!("Unexpected tensors in snapshot: {:?}", names);
+        }
+        Ok(())
+    }
+
+    /// Saves op states as `{prefix}{node name}.{tensor}` and session tensors as
+    /// `{prefix}.session.{id}`.
+    ///
+    /// Backslashes and dots in node names are escaped with a backslash, so a
+    /// node name never runs into the tensor name, and never starts with the
+    /// dot of the session tensors.
+    pub fn save_states(
+        &self,
+        prefix: &str,
+        tensors: &mut Vec<(String, Tensor)>,
+    ) -> TractResult<()> {
+        let model = self.plan.borrow().model();
+        for (node, state) in model.nodes().iter().zip(self.states.iter()) {
+            if let Some(state) = state {
+                state
+                    .save(node.op(), &node_state_prefix(prefix, &node.name), tensors)
+                    .with_context(|| format!("Saving state of {}", node))?;
+            }
+        }
+        let mut ids: Vec<&String> = self.session_state.tensors.keys().collect();
+        ids.sort();
+        for id in ids {
+            tensors.push((
+                format!("{}.session.{}", prefix, id),
+                self.session_state.tensors[id].clone(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Loads states saved by `save_states` with the same `prefix`, removing
+    /// them from `tensors`. The session tensors are replaced by the saved ones.
+    pub fn load_states(
+        &mut self,
+        prefix: &str,
+        tensors: &mut HashMap<String, Tensor>,
+    ) -> TractResult<()> {
+        let &mut SimpleState { ref plan, ref mut session_state, ref mut states, .. } = self;
+        let model = plan.borrow().model();
+        for (node, state) in model.nodes().iter().zip(states.iter_mut()) {
+            if let Some(state) = state {
+                state
+                    .load(node.op(), &node_state_prefix(prefix, &node.name), tensors)
+                    .with_context(|| format!("Loading state of {}", node))?;
+            }
+        }
+        session_state.tensors.clear();
+        let session_prefix = format!("{}.session.", prefix);
+        let ids: Vec<String> =
+            tensors.keys().filter(|name| name.starts_with(&session_prefix)).cloned().collect();
+        for id in ids {
+            let tensor = tensors.remove(&id).unwrap();
+            session_state.tensors.insert(id[session_prefix.len()..].to_string(), tensor);
+        }
+        Ok(())
+    }
+
+    /// Resets the op states of the stream in `slot`, when the model runs a
+    /// batch of independent streams whose size is the symbol `batch`.
+    pub fn reset_batch_slot(&mut self, batch: Symbol, slot: usize) -> TractResult<()> {
+        let &mut SimpleState { ref plan, ref mut states, .. } = self;
+        let model = plan.borrow().model();
+        for (node, state) in model.nodes().iter().zip(states.iter_mut()) {
+            if let Some(state) = state {
+                let inputs = model
+                    .node_input_facts(node.id)?
+                    .iter()
+                    .map(|f| f.to_typed_fact())
+                    .collect::<TractResult<TVec<_>>>()?;
+                let inputs: TVec<&TypedFact> = inputs.iter().collect();
+                state
+                    .reset_batch_slot(node.op(), &inputs, batch, slot)
+                    .with_context(|| format!("Resetting batch slot {} of {}", slot, node))?;
+            }
+        }
+        Ok(())
+    }
+
     pub fn run(&mut self, inputs: TVec<Tensor>) -> TractResult<TVec<Arc<Tensor>>> {
         self.run_plan_with_eval(inputs, self::eval)
     }
@@ -222,7 +347,7 @@ where
                         );
                     }
                     for (ix, (v, f)) in inputs.iter().zip(facts.iter()).enumerate() {
-                        if !f.matches(v)? {
+                        if !matches_with_symbols(*f, v, &session_state.resolved_symbols)? {
                             bail!(
                                 "Evaluating {}: input {:?}, expected {:?}, got {:?}",
                                 node,
@@ -252,7 +377,7 @@ where
                         if node.outputs[ix].successors.len() == 0 {
                             continue;
                         }
-                        if !f.matches(v)? {
+                        if !matches_with_symbols(*f, v, &session_state.resolved_symbols)? {
                             bail!(
                                 "Evaluating {}: output {:?}, expected {:?}, got {:?}",
                                 node,
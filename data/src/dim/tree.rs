@@ -18,6 +18,10 @@ impl Symbol {
         table.push(c);
         Symbol(c, table.len() - 1)
     }
+
+    pub fn as_char(&self) -> char {
+        self.0
+    }
 }
 
 impl From<char> for Symbol {
@@ -378,7 +382,7 @@ impl TDim {
                 Add(terms) => terms
                     .iter()
                     .map(|d| slope_rec(d, sym))
-                    .fold((1, 1), |a, b| ((a.0 * b.1 + a.1 * b.0), (b.1 * a.1))),
+                    .fold((0, 1), |a, b| ((a.0 * b.1 + a.1 * b.0), (b.1 * a.1))),
                 Mul(p, a) => {
                     let (n, d) = slope_rec(a, sym);
                     (p * n, d)
@@ -811,4 +815,11 @@ mod tests {
         let e = (s() - 3 + 1).div_ceil(1);
         assert_eq!(e, s() + -2);
     }
+
+    #[test]
+    fn slope() {
+        assert_eq!((s() + 3).slope(*S), (1, 1));
+        assert_eq!((s() * 3 + 1).div_ceil(4).slope(*S), (3, 4));
+        assert_eq!((s() / 4 - 2).slope(*S), (1, 4));
+    }
 }
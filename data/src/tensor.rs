@@ -651,22 +651,33 @@ impl Tensor {
 
     /// Access the data as a slice.
     pub fn as_slice<D: Datum>(&self) -> anyhow::Result<&[D]> {
-        unsafe { Ok(std::slice::from_raw_parts::<D>(self.as_ptr()?, self.len())) }
+        self.check_for_access::<D>()?;
+        unsafe { Ok(self.as_slice_unchecked()) }
     }
 
     /// Access the data as a mutable slice.
     pub fn as_slice_mut<D: Datum>(&mut self) -> anyhow::Result<&mut [D]> {
-        unsafe { Ok(std::slice::from_raw_parts_mut::<D>(self.as_ptr_mut()?, self.len())) }
+        self.check_for_access::<D>()?;
+        unsafe { Ok(self.as_slice_mut_unchecked()) }
     }
 
     /// Access the data as a slice.
     pub unsafe fn as_slice_unchecked<D: Datum>(&self) -> &[D] {
-        std::slice::from_raw_parts::<D>(self.data as *const D, self.len())
+        // empty tensors have no allocation
+        if self.data.is_null() {
+            &[]
+        } else {
+            std::slice::from_raw_parts::<D>(self.data as *const D, self.len())
+        }
     }
 
     /// Access the data as a mutable slice.
     pub unsafe fn as_slice_mut_unchecked<D: Datum>(&mut self) -> &mut [D] {
-        std::slice::from_raw_parts_mut::<D>(self.data as *mut D, self.len())
+        if self.data.is_null() {
+            &mut []
+        } else {
+            std::slice::from_raw_parts_mut::<D>(self.data as *mut D, self.len())
+        }
     }
 
     /// Access the data as a scalar.
@@ -684,11 +695,19 @@ impl Tensor {
     }
 
     pub unsafe fn as_bytes(&self) -> &[u8] {
-        std::slice::from_raw_parts(self.data, self.layout.size())
+        if self.data.is_null() {
+            &[]
+        } else {
+            std::slice::from_raw_parts(self.data, self.layout.size())
+        }
     }
 
     pub unsafe fn as_bytes_mut(&mut self) -> &mut [u8] {
-        std::slice::from_raw_parts_mut(self.data, self.layout.size())
+        if self.data.is_null() {
+            &mut []
+        } else {
+            std::slice::from_raw_parts_mut(self.data, self.layout.size())
+        }
     }
 
     unsafe fn is_uniform_t<T: Datum>(&self) -> bool {
@@ -0,0 +1,175 @@
+use proptest::proptest;
+use proptest::test_runner::TestCaseResult;
+use proptest::*;
+use tract_hir::internal::*;
+use tract_ndarray::*;
+
+use super::*;
+
+#[derive(Debug, Clone)]
+struct DeconvProblem {
+    stride: usize,
+    dilation: usize,
+    pad_before: usize,
+    pad_after: usize,
+    adjustment: usize,
+    bias: Option<f32>,
+    pre_conv: bool,
+    pulse: usize,
+    ker: Array3<f32>,
+    input: Array3<f32>,
+}
+
+impl Arbitrary for DeconvProblem {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> BoxedStrategy<DeconvProblem> {
+        (1usize..4, vec(1usize..4), 1usize..3, 1usize..4, bool::ANY, bool::ANY)
+            .prop_flat_map(|(stride, ker, dilation, pulse, bias, pre_conv)| {
+                let kernel_field = (ker.len() - 1) * dilation + 1;
+                (
+                    Just(stride),
+                    Just(ker),
+                    Just(dilation),
+                    0..kernel_field,
+                    0..kernel_field,
+                    0..stride,
+                    Just(bias),
+                    Just(pre_conv),
+                    Just(pulse),
+                    vec(2usize..10),
+                )
+            })
+            .prop_filter("padding can not exceed the kernel field", |p| {
+                p.3 + p.4 < (p.1.len() - 1) * p.2 + 1
+            })
+            .prop_map(
+                |(
+                    stride,
+                    ker,
+                    dilation,
+                    pad_before,
+                    pad_after,
+                    adjustment,
+                    bias,
+                    pre_conv,
+                    pulse,
+                    input,
+                )| {
+                    let input = Array3::from_shape_vec((1, 1, input.len()), input).unwrap(); // NCHW
+                    let ker = Array3::from_shape_vec((1, 1, ker.len()), ker).unwrap(); // OIHW
+                    DeconvProblem {
+                        stride,
+                        dilation,
+                        pad_before,
+                        pad_after,
+                        adjustment,
+                        bias: if bias { Some(0.5) } else { None },
+                        pre_conv,
+                        pulse,
+                        ker,
+                        input,
+                    }
+                },
+            )
+            .boxed()
+    }
+}
+
+impl DeconvProblem {
+    pub fn run(&self) -> TestCaseResult {
+        use tract_hir::ops::cnn::*;
+        use tract_hir::ops::nn::DataFormat;
+        use tract_hir::tract_core::ops::cnn::KernelFormat;
+        let mut model = InferenceModel::default();
+        let mut wire = model
+            .add_source("a", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(1, 1, S)))
+            .unwrap();
+        if self.pre_conv {
+            // a valid convolution delays the stream, and its first output
+            // frames are not to be used
+            let kernel = model.add_const("pre_kernel", tensor3(&[[[1f32, -1.]]])).unwrap();
+            wire =
+                model.wire_node("pre_conv", expand(Conv::default()), &[wire, kernel]).unwrap()[0];
+        }
+        let pool_spec = PoolSpec::new(
+            DataFormat::NCHW,
+            tvec!(self.ker.len()),
+            PaddingSpec::Explicit(tvec!(self.pad_before), tvec!(self.pad_after), false),
+            Some(tvec!(self.dilation)),
+            Some(tvec!(self.stride)),
+            Some(1),
+        );
+        let deconv = DeconvUnary::new(
+            pool_spec,
+            KernelFormat::OIHW,
+            self.ker.clone().into_arc_tensor(),
+            self.bias.map(|b| rctensor1(&[b])),
+            tvec!(self.adjustment),
+            1,
+        );
+        let deconv = model.wire_node("deconv", deconv, &[wire]).unwrap();
+        model.set_output_outlets(&deconv).unwrap();
+        proptest_regular_against_pulse(model, self.pulse as _, self.input.clone().into_dyn(), 2)
+    }
+}
+
+proptest! {
+    #[test]
+    fn proptest_deconv(pb in DeconvProblem::arbitrary()) { pb.run().unwrap() }
+}
+
+#[test]
+fn deconv_overlap() {
+    DeconvProblem {
+        stride: 1,
+        dilation: 1,
+        pad_before: 0,
+        pad_after: 0,
+        adjustment: 0,
+        bias: None,
+        pre_conv: false,
+        pulse: 1,
+        ker: arr3(&[[[1.0f32, 2.0]]]),
+        input: arr3(&[[[1.0f32, 10.0]]]),
+    }
+    .run()
+    .unwrap()
+}
+
+#[test]
+fn deconv_stride_padding_bias() {
+    DeconvProblem {
+        stride: 2,
+        dilation: 1,
+        pad_before: 1,
+        pad_after: 1,
+        adjustment: 1,
+        bias: Some(0.5),
+        pre_conv: false,
+        pulse: 2,
+        ker: arr3(&[[[1.0f32, 2.0, 3.0]]]),
+        input: arr3(&[[[1.0f32, 10.0, 100.0]]]),
+    }
+    .run()
+    .unwrap()
+}
+
+#[test]
+fn deconv_after_delay() {
+    DeconvProblem {
+        stride: 1,
+        dilation: 2,
+        pad_before: 0,
+        pad_after: 0,
+        adjustment: 0,
+        bias: None,
+        pre_conv: true,
+        pulse: 3,
+        ker: arr3(&[[[1.0f32, 1.0]]]),
+        input: arr3(&[[[1.0f32, 2.0, 4.0, 8.0]]]),
+    }
+    .run()
+    .unwrap()
+}
@@ -0,0 +1,27 @@
+use tract_hir::internal::*;
+use tract_hir::ops::array::Gather;
+use tract_ndarray::*;
+
+use super::*;
+
+fn gather_across_stream(indices: Tensor, pulse: usize) {
+    let mut model = InferenceModel::default();
+    let a = model
+        .add_source("a", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(3, S)))
+        .unwrap();
+    let indices = model.add_const("indices", indices).unwrap();
+    let gather = model.wire_node("gather", expand(Gather::new(0)), &[a, indices]).unwrap();
+    model.set_output_outlets(&gather).unwrap();
+    let input = Array2::from_shape_fn((3, 5), |(i, j)| (10 * i + j) as f32);
+    proptest_regular_against_pulse(model, pulse as _, input.into_dyn(), 1).unwrap()
+}
+
+#[test]
+fn gather_rows() {
+    gather_across_stream(tensor1(&[2i64, 0, 2]), 2)
+}
+
+#[test]
+fn gather_shifts_streaming_axis() {
+    gather_across_stream(tensor2(&[[1i64, 2], [0, 0]]), 3)
+}
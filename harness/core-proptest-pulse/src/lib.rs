@@ -12,7 +12,9 @@ use tract_ndarray::*;
 use tract_pulse::internal::*;
 
 mod conv_plus_conv;
+mod deconv;
 mod delay_plus_pool;
+mod gather;
 mod pad_plus_conv;
 
 #[allow(dead_code)]
@@ -238,3 +240,30 @@ fn test_pad_before_2() {
     let input = arr1(&[1.0, 2.0]);
     proptest_regular_against_pulse(model, 2, input.into_dyn(), 0).unwrap();
 }
+
+fn crop_then_concat(pulse: usize, input_len: usize, crop: usize, before: usize) -> TestCaseResult {
+    use tract_hir::ops::array::{Concat, Crop};
+    let mut model = InferenceModel::default();
+    let a = model
+        .add_source("a", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(S)))
+        .unwrap();
+    let crop = model.wire_node("crop", expand(Crop::new(0, crop, 0)), &[a]).unwrap();
+    let pre = model.add_const("pre", tensor1(&vec![-1f32; before])).unwrap();
+    model.wire_node("concat", expand(Concat::new(0)), &[pre, crop[0]]).unwrap();
+    model.auto_outputs().unwrap();
+
+    let input = Array1::range(1.0f32, input_len as f32 + 1.0, 1.0);
+    proptest_regular_against_pulse(model, pulse, input.into_dyn(), 0)
+}
+
+proptest! {
+    #[test]
+    fn proptest_crop_then_concat(pulse in 1usize..3, input_len in 0usize..10, crop in 0usize..4, before in 1usize..4) {
+        crop_then_concat(pulse, input_len + crop, crop, before)?;
+    }
+}
+
+#[test]
+fn test_concat_after_longer_delay() {
+    crop_then_concat(2, 6, 3, 1).unwrap()
+}
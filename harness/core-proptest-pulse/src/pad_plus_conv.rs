@@ -1,6 +1,5 @@
 use proptest::proptest;
 use proptest::test_runner::TestCaseResult;
-use proptest::*;
 use tract_hir::internal::*;
 use tract_hir::ops::array::{Pad, PadMode};
 use tract_ndarray::*;
@@ -24,8 +23,8 @@ impl Arbitrary for PadPlusConvProblem {
     type Strategy = BoxedStrategy<Self>;
 
     fn arbitrary_with(_: Self::Parameters) -> BoxedStrategy<PadPlusConvProblem> {
-        (1usize..3, vec(1usize..3), 1usize..3, 0usize..15, 0usize..15, 1usize..3, bool::ANY)
-            .prop_flat_map(|(stride, ker, dil, pad_before, pad_after, pulse_factor, edge)| {
+        (1usize..3, vec(1usize..3), 1usize..3, 0usize..15, 0usize..15, 1usize..3, 0usize..4)
+            .prop_flat_map(|(stride, ker, dil, pad_before, pad_after, pulse_factor, mode)| {
                 let min_input = (ker.len() * dil).max(pulse_factor * stride);
                 (
                     Just(stride),
@@ -35,14 +34,16 @@ impl Arbitrary for PadPlusConvProblem {
                     Just(pad_after),
                     Just(stride * pulse_factor),
                     vec(min_input..3 * min_input),
-                    Just(edge),
+                    Just(mode),
                 )
             })
-            .prop_map(|(stride, ker, dilation, pad_before, pad_after, pulse, input, edge)| {
-                let pad_mode = if edge && pad_before < pulse {
-                    PadMode::Edge
-                } else {
-                    PadMode::Constant(Tensor::from(9999f32).into())
+            .prop_map(|(stride, ker, dilation, pad_before, pad_after, pulse, input, mode)| {
+                let len = input.len();
+                let pad_mode = match mode {
+                    1 => PadMode::Edge,
+                    2 if pad_before < len && pad_after < len => PadMode::Reflect,
+                    3 if pad_before <= len && pad_after <= len => PadMode::Symmetric,
+                    _ => PadMode::Constant(Tensor::from(9999f32).into()),
                 };
                 let input = Array3::from_shape_vec((1, 1, input.len()), input).unwrap(); // NCHW
                 let ker = Array3::from_shape_vec((1, 1, ker.len()), ker).unwrap(); // OIHW
@@ -254,3 +255,51 @@ fn conv_9() {
     .run()
     .unwrap()
 }
+
+#[test]
+fn conv_edge_longer_than_pulse() {
+    PadPlusConvProblem {
+        pad_before: 3,
+        pad_after: 2,
+        pad_mode: PadMode::Edge,
+        stride: 1,
+        dilation: 1,
+        pulse: 2,
+        ker: arr3(&[[[1.0f32]]]),
+        input: arr3(&[[[1.0f32, 2.0, 3.0, 4.0]]]),
+    }
+    .run()
+    .unwrap()
+}
+
+#[test]
+fn conv_reflect() {
+    PadPlusConvProblem {
+        pad_before: 3,
+        pad_after: 2,
+        pad_mode: PadMode::Reflect,
+        stride: 1,
+        dilation: 1,
+        pulse: 1,
+        ker: arr3(&[[[1.0f32]]]),
+        input: arr3(&[[[1.0f32, 2.0, 3.0, 4.0, 5.0]]]),
+    }
+    .run()
+    .unwrap()
+}
+
+#[test]
+fn conv_symmetric() {
+    PadPlusConvProblem {
+        pad_before: 2,
+        pad_after: 3,
+        pad_mode: PadMode::Symmetric,
+        stride: 2,
+        dilation: 1,
+        pulse: 2,
+        ker: arr3(&[[[1.0f32, 2.0]]]),
+        input: arr3(&[[[1.0f32, 2.0, 3.0]]]),
+    }
+    .run()
+    .unwrap()
+}
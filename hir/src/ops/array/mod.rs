@@ -11,6 +11,7 @@ mod gather_nd;
 mod pad;
 pub mod permute_axes;
 mod reshape;
+mod reverse_sequence;
 mod rm_dims;
 mod scatter_elements;
 mod scatter_nd;
@@ -21,6 +22,7 @@ mod split;
 mod squeeze;
 mod strided_slice;
 mod tile;
+mod trilu;
 
 pub use add_dims::AddDims;
 pub use broadcast::MultiBroadcastTo;
@@ -35,6 +37,7 @@ pub use gather_nd::GatherNd;
 pub use pad::{Pad, PadMode};
 pub use permute_axes::PermuteAxes;
 pub use reshape::Reshape;
+pub use reverse_sequence::ReverseSequence;
 pub use rm_dims::RmDims;
 pub use scatter_elements::ScatterElements;
 pub use scatter_nd::ScatterNd;
@@ -45,3 +48,4 @@ pub use split::Split;
 pub use squeeze::Squeeze;
 pub use strided_slice::StridedSlice;
 pub use tile::Tile;
+pub use trilu::Trilu;
@@ -0,0 +1,24 @@
+use crate::infer::*;
+use crate::internal::*;
+
+pub use tract_core::ops::array::ReverseSequence;
+
+impl InferenceRulesOp for ReverseSequence {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 2)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        s.equals(&outputs[0].shape, &inputs[0].shape)?;
+        s.equals(&inputs[1].rank, 1)?;
+        s.equals(&inputs[1].shape[0], &inputs[0].shape[self.batch_axis])?;
+        Ok(())
+    }
+
+    as_op!();
+    to_typed!();
+}
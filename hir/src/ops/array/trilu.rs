@@ -0,0 +1,27 @@
+use crate::infer::*;
+use crate::internal::*;
+
+pub use tract_core::ops::array::Trilu;
+
+impl InferenceRulesOp for Trilu {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        if inputs.len() != 1 && inputs.len() != 2 {
+            bail!("Trilu expects one or two inputs, got {}", inputs.len());
+        }
+        check_output_arity(outputs, 1)?;
+        s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        s.equals(&outputs[0].shape, &inputs[0].shape)?;
+        if inputs.len() == 2 {
+            s.equals(&inputs[1].rank, 0)?;
+        }
+        Ok(())
+    }
+
+    as_op!();
+    to_typed!();
+}
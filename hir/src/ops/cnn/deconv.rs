@@ -0,0 +1,29 @@
+use crate::infer::*;
+use crate::internal::*;
+
+pub use tract_core::ops::cnn::DeconvUnary;
+
+impl InferenceRulesOp for DeconvUnary {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        s.equals(&outputs[0].rank, &inputs[0].rank)?;
+        s.given(&inputs[0].shape, move |s, ishape| {
+            let oshape = tract_core::ops::cnn::deconv::output_shape(
+                &self.pool_spec,
+                &ishape,
+                &self.adjustments,
+            )?;
+            s.equals(&outputs[0].shape, oshape)
+        })
+    }
+
+    as_op!();
+    to_typed!();
+}
@@ -1,6 +1,8 @@
 mod conv;
+mod deconv;
 mod pools;
 
 pub use conv::Conv;
+pub use deconv::DeconvUnary;
 pub use pools::{MaxPool, SumPool};
 pub use tract_core::ops::cnn::{ConvUnary, PaddingSpec, PoolSpec};
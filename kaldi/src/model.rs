@@ -7,6 +7,38 @@ pub struct KaldiProtoModel {
     pub config_lines: ConfigLines,
     pub components: HashMap<String, Component>,
     pub adjust_final_offset: isize,
+    /// Transition model of acoustic models (final.mdl).
+    pub transition_model: Option<TransitionModel>,
+    /// Priors of the output pdfs of acoustic models (final.mdl).
+    pub priors: Option<Arc<Tensor>>,
+    /// Turn the "output" log-posteriors into log-likelihoods by subtracting
+    /// the log-priors, as Kaldi decoders do.
+    pub subtract_log_priors: bool,
+}
+
+/// Kaldi TransitionModel, found ahead of the network in acoustic models. The
+/// HMM topology is not kept.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransitionModel {
+    pub tuples: Vec<TransitionTuple>,
+    pub log_probs: Arc<Tensor>,
+}
+
+impl TransitionModel {
+    pub fn num_pdfs(&self) -> usize {
+        let pdfs = self.tuples.iter().map(|t| t.forward_pdf.max(t.self_loop_pdf) as usize + 1);
+        pdfs.max().unwrap_or(0)
+    }
+}
+
+/// A transition state. HMM models (with Triples instead of Tuples) use the
+/// same pdf for the forward and the self-loop transitions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TransitionTuple {
+    pub phone: i32,
+    pub hmm_state: i32,
+    pub forward_pdf: i32,
+    pub self_loop_pdf: i32,
 }
 
 #[derive(Clone, Debug)]
@@ -32,25 +64,63 @@ pub struct OutputLine {
 #[derive(Clone, Debug, PartialEq)]
 pub enum GeneralDescriptor {
     Append(Vec<GeneralDescriptor>),
+    Const(f32, usize),
+    Failover(Box<GeneralDescriptor>, Box<GeneralDescriptor>),
     IfDefined(Box<GeneralDescriptor>),
     Name(String),
     Offset(Box<GeneralDescriptor>, isize),
+    ReplaceIndex(Box<GeneralDescriptor>, IndexVariable, isize),
+    Round(Box<GeneralDescriptor>, usize),
+    Scale(f32, Box<GeneralDescriptor>),
+    Sum(Box<GeneralDescriptor>, Box<GeneralDescriptor>),
+    Switch(Vec<GeneralDescriptor>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IndexVariable {
+    T,
+    X,
 }
 
 impl GeneralDescriptor {
     pub fn inputs(&self) -> TVec<&str> {
-        match self {
-            GeneralDescriptor::Append(ref gds) => gds.iter().fold(tvec!(), |mut acc, gd| {
+        fn merge<'a>(gds: impl Iterator<Item = &'a GeneralDescriptor>) -> TVec<&'a str> {
+            gds.fold(tvec!(), |mut acc, gd| {
                 gd.inputs().iter().for_each(|i| {
                     if !acc.contains(i) {
                         acc.push(i)
                     }
                 });
                 acc
-            }),
+            })
+        }
+        match self {
+            GeneralDescriptor::Append(ref gds) => merge(gds.iter()),
+            GeneralDescriptor::Const(_, _) => tvec!(),
+            GeneralDescriptor::Failover(ref a, ref b) => merge(vec![&**a, &**b].into_iter()),
             GeneralDescriptor::IfDefined(ref gd) => gd.inputs(),
             GeneralDescriptor::Name(ref s) => tvec!(&**s),
             GeneralDescriptor::Offset(ref gd, _) => gd.inputs(),
+            GeneralDescriptor::ReplaceIndex(ref gd, _, _) => gd.inputs(),
+            GeneralDescriptor::Round(ref gd, _) => gd.inputs(),
+            GeneralDescriptor::Scale(_, ref gd) => gd.inputs(),
+            GeneralDescriptor::Sum(ref a, ref b) => merge(vec![&**a, &**b].into_iter()),
+            GeneralDescriptor::Switch(ref gds) => merge(gds.iter()),
+        }
+    }
+
+    /// Time-invariant descriptors (constants, or a single frame picked by
+    /// ReplaceIndex) are wired as a single row.
+    fn is_time_invariant(&self) -> bool {
+        use GeneralDescriptor::*;
+        match self {
+            Const(_, _) | ReplaceIndex(_, IndexVariable::T, _) => true,
+            ReplaceIndex(gd, IndexVariable::X, _) | Scale(_, gd) | Failover(gd, _) => {
+                gd.is_time_invariant()
+            }
+            Sum(a, b) => a.is_time_invariant() && b.is_time_invariant(),
+            Append(gds) => gds.iter().all(|gd| gd.is_time_invariant()),
+            _ => false,
         }
     }
 
@@ -96,16 +166,125 @@ impl GeneralDescriptor {
                     expand(tract_hir::ops::array::Concat::new(1)),
                     tvec!(InferenceFact::default()),
                 )?;
-                model.add_edge(OutletId::new(id, 0), inlet)?;
+                deferred.insert(inlet, name.clone());
+                let reference = appendees.iter().find(|app| !app.is_time_invariant());
                 for (ix, appendee) in appendees.iter().enumerate() {
                     let name = format!("{}-{}", name, ix);
-                    appendee.wire(
-                        InletId::new(id, ix),
-                        &*name,
-                        model,
-                        deferred,
-                        adjust_final_offset,
-                    )?;
+                    match reference {
+                        Some(reference) if appendee.is_time_invariant() => {
+                            // repeat the row for as many frames as the other appendees
+                            let bc = model.add_node(
+                                &*name,
+                                crate::ops::descriptor::RowBroadcast,
+                                tvec!(InferenceFact::default()),
+                            )?;
+                            deferred.insert(InletId::new(id, ix), name.clone());
+                            appendee.wire(
+                                InletId::new(bc, 0),
+                                &*format!("{}.row", name),
+                                model,
+                                deferred,
+                                adjust_final_offset,
+                            )?;
+                            reference.wire(
+                                InletId::new(bc, 1),
+                                &*format!("{}.reference", name),
+                                model,
+                                deferred,
+                                adjust_final_offset,
+                            )?;
+                        }
+                        _ => appendee.wire(
+                            InletId::new(id, ix),
+                            &*name,
+                            model,
+                            deferred,
+                            adjust_final_offset,
+                        )?,
+                    }
+                }
+                return Ok(());
+            }
+            &Const(value, dim) => {
+                let konst = tract_ndarray::Array2::from_elem((1, *dim), *value);
+                let name = format!("{}.Const", name);
+                model.add_const(&*name, konst.into_tensor())?;
+                deferred.insert(inlet, name);
+                return Ok(());
+            }
+            &Failover(ref a, _) => {
+                // Kaldi only falls back on the second descriptor where the first one can
+                // not be computed, which does not happen once the network is in a
+                // steady state.
+                return a.wire(inlet, name, model, deferred, adjust_final_offset);
+            }
+            &ReplaceIndex(ref gd, IndexVariable::X, 0) => {
+                return gd.wire(inlet, name, model, deferred, adjust_final_offset);
+            }
+            &ReplaceIndex(ref gd, IndexVariable::T, t) if *t >= 0 => {
+                let name = format!("{}.ReplaceIndex", name);
+                let id = model.add_node(
+                    &*name,
+                    tract_hir::ops::array::Slice::new(0, *t as usize, *t as usize + 1),
+                    tvec!(InferenceFact::default()),
+                )?;
+                deferred.insert(inlet, name.clone());
+                gd.wire(InletId::new(id, 0), &*name, model, deferred, adjust_final_offset)?;
+                return Ok(());
+            }
+            &Round(ref gd, 1) => {
+                return gd.wire(inlet, name, model, deferred, adjust_final_offset);
+            }
+            &Round(ref gd, modulus) if *modulus > 1 => {
+                let name = format!("{}.Round", name);
+                let id = model.add_node(
+                    &*name,
+                    crate::ops::descriptor::Round::new(*modulus),
+                    tvec!(InferenceFact::default()),
+                )?;
+                deferred.insert(inlet, name.clone());
+                gd.wire(InletId::new(id, 0), &*name, model, deferred, adjust_final_offset)?;
+                return Ok(());
+            }
+            &Scale(scale, ref gd) => {
+                let name = format!("{}.Scale", name);
+                let id = model.add_node(
+                    &*name,
+                    tract_hir::ops::math::Mul.into_hir(),
+                    tvec!(InferenceFact::default()),
+                )?;
+                deferred.insert(inlet, name.clone());
+                let factor = format!("{}.factor", name);
+                model.add_const(&*factor, tensor0(*scale))?;
+                deferred.insert(InletId::new(id, 1), factor);
+                gd.wire(InletId::new(id, 0), &*name, model, deferred, adjust_final_offset)?;
+                return Ok(());
+            }
+            &Sum(ref a, ref b) => {
+                let name = format!("{}.Sum", name);
+                let id = model.add_node(
+                    &*name,
+                    tract_hir::ops::math::Add.into_hir(),
+                    tvec!(InferenceFact::default()),
+                )?;
+                deferred.insert(inlet, name.clone());
+                for (ix, term) in [a, b].iter().enumerate() {
+                    let name = format!("{}-{}", name, ix);
+                    term.wire(InletId::new(id, ix), &*name, model, deferred, adjust_final_offset)?;
+                }
+                return Ok(());
+            }
+            &Switch(cases) if !cases.is_empty() => {
+                let name = format!("{}.Switch", name);
+                let id = model.add_node(
+                    &*name,
+                    crate::ops::descriptor::Switch,
+                    tvec!(InferenceFact::default()),
+                )?;
+                deferred.insert(inlet, name.clone());
+                for (ix, case) in cases.iter().enumerate() {
+                    let name = format!("{}-{}", name, ix);
+                    case.wire(InletId::new(id, ix), &*name, model, deferred, adjust_final_offset)?;
                 }
                 return Ok(());
             }
@@ -134,7 +313,7 @@ impl GeneralDescriptor {
                     expand(tract_hir::ops::array::Crop::new(0, crop as usize, 0)),
                     tvec!(InferenceFact::default()),
                 )?;
-                model.add_edge(OutletId::new(id, 0), inlet)?;
+                deferred.insert(inlet, name.clone());
                 n.wire(InletId::new(id, 0), &*name, model, deferred, adjust_final_offset)?;
                 return Ok(());
             }
@@ -161,12 +340,28 @@ pub struct ComponentNode {
 pub struct Component {
     pub klass: String,
     pub attributes: HashMap<String, Arc<Tensor>>,
+    pub components: Vec<Component>,
 }
 
 pub struct ParsingContext<'a> {
     pub proto_model: &'a KaldiProtoModel,
 }
 
+impl<'a> ParsingContext<'a> {
+    /// The component used by a component node.
+    pub fn component_for_node(&self, name: &str) -> TractResult<&'a Component> {
+        let node = self.proto_model.config_lines.nodes.iter().find(|l| l.0 == name);
+        if let Some((_, NodeLine::Component(line))) = node {
+            self.proto_model
+                .components
+                .get(&line.component)
+                .with_context(|| format!("Could not find component {}", line.component))
+        } else {
+            bail!("Could not find component node {}", name)
+        }
+    }
+}
+
 #[derive(Clone, Default)]
 pub struct KaldiOpRegister(
     pub HashMap<String, fn(&ParsingContext, node: &str) -> TractResult<Box<dyn InferenceOp>>>,
@@ -265,8 +460,14 @@ impl Framework<KaldiProtoModel, InferenceModel> for Kaldi {
         }
         let mut outputs = vec![];
         for o in &proto_model.config_lines.outputs {
-            let output = model.add_node(
-                &*o.output_alias,
+            let subtract_log_priors = proto_model.subtract_log_priors && o.output_alias == "output";
+            let name = if subtract_log_priors {
+                format!("{}.log_posteriors", o.output_alias)
+            } else {
+                o.output_alias.clone()
+            };
+            let mut output = model.add_node(
+                &*name,
                 tract_hir::ops::identity::Identity::default(),
                 tvec!(InferenceFact::default()),
             )?;
@@ -277,6 +478,27 @@ impl Framework<KaldiProtoModel, InferenceModel> for Kaldi {
                 &mut inputs_to_wire,
                 Some(proto_model.adjust_final_offset),
             )?;
+            if subtract_log_priors {
+                let priors = proto_model
+                    .priors
+                    .as_ref()
+                    .context("Subtracting log priors requires a model with priors")?;
+                let log_priors = priors
+                    .to_array_view::<f32>()?
+                    .mapv(f32::ln)
+                    .insert_axis(tract_ndarray::Axis(0))
+                    .into_tensor();
+                let log_priors =
+                    model.add_const(format!("{}.log_priors", o.output_alias), log_priors)?;
+                let id = model.add_node(
+                    &*o.output_alias,
+                    tract_hir::ops::math::Sub.into_hir(),
+                    tvec!(InferenceFact::default()),
+                )?;
+                model.add_edge(OutletId::new(output, 0), InletId::new(id, 0))?;
+                model.add_edge(log_priors, InletId::new(id, 1))?;
+                output = id;
+            }
             outputs.push(OutletId::new(output, 0));
         }
         for (inlet, name) in inputs_to_wire {
@@ -11,20 +11,34 @@ macro_rules! op_kaldi {
 }
 
 pub(crate) mod affine;
+mod attention;
+mod conv;
+pub(crate) mod descriptor;
 pub(crate) mod lstm_nonlin;
 pub(crate) mod memory;
 mod renorm;
+mod scale_offset;
+mod sum_block;
 
 pub const AFFINE: &'static [&'static str] =
-    &["FixedAffineComponent", "NaturalGradientAffineComponent"];
+    &["FixedAffineComponent", "NaturalGradientAffineComponent", "LinearComponent"];
 
 pub fn register_all_ops(reg: &mut KaldiOpRegister) {
     for affine in AFFINE {
         reg.insert(affine, affine::affine_component);
     }
+    reg.insert("TdnnComponent", affine::tdnn_component);
     reg.insert("BackpropTruncationComponent", |_, _| {
         Ok(Box::new(tract_hir::ops::identity::Identity::default()))
     });
+    reg.insert("GeneralDropoutComponent", |_, _| {
+        Ok(Box::new(tract_hir::ops::identity::Identity::default()))
+    });
+    reg.insert("BatchNormComponent", scale_offset::batch_norm);
+    reg.insert("ScaleAndOffsetComponent", scale_offset::scale_and_offset);
+    reg.insert("TimeHeightConvolutionComponent", conv::time_height_convolution);
+    reg.insert("RestrictedAttentionComponent", attention::restricted_attention);
+    reg.insert("SumBlockComponent", sum_block::sum_block);
     reg.insert("NormalizeComponent", renorm::renorm);
     reg.insert("LstmNonlinearityComponent", lstm_nonlin::lstm_nonlin);
     reg.insert("RectifiedLinearComponent", |_, _| {
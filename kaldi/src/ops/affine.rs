@@ -12,22 +12,76 @@ pub fn affine_component(ctx: &ParsingContext, name: &str) -> TractResult<Box<dyn
     };
     let component = &ctx.proto_model.components[&line.component];
     let (kernel_len, dilation) = line.input.as_conv_shape_dilation().unwrap_or((1, 1));
-    let kernel: &Tensor =
-        component.attributes.get("LinearParams").context("missing attribute LinearParams")?;
-    let bias = component.attributes.get("BiasParams").context("missing attribute BiasParams")?;
-    // O•TI -> t -> TI•O -> T•I•O = HWIO
-    let o_ti = kernel.to_array_view::<f32>()?;
-    let t_i_o_shape = (kernel_len, kernel.len() / kernel_len / bias.len(), bias.len());
-    let t_i_o =
-        tract_ndarray::Array::from_shape_vec(t_i_o_shape, o_ti.t().iter().cloned().collect())?;
+    let (kernel, bias) = params(&component.attributes)?;
+    let linear_params = kernel_t_i_o(&kernel, kernel_len, bias.len())?;
+    Ok(expand(Affine { kernel_len, dilation, linear_params, bias_params: bias }))
+}
+
+/// TdnnComponent splices its input at its time offsets before the affine
+/// transform. This is a convolution (valid, like the Offset descriptors)
+/// dilated by the common step between offsets, with zero taps where the
+/// offsets have holes.
+pub fn tdnn_component(ctx: &ParsingContext, name: &str) -> TractResult<Box<dyn InferenceOp>> {
+    let component = ctx.component_for_node(name)?;
+    let offsets =
+        component.attributes.get("TimeOffsets").context("missing attribute TimeOffsets")?;
+    let offsets: Vec<i64> = offsets.cast_to::<i64>()?.as_slice::<i64>()?.to_vec();
+    if offsets.is_empty() || offsets.windows(2).any(|w| w[0] >= w[1]) {
+        bail!("TimeOffsets must be sorted and non-empty, got {:?}", offsets);
+    }
+    let (kernel, bias) = params(&component.attributes)?;
+    let kernel = kernel_t_i_o(&kernel, offsets.len(), bias.len())?;
+    let dilation = offsets.windows(2).map(|w| w[1] - w[0]).fold(0, gcd).max(1);
+    let kernel_len = ((offsets[offsets.len() - 1] - offsets[0]) / dilation) as usize + 1;
+    let mut linear_params =
+        tract_ndarray::Array3::<f32>::zeros((kernel_len, kernel.shape()[1], kernel.shape()[2]));
+    let kernel = kernel.to_array_view::<f32>()?;
+    for (ix, offset) in offsets.iter().enumerate() {
+        let tap = ((offset - offsets[0]) / dilation) as usize;
+        linear_params
+            .index_axis_mut(tract_ndarray::Axis(0), tap)
+            .assign(&kernel.index_axis(tract_ndarray::Axis(0), ix));
+    }
     Ok(expand(Affine {
         kernel_len,
-        dilation,
-        linear_params: t_i_o.into_arc_tensor(),
-        bias_params: Arc::clone(bias),
+        dilation: dilation as usize,
+        linear_params: linear_params.into_arc_tensor(),
+        bias_params: bias,
     }))
 }
 
+pub(crate) fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Linear params and bias, with a zero bias for components that have none
+/// (LinearComponent).
+fn params(attributes: &HashMap<String, Arc<Tensor>>) -> TractResult<(Arc<Tensor>, Arc<Tensor>)> {
+    let kernel = attributes
+        .get("LinearParams")
+        .or_else(|| attributes.get("Params"))
+        .context("missing attribute LinearParams")?;
+    let bias = if let Some(bias) = attributes.get("BiasParams") {
+        bias.clone()
+    } else {
+        rctensor1(&vec![0f32; kernel.shape()[0]])
+    };
+    Ok((kernel.clone(), bias))
+}
+
+// O•TI -> t -> TI•O -> T•I•O = HWIO
+fn kernel_t_i_o(kernel: &Tensor, kernel_len: usize, outputs: usize) -> TractResult<Arc<Tensor>> {
+    let o_ti = kernel.to_array_view::<f32>()?;
+    let t_i_o_shape = (kernel_len, kernel.len() / kernel_len / outputs, outputs);
+    let t_i_o =
+        tract_ndarray::Array::from_shape_vec(t_i_o_shape, o_ti.t().iter().cloned().collect())?;
+    Ok(t_i_o.into_arc_tensor())
+}
+
 #[derive(Clone, Debug, new, Hash)]
 struct Affine {
     kernel_len: usize,
@@ -108,3 +162,48 @@ impl Expansion for Affine {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::nnet3;
+    use tract_hir::prelude::*;
+    use tract_pulse::internal::*;
+
+    #[test]
+    fn tdnn_with_holes_in_offsets() {
+        let slice = r#"<Nnet3>
+input-node name=input dim=2
+component-node name=tdnn input=input component=tdnn1
+output-node name=output input=tdnn
+<NumComponents> 1
+<ComponentName> tdnn1 <TdnnComponent> <LearningRate> 0.001 <TimeOffsets> [ -1 0 2 ]
+<LinearParams>  [
+  1 2 3 4 5 6 ]
+<BiasParams>  [ 0.5 ]
+</TdnnComponent>
+</Nnet3>"#;
+        let proto = nnet3(slice.as_bytes()).unwrap();
+        let model = crate::kaldi().model_for_proto_model(&proto).unwrap();
+        let model = model
+            .into_typed()
+            .unwrap()
+            .concretize_dims(&SymbolValues::default().with(stream_symbol(), 6))
+            .unwrap();
+        let plan = SimplePlan::new(model.into_optimized().unwrap()).unwrap();
+        let x: Vec<f32> = (0..12).map(|i| i as f32).collect();
+        let input = tract_ndarray::Array2::from_shape_vec((6, 2), x).unwrap();
+        let output = plan.run(tvec!(input.clone().into_tensor())).unwrap();
+        // out[t] = b + W(-1).x[t-1] + W(0).x[t] + W(2).x[t+2], for t in 1..4
+        let expected: Vec<f32> = (1..4)
+            .map(|t| {
+                0.5 + 1. * input[(t - 1, 0)]
+                    + 2. * input[(t - 1, 1)]
+                    + 3. * input[(t, 0)]
+                    + 4. * input[(t, 1)]
+                    + 5. * input[(t + 2, 0)]
+                    + 6. * input[(t + 2, 1)]
+            })
+            .collect();
+        assert_eq!(*output[0], tensor1(&expected).into_shape(&[3, 1]).unwrap());
+    }
+}
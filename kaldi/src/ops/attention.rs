@@ -0,0 +1,282 @@
+use tract_hir::internal::*;
+use tract_hir::tract_core::ops::array::{Slice, TypedConcat};
+use tract_hir::tract_core::ops::change_axes::AxisOp;
+use tract_hir::tract_core::ops::math;
+use tract_hir::tract_core::ops::matmul::MatMul;
+
+use crate::model::ParsingContext;
+
+pub fn restricted_attention(ctx: &ParsingContext, name: &str) -> TractResult<Box<dyn InferenceOp>> {
+    let component = ctx.component_for_node(name)?;
+    let attr =
+        |n: &str| component.attributes.get(n).with_context(|| format!("missing attribute {}", n));
+    let int = |n: &str| -> TractResult<usize> { Ok(attr(n)?.cast_to_scalar::<i64>()? as usize) };
+    let key_dim = int("KeyDim")?;
+    let output_context = match component.attributes.get("OutputContext") {
+        Some(b) => *b.to_scalar::<bool>()?,
+        None => true,
+    };
+    let key_scale = match component.attributes.get("KeyScale") {
+        Some(s) => *s.to_scalar::<f32>()?,
+        None => 1.0 / (key_dim as f32).sqrt(),
+    };
+    Ok(expand(RestrictedAttention {
+        num_heads: int("NumHeads")?,
+        key_dim,
+        value_dim: int("ValueDim")?,
+        num_left_inputs: int("NumLeftInputs")?,
+        num_right_inputs: int("NumRightInputs")?,
+        time_stride: int("TimeStride")?,
+        output_context,
+        key_scale,
+    }))
+}
+
+/// Kaldi RestrictedAttentionComponent.
+///
+/// Each head reads a block of keys, values and queries from every frame. The
+/// queries are the key_dim query proper followed by one positional term per
+/// frame of the context window (the frames at `t + i * time_stride` for i
+/// from -num_left_inputs to num_right_inputs). Time is valid, like the
+/// Offset descriptors.
+#[derive(Clone, Debug, Educe)]
+#[educe(Hash)]
+struct RestrictedAttention {
+    num_heads: usize,
+    key_dim: usize,
+    value_dim: usize,
+    num_left_inputs: usize,
+    num_right_inputs: usize,
+    time_stride: usize,
+    output_context: bool,
+    #[educe(Hash(method = "hash_f32"))]
+    key_scale: f32,
+}
+
+impl_dyn_hash!(RestrictedAttention);
+
+impl RestrictedAttention {
+    fn context_dim(&self) -> usize {
+        self.num_left_inputs + 1 + self.num_right_inputs
+    }
+
+    fn input_dim_per_head(&self) -> usize {
+        2 * self.key_dim + self.value_dim + self.context_dim()
+    }
+
+    fn output_dim_per_head(&self) -> usize {
+        self.value_dim + if self.output_context { self.context_dim() } else { 0 }
+    }
+
+    fn time_context(&self) -> usize {
+        (self.context_dim() - 1) * self.time_stride
+    }
+
+    /// T•H•X -> T'•H•X for the frames of a context position, with an extra
+    /// axis for the matmuls.
+    fn part(
+        &self,
+        model: &mut TypedModel,
+        name: &str,
+        input: OutletId,
+        position: usize,
+        axis: usize,
+    ) -> TractResult<OutletId> {
+        let start = position * self.time_stride;
+        let frames = model.outlet_fact(input)?.shape[0].clone();
+        let end = frames - (self.time_context() - start);
+        let wire =
+            model.wire_node(format!("{}.frames", name), Slice::new(0, start, end), &[input])?;
+        Ok(model.wire_node(name, AxisOp::Add(axis), &wire)?[0])
+    }
+}
+
+impl Expansion for RestrictedAttention {
+    fn name(&self) -> std::borrow::Cow<str> {
+        "RestrictedAttention".into()
+    }
+
+    op_kaldi!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&outputs[0].rank, 2)?;
+        s.equals(&inputs[0].shape[1], (self.num_heads * self.input_dim_per_head()).to_dim())?;
+        s.equals(&outputs[0].shape[1], (self.num_heads * self.output_dim_per_head()).to_dim())?;
+        s.given(&inputs[0].shape[0], move |s, frames| {
+            s.equals(&outputs[0].shape[0], frames - self.time_context().to_dim())
+        })?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let (heads, kd, vd, cd) =
+            (self.num_heads, self.key_dim, self.value_dim, self.context_dim());
+        let input = model.wire_node(
+            format!("{}.heads", prefix),
+            AxisOp::Reshape(
+                1,
+                tvec!((heads * self.input_dim_per_head()).to_dim()),
+                tvec!(heads.to_dim(), self.input_dim_per_head().to_dim()),
+            ),
+            inputs,
+        )?[0];
+        // slice each block once, so that every slice has its own input
+        let mut block = |name: &str, dims: std::ops::Range<usize>| -> TractResult<OutletId> {
+            Ok(model.wire_node(
+                format!("{}.{}", prefix, name),
+                Slice::new(2, dims.start, dims.end),
+                &[input],
+            )?[0])
+        };
+        let key = block("key", 0..kd)?;
+        let value = block("value", kd..kd + vd)?;
+        let query = block("query", kd + vd..2 * kd + vd)?;
+        let position = block("position", 2 * kd + vd..2 * kd + vd + cd)?;
+        let mut keys = tvec!();
+        let mut values = tvec!();
+        for c in 0..cd {
+            let name = format!("{}.key-{}", prefix, c);
+            keys.push(self.part(model, &name, key, c, 3)?);
+            let name = format!("{}.value-{}", prefix, c);
+            values.push(self.part(model, &name, value, c, 2)?);
+        }
+        let center = self.num_left_inputs;
+        let name = format!("{}.query-{}", prefix, center);
+        let query = self.part(model, &name, query, center, 2)?;
+        let name = format!("{}.position-{}", prefix, center);
+        let position = self.part(model, &name, position, center, 2)?;
+
+        let keys =
+            model.wire_node(format!("{}.keys", prefix), TypedConcat::concat_vars(3, cd), &keys)?;
+        let values = model.wire_node(
+            format!("{}.values", prefix),
+            TypedConcat::concat_vars(2, cd),
+            &values,
+        )?;
+        // T•H•1•K x T•H•K•C -> T•H•1•C
+        let dots =
+            model.wire_node(format!("{}.dots", prefix), MatMul::default(), &[query, keys[0]])?;
+        let scaled = model.wire_node(
+            format!("{}.scaled", prefix),
+            math::mul::unary(tensor0(self.key_scale).into_shape(&[1, 1, 1, 1])?.into_arc_tensor()),
+            &dots,
+        )?;
+        let logits = model.wire_node(
+            format!("{}.logits", prefix),
+            math::add::bin_typed(),
+            &[scaled[0], position],
+        )?;
+        let weights = tract_hir::ops::nn::LayerSoftmax::new(3).wire(
+            &format!("{}.weights", prefix),
+            model,
+            &logits,
+        )?;
+        // T•H•1•C x T•H•C•V -> T•H•1•V
+        let mut output = model.wire_node(
+            format!("{}.output", prefix),
+            MatMul::default(),
+            &[weights[0], values[0]],
+        )?;
+        if self.output_context {
+            output = model.wire_node(
+                format!("{}.with_context", prefix),
+                TypedConcat::concat_vars(3, 2),
+                &[output[0], weights[0]],
+            )?;
+        }
+        let output = model.wire_node(format!("{}.rm_axis", prefix), AxisOp::Rm(2), &output)?;
+        model.wire_node(
+            prefix,
+            AxisOp::Reshape(
+                1,
+                tvec!(heads.to_dim(), self.output_dim_per_head().to_dim()),
+                tvec!((heads * self.output_dim_per_head()).to_dim()),
+            ),
+            &output,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::nnet3;
+    use tract_hir::prelude::*;
+    use tract_pulse::internal::*;
+
+    const MODEL: &str = r#"<Nnet3>
+input-node name=input dim=6
+component-node name=attention input=input component=attention1
+output-node name=output input=attention
+<NumComponents> 1
+<ComponentName> attention1 <RestrictedAttentionComponent> <NumHeads> 1 <KeyDim> 1 <ValueDim> 1 <NumLeftInputs> 1 <NumRightInputs> 1 <TimeStride> 1 <NumLeftInputsRequired> 1 <NumRightInputsRequired> 1 <OutputContext> T <KeyScale> 1 <StatsCount> 0 <EntropyStats> [ ]
+<PosteriorStats> [ ]
+</RestrictedAttentionComponent>
+</Nnet3>"#;
+
+    fn model() -> TypedModel {
+        let proto = nnet3(MODEL.as_bytes()).unwrap();
+        crate::kaldi().model_for_proto_model(&proto).unwrap().into_typed().unwrap()
+    }
+
+    fn run(model: TypedModel, input: Tensor) -> TVec<Arc<Tensor>> {
+        let frames = input.shape()[0] as i64;
+        let model =
+            model.concretize_dims(&SymbolValues::default().with(stream_symbol(), frames)).unwrap();
+        SimplePlan::new(model.into_optimized().unwrap()).unwrap().run(tvec!(input)).unwrap()
+    }
+
+    #[test]
+    fn attention_one_frame() {
+        // frames are [ key, value, query, positions... ]
+        let input = tensor2(&[
+            [1f32, 10., 0., 0., 0., 0.],
+            [0., 20., 1., 0., 0., 0.5],
+            [-1., 30., 0., 0., 0., 0.],
+        ]);
+        let output = run(model(), input);
+        let logits = [1f32, 0., -0.5];
+        let sum: f32 = logits.iter().map(|l| l.exp()).sum();
+        let w: Vec<f32> = logits.iter().map(|l| l.exp() / sum).collect();
+        let expected = tensor2(&[[10. * w[0] + 20. * w[1] + 30. * w[2], w[0], w[1], w[2]]]);
+        output[0].close_enough(&expected, true).unwrap();
+    }
+
+    #[test]
+    fn attention_pulse() {
+        let model = model();
+        let input = tensor1(&(0..42).map(|i| ((i * 7) % 11) as f32 / 5. - 1.).collect::<Vec<_>>())
+            .into_shape(&[7, 6])
+            .unwrap();
+        let expected = run(model.clone(), input.clone());
+
+        let pulsed = PulsedModel::new(&model.clone().declutter().unwrap(), 1).unwrap();
+        let delay = pulsed.output_fact(0).unwrap().delay;
+        assert_eq!(delay, 2);
+        let mut state =
+            SimpleState::new(SimplePlan::new(pulsed.into_typed().unwrap()).unwrap()).unwrap();
+        let mut found = vec![];
+        for t in 0..7 {
+            let frame = input.slice(0, t, t + 1).unwrap();
+            let output = state.run(tvec!(frame)).unwrap().remove(0);
+            if t >= delay {
+                found.push(output.into_tensor());
+            }
+        }
+        let found = Tensor::stack_tensors(0, &found).unwrap();
+        found.close_enough(&expected[0], true).unwrap();
+    }
+}
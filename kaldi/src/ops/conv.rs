@@ -0,0 +1,242 @@
+use tract_hir::internal::*;
+use tract_hir::tract_core::ops::change_axes::AxisOp;
+use tract_ndarray::s;
+
+use crate::model::ParsingContext;
+
+/// TimeHeightConvolutionComponent sees each frame as a height by filters
+/// image (filters varying fastest) and convolves the time-height plane at its
+/// (time, height) offsets. Time is valid, like the Offset descriptors, height
+/// is zero-padded to the HeightOut output rows.
+pub fn time_height_convolution(
+    ctx: &ParsingContext,
+    name: &str,
+) -> TractResult<Box<dyn InferenceOp>> {
+    let component = ctx.component_for_node(name)?;
+    let attr =
+        |n: &str| component.attributes.get(n).with_context(|| format!("missing attribute {}", n));
+    let int = |n: &str| -> TractResult<usize> { Ok(attr(n)?.cast_to_scalar::<i64>()? as usize) };
+    let filters_in = int("NumFiltersIn")?;
+    let filters_out = int("NumFiltersOut")?;
+    let height_in = int("HeightIn")?;
+    let height_out = int("HeightOut")?;
+    let subsample = int("HeightSubsampleOut")?;
+    let offsets: Vec<(i64, i64)> = attr("Offsets")?
+        .cast_to::<i64>()?
+        .as_slice::<i64>()?
+        .chunks(2)
+        .map(|pair| (pair[0], pair[1]))
+        .collect();
+    if offsets.is_empty() {
+        bail!("TimeHeightConvolutionComponent {} has no offsets", name);
+    }
+    let params = attr("LinearParams")?;
+    let bias = attr("BiasParams")?;
+    if params.shape() != [filters_out, offsets.len() * filters_in] {
+        bail!(
+            "Expected {}x{} LinearParams, got {:?}",
+            filters_out,
+            offsets.len() * filters_in,
+            params.shape()
+        );
+    }
+    let t0 = offsets.iter().map(|o| o.0).min().unwrap();
+    let t1 = offsets.iter().map(|o| o.0).max().unwrap();
+    let h0 = offsets.iter().map(|o| o.1).min().unwrap();
+    let h1 = offsets.iter().map(|o| o.1).max().unwrap();
+    let dt = offsets.iter().map(|o| o.0 - t0).fold(0, super::affine::gcd).max(1);
+    let dh = offsets.iter().map(|o| o.1 - h0).fold(0, super::affine::gcd).max(1);
+    let kernel_shape = tvec!(((t1 - t0) / dt) as usize + 1, ((h1 - h0) / dh) as usize + 1);
+
+    // linear params are filters_out by offsets * filters_in: scatter them as
+    // HWIO taps, with zeros where the offsets do not cover the kernel
+    let mut kernel = tract_ndarray::Array4::<f32>::zeros((
+        kernel_shape[0],
+        kernel_shape[1],
+        filters_in,
+        filters_out,
+    ));
+    let params = params.to_array_view::<f32>()?.into_dimensionality::<tract_ndarray::Ix2>()?;
+    for (ix, (t, h)) in offsets.iter().enumerate() {
+        let (t, h) = (((t - t0) / dt) as usize, ((h - h0) / dh) as usize);
+        kernel
+            .slice_mut(s![t, h, .., ..])
+            .assign(&params.slice(s![.., ix * filters_in..(ix + 1) * filters_in]).t());
+    }
+
+    let skip = h0.max(0) as usize;
+    let pad_before = (-h0).max(0) as usize;
+    if skip >= height_in {
+        bail!("Height offsets {}..={} do not overlap the {} input rows", h0, h1, height_in);
+    }
+    let kernel_height = (h1 - h0) as usize + 1;
+    let needed = (height_out - 1) * subsample + kernel_height;
+    let pad_after = needed.saturating_sub(height_in - skip + pad_before);
+    Ok(expand(TimeHeightConvolution {
+        height_in,
+        filters_in,
+        height_out,
+        filters_out,
+        skip,
+        pad_before,
+        pad_after,
+        subsample,
+        kernel_shape,
+        dilations: tvec!(dt as usize, dh as usize),
+        kernel: kernel.into_arc_tensor(),
+        bias: bias.clone(),
+    }))
+}
+
+#[derive(Clone, Debug, Hash)]
+struct TimeHeightConvolution {
+    height_in: usize,
+    filters_in: usize,
+    height_out: usize,
+    filters_out: usize,
+    skip: usize,
+    pad_before: usize,
+    pad_after: usize,
+    subsample: usize,
+    kernel_shape: TVec<usize>,
+    dilations: TVec<usize>,
+    kernel: Arc<Tensor>, // HWIO
+    bias: Arc<Tensor>,
+}
+
+impl_dyn_hash!(TimeHeightConvolution);
+
+impl TimeHeightConvolution {
+    fn time_context(&self) -> usize {
+        (self.kernel_shape[0] - 1) * self.dilations[0]
+    }
+}
+
+impl Expansion for TimeHeightConvolution {
+    fn name(&self) -> std::borrow::Cow<str> {
+        "TimeHeightConvolution".into()
+    }
+
+    op_kaldi!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, f32::datum_type())?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&outputs[0].rank, 2)?;
+        s.equals(&inputs[0].shape[1], (self.height_in * self.filters_in).to_dim())?;
+        s.equals(&outputs[0].shape[1], (self.height_out * self.filters_out).to_dim())?;
+        s.given(&inputs[0].shape[0], move |s, frames| {
+            s.equals(&outputs[0].shape[0], frames - self.time_context().to_dim())
+        })?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        use tract_hir::ops::cnn::*;
+        use tract_hir::ops::nn::*;
+        use tract_hir::tract_core::ops::array::Slice;
+        use tract_hir::tract_core::ops::cnn::KernelFormat;
+        let mut wire = model.wire_node(
+            format!("{}.frames_as_images", prefix),
+            AxisOp::Reshape(
+                1,
+                tvec!((self.height_in * self.filters_in).to_dim()),
+                tvec!(self.height_in.to_dim(), self.filters_in.to_dim()),
+            ),
+            inputs,
+        )?;
+        if self.skip > 0 {
+            wire = model.wire_node(
+                format!("{}.skip_rows", prefix),
+                Slice::new(1, self.skip, self.height_in),
+                &wire,
+            )?;
+        }
+        wire = model.wire_node(
+            format!("{}.conv", prefix),
+            ConvUnary {
+                pool_spec: PoolSpec::new(
+                    DataFormat::HWC,
+                    self.kernel_shape.clone(),
+                    PaddingSpec::Explicit(
+                        tvec!(0, self.pad_before),
+                        tvec!(0, self.pad_after),
+                        false,
+                    ),
+                    Some(self.dilations.clone()),
+                    Some(tvec!(1, self.subsample)),
+                    Some(self.filters_out),
+                ),
+                kernel_fmt: KernelFormat::HWIO,
+                kernel: self.kernel.clone(),
+                group: 1,
+                bias: Some(self.bias.clone()),
+                q_params: None,
+            },
+            &wire,
+        )?;
+        let height = model.outlet_fact(wire[0])?.shape[1].to_usize()?;
+        if height > self.height_out {
+            wire = model.wire_node(
+                format!("{}.crop_rows", prefix),
+                Slice::new(1, 0, self.height_out),
+                &wire,
+            )?;
+        }
+        model.wire_node(
+            prefix,
+            AxisOp::Reshape(
+                1,
+                tvec!(self.height_out.to_dim(), self.filters_out.to_dim()),
+                tvec!((self.height_out * self.filters_out).to_dim()),
+            ),
+            &wire,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::nnet3;
+    use tract_hir::prelude::*;
+    use tract_pulse::internal::*;
+
+    #[test]
+    fn time_height_convolution_cross() {
+        let slice = r#"<Nnet3>
+input-node name=input dim=3
+component-node name=conv input=input component=conv1
+output-node name=output input=conv
+<NumComponents> 1
+<ComponentName> conv1 <TimeHeightConvolutionComponent> <MaxChange> 0.75 <LearningRate> 0.001 <Model> <ConvolutionModel> <NumFiltersIn> 1 <NumFiltersOut> 1 <HeightIn> 3 <HeightOut> 3 <HeightSubsampleOut> 1 <Offsets> [ -1,0 0,-1 0,1 1,0 ] <RequiredTimeOffsets> [ -1 0 1 ] </ConvolutionModel> <LinearParams> [
+  1 2 3 4 ]
+<BiasParams> [ 0.5 ]
+<MaxMemoryMb> 200 <UseNaturalGradient> T <NumMinibatchesHistory> 4 <AlphaInOut> 4 4 <RankInOut> 20 80 </TimeHeightConvolutionComponent>
+</Nnet3>"#;
+        let proto = nnet3(slice.as_bytes()).unwrap();
+        let model = crate::kaldi().model_for_proto_model(&proto).unwrap();
+        let model = model
+            .into_typed()
+            .unwrap()
+            .concretize_dims(&SymbolValues::default().with(stream_symbol(), 3))
+            .unwrap();
+        let plan = SimplePlan::new(model.into_optimized().unwrap()).unwrap();
+        let input = tensor2(&[[1f32, 2., 3.], [4., 5., 6.], [7., 8., 9.]]);
+        let output = plan.run(tvec!(input)).unwrap();
+        // height h at t=1: x[0][h] + 2 * x[1][h-1] + 3 * x[1][h+1] + 4 * x[2][h] + 0.5
+        assert_eq!(*output[0], tensor2(&[[44.5f32, 60.5, 49.5]]));
+    }
+}
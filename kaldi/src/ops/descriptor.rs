@@ -0,0 +1,224 @@
+use tract_hir::internal::*;
+use tract_ndarray::Axis;
+
+/// Repeats a single row (first input) as many times as there are frames in
+/// the second input.
+#[derive(Clone, Debug, Hash)]
+pub struct RowBroadcast;
+
+impl_dyn_hash!(RowBroadcast);
+
+impl Op for RowBroadcast {
+    fn name(&self) -> Cow<str> {
+        "RowBroadcast".into()
+    }
+
+    op_kaldi!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for RowBroadcast {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (row, reference) = args_2!(inputs);
+        let shape = [reference.shape()[0], row.shape()[1]];
+        use tract_hir::tract_core::ops::array::MultiBroadcastTo;
+        dispatch_datum!(MultiBroadcastTo::eval_t(row.datum_type())(&*row, &shape))
+    }
+}
+
+impl InferenceRulesOp for RowBroadcast {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&inputs[1].rank, 2)?;
+        s.equals(&outputs[0].rank, 2)?;
+        s.equals(&inputs[0].shape[0], 1.to_dim())?;
+        s.equals(&inputs[0].shape[1], &outputs[0].shape[1])?;
+        s.equals(&inputs[1].shape[0], &outputs[0].shape[0])?;
+        Ok(())
+    }
+
+    as_op!();
+    to_typed!();
+}
+
+impl TypedOp for RowBroadcast {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(TypedFact::dt_shape(
+            inputs[0].datum_type,
+            &[inputs[1].shape[0].clone(), inputs[0].shape[1].clone()]
+        )))
+    }
+
+    as_op!();
+}
+
+/// Kaldi Round descriptor: frame t reads the input at `t - t % modulus`.
+#[derive(Clone, Debug, new, Hash)]
+pub struct Round {
+    pub modulus: usize,
+}
+
+impl_dyn_hash!(Round);
+
+impl Op for Round {
+    fn name(&self) -> Cow<str> {
+        "Round".into()
+    }
+
+    op_kaldi!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Round {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let frames: Vec<usize> =
+            (0..input.shape()[0]).map(|t| t - t % self.modulus).collect::<Vec<_>>();
+        fn eval_t<T: Datum>(input: &Tensor, frames: &[usize]) -> TractResult<Tensor> {
+            Ok(input.to_array_view::<T>()?.select(Axis(0), frames).into_tensor())
+        }
+        let output = dispatch_datum!(eval_t(input.datum_type())(&*input, &*frames))?;
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for Round {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        Ok(())
+    }
+
+    as_op!();
+    to_typed!();
+}
+
+impl TypedOp for Round {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(inputs[0].clone()))
+    }
+
+    as_op!();
+}
+
+/// Kaldi Switch descriptor: frame t is read from input `t % n`.
+#[derive(Clone, Debug, Hash)]
+pub struct Switch;
+
+impl_dyn_hash!(Switch);
+
+impl Op for Switch {
+    fn name(&self) -> Cow<str> {
+        "Switch".into()
+    }
+
+    op_kaldi!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Switch {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        fn eval_t<T: Datum>(inputs: &[Arc<Tensor>]) -> TractResult<Tensor> {
+            let views =
+                inputs.iter().map(|i| i.to_array_view::<T>()).collect::<TractResult<Vec<_>>>()?;
+            let mut output = views[0].to_owned();
+            for (t, mut frame) in output.axis_iter_mut(Axis(0)).enumerate() {
+                frame.assign(&views[t % views.len()].index_axis(Axis(0), t));
+            }
+            Ok(output.into_tensor())
+        }
+        let output = dispatch_datum!(eval_t(inputs[0].datum_type())(&*inputs))?;
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for Switch {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_output_arity(&outputs, 1)?;
+        for input in inputs {
+            s.equals(&input.datum_type, &outputs[0].datum_type)?;
+            s.equals(&input.shape, &outputs[0].shape)?;
+        }
+        Ok(())
+    }
+
+    as_op!();
+    to_typed!();
+}
+
+impl TypedOp for Switch {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if inputs.iter().any(|i| i.shape != inputs[0].shape) {
+            bail!("Switch inputs must all have the same shape: {:?}", inputs)
+        }
+        Ok(tvec!(inputs[0].clone()))
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::nnet3;
+    use tract_hir::prelude::*;
+    use tract_pulse::internal::*;
+
+    #[test]
+    fn descriptor_expressions() {
+        let slice = r#"<Nnet3>
+input-node name=input dim=2
+dim-range-node name=sum input-node=Sum(Scale(2, input), Const(1, 2)) dim=2 dim-offset=0
+output-node name=output input=Append(Failover(sum, input), Const(-1, 1), ReplaceIndex(input, t, 0), Round(input, 2), Switch(input, sum))
+<NumComponents> 0
+</Nnet3>"#;
+        let proto = nnet3(slice.as_bytes()).unwrap();
+        let model = crate::kaldi().model_for_proto_model(&proto).unwrap();
+        let model = model
+            .into_typed()
+            .unwrap()
+            .concretize_dims(&SymbolValues::default().with(stream_symbol(), 3))
+            .unwrap();
+        let plan = SimplePlan::new(model.into_optimized().unwrap()).unwrap();
+        let output = plan.run(tvec!(tensor2(&[[1f32, 2.], [3., 4.], [5., 6.]]))).unwrap();
+        assert_eq!(
+            *output[0],
+            tensor2(&[
+                [3f32, 5., -1., 1., 2., 1., 2., 1., 2.],
+                [7., 9., -1., 1., 2., 1., 2., 7., 9.],
+                [11., 13., -1., 1., 2., 5., 6., 5., 6.],
+            ])
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use tract_hir::internal::*;
+
+use crate::model::ParsingContext;
+
+pub fn batch_norm(ctx: &ParsingContext, name: &str) -> TractResult<Box<dyn InferenceOp>> {
+    let component = ctx.component_for_node(name)?;
+    let attr =
+        |n: &str| component.attributes.get(n).with_context(|| format!("missing attribute {}", n));
+    if !*attr("TestMode")?.to_scalar::<bool>()? {
+        bail!("BatchNormComponent {} is not in test mode", name);
+    }
+    let dim = attr("Dim")?.cast_to_scalar::<i64>()? as usize;
+    let epsilon = *attr("Epsilon")?.to_scalar::<f32>()?;
+    let target_rms = *attr("TargetRms")?.to_scalar::<f32>()?;
+    let mean = attr("StatsMean")?.to_array_view::<f32>()?.to_owned();
+    let var = attr("StatsVar")?.to_array_view::<f32>()?.to_owned();
+    // y = (x - mean) * scale, with scale = target_rms / sqrt(var + epsilon)
+    let scales = var.mapv(|v| target_rms / (v + epsilon).sqrt());
+    let offsets = -&mean * &scales;
+    Ok(expand(ScaleAndOffset::new(
+        tile(scales.into_tensor(), dim)?.into_arc_tensor(),
+        tile(offsets.into_tensor(), dim)?.into_arc_tensor(),
+    )))
+}
+
+pub fn scale_and_offset(ctx: &ParsingContext, name: &str) -> TractResult<Box<dyn InferenceOp>> {
+    let component = ctx.component_for_node(name)?;
+    let attr =
+        |n: &str| component.attributes.get(n).with_context(|| format!("missing attribute {}", n));
+    let dim = attr("Dim")?.cast_to_scalar::<i64>()? as usize;
+    Ok(expand(ScaleAndOffset::new(
+        tile(attr("Scales")?.as_ref().clone(), dim)?.into_arc_tensor(),
+        tile(attr("Offsets")?.as_ref().clone(), dim)?.into_arc_tensor(),
+    )))
+}
+
+/// Kaldi block parameters (BlockDim) apply to each block of the feature dim.
+fn tile(block: Tensor, dim: usize) -> TractResult<Tensor> {
+    let block_dim = block.len();
+    if block_dim == 0 || dim % block_dim != 0 {
+        bail!("Dim {} is not a multiple of the block dim {}", dim, block_dim);
+    }
+    let blocks = tvec!(block; dim / block_dim);
+    Tensor::stack_tensors(0, &blocks)
+}
+
+/// Per-feature affine transform: `y = x * scales + offsets`.
+#[derive(Clone, Debug, new, Hash)]
+struct ScaleAndOffset {
+    scales: Arc<Tensor>,
+    offsets: Arc<Tensor>,
+}
+
+impl_dyn_hash!(ScaleAndOffset);
+
+impl Expansion for ScaleAndOffset {
+    fn name(&self) -> std::borrow::Cow<str> {
+        "ScaleAndOffset".into()
+    }
+
+    op_kaldi!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        s.equals(&inputs[0].shape[1], self.scales.len().to_dim())?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let scales = self.scales.clone().into_tensor().into_shape(&[1, self.scales.len()])?;
+        let offsets = self.offsets.clone().into_tensor().into_shape(&[1, self.offsets.len()])?;
+        let scaled = model.wire_node(
+            prefix.to_string() + ".scale",
+            tract_hir::ops::math::mul::unary(scales.into_arc_tensor()),
+            inputs,
+        )?;
+        model.wire_node(
+            prefix,
+            tract_hir::ops::math::add::unary(offsets.into_arc_tensor()),
+            &scaled,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::nnet3;
+    use tract_hir::prelude::*;
+    use tract_pulse::internal::*;
+
+    #[test]
+    fn batch_norm_blocks_and_scale_offset() {
+        let slice = r#"<Nnet3>
+input-node name=input dim=4
+component-node name=bn input=input component=bn1
+component-node name=so input=bn component=so1
+output-node name=output input=so
+<NumComponents> 2
+<ComponentName> bn1 <BatchNormComponent> <Dim> 4 <BlockDim> 2 <Epsilon> 0 <TargetRms> 2 <TestMode> T <Count> 10 <StatsMean> [ 1 -1 ] <StatsVar> [ 4 1 ] </BatchNormComponent>
+<ComponentName> so1 <ScaleAndOffsetComponent> <LearningRate> 0.001 <Dim> 4 <Scales> [ 1 2 3 4 ] <Offsets> [ 0 0 0 1 ] <UseNaturalGradient> T <Rank> 20 </ScaleAndOffsetComponent>
+</Nnet3>"#;
+        let proto = nnet3(slice.as_bytes()).unwrap();
+        let model = crate::kaldi().model_for_proto_model(&proto).unwrap();
+        let model = model
+            .into_typed()
+            .unwrap()
+            .concretize_dims(&SymbolValues::default().with(stream_symbol(), 1))
+            .unwrap();
+        let plan = SimplePlan::new(model.into_optimized().unwrap()).unwrap();
+        let output = plan.run(tvec!(tensor2(&[[3f32, 0., 1., 1.]]))).unwrap();
+        // batch norm: [(3-1)*2/2, (0+1)*2/1, (1-1)*2/2, (1+1)*2/1] = [2, 2, 0, 4]
+        assert_eq!(*output[0], tensor2(&[[2f32, 4., 0., 17.]]));
+    }
+}
@@ -0,0 +1,89 @@
+use tract_hir::internal::*;
+use tract_hir::tract_core::ops::change_axes::AxisOp;
+
+use crate::model::ParsingContext;
+
+pub fn sum_block(ctx: &ParsingContext, name: &str) -> TractResult<Box<dyn InferenceOp>> {
+    let component = ctx.component_for_node(name)?;
+    let attr =
+        |n: &str| component.attributes.get(n).with_context(|| format!("missing attribute {}", n));
+    let input_dim = attr("InputDim")?.cast_to_scalar::<i64>()? as usize;
+    let output_dim = attr("OutputDim")?.cast_to_scalar::<i64>()? as usize;
+    if output_dim == 0 || input_dim % output_dim != 0 {
+        bail!("InputDim {} is not a multiple of OutputDim {}", input_dim, output_dim);
+    }
+    let scale = match component.attributes.get("Scale") {
+        Some(scale) => *scale.to_scalar::<f32>()?,
+        None => 1.0,
+    };
+    Ok(expand(SumBlock { blocks: input_dim / output_dim, output_dim, scale }))
+}
+
+/// Sum of the consecutive output_dim-sized blocks of the input, scaled.
+#[derive(Clone, Debug, Educe)]
+#[educe(Hash)]
+struct SumBlock {
+    blocks: usize,
+    output_dim: usize,
+    #[educe(Hash(method = "hash_f32"))]
+    scale: f32,
+}
+
+impl_dyn_hash!(SumBlock);
+
+impl Expansion for SumBlock {
+    fn name(&self) -> std::borrow::Cow<str> {
+        "SumBlock".into()
+    }
+
+    op_kaldi!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&outputs[0].rank, 2)?;
+        s.equals(&inputs[0].shape[0], &outputs[0].shape[0])?;
+        s.equals(&inputs[0].shape[1], (self.blocks * self.output_dim).to_dim())?;
+        s.equals(&outputs[0].shape[1], self.output_dim.to_dim())?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        use tract_hir::tract_core::ops::nn::{Reduce, Reducer};
+        let blocks = model.wire_node(
+            format!("{}.blocks", prefix),
+            AxisOp::Reshape(
+                1,
+                tvec!((self.blocks * self.output_dim).to_dim()),
+                tvec!(self.blocks.to_dim(), self.output_dim.to_dim()),
+            ),
+            inputs,
+        )?;
+        let sum = model.wire_node(
+            format!("{}.sum", prefix),
+            Reduce::new(tvec!(1), Reducer::Sum),
+            &blocks,
+        )?;
+        let mut wire = model.wire_node(format!("{}.rm_axis", prefix), AxisOp::Rm(1), &sum)?;
+        if self.scale != 1.0 {
+            wire = model.wire_node(
+                format!("{}.scale", prefix),
+                tract_hir::ops::math::mul::unary(rctensor2(&[[self.scale]])),
+                &wire,
+            )?;
+        }
+        Ok(wire)
+    }
+}
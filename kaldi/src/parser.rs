@@ -12,10 +12,14 @@ use nom::{
 
 use std::collections::HashMap;
 
-use crate::model::{Component, KaldiProtoModel};
+use crate::model::{
+    Component, ComponentNode, ConfigLines, GeneralDescriptor, KaldiProtoModel, NodeLine,
+    TransitionModel,
+};
 
 use tract_itertools::Itertools;
 
+mod am;
 mod bin;
 mod components;
 mod config_lines;
@@ -23,15 +27,25 @@ mod descriptor;
 mod text;
 
 pub fn nnet3(slice: &[u8]) -> TractResult<KaldiProtoModel> {
-    let (_, (config, components)) = parse_top_level(slice).map_err(|e| match e {
-        nom::Err::Error(err) => format_err!(
-            "Parsing kaldi enveloppe at: {:?}",
-            err.input.iter().take(120).map(|b| format!("{}", *b as char)).join("")
-        ),
-        e => format_err!("{:?}", e),
-    })?;
-    let config_lines = config_lines::parse_config(config)?;
-    Ok(KaldiProtoModel { config_lines, components, adjust_final_offset: 0 })
+    let (_, (transition_model, config, components, priors)) =
+        parse_top_level(slice).map_err(|e| match e {
+            nom::Err::Error(err) => format_err!(
+                "Parsing kaldi enveloppe at: {:?}",
+                err.input.iter().take(120).map(|b| format!("{}", *b as char)).join("")
+            ),
+            e => format_err!("{:?}", e),
+        })?;
+    let mut components = components;
+    let mut config_lines = config_lines::parse_config(config)?;
+    expand_composite_components(&mut config_lines, &mut components);
+    Ok(KaldiProtoModel {
+        config_lines,
+        components,
+        adjust_final_offset: 0,
+        transition_model,
+        priors: priors.map(|p| p.into_arc_tensor()),
+        subtract_log_priors: false,
+    })
 }
 
 pub fn if_then_else<'a, T>(
@@ -42,8 +56,13 @@ pub fn if_then_else<'a, T>(
     map(pair(cond(condition, then), cond(!condition, otherwise)), |(a, b)| a.or(b).unwrap())
 }
 
-fn parse_top_level(i: &[u8]) -> IResult<&[u8], (&str, HashMap<String, Component>)> {
+type TopLevel<'a> = (Option<TransitionModel>, &'a str, HashMap<String, Component>, Option<Tensor>);
+
+/// A bare Nnet3, or an acoustic model (final.mdl): a transition model, the
+/// Nnet3 and the priors.
+fn parse_top_level(i: &[u8]) -> IResult<&[u8], TopLevel<'_>> {
     let (i, bin) = map(opt(tag([0, 0x42])), |o| Option::is_some(&o))(i)?;
+    let (i, transition_model) = opt(|i| am::transition_model(bin, i))(i)?;
     let (i, _) = open(i, "Nnet3")?;
     let (i, config_lines) = map_res(take_until("<NumComponents>"), std::str::from_utf8)(i)?;
     let (i, num_components) = num_components(bin, i)?;
@@ -57,7 +76,9 @@ fn parse_top_level(i: &[u8]) -> IResult<&[u8], (&str, HashMap<String, Component>
         components.insert(name.to_owned(), comp);
     }
     let (i, _) = close(i, "Nnet3")?;
-    Ok((i, (config_lines, components)))
+    let (i, priors) =
+        if transition_model.is_some() { am::am_nnet_simple(bin, i)? } else { (i, None) };
+    Ok((i, (transition_model, config_lines, components, priors)))
 }
 
 fn num_components(bin: bool, i: &[u8]) -> IResult<&[u8], usize> {
@@ -69,12 +90,62 @@ fn num_components(bin: bool, i: &[u8]) -> IResult<&[u8], usize> {
 fn component(bin: bool) -> impl Fn(&[u8]) -> IResult<&[u8], Component> {
     move |i: &[u8]| {
         let (i, klass) = open_any(i)?;
-        let (i, attributes) = if bin { bin::attributes(i, klass)? } else { text::attributes(i)? };
+        let (i, attributes) =
+            if bin { bin::attributes(i, klass)? } else { text::attributes(i, klass)? };
+        let (i, components) = if klass == "CompositeComponent" {
+            let n = attributes
+                .get("NumComponents")
+                .and_then(|n| n.cast_to_scalar::<i64>().ok())
+                .unwrap_or(0);
+            nom::multi::count(component(bin), n as usize)(i)?
+        } else {
+            (i, vec![])
+        };
         let (i, _) = close(i, klass)?;
-        Ok((i, Component { klass: klass.to_string(), attributes }))
+        Ok((i, Component { klass: klass.to_string(), attributes, components }))
     }
 }
 
+/// CompositeComponent chains its components: each composite component node
+/// is replaced by one component node per sub-component, the last one keeping
+/// the node name.
+fn expand_composite_components(
+    config_lines: &mut ConfigLines,
+    components: &mut HashMap<String, Component>,
+) {
+    let mut nodes = vec![];
+    for (name, node) in config_lines.nodes.drain(..) {
+        let composite = match &node {
+            NodeLine::Component(line) => components
+                .get(&line.component)
+                .filter(|c| c.klass == "CompositeComponent" && !c.components.is_empty())
+                .cloned(),
+            _ => None,
+        };
+        let (line, composite) = match (node, composite) {
+            (NodeLine::Component(line), Some(composite)) => (line, composite),
+            (node, _) => {
+                nodes.push((name, node));
+                continue;
+            }
+        };
+        let mut input = line.input;
+        for (ix, sub) in composite.components.iter().enumerate() {
+            let component = format!("{}.{}", line.component, ix);
+            components.entry(component.clone()).or_insert_with(|| sub.clone());
+            let node_name = if ix + 1 == composite.components.len() {
+                name.clone()
+            } else {
+                format!("{}.{}", name, ix)
+            };
+            nodes
+                .push((node_name.clone(), NodeLine::Component(ComponentNode { input, component })));
+            input = GeneralDescriptor::Name(node_name);
+        }
+    }
+    config_lines.nodes = nodes;
+}
+
 fn component_name(i: &[u8]) -> IResult<&[u8], &str> {
     multispaced(delimited(|i| open(i, "ComponentName"), name, multispace0))(i)
 }
@@ -91,6 +162,19 @@ pub fn open_any(i: &[u8]) -> IResult<&[u8], &str> {
     multispaced(delimited(tag("<"), name, tag(">")))(i)
 }
 
+/// An attribute tag: the tags of sub-components (in CompositeComponent) end
+/// the attribute list.
+pub fn attribute_name(i: &[u8]) -> IResult<&[u8], &str> {
+    verify(open_any, |n: &str| !n.ends_with("Component"))(i)
+}
+
+/// Closing tag of a model nested in a component (like the ConvolutionModel of
+/// TimeHeightConvolutionComponent), whose attributes are flattened into the
+/// component ones.
+pub fn close_nested<'a>(i: &'a [u8], klass: &str) -> IResult<&'a [u8], &'a str> {
+    verify(multispaced(delimited(tag("</"), name, tag(">"))), |n: &str| n != klass)(i)
+}
+
 pub fn name(i: &[u8]) -> IResult<&[u8], &str> {
     map_res(
         recognize(pair(
@@ -0,0 +1,177 @@
+use tract_hir::internal::*;
+
+use nom::IResult;
+use nom::{branch::alt, bytes::complete::*, combinator::*, multi::count};
+
+use super::components::KaldiAttributeKind;
+use super::{close, integer, multispaced, open};
+use crate::model::{TransitionModel, TransitionTuple};
+
+/// TransitionModel written ahead of the network in acoustic models. The
+/// topology is skipped.
+pub fn transition_model(bin: bool, i: &[u8]) -> IResult<&[u8], TransitionModel> {
+    let (i, _) = open(i, "TransitionModel")?;
+    let (i, _) = open(i, "Topology")?;
+    let (i, _) = take_until("</Topology>")(i)?;
+    let (i, _) = close(i, "Topology")?;
+    let (i, hmm) =
+        alt((map(|i| open(i, "Triples"), |_| true), map(|i| open(i, "Tuples"), |_| false)))(i)?;
+    // each tuple takes several bytes, so a count beyond the input is corrupt
+    let (i, len) =
+        verify(multispaced(integer(bin)), |&len: &i32| len >= 0 && len as usize <= i.len())(i)?;
+    let (i, tuples) = count(transition_tuple(bin, hmm), len as usize)(i)?;
+    let (i, _) = close(i, if hmm { "Triples" } else { "Tuples" })?;
+    let (i, _) = open(i, "LogProbs")?;
+    let (i, log_probs) = float_vector(bin, i)?;
+    let (i, _) = close(i, "LogProbs")?;
+    let (i, _) = close(i, "TransitionModel")?;
+    Ok((i, TransitionModel { tuples, log_probs: log_probs.into_arc_tensor() }))
+}
+
+fn transition_tuple<'a>(
+    bin: bool,
+    hmm: bool,
+) -> impl FnMut(&'a [u8]) -> IResult<&'a [u8], TransitionTuple> {
+    move |i: &[u8]| {
+        let (i, phone) = multispaced(integer(bin))(i)?;
+        let (i, hmm_state) = multispaced(integer(bin))(i)?;
+        let (i, forward_pdf) = multispaced(integer(bin))(i)?;
+        let (i, self_loop_pdf) = if hmm { (i, forward_pdf) } else { multispaced(integer(bin))(i)? };
+        Ok((i, TransitionTuple { phone, hmm_state, forward_pdf, self_loop_pdf }))
+    }
+}
+
+/// What AmNnetSimple writes after the network: its context (which we do not
+/// need) and the priors of the outputs, empty if the model has none.
+pub fn am_nnet_simple(bin: bool, i: &[u8]) -> IResult<&[u8], Option<Tensor>> {
+    let (i, _) = open(i, "LeftContext")?;
+    let (i, _) = multispaced(integer(bin))(i)?;
+    let (i, _) = open(i, "RightContext")?;
+    let (i, _) = multispaced(integer(bin))(i)?;
+    let (i, _) = open(i, "Priors")?;
+    let (i, priors) = float_vector(bin, i)?;
+    Ok((i, Some(priors).filter(|p| p.len() > 0)))
+}
+
+fn float_vector(bin: bool, i: &[u8]) -> IResult<&[u8], Tensor> {
+    if bin {
+        KaldiAttributeKind::FloatVector.parse_bin(i)
+    } else {
+        multispaced(super::text::vector)(i)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::nnet3;
+    use tract_hir::prelude::*;
+    use tract_pulse::internal::*;
+
+    const NNET: &str = "<Nnet3>
+input-node name=input dim=2
+output-node name=output input=input
+<NumComponents> 0
+</Nnet3>
+";
+
+    #[test]
+    fn text_final_mdl() {
+        let mdl = format!(
+            "<TransitionModel>
+<Topology>
+<TopologyEntry>
+<ForPhones>
+1 2
+</ForPhones>
+<State> 0 <PdfClass> 0 <Transition> 0 0.5 <Transition> 1 0.5 </State>
+<State> 1 </State>
+</TopologyEntry>
+</Topology>
+<Triples> 2
+1 0 0
+2 0 1
+</Triples>
+<LogProbs>
+ [ 0 -0.6931472 -0.6931472 -0.6931472 -0.6931472 ]
+</LogProbs>
+</TransitionModel>
+{}<LeftContext> 0 <RightContext> 0
+<Priors>  [ 0.25 0.75 ]
+",
+            NNET
+        );
+        let proto = nnet3(mdl.as_bytes()).unwrap();
+        let tm = proto.transition_model.as_ref().unwrap();
+        assert_eq!(tm.tuples.len(), 2);
+        assert_eq!(tm.tuples[1].forward_pdf, 1);
+        assert_eq!(tm.tuples[1].self_loop_pdf, 1);
+        assert_eq!(tm.num_pdfs(), 2);
+        assert_eq!(tm.log_probs.len(), 5);
+        assert_eq!(**proto.priors.as_ref().unwrap(), tensor1(&[0.25f32, 0.75]));
+
+        let mut proto = proto;
+        proto.subtract_log_priors = true;
+        let model = crate::kaldi().model_for_proto_model(&proto).unwrap();
+        let model = model
+            .into_typed()
+            .unwrap()
+            .concretize_dims(&SymbolValues::default().with(stream_symbol(), 1))
+            .unwrap();
+        let plan = SimplePlan::new(model.into_optimized().unwrap()).unwrap();
+        let output = plan.run(tvec!(tensor2(&[[0f32, 1.]]))).unwrap();
+        let expected = tensor2(&[[-0.25f32.ln(), 1. - 0.75f32.ln()]]);
+        output[0].close_enough(&expected, true).unwrap();
+    }
+
+    #[test]
+    fn transition_model_bad_count() {
+        let tm = |len: &str| {
+            let mdl = format!(
+                "<TransitionModel> <Topology> </Topology> <Triples> {} 1 0 0 </Triples> <LogProbs> [ 0 ] </LogProbs> </TransitionModel>",
+                len
+            );
+            super::transition_model(false, mdl.as_bytes()).is_ok()
+        };
+        assert!(tm("1"));
+        assert!(!tm("-1"));
+        assert!(!tm("2000000000"));
+    }
+
+    #[test]
+    fn bin_final_mdl() {
+        fn int(bytes: &mut Vec<u8>, i: i32) {
+            bytes.push(4);
+            bytes.extend_from_slice(&i.to_le_bytes());
+        }
+        fn floats(bytes: &mut Vec<u8>, fs: &[f32]) {
+            bytes.extend_from_slice(b"FV ");
+            int(bytes, fs.len() as i32);
+            fs.iter().for_each(|f| bytes.extend_from_slice(&f.to_le_bytes()));
+        }
+        let mut mdl = b"\x00B<TransitionModel> <Topology> ".to_vec();
+        int(&mut mdl, 1); // phones and the rest of the topology, skipped
+        mdl.extend_from_slice(b"</Topology> <Tuples> ");
+        int(&mut mdl, 1);
+        for i in &[1, 0, 0, 3] {
+            int(&mut mdl, *i);
+        }
+        mdl.extend_from_slice(b"</Tuples> <LogProbs> ");
+        floats(&mut mdl, &[0., -0.5]);
+        mdl.extend_from_slice(b"</LogProbs> </TransitionModel> <Nnet3> ");
+        mdl.extend_from_slice(&NNET.as_bytes()[8..NNET.find("<NumComponents>").unwrap()]);
+        mdl.extend_from_slice(b"<NumComponents> ");
+        int(&mut mdl, 0);
+        mdl.extend_from_slice(b"</Nnet3> <LeftContext> ");
+        int(&mut mdl, 0);
+        mdl.extend_from_slice(b"<RightContext> ");
+        int(&mut mdl, 0);
+        mdl.extend_from_slice(b"<Priors> ");
+        floats(&mut mdl, &[]);
+        let proto = nnet3(&mdl).unwrap();
+        let tm = proto.transition_model.unwrap();
+        assert_eq!(tm.tuples.len(), 1);
+        assert_eq!(tm.tuples[0].self_loop_pdf, 3);
+        assert_eq!(*tm.log_probs, tensor1(&[0f32, -0.5]));
+        assert!(proto.priors.is_none());
+    }
+}
@@ -6,11 +6,21 @@ use nom::IResult;
 use super::components::COMPONENTS;
 
 pub fn attributes<'a>(i: &'a [u8], klass: &str) -> IResult<&'a [u8], HashMap<String, Arc<Tensor>>> {
-    map(nom::multi::many0(|j| attribute(j, klass)), |v| v.into_iter().collect())(i)
+    map(
+        nom::multi::many0(nom::branch::alt((
+            map(|j| super::close_nested(j, klass), |_| None),
+            map(|j| attribute(j, klass), Some),
+        ))),
+        |v| v.into_iter().flatten().collect(),
+    )(i)
 }
 
 fn attribute<'a>(i: &'a [u8], klass: &str) -> IResult<&'a [u8], (String, Arc<Tensor>)> {
-    let (i, name) = super::open_any(i)?;
-    let (i, value) = COMPONENTS[klass][name].parse_bin(i)?;
+    let (i, name) = super::attribute_name(i)?;
+    let kind = COMPONENTS
+        .get(klass)
+        .and_then(|attributes| attributes.get(name))
+        .ok_or_else(|| nom::Err::Error(nom::error::make_error(i, nom::error::ErrorKind::Tag)))?;
+    let (i, value) = kind.parse_bin(i)?;
     Ok((i, (name.to_string(), value.into_arc_tensor())))
 }
@@ -10,15 +10,20 @@ use nom::{
     bytes::complete::*,
     combinator::*,
     multi::many_m_n,
-    number::complete::{le_f32, le_f64},
+    number::complete::{le_f32, le_f64, le_i32},
     sequence::*,
     IResult,
 };
 
 pub enum KaldiAttributeKind {
     Bool,
+    Flag,
     Int,
+    IntPair,
     Float,
+    FloatPair,
+    IntVector,
+    IntPairVector,
     FloatVector,
     FloatMatrix,
 }
@@ -30,8 +35,17 @@ impl KaldiAttributeKind {
                 map(tag("F"), |_| Tensor::from(false)),
                 map(tag("T"), |_| Tensor::from(true)),
             ))(i),
+            Flag => Ok((i, Tensor::from(true))),
             Int => map(super::integer(true), Tensor::from)(i),
+            IntPair => {
+                map(pair(super::integer(true), super::integer(true)), |(a, b)| tensor1(&[a, b]))(i)
+            }
             Float => map(Self::parse_float_value, Tensor::from)(i),
+            FloatPair => map(pair(Self::parse_float_value, Self::parse_float_value), |(a, b)| {
+                tensor1(&[a, b])
+            })(i),
+            IntVector => Self::parse_int_vector(i),
+            IntPairVector => Self::parse_int_pair_vector(i),
             FloatVector => preceded(multispaced(tag("FV")), Self::parse_float_vector)(i),
             FloatMatrix => preceded(multispaced(tag("FM")), Self::parse_float_matrix)(i),
         }
@@ -41,11 +55,33 @@ impl KaldiAttributeKind {
         alt((preceded(tag([4]), le_f32), map(preceded(tag([8]), le_f64), |f| f as f32)))(i)
     }
 
+    fn parse_int_vector<'a>(i: &'a [u8]) -> IResult<&'a [u8], Tensor> {
+        let (i, len) = super::integer(true)(i)?;
+        // FIXME pending merge of https://github.com/Geal/nom/pull/995
+        if len == 0 {
+            Ok((i, tensor1(&[0i32; 0])))
+        } else {
+            map(many_m_n(len as usize, len as usize, le_i32), |data| tensor1(&*data))(i)
+        }
+    }
+
+    fn parse_int_pair_vector<'a>(i: &'a [u8]) -> IResult<&'a [u8], Tensor> {
+        let (i, len) = super::integer(true)(i)?;
+        // FIXME pending merge of https://github.com/Geal/nom/pull/995
+        if len == 0 {
+            Ok((i, tensor2(&[[0i32; 2]; 0])))
+        } else {
+            map_res(many_m_n(2 * len as usize, 2 * len as usize, le_i32), move |data| {
+                tensor1(&*data).into_shape(&[len as usize, 2])
+            })(i)
+        }
+    }
+
     fn parse_float_vector<'a>(i: &'a [u8]) -> IResult<&'a [u8], Tensor> {
         let (i, len) = super::integer(true)(i)?;
         // FIXME pending merge of https://github.com/Geal/nom/pull/995
         if len == 0 {
-            Ok((i, tensor1(&[0.0f32])))
+            Ok((i, tensor1(&[0.0f32; 0])))
         } else {
             map(many_m_n(len as usize, len as usize, le_f32), |data| tensor1(&*data))(i)
         }
@@ -139,6 +175,143 @@ lazy_static::lazy_static! {
             "NumDimsSelfRepaired" => Float,
             "NumDimsProcessed" => Float,
             "SelfRepairScale" => Float,
+        },
+        "TdnnComponent" => hashmap!{
+            "LearningRateFactor" => Float,
+            "IsGradient" => Bool,
+            "MaxChange" => Float,
+            "L2Regularize" => Float,
+            "LearningRate" => Float,
+            "TimeOffsets" => IntVector,
+            "LinearParams" => FloatMatrix,
+            "BiasParams" => FloatVector,
+            "OrthonormalConstraint" => Float,
+            "UseNaturalGradient" => Bool,
+            "NumSamplesHistory" => Float,
+            "AlphaInOut" => FloatPair,
+            "RankInOut" => IntPair,
+        },
+        "LinearComponent" => hashmap!{
+            "LearningRateFactor" => Float,
+            "IsGradient" => Bool,
+            "MaxChange" => Float,
+            "L2Regularize" => Float,
+            "LearningRate" => Float,
+            "Params" => FloatMatrix,
+            "OrthonormalConstraint" => Float,
+            "UseNaturalGradient" => Bool,
+            "RankInOut" => IntPair,
+            "Alpha" => Float,
+            "NumSamplesHistory" => Float,
+            "UpdatePeriod" => Int,
+        },
+        "BatchNormComponent" => hashmap!{
+            "Dim" => Int,
+            "BlockDim" => Int,
+            "Epsilon" => Float,
+            "TargetRms" => Float,
+            "TestMode" => Bool,
+            "Count" => Float,
+            "StatsMean" => FloatVector,
+            "StatsVar" => FloatVector,
+        },
+        "ScaleAndOffsetComponent" => hashmap!{
+            "LearningRateFactor" => Float,
+            "IsGradient" => Bool,
+            "MaxChange" => Float,
+            "L2Regularize" => Float,
+            "LearningRate" => Float,
+            "Dim" => Int,
+            "Scales" => FloatVector,
+            "Offsets" => FloatVector,
+            "UseNaturalGradient" => Bool,
+            "Rank" => Int,
+        },
+        "GeneralDropoutComponent" => hashmap!{
+            "Dim" => Int,
+            "BlockDim" => Int,
+            "TimePeriod" => Int,
+            "DropoutProportion" => Float,
+            "SpecAugmentMaxProportion" => Float,
+            "SpecAugmentMaxRegions" => Int,
+            "Continuous" => Flag,
+            "TestMode" => Bool,
+        },
+        "TimeHeightConvolutionComponent" => hashmap!{
+            "LearningRateFactor" => Float,
+            "IsGradient" => Bool,
+            "MaxChange" => Float,
+            "L2Regularize" => Float,
+            "LearningRate" => Float,
+            "Model" => Flag,
+            "ConvolutionModel" => Flag,
+            "NumFiltersIn" => Int,
+            "NumFiltersOut" => Int,
+            "HeightIn" => Int,
+            "HeightOut" => Int,
+            "HeightSubsampleOut" => Int,
+            "Offsets" => IntPairVector,
+            "RequiredTimeOffsets" => IntVector,
+            "LinearParams" => FloatMatrix,
+            "BiasParams" => FloatVector,
+            "MaxMemoryMb" => Float,
+            "UseNaturalGradient" => Bool,
+            "NumMinibatchesHistory" => Float,
+            "AlphaInOut" => FloatPair,
+            "RankInOut" => IntPair,
+        },
+        "RestrictedAttentionComponent" => hashmap!{
+            "NumHeads" => Int,
+            "KeyDim" => Int,
+            "ValueDim" => Int,
+            "NumLeftInputs" => Int,
+            "NumRightInputs" => Int,
+            "TimeStride" => Int,
+            "NumLeftInputsRequired" => Int,
+            "NumRightInputsRequired" => Int,
+            "OutputContext" => Bool,
+            "KeyScale" => Float,
+            "StatsCount" => Float,
+            "EntropyStats" => FloatVector,
+            "PosteriorStats" => FloatMatrix,
+        },
+        "SumBlockComponent" => hashmap!{
+            "InputDim" => Int,
+            "OutputDim" => Int,
+            "Scale" => Float,
+        },
+        "CompositeComponent" => hashmap!{
+            "LearningRateFactor" => Float,
+            "IsGradient" => Bool,
+            "MaxChange" => Float,
+            "L2Regularize" => Float,
+            "LearningRate" => Float,
+            "MaxRowsProcess" => Int,
+            "NumComponents" => Int,
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bin_int_vector_and_pairs() {
+        let mut bytes = vec![4u8];
+        bytes.extend_from_slice(&3i32.to_le_bytes());
+        for i in &[-3i32, 0, 3] {
+            bytes.extend_from_slice(&i.to_le_bytes());
+        }
+        assert_eq!(IntVector.parse_bin(&bytes).unwrap().1, tensor1(&[-3i32, 0, 3]));
+        let bytes = [4u8, 20, 0, 0, 0, 4, 80, 0, 0, 0];
+        assert_eq!(IntPair.parse_bin(&bytes).unwrap().1, tensor1(&[20i32, 80]));
+        assert_eq!(Flag.parse_bin(b"<TestMode>").unwrap(), (&b"<TestMode>"[..], tensor0(true)));
+        let mut bytes = vec![4u8];
+        bytes.extend_from_slice(&2i32.to_le_bytes());
+        for i in &[-1i32, 0, 0, 1] {
+            bytes.extend_from_slice(&i.to_le_bytes());
+        }
+        assert_eq!(IntPairVector.parse_bin(&bytes).unwrap().1, tensor2(&[[-1i32, 0], [0, 1]]));
+    }
+}
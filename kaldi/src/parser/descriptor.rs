@@ -1,9 +1,10 @@
 use nom::IResult;
 use nom::{
-    bytes::complete::*, character::complete::*, combinator::*, multi::separated_list0, sequence::*,
+    bytes::complete::*, character::complete::*, combinator::*, multi::separated_list0,
+    number::complete::float, sequence::*,
 };
 
-use crate::model::GeneralDescriptor;
+use crate::model::{GeneralDescriptor, IndexVariable};
 use crate::parser::spaced;
 
 pub fn parse_general(i: &str) -> IResult<&str, GeneralDescriptor> {
@@ -37,10 +38,97 @@ pub fn parse_general(i: &str) -> IResult<&str, GeneralDescriptor> {
             ),
             |inner| GeneralDescriptor::IfDefined(Box::new(inner)),
         ),
+        map(
+            preceded(
+                tag("Sum"),
+                cut(delimited(
+                    spaced(tag("(")),
+                    separated_pair(parse_general, spaced(tag(",")), parse_general),
+                    spaced(tag(")")),
+                )),
+            ),
+            |(a, b)| GeneralDescriptor::Sum(Box::new(a), Box::new(b)),
+        ),
+        map(
+            preceded(
+                tag("Scale"),
+                cut(delimited(
+                    spaced(tag("(")),
+                    separated_pair(float, spaced(tag(",")), parse_general),
+                    spaced(tag(")")),
+                )),
+            ),
+            |(scale, inner)| GeneralDescriptor::Scale(scale, Box::new(inner)),
+        ),
+        map(
+            preceded(
+                tag("Const"),
+                cut(delimited(
+                    spaced(tag("(")),
+                    separated_pair(float, spaced(tag(",")), integer),
+                    spaced(tag(")")),
+                )),
+            ),
+            |(value, dim)| GeneralDescriptor::Const(value, dim as usize),
+        ),
+        map(
+            preceded(
+                tag("ReplaceIndex"),
+                cut(delimited(
+                    spaced(tag("(")),
+                    tuple((
+                        parse_general,
+                        preceded(spaced(tag(",")), index_variable),
+                        preceded(spaced(tag(",")), integer),
+                    )),
+                    spaced(tag(")")),
+                )),
+            ),
+            |(inner, variable, value)| {
+                GeneralDescriptor::ReplaceIndex(Box::new(inner), variable, value as isize)
+            },
+        ),
+        map(
+            preceded(
+                tag("Round"),
+                cut(delimited(
+                    spaced(tag("(")),
+                    separated_pair(parse_general, spaced(tag(",")), integer),
+                    spaced(tag(")")),
+                )),
+            ),
+            |(inner, modulus)| GeneralDescriptor::Round(Box::new(inner), modulus as usize),
+        ),
+        map(
+            preceded(
+                tag("Switch"),
+                cut(delimited(
+                    spaced(tag("(")),
+                    separated_list0(spaced(tag(",")), parse_general),
+                    spaced(tag(")")),
+                )),
+            ),
+            GeneralDescriptor::Switch,
+        ),
+        map(
+            preceded(
+                tag("Failover"),
+                cut(delimited(
+                    spaced(tag("(")),
+                    separated_pair(parse_general, spaced(tag(",")), parse_general),
+                    spaced(tag(")")),
+                )),
+            ),
+            |(a, b)| GeneralDescriptor::Failover(Box::new(a), Box::new(b)),
+        ),
         map(super::config_lines::identifier, |i| GeneralDescriptor::Name(i.to_string())),
     )))(i)
 }
 
+fn index_variable(i: &str) -> IResult<&str, IndexVariable> {
+    nom::branch::alt((value(IndexVariable::T, tag("t")), value(IndexVariable::X, tag("x"))))(i)
+}
+
 pub fn integer(i: &str) -> IResult<&str, i32> {
     map_res(recognize(pair(opt(tag("-")), digit1)), |s: &str| s.parse::<i32>())(i)
 }
@@ -78,4 +166,35 @@ mod test {
             Append(vec!(name("input"), IfDefined(Offset(name("lstm1.c").into(), -1).into())))
         )
     }
+
+    #[test]
+    fn test_residual() {
+        assert_eq!(
+            parse_general("Sum(Scale(0.66, tdnn1), Offset(tdnn2, -3))").unwrap().1,
+            Sum(Scale(0.66, name("tdnn1").into()).into(), Offset(name("tdnn2").into(), -3).into())
+        )
+    }
+
+    #[test]
+    fn test_ivector_and_const() {
+        assert_eq!(
+            parse_general("Append(input, ReplaceIndex(ivector, t, 0), Const(1.0, 2))").unwrap().1,
+            Append(vec!(
+                name("input"),
+                ReplaceIndex(name("ivector").into(), IndexVariable::T, 0),
+                Const(1.0, 2)
+            ))
+        )
+    }
+
+    #[test]
+    fn test_round_switch_failover() {
+        assert_eq!(
+            parse_general("Failover(Round(lstm1.r, 3), Switch(a, Offset(b, 1)))").unwrap().1,
+            Failover(
+                Round(name("lstm1.r").into(), 3).into(),
+                Switch(vec!(name("a"), Offset(name("b").into(), 1))).into()
+            )
+        )
+    }
 }
@@ -6,17 +6,52 @@ use nom::{
     number::complete::float, sequence::*,
 };
 
-use super::{integer, multispaced, open_any, spaced};
+use super::{attribute_name, close_nested, integer, multispaced, spaced};
 
-pub fn attributes(i: &[u8]) -> IResult<&[u8], HashMap<String, Arc<Tensor>>> {
-    let (i, attributes) = nom::multi::many0(map(pair(open_any, tensor), |(k, v)| {
-        (k.to_string(), v.into_arc_tensor())
-    }))(i)?;
-    Ok((i, attributes.into_iter().collect()))
+pub fn attributes<'a>(i: &'a [u8], klass: &str) -> IResult<&'a [u8], HashMap<String, Arc<Tensor>>> {
+    // tags without a value (like <Continuous>) are flags
+    let (i, attributes) = nom::multi::many0(nom::branch::alt((
+        map(|i| close_nested(i, klass), |_| None),
+        map(pair(attribute_name, opt(tensor)), |(k, v)| {
+            Some((k.to_string(), v.unwrap_or_else(|| Tensor::from(true)).into_arc_tensor()))
+        }),
+    )))(i)?;
+    Ok((i, attributes.into_iter().flatten().collect()))
 }
 
 pub fn tensor(i: &[u8]) -> IResult<&[u8], Tensor> {
-    nom::branch::alt((scalar, vector, matrix))(i)
+    nom::branch::alt((scalars, vector, matrix, pair_vector))(i)
+}
+
+/// Integer pairs, like the (time, height) <Offsets> of convolutions: [ -1,-1 -1,0 ].
+pub fn pair_vector(i: &[u8]) -> IResult<&[u8], Tensor> {
+    map_res(
+        delimited(
+            spaced(tag("[")),
+            separated_list0(space1, separated_pair(integer(false), tag(","), integer(false))),
+            spaced(tag("]")),
+        ),
+        |pairs| {
+            let len = pairs.len();
+            let data = pairs.into_iter().flat_map(|(a, b)| vec![a, b]).collect::<Vec<i32>>();
+            tensor1(&data).into_shape(&[len, 2])
+        },
+    )(i)
+}
+
+/// One scalar, or a few of them on the same line (like <RankInOut> 20 80).
+pub fn scalars(i: &[u8]) -> IResult<&[u8], Tensor> {
+    map_res(pair(scalar, nom::multi::many0(preceded(space1, scalar))), |(first, others)| {
+        if others.is_empty() {
+            Ok(first)
+        } else {
+            let items: Vec<Tensor> = std::iter::once(first)
+                .chain(others)
+                .map(|t| t.into_shape(&[1]))
+                .collect::<TractResult<_>>()?;
+            Tensor::stack_tensors(0, &items)
+        }
+    })(i)
 }
 
 pub fn scalar(i: &[u8]) -> IResult<&[u8], Tensor> {
@@ -53,6 +88,7 @@ pub fn matrix(i: &[u8]) -> IResult<&[u8], Tensor> {
 mod tests {
     use super::super::nnet3;
     use super::*;
+    use tract_pulse::internal::*;
 
     #[test]
     fn test_nnet3_1() {
@@ -89,6 +125,70 @@ output-node name=output input=fixed1
         );
     }
 
+    #[test]
+    fn test_tdnnf_components() {
+        let slice = r#"<Nnet3>
+
+input-node name=input dim=2
+component-node name=tdnn1 input=input component=tdnn1
+component-node name=drop1 input=tdnn1 component=drop1
+output-node name=output input=drop1
+
+<NumComponents> 2
+<ComponentName> tdnn1 <TdnnComponent> <MaxChange> 0.75 <LearningRate> 0.001 <TimeOffsets> [ -1 0 1 ]
+<LinearParams>  [
+  1 2 3 4 5 6 ]
+<BiasParams>  [ 0.5 ]
+<OrthonormalConstraint> 0 <UseNaturalGradient> T <NumSamplesHistory> 2000 <AlphaInOut> 4 4 <RankInOut> 20 80 </TdnnComponent>
+<ComponentName> drop1 <GeneralDropoutComponent> <Dim> 1 <BlockDim> 1 <TimePeriod> 0 <DropoutProportion> 0.5 <Continuous> <TestMode> T </GeneralDropoutComponent>
+</Nnet3>"#;
+        let model = nnet3(slice.as_bytes()).unwrap();
+        let tdnn = &model.components["tdnn1"].attributes;
+        assert_eq!(*tdnn["TimeOffsets"], tensor1(&[-1f32, 0., 1.]));
+        assert_eq!(*tdnn["RankInOut"], tensor1(&[20f32, 80.]));
+        assert_eq!(*tdnn["UseNaturalGradient"], tensor0(true));
+        let dropout = &model.components["drop1"].attributes;
+        assert_eq!(*dropout["Continuous"], tensor0(true));
+        assert_eq!(*dropout["TestMode"], tensor0(true));
+    }
+
+    #[test]
+    fn test_composite_component() {
+        let slice = r#"<Nnet3>
+
+input-node name=input dim=3
+component-node name=block1 input=input component=composite1
+output-node name=output input=block1
+
+<NumComponents> 1
+<ComponentName> composite1 <CompositeComponent> <LearningRate> 0.001 <MaxRowsProcess> 2048 <NumComponents> 2
+<TimeHeightConvolutionComponent> <LearningRate> 0.001 <Model> <ConvolutionModel> <NumFiltersIn> 1 <NumFiltersOut> 1 <HeightIn> 3 <HeightOut> 3 <HeightSubsampleOut> 1 <Offsets> [ -1,0 0,-1 0,1 1,0 ] <RequiredTimeOffsets> [ -1 0 1 ] </ConvolutionModel> <LinearParams> [
+  1 2 3 4 ]
+<BiasParams> [ 0.5 ]
+<MaxMemoryMb> 200 </TimeHeightConvolutionComponent>
+<SumBlockComponent> <InputDim> 3 <OutputDim> 1 <Scale> 2 </SumBlockComponent>
+</CompositeComponent>
+</Nnet3>"#;
+        let proto = nnet3(slice.as_bytes()).unwrap();
+        let conv = &proto.components["composite1.0"].attributes;
+        assert_eq!(*conv["Offsets"], tensor2(&[[-1i32, 0], [0, -1], [0, 1], [1, 0]]));
+        assert_eq!(*conv["NumFiltersOut"], tensor0(1f32));
+        assert_eq!(proto.components["composite1.1"].klass, "SumBlockComponent");
+        let nodes: Vec<&str> = proto.config_lines.nodes.iter().map(|n| &*n.0).collect();
+        assert_eq!(nodes, vec!["block1.0", "block1"]);
+
+        let model = crate::kaldi().model_for_proto_model(&proto).unwrap();
+        let model = model
+            .into_typed()
+            .unwrap()
+            .concretize_dims(&SymbolValues::default().with(stream_symbol(), 3))
+            .unwrap();
+        let plan = SimplePlan::new(model.into_optimized().unwrap()).unwrap();
+        let input = tensor2(&[[1f32, 2., 3.], [4., 5., 6.], [7., 8., 9.]]);
+        let output = plan.run(tvec!(input)).unwrap();
+        assert_eq!(*output[0], tensor2(&[[2. * (44.5f32 + 60.5 + 49.5)]]));
+    }
+
     #[test]
     fn fixed_affine_40x10_T40_S3() {
         let slice = std::fs::read("test_cases/fixed_affine_40x10_T40_S3/model.raw.txt").unwrap();
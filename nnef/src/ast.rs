@@ -7,6 +7,45 @@ pub mod parse;
 pub struct ProtoModel {
     pub doc: Document,
     pub tensors: Vec<(String, Arc<Tensor>)>,
+    pub quantization: Option<HashMap<String, QuantFormat>>,
+}
+
+/// Quantization of a tensor, as found in a graph.quant file.
+///
+/// Real values are `scale * (q - zero_point)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum QuantFormat {
+    Linear { zero_point: i32, scale: f32, bits: i8, signed: bool },
+}
+
+impl QuantFormat {
+    pub fn linear(zero_point: i32, scale: f32, bits: i8, signed: bool) -> QuantFormat {
+        QuantFormat::Linear { zero_point, scale, bits, signed }
+    }
+
+    pub fn zero_point(&self) -> i32 {
+        match self {
+            QuantFormat::Linear { zero_point, .. } => *zero_point,
+        }
+    }
+
+    pub fn scale(&self) -> f32 {
+        match self {
+            QuantFormat::Linear { scale, .. } => *scale,
+        }
+    }
+
+    pub fn datum_type(&self) -> TractResult<DatumType> {
+        match self {
+            QuantFormat::Linear { bits: 8, signed: true, .. } => Ok(DatumType::I8),
+            QuantFormat::Linear { bits: 8, signed: false, .. } => Ok(DatumType::U8),
+            QuantFormat::Linear { bits: 16, signed: true, .. } => Ok(DatumType::I16),
+            QuantFormat::Linear { bits: 16, signed: false, .. } => Ok(DatumType::U16),
+            QuantFormat::Linear { bits: 32, signed: true, .. } => Ok(DatumType::I32),
+            QuantFormat::Linear { bits: 32, signed: false, .. } => Ok(DatumType::U32),
+            _ => bail!("Unsupported quantization format {:?}", self),
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
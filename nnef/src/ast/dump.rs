@@ -42,6 +42,19 @@ impl<'a> Dumper<'a> {
         Ok(())
     }
 
+    pub fn quantization(&mut self, quant: &HashMap<String, QuantFormat>) -> TractResult<()> {
+        for (id, format) in quant.iter().sorted_by_key(|(id, _)| *id) {
+            match format {
+                QuantFormat::Linear { zero_point, scale, bits, signed } => writeln!(
+                    self.w,
+                    "{:?}: zero_point_linear_quantize(zero_point = {}, scale = {:?}, bits = {}, signed = {}, symmetric = false);",
+                    id, zero_point, scale, bits, signed
+                )?,
+            }
+        }
+        Ok(())
+    }
+
     fn fragment_def(&mut self, def: &FragmentDef) -> TractResult<()> {
         self.fragment_decl(&def.decl)?;
         if let Some(body) = &def.body {
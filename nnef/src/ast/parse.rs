@@ -31,6 +31,65 @@ pub fn parse_parameters(doc: &str) -> TractResult<Vec<Parameter>> {
     all_consuming(parameter_list)(doc).map(|pair| pair.1).map_err(translate_error)
 }
 
+#[inline(never)]
+pub fn parse_quantization(doc: &str) -> TractResult<Vec<(String, QuantFormat)>> {
+    all_consuming(quantization)(doc)
+        .map_err(translate_error)?
+        .1
+        .into_iter()
+        .map(|(id, inv)| Ok((id, quant_format(&inv)?)))
+        .collect()
+}
+
+// <quantization> ::= (<string-literal> ":" <invocation> ";")*
+fn quantization(i: &str) -> IResult<&str, Vec<(String, Invocation)>> {
+    spaced(many0(pair(
+        terminated(spaced(string_literal), stag(":")),
+        terminated(invocation, stag(";")),
+    )))(i)
+}
+
+fn quant_format(inv: &Invocation) -> TractResult<QuantFormat> {
+    let arg = |name: &str| -> TractResult<&Literal> {
+        inv.arguments
+            .iter()
+            .find(|arg| arg.id.as_deref() == Some(name))
+            .and_then(|arg| if let RValue::Literal(lit) = &arg.rvalue { Some(lit) } else { None })
+            .with_context(|| format!("Expected a literal argument {} in {}", name, inv.id))
+    };
+    let number = |name: &str| -> TractResult<f32> {
+        match arg(name)? {
+            Literal::Numeric(n) => Ok(n.parse::<f32>()?),
+            lit => bail!("Expected a number for {}, got {:?}", name, lit),
+        }
+    };
+    let logical = |name: &str| -> TractResult<bool> {
+        match arg(name)? {
+            Literal::Logical(b) => Ok(*b),
+            lit => bail!("Expected a logical for {}, got {:?}", name, lit),
+        }
+    };
+    let bits = number("bits")?;
+    if !(1.0..=32.0).contains(&bits) || bits.fract() != 0.0 {
+        bail!("Quantization bits must be an integer between 1 and 32, got {}", bits)
+    }
+    let bits = bits as i8;
+    match &*inv.id {
+        "zero_point_linear_quantize" => Ok(QuantFormat::linear(
+            number("zero_point")? as i32,
+            number("scale")?,
+            bits,
+            logical("signed")?,
+        )),
+        "linear_quantize" => {
+            let (min, max) = (number("min")?, number("max")?);
+            let scale = (max - min) / ((1u64 << bits) - 1) as f32;
+            Ok(QuantFormat::linear((-min / scale).round() as i32, scale, bits, false))
+        }
+        _ => bail!("Unsupported quantization {}", inv.id),
+    }
+}
+
 // <document> ::= <version> <extension>* <fragmentdefinition>* <graph-definition>
 fn document(i: &str) -> IResult<&str, Document> {
     map(
@@ -609,6 +668,19 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_quantization_bits_are_checked() {
+        let q = |bits: &str| {
+            parse_quantization(&format!(
+                r#""x": linear_quantize(min = -1.0, max = 1.0, bits = {});"#,
+                bits
+            ))
+        };
+        assert!(q("8").is_ok());
+        assert!(q("0").is_err());
+        assert!(q("64").is_err());
+    }
+
     #[test]
     fn test_numeric() {
         p(numeric_literal, "12.0");
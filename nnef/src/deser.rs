@@ -37,16 +37,16 @@ impl<'mb> ModelBuilder<'mb> {
         }
         self.scopes.push(HashMap::new());
         self.wire_body(&self.proto_model.doc.graph_def.body)?;
-        let vars = self.scopes.pop().unwrap();
         let outputs = self
             .proto_model
             .doc
             .graph_def
             .results
             .iter()
-            .map(|s| vars[s].to::<OutletId>(self))
+            .map(|s| self.scopes[0][s].clone().to::<OutletId>(self))
             .collect::<TractResult<TVec<OutletId>>>()?;
         self.model.set_output_outlets(&outputs)?;
+        // properties may refer to variables from the graph body
         if let Some(properties) = self
             .proto_model
             .doc
@@ -60,6 +60,7 @@ impl<'mb> ModelBuilder<'mb> {
                 properties.right.resolve(self)?.to(self)?;
             self.model.properties = properties.into_iter().collect();
         }
+        self.scopes.pop();
         Ok(())
     }
 
@@ -138,6 +139,24 @@ impl<'mb> ModelBuilder<'mb> {
         ))
     }
 
+    /// Quantization format of a graph-level tensor, if `rv` names one.
+    pub fn quantization(&self, rv: &RValue) -> Option<QuantFormat> {
+        if let RValue::Identifier(id) = rv {
+            if self.scopes.len() == 1 {
+                return self.proto_model.quantization.as_ref()?.get(id).cloned();
+            }
+        }
+        None
+    }
+
+    /// Quantization format of the graph-level tensor being assigned, if any.
+    pub fn assigned_quantization(&self) -> Option<QuantFormat> {
+        if self.scopes.len() == 1 && self.naming_scopes.len() == 1 {
+            return self.proto_model.quantization.as_ref()?.get(&self.naming_scopes[0]).cloned();
+        }
+        None
+    }
+
     pub fn wire(
         &mut self,
         op: impl Into<Box<dyn TypedOp>>,
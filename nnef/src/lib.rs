@@ -22,7 +22,9 @@ pub mod prelude {
 
 pub mod internal {
     pub use crate::ast::parse::parse_parameters;
-    pub use crate::ast::{param, FragmentDecl, FragmentDef, Parameter, RValue, TypeName};
+    pub use crate::ast::{
+        param, FragmentDecl, FragmentDef, Parameter, QuantFormat, RValue, TypeName,
+    };
     pub use crate::deser::{ModelBuilder, ResolvedInvocation};
     pub use crate::framework::Nnef;
     pub use crate::prelude::*;
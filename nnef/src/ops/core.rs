@@ -5,6 +5,7 @@ mod broadcast;
 mod cast;
 mod downsample;
 mod gather;
+mod grid_sample;
 mod one_hot;
 mod reduce;
 mod scan;
@@ -41,6 +42,7 @@ pub fn register(registry: &mut Registry) {
     cast::register(registry);
     downsample::register(registry);
     gather::register(registry);
+    grid_sample::register(registry);
     one_hot::register(registry);
     reduce::register(registry);
     scatter::register(registry);
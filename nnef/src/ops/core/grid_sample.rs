@@ -0,0 +1,45 @@
+use crate::internal::*;
+use crate::ser::*;
+use tract_core::ops::cnn::GridSample;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<GridSample>(), ser_grid_sample);
+    registry.register_primitive(
+        "tract_core_grid_sample",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Scalar.tensor().named("grid"),
+            TypeName::String.named("mode").default("bilinear"),
+            TypeName::String.named("padding").default("zeros"),
+            TypeName::Logical.named("align_corners").default(false),
+        ],
+        de_grid_sample,
+    );
+}
+
+fn ser_grid_sample(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op().downcast_ref::<GridSample>().unwrap();
+    let input = ast.mapping[&node.inputs[0]].clone();
+    let grid = ast.mapping[&node.inputs[1]].clone();
+    Ok(Some(invocation(
+        "tract_core_grid_sample",
+        &[input, grid],
+        &[
+            ("mode", string(op.mode.as_str())),
+            ("padding", string(op.padding.as_str())),
+            ("align_corners", logical(op.align_corners)),
+        ],
+    )))
+}
+
+fn de_grid_sample(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let grid = invocation.named_arg_as(builder, "grid")?;
+    let mode = invocation.named_arg_as::<String>(builder, "mode")?.parse()?;
+    let padding = invocation.named_arg_as::<String>(builder, "padding")?.parse()?;
+    let align_corners = invocation.named_arg_as(builder, "align_corners")?;
+    builder.wire(GridSample { mode, padding, align_corners }, &[input, grid])
+}
@@ -280,7 +280,14 @@ pub fn conv_or_deconv(
 
     let border: String = invocation.named_arg_as(builder, "border")?;
     assert_eq!(border, "constant");
+    let kernel_quant = builder.quantization(&*invocation.named_arg("filter")?);
+    let input_quant = builder.quantization(&*invocation.named_arg("input")?);
+    let output_quant = builder.assigned_quantization();
+    let quantized = kernel_quant.is_some() || input_quant.is_some() || output_quant.is_some();
     let op: Box<dyn TypedOp> = if deconv {
+        if quantized {
+            bail!("Quantized deconvolution is not supported");
+        }
         let output_shape = invocation.named_arg_as::<TVec<usize>>(builder, "output_shape")?;
         let output_shape = Some(output_shape).filter(|os| os.len() == pool_spec.rank());
         let adjustments = if let Some(output_shape) = output_shape {
@@ -301,7 +308,26 @@ pub fn conv_or_deconv(
             group,
         ))
     } else {
-        Box::new(ConvUnary::new(pool_spec, KernelFormat::OIHW, kernel.clone(), group, bias, None))
+        let q_params = if quantized {
+            let output_dt =
+                output_quant.map(|q| q.datum_type()).transpose()?.unwrap_or(DatumType::I32);
+            let params = q_params(
+                (kernel_quant, kernel.datum_type()),
+                (input_quant, input_fact.datum_type),
+                (output_quant, output_dt),
+            )?;
+            Some((output_dt, params))
+        } else {
+            None
+        };
+        Box::new(ConvUnary::new(
+            pool_spec,
+            KernelFormat::OIHW,
+            kernel.clone(),
+            group,
+            bias,
+            q_params,
+        ))
     };
     builder.wire(op, &[input])
 }
@@ -453,9 +479,43 @@ pub fn matmul(
     let b = invocation.named_arg_as(builder, "B")?;
     let a_trans = invocation.named_arg_as(builder, "transposeA")?;
     let b_trans = invocation.named_arg_as(builder, "transposeB")?;
+    let a_quant = builder.quantization(&*invocation.named_arg("A")?);
+    let b_quant = builder.quantization(&*invocation.named_arg("B")?);
+    let c_quant = builder.assigned_quantization();
+    if a_quant.is_some() || b_quant.is_some() || c_quant.is_some() {
+        let a_dt = builder.model.outlet_fact(a)?.datum_type;
+        let b_dt = builder.model.outlet_fact(b)?.datum_type;
+        let c_dt = c_quant.map(|q| q.datum_type()).transpose()?.unwrap_or(DatumType::I32);
+        let params = q_params((a_quant, a_dt), (b_quant, b_dt), (c_quant, c_dt))?;
+        let bias = builder.wire(ops::konst::Const::new(rctensor0(0i32)), &[])?[0];
+        let op = ops::matmul::QMatMul::new(a_trans, b_trans, false, c_dt, params);
+        return builder.wire(op, &[a, b, bias]);
+    }
     builder.wire(ops::matmul::MatMul { a_trans, b_trans, c_trans: false }, &[a, b])
 }
 
+fn q_params(
+    a: (Option<QuantFormat>, DatumType),
+    b: (Option<QuantFormat>, DatumType),
+    c: (Option<QuantFormat>, DatumType),
+) -> TractResult<ops::matmul::QParams> {
+    let zero_point = |(q, dt): (Option<QuantFormat>, DatumType)| -> TractResult<AttrOrInput> {
+        let zp = q.map(|q| q.zero_point()).unwrap_or(0);
+        Ok(tensor0(zp).cast_to_dt(dt)?.into_owned().into())
+    };
+    let scale = |(q, _): (Option<QuantFormat>, DatumType)| -> AttrOrInput {
+        tensor0(q.map(|q| q.scale()).unwrap_or(1.0)).into()
+    };
+    Ok(ops::matmul::QParams {
+        a0: zero_point(a)?,
+        a_scale: scale(a),
+        b0: zero_point(b)?,
+        b_scale: scale(b),
+        c0: zero_point(c)?,
+        c_scale: scale(c),
+    })
+}
+
 /*
 * fragment select<?>(
 condition: tensor<logical>,     # the condition for selecting the result
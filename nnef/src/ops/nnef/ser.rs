@@ -2,6 +2,7 @@ use crate::internal::*;
 use crate::ser::*;
 use tract_core::ops;
 use tract_core::ops::cnn::PoolSpec;
+use tract_core::ops::matmul::QParams;
 use tract_core::ops::nn::DataFormat;
 
 pub fn source(
@@ -96,6 +97,7 @@ pub fn pad(
         }
         PadMode::Reflect => "reflect",
         PadMode::Edge => "replicated",
+        PadMode::Symmetric => bail!("Symmetric padding can not be expressed in NNEF"),
     };
     params.push(("border", string(border)));
     Ok(Some(invocation("pad", &[wire], &params)))
@@ -179,7 +181,8 @@ pub fn conv_or_deconv(
     bias: &Option<Arc<Tensor>>,
     group: usize,
     deconv: bool,
-    adjustments: Option<&[usize]>
+    adjustments: Option<&[usize]>,
+    q_params: Option<&(DatumType, QParams)>,
 ) -> TractResult<Option<Arc<RValue>>> {
     use tract_core::ops::cnn::PaddingSpec;
     let ci = pool_spec
@@ -193,9 +196,34 @@ pub fn conv_or_deconv(
     let mut kernel_shape = tvec!(co, ci / group);
     kernel_shape.extend(pool_spec.kernel_shape.iter().copied());
     weights.set_shape(&*kernel_shape)?;
+    // kernel, input and output quantization
+    let quant = if let Some((c_dt, qp)) = q_params {
+        let input_dt = ast.model.outlet_fact(node.inputs[0])?.datum_type;
+        Some([
+            quant_format(ast, node, &qp.a0, &qp.a_scale, weights.datum_type())?,
+            quant_format(ast, node, &qp.b0, &qp.b_scale, input_dt)?,
+            quant_format(ast, node, &qp.c0, &qp.c_scale, *c_dt)?,
+        ])
+    } else {
+        None
+    };
     let weigths = ast.konst_variable(format!("{}_weigths", node.name), &weights.into_arc_tensor())?;
     wire = ast.force_assign(format!("{}_input", node.name), &wire);
-    let conv_fragment = conv_or_deconv_fragment(ast, pool_spec.data_format, pool_spec.rank(), deconv);
+    if let Some(quant) = &quant {
+        ast.quantize(&weigths, quant[0])?;
+        ast.quantize(&wire, quant[1])?;
+    }
+    // quantized tensors must be visible at graph level, so the layout
+    // conversion is not hidden in a fragment
+    let nchw_wrapped = quant.is_some() && pool_spec.data_format != DataFormat::NCHW;
+    let conv_fragment = if let Some(quant) = quant.as_ref().filter(|_| nchw_wrapped) {
+        wire = data_into_ncwh(pool_spec.data_format, pool_spec.rank(), wire);
+        wire = ast.force_assign(format!("{}_input_nchw", node.name), &wire);
+        ast.quantize(&wire, quant[1])?;
+        if deconv { "deconv" } else { "conv" }.to_string()
+    } else {
+        conv_or_deconv_fragment(ast, pool_spec.data_format, pool_spec.rank(), deconv)
+    };
     let padding = match &pool_spec.padding {
         PaddingSpec::Explicit(bef, after, _) => array(
             &bef.iter()
@@ -211,7 +239,14 @@ pub fn conv_or_deconv(
     };
     let mut inputs = tvec![wire, weigths];
     if let Some(bias) = bias.as_ref() {
-        let bias = ast.konst(format!("{}_bias", node.name), bias)?;
+        let bias = if let Some(quant) = &quant {
+            let bias = ast.konst_variable(format!("{}_bias", node.name), bias)?;
+            let scale = quant[0].scale() * quant[1].scale();
+            ast.quantize(&bias, QuantFormat::linear(0, scale, 32, true))?;
+            bias
+        } else {
+            ast.konst(format!("{}_bias", node.name), bias)?
+        };
         inputs.push(bias)
     }
     let mut named_args = tvec![
@@ -226,17 +261,65 @@ pub fn conv_or_deconv(
         named_args.push(("output_shape", ints(&*output_shape)));
     };
     wire = invocation(&conv_fragment, &inputs, &&named_args);
+    if let Some(quant) = quant.as_ref().filter(|_| nchw_wrapped) {
+        wire = ast.force_assign(format!("{}_nchw", node.name), &wire);
+        ast.quantize(&wire, quant[2])?;
+        wire = data_from_ncwh(pool_spec.data_format, pool_spec.rank(), wire);
+    }
     wire = ast.force_assign(&node.name, &wire);
+    if let Some(quant) = &quant {
+        ast.quantize(&wire, quant[2])?;
+    }
     Ok(Some(wire))
 }
 
+fn quant_format(
+    ast: &IntoAst,
+    node: &TypedNode,
+    zero_point: &AttrOrInput,
+    scale: &AttrOrInput,
+    dt: DatumType,
+) -> TractResult<QuantFormat> {
+    let tensor = |qp: &AttrOrInput| -> TractResult<Arc<Tensor>> {
+        match qp {
+            AttrOrInput::Attr(t) => Ok(t.clone()),
+            AttrOrInput::Input(ix) => ast
+                .model
+                .outlet_fact(node.inputs[*ix])?
+                .konst
+                .clone()
+                .context("Dynamic quantization parameters can not be expressed in NNEF"),
+        }
+    };
+    let (zero_point, scale) = (tensor(zero_point)?, tensor(scale)?);
+    if zero_point.len() != 1 || scale.len() != 1 {
+        bail!("Only per-tensor quantization can be expressed in NNEF");
+    }
+    Ok(QuantFormat::linear(
+        zero_point.cast_to_scalar::<i32>()?,
+        scale.cast_to_scalar::<f32>()?,
+        8 * dt.size_of() as i8,
+        dt.is_signed(),
+    ))
+}
+
 pub fn conv(
     ast: &mut IntoAst,
     node: &TypedNode,
     op: &ops::cnn::conv::ConvUnary,
 ) -> TractResult<Option<Arc<RValue>>> {
     let weights = op.kernel_as_group_o_ihw()?.into_tensor();
-    conv_or_deconv(ast, node, &op.pool_spec, weights, &op.bias, op.group, false, None)
+    conv_or_deconv(
+        ast,
+        node,
+        &op.pool_spec,
+        weights,
+        &op.bias,
+        op.group,
+        false,
+        None,
+        op.q_params.as_ref(),
+    )
 }
 
 pub fn deconv(
@@ -250,7 +333,17 @@ pub fn deconv(
         *op.kernel_format.i(op.kernel.shape()),
         *op.kernel_format.o(op.kernel.shape()),
     )?;
-    conv_or_deconv(ast, node, &op.pool_spec, weights.into_tensor(), &op.bias, op.group, true, Some(&op.adjustments))
+    conv_or_deconv(
+        ast,
+        node,
+        &op.pool_spec,
+        weights.into_tensor(),
+        &op.bias,
+        op.group,
+        true,
+        Some(&op.adjustments),
+        None,
+    )
 }
 
 fn cnn_pool_fragment<'a>(
@@ -406,26 +499,36 @@ pub fn reduce(
     Ok(Some(invocation(oper, &[wire], &[("axes", ints(&*op.axes))])))
 }
 
-pub fn matmul(
-    ast: &mut IntoAst,
-    node: &TypedNode,
-    op: &ops::matmul::MatMul,
-) -> TractResult<Option<Arc<RValue>>> {
-    let a = ast.force_assign(format!("{}_a", node.name), &ast.mapping[&node.inputs[0]].clone());
-    let b = ast.force_assign(format!("{}_b", node.name), &ast.mapping[&node.inputs[1]].clone());
-    let c = if op.c_trans {
+fn matmul_invocation(
+    a: Arc<RValue>,
+    b: Arc<RValue>,
+    a_trans: bool,
+    b_trans: bool,
+    c_trans: bool,
+) -> Arc<RValue> {
+    if c_trans {
         invocation(
             "matmul",
             &[b, a],
-            &[("transposeA", logical(!op.b_trans)), ("transposeB", logical(!op.a_trans))],
+            &[("transposeA", logical(!b_trans)), ("transposeB", logical(!a_trans))],
         )
     } else {
         invocation(
             "matmul",
             &[a, b],
-            &[("transposeA", logical(op.a_trans)), ("transposeB", logical(op.b_trans))],
+            &[("transposeA", logical(a_trans)), ("transposeB", logical(b_trans))],
         )
-    };
+    }
+}
+
+pub fn matmul(
+    ast: &mut IntoAst,
+    node: &TypedNode,
+    op: &ops::matmul::MatMul,
+) -> TractResult<Option<Arc<RValue>>> {
+    let a = ast.force_assign(format!("{}_a", node.name), &ast.mapping[&node.inputs[0]].clone());
+    let b = ast.force_assign(format!("{}_b", node.name), &ast.mapping[&node.inputs[1]].clone());
+    let c = matmul_invocation(a, b, op.a_trans, op.b_trans, op.c_trans);
     Ok(Some(ast.force_assign(&node.name, &c)))
 }
 
@@ -436,22 +539,35 @@ pub fn matmul_unary(
 ) -> TractResult<Option<Arc<RValue>>> {
     let a = ast.konst(format!("{}_a", node.name), &op.a)?;
     let b = ast.force_assign(format!("{}_b", node.name), &ast.mapping[&node.inputs[0]].clone());
-    let c = if op.c_trans {
-        invocation(
-            "matmul",
-            &[b, a],
-            &[("transposeA", logical(!op.b_trans)), ("transposeB", logical(!op.a_trans))],
-        )
-    } else {
-        invocation(
-            "matmul",
-            &[a, b],
-            &[("transposeA", logical(op.a_trans)), ("transposeB", logical(op.b_trans))],
-        )
-    };
+    let c = matmul_invocation(a, b, op.a_trans, op.b_trans, op.c_trans);
     Ok(Some(ast.force_assign(&node.name, &c)))
 }
 
+pub fn qmatmul(
+    ast: &mut IntoAst,
+    node: &TypedNode,
+    op: &ops::matmul::QMatMul,
+) -> TractResult<Option<Arc<RValue>>> {
+    match &ast.model.outlet_fact(node.inputs[2])?.konst {
+        Some(bias) if bias.is_uniform() && bias.cast_to_scalar::<f32>()? == 0.0 => (),
+        _ => bail!("Quantized matmul with a bias can not be expressed in NNEF"),
+    }
+    let qp = &op.params;
+    let a_dt = ast.model.outlet_fact(node.inputs[0])?.datum_type;
+    let b_dt = ast.model.outlet_fact(node.inputs[1])?.datum_type;
+    let a_quant = quant_format(ast, node, &qp.a0, &qp.a_scale, a_dt)?;
+    let b_quant = quant_format(ast, node, &qp.b0, &qp.b_scale, b_dt)?;
+    let c_quant = quant_format(ast, node, &qp.c0, &qp.c_scale, op.output_type)?;
+    let a = ast.force_assign(format!("{}_a", node.name), &ast.mapping[&node.inputs[0]].clone());
+    ast.quantize(&a, a_quant)?;
+    let b = ast.force_assign(format!("{}_b", node.name), &ast.mapping[&node.inputs[1]].clone());
+    ast.quantize(&b, b_quant)?;
+    let c = matmul_invocation(a, b, op.a_trans, op.b_trans, op.c_trans);
+    let c = ast.force_assign(&node.name, &c);
+    ast.quantize(&c, c_quant)?;
+    Ok(Some(c))
+}
+
 pub fn select(
     ast: &mut IntoAst,
     node: &TypedNode,
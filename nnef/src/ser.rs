@@ -28,6 +28,7 @@ pub struct IntoAst<'a> {
     pub results: Vec<String>,
     pub mapping: HashMap<OutletId, Arc<RValue>>,
     pub tensors: Vec<(String, Arc<Tensor>)>,
+    pub quantization: HashMap<String, QuantFormat>,
     pub fragments: HashMap<String, FragmentDef>,
     pub body: Vec<Assignment>,
 }
@@ -50,6 +51,7 @@ impl<'a> IntoAst<'a> {
             results: vec![],
             mapping: Default::default(),
             tensors: Default::default(),
+            quantization: Default::default(),
             fragments: Default::default(),
             body: vec![],
             parent: None,
@@ -161,7 +163,11 @@ impl<'a> IntoAst<'a> {
             .model
             .properties
             .iter()
-            .map(|(k, v)| Ok(tuple_2(string(k), self.konst(k, v)?.as_ref().clone())))
+            .map(|(k, v)| {
+                // uniform tensors would be inlined as scalars, losing their shape
+                let v = if v.rank() == 0 { self.konst(k, v)? } else { self.konst_variable(k, v)? };
+                Ok(tuple_2(string(k), v.as_ref().clone()))
+            })
             .collect::<TractResult<Vec<_>>>()?;
         properties.push(tuple_2(
             string("tract_nnef_format_version".to_string()),
@@ -170,7 +176,9 @@ impl<'a> IntoAst<'a> {
                 .clone(),
         ));
         let properties: Assignment = assignment("properties", Arc::new(array(properties)));
-        let IntoAst { prefix, mut fragments, body, tensors, parameters, results, .. } = self;
+        let IntoAst {
+            prefix, mut fragments, body, tensors, quantization, parameters, results, ..
+        } = self;
         let mut id = prefix
             .map(|p| p.trim_end_matches(&['-', '/', '.'][..]).replace(&['-', '/', '.'][..], "_"))
             .unwrap_or("network".into());
@@ -204,7 +212,8 @@ impl<'a> IntoAst<'a> {
             fragments: fragments.into_iter().map(|(_, v)| v).collect(),
             graph_def: GraphDef { id, parameters, results, body },
         };
-        Ok(ProtoModel { doc, tensors })
+        let quantization = Some(quantization).filter(|q| !q.is_empty());
+        Ok(ProtoModel { doc, tensors, quantization })
     }
 
     fn node(&mut self, node: &TypedNode) -> TractResult<TVec<Arc<RValue>>> {
@@ -292,7 +301,9 @@ impl<'a> IntoAst<'a> {
                 return Ok(string(tensor.to_scalar::<String>().unwrap()).into());
             } else if tensor.datum_type() == DatumType::F32 {
                 return Ok(numeric(tensor.cast_to_scalar::<f32>().unwrap()).into());
-            } else if self.ensure_registry("tract_core").is_ok() {
+            } else if tensor.datum_type() != DatumType::TDim
+                && self.ensure_registry("tract_core").is_ok()
+            {
                 let value = numeric(tensor.cast_to_scalar::<i64>()?);
                 let to = string(format!("{:?}", tensor.datum_type()).to_lowercase());
                 return Ok(invocation("tract_core_cast", &[value.into()], &[("to", to)]));
@@ -316,6 +327,22 @@ impl<'a> IntoAst<'a> {
         Ok(ident(id).into())
     }
 
+    /// Attach a quantization format to an assigned tensor.
+    pub fn quantize(&mut self, tensor: &RValue, format: QuantFormat) -> TractResult<()> {
+        let id = if let RValue::Identifier(id) = tensor {
+            id
+        } else {
+            bail!("Only identifiers can be quantized, got {:?}", tensor)
+        };
+        if let Some(previous) = self.quantization.get(id) {
+            if previous != &format {
+                bail!("Conflicting quantization for {}: {:?} and {:?}", id, previous, format);
+            }
+        }
+        self.quantization.insert(id.to_string(), format);
+        Ok(())
+    }
+
     fn assignment(&mut self, name: impl Into<String>, right: Arc<RValue>) {
         let name = name.into();
         if &*right == &ident(&name) {
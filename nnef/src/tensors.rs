@@ -37,7 +37,8 @@ pub fn read_tensor<R: std::io::Read>(mut reader: R) -> TractResult<Tensor> {
             header.dims[0..header.rank as usize].iter().map(|d| *d as _).collect();
         let len = shape.iter().product::<usize>();
         if header.bits_per_item != 0xFFFFFFFF
-            && len * (header.bits_per_item as usize / 8) != header.data_size_bytes as usize
+            && header.bits_per_item != 0xFFFF
+            && (len * header.bits_per_item as usize + 7) / 8 != header.data_size_bytes as usize
         {
             bail!(
                 "Shape and len mismatch: shape:{:?}, bits_per_item:{}, bytes:{} ",
@@ -61,27 +62,46 @@ pub fn read_tensor<R: std::io::Read>(mut reader: R) -> TractResult<Tensor> {
             (0, 0x0100, 16) => DatumType::I16,
             (0, 0x0100, 32) => DatumType::I32,
             (0, 0x0100, 64) => DatumType::I64,
+            // quantized items are plain integers, scale and zero point are in graph.quant
+            (0, 2, 8) => DatumType::U8,
+            (0, 2, 16) => DatumType::U16,
+            (0, 2, 32) => DatumType::U32,
+            (0, 0x0102, 8) => DatumType::I8,
+            (0, 0x0102, 16) => DatumType::I16,
+            (0, 0x0102, 32) => DatumType::I32,
+            (0, 3, 1) | (0, 3, 8) => DatumType::Bool,
             (TRACT_ITEM_TYPE_VENDOR, 0x1000, 0xFFFF) => DatumType::String,
+            (TRACT_ITEM_TYPE_VENDOR, 0x2000, 0xFFFF) => DatumType::TDim,
             _ => bail!(
                 "Unsupported type in tensor type:{} bits_per_item:{}",
                 header.item_type,
                 header.bits_per_item
             ),
         };
-        if dt.is_copy() {
+        if dt == DatumType::Bool && header.bits_per_item == 1 {
+            let mut bytes = vec![0u8; header.data_size_bytes as usize];
+            reader.read_exact(&mut bytes)?;
+            let items: Vec<bool> =
+                (0..len).map(|ix| bytes[ix / 8] & (0x80 >> (ix % 8)) != 0).collect();
+            Ok(tensor1(&items).into_shape(&shape)?)
+        } else if dt.is_copy() {
             let mut tensor = Tensor::uninitialized_dt(dt, &shape)?;
             reader.read_exact(tensor.as_bytes_mut())?;
             Ok(tensor)
         } else if dt == DatumType::String {
-            let mut tensor = Tensor::zero_dt(dt, &shape)?;
-            for item in tensor.as_slice_mut_unchecked::<String>() {
+            let mut items = Vec::with_capacity(len);
+            for _ in 0..len {
                 let len: u32 = reader.read_u32::<LE>()?;
                 let mut bytes = Vec::with_capacity(len as usize);
                 bytes.set_len(len as usize);
                 reader.read_exact(&mut bytes)?;
-                *item = String::from_utf8(bytes)?;
+                items.push(String::from_utf8(bytes)?);
             }
-            Ok(tensor)
+            Ok(tensor1(&items).into_shape(&shape)?)
+        } else if dt == DatumType::TDim {
+            let items: Vec<TDim> =
+                (0..len).map(|_| read_tdim(&mut reader)).collect::<TractResult<_>>()?;
+            Ok(tensor1(&items).into_shape(&shape)?)
         } else {
             todo!()
         }
@@ -109,27 +129,104 @@ pub fn write_tensor<W: std::io::Write>(w: &mut W, tensor: &Tensor) -> TractResul
             0x100
         } else if tensor.datum_type().is_unsigned() {
             1
+        } else if tensor.datum_type() == DatumType::Bool {
+            header.bits_per_item = 1;
+            header.data_size_bytes = ((tensor.len() + 7) / 8) as u32;
+            3
         } else if tensor.datum_type() == DatumType::String {
             header.item_type_vendor = TRACT_ITEM_TYPE_VENDOR;
             header.bits_per_item = 0xFFFF;
             0x1000
+        } else if tensor.datum_type() == DatumType::TDim {
+            header.item_type_vendor = TRACT_ITEM_TYPE_VENDOR;
+            header.bits_per_item = 0xFFFF;
+            0x2000
         } else {
             bail!("Don't know how to serialize {:?}", tensor.datum_type())
         };
         let header_buf: &[u8; 128] = std::mem::transmute(&header);
         w.write_all(&*header_buf)?;
-        if tensor.datum_type().is_copy() {
+        if tensor.datum_type() == DatumType::Bool {
+            let mut bytes = vec![0u8; header.data_size_bytes as usize];
+            for (ix, item) in tensor.as_slice_unchecked::<bool>().iter().enumerate() {
+                if *item {
+                    bytes[ix / 8] |= 0x80 >> (ix % 8);
+                }
+            }
+            w.write_all(&bytes)?;
+        } else if tensor.datum_type().is_copy() {
             w.write_all(tensor.as_bytes())?;
         } else if tensor.datum_type() == DatumType::String {
             for s in tensor.as_slice_unchecked::<String>() {
                 w.write_u32::<LE>(s.as_bytes().len() as u32)?;
                 w.write_all(s.as_bytes())?;
             }
+        } else if tensor.datum_type() == DatumType::TDim {
+            for d in tensor.as_slice_unchecked::<TDim>() {
+                write_tdim(w, d)?;
+            }
         }
         Ok(())
     }
 }
 
+// TDim items are written as an expression tree: a tag byte, then the
+// node payload.
+fn write_tdim<W: std::io::Write>(w: &mut W, dim: &TDim) -> TractResult<()> {
+    match dim {
+        TDim::Val(v) => {
+            w.write_u8(0)?;
+            w.write_i64::<LE>(*v)?;
+        }
+        TDim::Sym(s) => {
+            w.write_u8(1)?;
+            w.write_u32::<LE>(s.as_char() as u32)?;
+        }
+        TDim::Add(terms) => {
+            w.write_u8(2)?;
+            w.write_u32::<LE>(terms.len() as u32)?;
+            for t in terms {
+                write_tdim(w, t)?;
+            }
+        }
+        TDim::Mul(a, b) => {
+            w.write_u8(3)?;
+            w.write_i64::<LE>(*a)?;
+            write_tdim(w, b)?;
+        }
+        TDim::Div(a, q) => {
+            w.write_u8(4)?;
+            write_tdim(w, a)?;
+            w.write_u64::<LE>(*q)?;
+        }
+    }
+    Ok(())
+}
+
+fn read_tdim<R: std::io::Read>(reader: &mut R) -> TractResult<TDim> {
+    Ok(match reader.read_u8()? {
+        0 => TDim::Val(reader.read_i64::<LE>()?),
+        1 => {
+            let c = reader.read_u32::<LE>()?;
+            let c = std::char::from_u32(c).with_context(|| format!("Invalid symbol {}", c))?;
+            TDim::Sym(Symbol::from(c))
+        }
+        2 => {
+            let len = reader.read_u32::<LE>()?;
+            TDim::Add((0..len).map(|_| read_tdim(reader)).collect::<TractResult<_>>()?)
+        }
+        3 => {
+            let a = reader.read_i64::<LE>()?;
+            TDim::Mul(a, Box::new(read_tdim(reader)?))
+        }
+        4 => {
+            let a = read_tdim(reader)?;
+            TDim::Div(Box::new(a), reader.read_u64::<LE>()?)
+        }
+        tag => bail!("Invalid TDim tag {}", tag),
+    })
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -138,4 +235,26 @@ mod test {
     fn header_is_128_bytes() {
         assert_eq!(std::mem::size_of::<Header>(), 128);
     }
+
+    fn round_trip(t: &Tensor) -> Tensor {
+        let mut buffer = vec![];
+        write_tensor(&mut buffer, t).unwrap();
+        read_tensor(&*buffer).unwrap()
+    }
+
+    #[test]
+    fn bool_round_trip() {
+        let t = tensor2(&[[true, false, false, true, true], [false, true, true, false, true]]);
+        let mut buffer = vec![];
+        write_tensor(&mut buffer, &t).unwrap();
+        assert_eq!(buffer.len(), 128 + 2);
+        assert_eq!(round_trip(&t), t);
+    }
+
+    #[test]
+    fn tdim_round_trip() {
+        let s = TDim::from(Symbol::from('S'));
+        let t = tensor1(&[s.clone() * 2 + 1, (s.clone() + 3) / 2, s, 12.into()]);
+        assert_eq!(round_trip(&t), t);
+    }
 }
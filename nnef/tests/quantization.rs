@@ -0,0 +1,94 @@
+use tract_core::ops::cnn::{ConvUnary, KernelFormat, PaddingSpec, PoolSpec};
+use tract_core::ops::matmul::{QMatMul, QParams};
+use tract_core::ops::nn::DataFormat;
+use tract_nnef::ast::{dump, parse, QuantFormat};
+use tract_nnef::internal::*;
+
+fn q_params(a: (Tensor, f32), b: (Tensor, f32), c: (Tensor, f32)) -> QParams {
+    QParams {
+        a0: a.0.into(),
+        a_scale: tensor0(a.1).into(),
+        b0: b.0.into(),
+        b_scale: tensor0(b.1).into(),
+        c0: c.0.into(),
+        c_scale: tensor0(c.1).into(),
+    }
+}
+
+fn round_trip(model: &TypedModel) -> TractResult<TypedModel> {
+    let nnef = tract_nnef::nnef().with_tract_core();
+    let mut buffer = vec![];
+    nnef.write_to_tar(model, &mut buffer)?;
+    let proto = nnef.proto_model_for_read(&mut &*buffer)?;
+    assert!(proto.quantization.is_some());
+    nnef.model_for_proto_model(&proto)
+}
+
+fn run(model: &TypedModel, input: Tensor) -> TractResult<Arc<Tensor>> {
+    Ok(SimplePlan::new(model)?.run(tvec!(input))?.remove(0))
+}
+
+#[test]
+fn parse_dump_parse_quantization() -> TractResult<()> {
+    let quant = parse::parse_quantization(
+        r#"
+        "input": linear_quantize(min = -64.0, max = 63.5, bits = 8);
+        "weights": zero_point_linear_quantize(zero_point = -3, scale = 0.125, bits = 8, signed = true, symmetric = false);
+        "#,
+    )?;
+    assert_eq!(quant[0], ("input".to_string(), QuantFormat::linear(128, 0.5, 8, false)));
+    assert_eq!(quant[1], ("weights".to_string(), QuantFormat::linear(-3, 0.125, 8, true)));
+    let quant: HashMap<String, QuantFormat> = quant.into_iter().collect();
+    let mut dumped = vec![];
+    dump::Dumper::new(&mut dumped).quantization(&quant)?;
+    let reparsed = parse::parse_quantization(&String::from_utf8(dumped)?)?;
+    assert_eq!(quant, reparsed.into_iter().collect());
+    Ok(())
+}
+
+#[test]
+fn qmatmul_round_trip() -> TractResult<()> {
+    let mut model = TypedModel::default();
+    let a = model.add_source("a", TypedFact::dt_shape(i8::datum_type(), &[2, 3]))?;
+    let b = model.add_const("b", tensor2(&[[1i8, -2, 3, 4], [5, 6, -7, 8], [9, 10, 11, -12]]))?;
+    let bias = model.add_const("bias", tensor0(0i32))?;
+    let params = q_params((tensor0(1i8), 0.5), (tensor0(-2i8), 0.25), (tensor0(3i8), 0.1));
+    let op = QMatMul::new(false, false, false, i8::datum_type(), params.clone());
+    let c = model.wire_node("c", op, &[a, b, bias])?;
+    model.set_output_outlets(&c)?;
+
+    let reloaded = round_trip(&model)?;
+    let qmm = reloaded.nodes().iter().find_map(|n| n.op_as::<QMatMul>()).unwrap();
+    assert_eq!(qmm.params, params);
+    assert_eq!(qmm.output_type, i8::datum_type());
+    let input = tensor2(&[[1i8, 2, 3], [-4, 5, -6]]);
+    assert_eq!(run(&model, input.clone())?, run(&reloaded, input)?);
+    Ok(())
+}
+
+#[test]
+fn quantized_conv_round_trip() -> TractResult<()> {
+    let mut model = TypedModel::default();
+    let input = model.add_source("input", TypedFact::dt_shape(u8::datum_type(), &[1, 4, 4, 2]))?;
+    let kernel =
+        tensor1(&(0..24).map(|i| i as i8 - 12).collect::<Vec<_>>()).into_shape(&[3, 2, 2, 2])?;
+    let params = q_params((tensor0(0i8), 0.05), (tensor0(128u8), 0.02), (tensor0(10u8), 0.1));
+    let op = ConvUnary::new(
+        PoolSpec::new(DataFormat::NHWC, tvec!(2, 2), PaddingSpec::Valid, None, None, Some(3)),
+        KernelFormat::OIHW,
+        kernel.into_arc_tensor(),
+        1,
+        Some(rctensor1(&[100i32, -100, 0])),
+        Some((u8::datum_type(), params.clone())),
+    );
+    let output = model.wire_node("conv", op, &[input])?;
+    model.set_output_outlets(&output)?;
+
+    let reloaded = round_trip(&model)?;
+    let conv = reloaded.nodes().iter().find_map(|n| n.op_as::<ConvUnary>()).unwrap();
+    assert_eq!(conv.q_params, Some((u8::datum_type(), params)));
+    let input =
+        tensor1(&(0..32).map(|i| i as u8 * 8).collect::<Vec<_>>()).into_shape(&[1, 4, 4, 2])?;
+    assert_eq!(run(&model, input.clone())?, run(&reloaded, input)?);
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use tract_core::ops::konst::Const;
+use tract_nnef::internal::*;
+
+fn round_trip(model: &TypedModel) -> TractResult<TypedModel> {
+    let nnef = tract_nnef::nnef().with_tract_core();
+    let mut buffer = vec![];
+    nnef.write_to_tar(model, &mut buffer)?;
+    nnef.model_for_read(&mut &*buffer)
+}
+
+#[test]
+fn bool_and_tdim_constants() -> TractResult<()> {
+    let s = TDim::from(Symbol::from('S'));
+    let dims = rctensor1(&[s.clone() * 2 + 1, 3.into()]);
+    let mut model = TypedModel::default();
+    let x = model.add_source("x", TypedFact::dt_shape(f32::datum_type(), &[4]))?;
+    let mask = model.add_const("mask", tensor1(&[true, false, false, true]))?;
+    let zero = model.add_const("zero", tensor1(&[0f32; 4]))?;
+    let masked = model.wire_node("masked", tract_core::ops::logic::Iff, &[mask, x, zero])?[0];
+    let dims = model.add_const("dims", dims.clone())?;
+    model.set_output_outlets(&[masked, dims])?;
+
+    let reloaded = round_trip(&model)?;
+    let konsts: Vec<Arc<Tensor>> =
+        reloaded.nodes().iter().filter_map(|n| n.op_as::<Const>()).map(|k| k.0.clone()).collect();
+    assert!(konsts.contains(&rctensor1(&[true, false, false, true])));
+    assert!(konsts.contains(&rctensor1(&[s * 2 + 1, 3.into()])));
+    let result = SimplePlan::new(&reloaded)?.run(tvec!(tensor1(&[1f32, 2., 3., 4.])))?;
+    assert_eq!(*result[0], tensor1(&[1f32, 0., 0., 4.]));
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "flate2")]
+fn gzipped_archive() -> TractResult<()> {
+    let mut model = TypedModel::default();
+    let x = model.add_source("x", TypedFact::dt_shape(f32::datum_type(), &[3]))?;
+    let bias = model.add_const("bias", tensor1(&[1f32, 2., 3.]))?;
+    let y = model.wire_node("y", tract_core::ops::math::add::bin_typed(), &[x, bias])?;
+    model.set_output_outlets(&y)?;
+
+    let nnef = tract_nnef::nnef().with_tract_core();
+    let buffer = nnef.write_to_tar_gz(&model, vec![])?;
+    assert_eq!(&buffer[0..2], &[0x1f, 0x8b]);
+    let reloaded = nnef.model_for_read(&mut &*buffer)?;
+    let result = SimplePlan::new(&reloaded)?.run(tvec!(tensor1(&[1f32, 1., 1.])))?;
+    assert_eq!(*result[0], tensor1(&[2f32, 3., 4.]));
+    Ok(())
+}
+
+#[test]
+fn graph_properties() -> TractResult<()> {
+    let mut model = TypedModel::default();
+    let x = model.add_source("x", TypedFact::dt_shape(f32::datum_type(), &[2]))?;
+    model.set_output_outlets(&[x])?;
+    let labels = tensor1(&["cat".to_string(), "dog".to_string()]);
+    let properties = vec![
+        ("producer", rctensor0("pytorch".to_string())),
+        ("labels", labels.into_arc_tensor()),
+        ("mean", rctensor1(&[0.5f32, 0.5])),
+        ("version", rctensor0(3i64)),
+    ];
+    for (k, v) in &properties {
+        model.properties.insert(k.to_string(), v.clone());
+    }
+
+    let reloaded = round_trip(&model)?;
+    for (k, v) in &properties {
+        assert_eq!(&reloaded.properties[*k], v);
+    }
+    Ok(())
+}
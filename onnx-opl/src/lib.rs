@@ -6,7 +6,6 @@ use tract_nnef::internal::*;
 #[macro_use]
 mod macros;
 
-pub mod erf;
 pub mod is_inf;
 pub mod is_nan;
 pub mod lrn;
@@ -27,7 +26,8 @@ impl WithOnnx for tract_nnef::framework::Nnef {
 fn onnx_opl_registry() -> Registry {
     let mut registry: Registry = Registry::new("tract_onnx");
     ml::register(&mut registry);
-    registry.register_unit_element_wise("tract_onnx_erf", &erf::Erf {});
+    registry
+        .register_unit_element_wise("tract_onnx_erf", &tract_nnef::tract_core::ops::math::Erf {});
     registry.register_element_wise(
         "tract_onnx_isinf",
         TypeId::of::<is_inf::IsInf>(),
@@ -69,13 +69,7 @@ impl<'a> ParsingContext<'a> {
                 let id = model.add_const(input.name.to_owned(), init)?;
                 outlets_by_name.insert(input.name.to_owned(), id);
             } else {
-                let fact = input.r#type.as_ref().unwrap().value.as_ref().unwrap();
-                #[allow(irrefutable_let_patterns)]
-                let fact: InferenceFact = if let pb::type_proto::Value::TensorType(fact) = fact {
-                    fact.try_into()?
-                } else {
-                    bail!("Can not parse tensor type");
-                };
+                let fact: InferenceFact = input.r#type.as_ref().unwrap().try_into()?;
                 trace!("Input: {} is a source ({:?})", input.name, fact);
                 let id = model.add_source(&*input.name, fact)?;
                 outlets_by_name.insert(input.name.to_owned(), id);
@@ -152,12 +146,7 @@ impl<'a> ParsingContext<'a> {
         }
         let mut outputs = vec![];
         for output in graph.output.iter() {
-            let fact = if let Some(fact) = output.r#type.as_ref().unwrap().value.as_ref() {
-                let pb::type_proto::Value::TensorType(fact) = fact;
-                fact.try_into()?
-            } else {
-                InferenceFact::default()
-            };
+            let fact: InferenceFact = output.r#type.as_ref().unwrap().try_into()?;
             let outlet = outlets_by_name[&*output.name];
             outputs.push(outlet);
             model.set_outlet_label(outlet, output.name.clone())?;
@@ -221,7 +210,29 @@ impl Onnx {
             parent_graphs: vec![],
             onnx_operator_set_version,
         };
-        ctx.parse_graph(graph.as_ref().unwrap())
+        let mut result = ctx.parse_graph(graph.as_ref().unwrap())?;
+        let properties = &mut result.model.properties;
+        for (key, value) in &[
+            ("onnx.producer_name", &proto.producer_name),
+            ("onnx.producer_version", &proto.producer_version),
+            ("onnx.domain", &proto.domain),
+            ("onnx.doc_string", &proto.doc_string),
+        ] {
+            if !value.is_empty() {
+                properties.insert(key.to_string(), rctensor0(value.to_string()));
+            }
+        }
+        if proto.model_version != 0 {
+            properties.insert("onnx.model_version".to_string(), rctensor0(proto.model_version));
+        }
+        if onnx_operator_set_version != 0 {
+            properties
+                .insert("onnx.opset_version".to_string(), rctensor0(onnx_operator_set_version));
+        }
+        for prop in &proto.metadata_props {
+            properties.insert(prop.key.clone(), rctensor0(prop.value.clone()));
+        }
+        Ok(result)
     }
 }
 
@@ -249,3 +260,30 @@ impl Framework<pb::ModelProto, InferenceModel> for Onnx {
         Ok(model)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn model_metadata_in_properties() -> TractResult<()> {
+        let proto = pb::ModelProto {
+            producer_name: "pytorch".to_string(),
+            model_version: 3,
+            opset_import: vec![pb::OperatorSetIdProto { domain: "".to_string(), version: 11 }],
+            metadata_props: vec![pb::StringStringEntryProto {
+                key: "labels".to_string(),
+                value: "cat,dog".to_string(),
+            }],
+            graph: Some(pb::GraphProto::default()),
+            ..pb::ModelProto::default()
+        };
+        let model = Onnx::default().model_for_proto_model(&proto)?.into_typed()?;
+        assert_eq!(model.properties["onnx.producer_name"], rctensor0("pytorch".to_string()));
+        assert_eq!(model.properties["onnx.model_version"], rctensor0(3i64));
+        assert_eq!(model.properties["onnx.opset_version"], rctensor0(11i64));
+        assert_eq!(model.properties["labels"], rctensor0("cat,dog".to_string()));
+        assert!(!model.properties.contains_key("onnx.domain"));
+        Ok(())
+    }
+}
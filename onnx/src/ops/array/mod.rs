@@ -3,6 +3,7 @@ mod nonzero;
 mod one_hot;
 mod pad;
 mod slice;
+mod unique;
 
 use tract_hir::internal::*;
 use tract_hir::ops::array;
@@ -25,6 +26,7 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("OneHot", one_hot::one_hot);
     reg.insert("Pad", pad::pad);
     reg.insert("Reshape", |_, _| Ok((expand(array::Reshape::default()), vec![])));
+    reg.insert("ReverseSequence", reverse_sequence);
     reg.insert("Scatter", scatter_elements);
     reg.insert("ScatterElements", scatter_elements);
     reg.insert("ScatterND", |_, _| Ok((Box::new(array::ScatterNd), vec![])));
@@ -35,6 +37,8 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("Squeeze", squeeze);
     reg.insert("Tile", |_, _| Ok((expand(array::Tile::default()), vec![])));
     reg.insert("Transpose", transpose);
+    reg.insert("Trilu", trilu);
+    reg.insert("Unique", unique::unique);
     reg.insert("Unsqueeze", unsqueeze);
 }
 
@@ -120,6 +124,16 @@ pub fn gather_nd(
     Ok((Box::new(array::GatherNd::new(batch_dims)), vec![]))
 }
 
+pub fn reverse_sequence(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let batch_axis = node.get_attr_opt("batch_axis")?.unwrap_or(1);
+    let time_axis = node.get_attr_opt("time_axis")?.unwrap_or(0);
+    node.expect_attr("batch_axis", batch_axis != time_axis, "a different axis than time_axis")?;
+    Ok((Box::new(array::ReverseSequence::new(batch_axis, time_axis)), vec![]))
+}
+
 pub fn scatter_elements(
     _ctx: &ParsingContext,
     node: &NodeProto,
@@ -153,6 +167,14 @@ pub fn transpose(
     Ok((expand(array::PermuteAxes::new(perm.map(|t| t.into()))), vec![]))
 }
 
+pub fn trilu(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let upper = node.get_attr_opt("upper")?.unwrap_or(true);
+    Ok((Box::new(array::Trilu::new(upper)), vec![]))
+}
+
 pub fn unsqueeze(
     _ctx: &ParsingContext,
     node: &NodeProto,
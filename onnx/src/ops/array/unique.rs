@@ -0,0 +1,85 @@
+use crate::model::{optional_outputs, ParsingContext};
+use crate::pb::NodeProto;
+use tract_hir::internal::*;
+
+pub fn unique(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let axis = node.get_attr_opt("axis")?;
+    let sorted = node.get_attr_opt("sorted")?.unwrap_or(true);
+    let mut outputs = optional_outputs(node);
+    let optional_outputs = [
+        outputs.next().unwrap(),
+        outputs.next().unwrap(),
+        outputs.next().unwrap(),
+        outputs.next().unwrap(),
+    ];
+    Ok((expand(Unique { axis, sorted, optional_outputs }), vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct Unique {
+    axis: Option<i64>,
+    sorted: bool,
+    optional_outputs: [Option<usize>; 4],
+}
+
+impl_dyn_hash!(Unique);
+
+impl Expansion for Unique {
+    fn name(&self) -> Cow<str> {
+        "Unique".into()
+    }
+
+    op_onnx!();
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(self.optional_outputs.iter().filter(|o| o.is_some()).count())
+    }
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1)?;
+        check_output_arity(outputs, self.nboutputs()?)?;
+        if let Some(y) = self.optional_outputs[0] {
+            s.equals(&outputs[y].datum_type, &inputs[0].datum_type)?;
+            if self.axis.is_some() {
+                s.equals(&outputs[y].rank, &inputs[0].rank)?;
+            } else {
+                s.equals(&outputs[y].rank, 1)?;
+            }
+        }
+        for ix in self.optional_outputs[1..].iter().flatten() {
+            s.equals(&outputs[*ix].datum_type, i64::datum_type())?;
+            s.equals(&outputs[*ix].rank, 1)?;
+        }
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let rank = model.outlet_fact(inputs[0])?.rank() as i64;
+        let axis = self.axis.map(|axis| if axis < 0 { axis + rank } else { axis } as usize);
+        let wires = model.wire_node(
+            prefix,
+            tract_core::ops::array::Unique::new(axis, self.sorted),
+            inputs,
+        )?;
+        let mut outputs = tvec!(0.into(); self.nboutputs()?);
+        for (wire, output) in wires.iter().zip(self.optional_outputs.iter()) {
+            if let Some(ix) = output {
+                outputs[*ix] = *wire;
+            }
+        }
+        Ok(outputs)
+    }
+}
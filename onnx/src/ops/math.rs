@@ -43,7 +43,7 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("Asinh", |_, _| Ok((Box::new(ops::math::asinh()), vec![])));
     reg.insert("Atanh", |_, _| Ok((Box::new(ops::math::atanh()), vec![])));
 
-    reg.insert("Erf", |_, _| Ok((Box::new(tract_onnx_opl::erf::erf()), vec![])));
+    reg.insert("Erf", |_, _| Ok((Box::new(ops::math::erf()), vec![])));
     reg.insert("Exp", |_, _| Ok((Box::new(ops::math::exp()), vec![])));
     reg.insert("Log", |_, _| Ok((Box::new(ops::math::ln()), vec![])));
     reg.insert("Sqrt", |_, _| Ok((Box::new(ops::math::sqrt()), vec![])));
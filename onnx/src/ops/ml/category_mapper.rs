@@ -8,7 +8,7 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
 }
 
 #[derive(Debug, Clone, Hash)]
-struct CategoryMapper {
+pub struct CategoryMapper {
     pub from: Arc<Tensor>,
     pub to: Arc<Tensor>,
     pub fallback: Arc<Tensor>,
@@ -0,0 +1,116 @@
+use crate::model::{OnnxOpRegister, ParsingContext};
+use crate::pb::*;
+use tract_hir::internal::*;
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert("Imputer", imputer);
+}
+
+#[derive(Debug, Clone, Hash)]
+struct Imputer {
+    imputed: Arc<Tensor>,
+    replaced: Arc<Tensor>,
+}
+
+impl_dyn_hash!(Imputer);
+
+impl Expansion for Imputer {
+    fn name(&self) -> Cow<str> {
+        "Imputer".into()
+    }
+
+    fn op_families(&self) -> &'static [&'static str] {
+        &["onnx-ml"]
+    }
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        s.equals(&inputs[0].datum_type, self.imputed.datum_type())?;
+        s.equals(&outputs[0].datum_type, self.imputed.datum_type())?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let rank = model.outlet_fact(inputs[0])?.rank();
+        let is_nan = self.replaced.datum_type() == f32::datum_type()
+            && self.replaced.to_scalar::<f32>()?.is_nan();
+        let missing = if is_nan {
+            model.wire_node(
+                format!("{}.is_nan", prefix),
+                tract_onnx_opl::is_nan::is_nan(),
+                inputs,
+            )?
+        } else {
+            model.wire_node(
+                format!("{}.is_missing", prefix),
+                tract_core::ops::logic::equals::unary(
+                    self.replaced.as_ref().clone().broadcast_into_rank(rank)?.into_arc_tensor(),
+                ),
+                inputs,
+            )?
+        };
+        let imputed = model.add_const(
+            format!("{}.imputed", prefix),
+            self.imputed.as_ref().clone().broadcast_into_rank(rank)?.into_arc_tensor(),
+        )?;
+        model.wire_node(
+            format!("{}.iff", prefix),
+            tract_core::ops::logic::Iff,
+            &[missing[0], imputed, inputs[0]],
+        )
+    }
+}
+
+fn imputer(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let floats: Option<Vec<f32>> = node.get_attr_opt_vec("imputed_value_floats")?;
+    let ints: Option<Vec<i64>> = node.get_attr_opt_vec("imputed_value_int64s")?;
+    let op = match (floats, ints) {
+        (Some(floats), None) => {
+            let replaced: f32 = node.get_attr_opt("replaced_value_float")?.unwrap_or(0.0);
+            Imputer { imputed: rctensor1(&floats), replaced: rctensor0(replaced) }
+        }
+        (None, Some(ints)) => {
+            let replaced: i64 = node.get_attr_opt("replaced_value_int64")?.unwrap_or(0);
+            Imputer { imputed: rctensor1(&ints), replaced: rctensor0(replaced) }
+        }
+        _ => bail!("Imputer requires exactly one of imputed_value_floats and imputed_value_int64s"),
+    };
+    Ok((expand(op), vec![]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tract_ndarray::arr2;
+
+    #[test]
+    fn replace_nan_per_feature() {
+        let op = Imputer { imputed: rctensor1(&[1f32, 2.]), replaced: rctensor0(f32::NAN) };
+        let input = arr2(&[[f32::NAN, 5.], [3., f32::NAN]]).into_tensor();
+        let output = expand(op).eval(tvec!(input.into())).unwrap();
+        assert_eq!(*output[0], arr2(&[[1f32, 5.], [3., 2.]]).into_tensor());
+    }
+
+    #[test]
+    fn replace_int_value() {
+        let op = Imputer { imputed: rctensor1(&[7i64]), replaced: rctensor0(-1i64) };
+        let input = arr2(&[[-1i64, 4], [2, -1]]).into_tensor();
+        let output = expand(op).eval(tvec!(input.into())).unwrap();
+        assert_eq!(*output[0], arr2(&[[7i64, 4], [2, 7]]).into_tensor());
+    }
+}
@@ -0,0 +1,149 @@
+use super::category_mapper::CategoryMapper;
+use crate::model::{OnnxOpRegister, ParsingContext};
+use crate::pb::*;
+use tract_hir::internal::*;
+use tract_onnx_opl::ml::*;
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert("LabelEncoder", label_encoder);
+}
+
+/// LabelEncoder from ai.onnx.ml opset 1: strings are mapped to their index in
+/// classes, and integers back to the class string. The direction depends on
+/// the input type.
+#[derive(Debug, Clone, Hash)]
+struct LabelEncoderV1 {
+    classes: Arc<Tensor>,
+    default_int: i64,
+    default_string: String,
+}
+
+impl_dyn_hash!(LabelEncoderV1);
+
+impl Expansion for LabelEncoderV1 {
+    fn name(&self) -> Cow<str> {
+        "LabelEncoder".into()
+    }
+
+    fn op_families(&self) -> &'static [&'static str] {
+        &["onnx-ml"]
+    }
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        s.given(&inputs[0].datum_type, move |s, dt| {
+            if dt == String::datum_type() {
+                s.equals(&outputs[0].datum_type, i64::datum_type())
+            } else {
+                s.equals(&outputs[0].datum_type, String::datum_type())
+            }
+        })?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        if model.outlet_fact(inputs[0])?.datum_type == String::datum_type() {
+            let wire = model.wire_node(
+                format!("{}.reverse", prefix),
+                ReverseLookup::new(self.classes.clone(), -1)?,
+                inputs,
+            )?;
+            let indices: Vec<i64> = (0..self.classes.len() as i64).collect();
+            model.wire_node(
+                format!("{}.direct", prefix),
+                DirectLookup::new(rctensor1(&indices), rctensor0(self.default_int))?,
+                &wire,
+            )
+        } else {
+            let wire = model.wire_node(
+                format!("{}.cast", prefix),
+                tract_core::ops::cast::cast(i32::datum_type()),
+                inputs,
+            )?;
+            model.wire_node(
+                format!("{}.direct", prefix),
+                DirectLookup::new(self.classes.clone(), rctensor0(self.default_string.clone()))?,
+                &wire,
+            )
+        }
+    }
+}
+
+fn label_encoder(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let default_int: i64 = node.get_attr_opt("default_int64")?.unwrap_or(-1);
+    let default_string: String =
+        node.get_attr_opt("default_string")?.unwrap_or_else(|| "_Unused".to_string());
+    if let Some(classes) = node.get_attr_opt_vec::<String>("classes_strings")? {
+        let op = LabelEncoderV1 { classes: rctensor1(&classes), default_int, default_string };
+        return Ok((expand(op), vec![]));
+    }
+    let from = if let Some(keys) = node.get_attr_opt_vec::<String>("keys_strings")? {
+        rctensor1(&keys)
+    } else if let Some(keys) = node.get_attr_opt_vec::<i64>("keys_int64s")? {
+        rctensor1(&keys)
+    } else if node.get_attr_opt_vec::<f32>("keys_floats")?.is_some() {
+        bail!("LabelEncoder with float keys is not supported")
+    } else {
+        bail!("LabelEncoder requires one of keys_strings or keys_int64s")
+    };
+    let (to, fallback) = if let Some(values) = node.get_attr_opt_vec::<String>("values_strings")? {
+        (rctensor1(&values), rctensor0(default_string))
+    } else if let Some(values) = node.get_attr_opt_vec::<i64>("values_int64s")? {
+        (rctensor1(&values), rctensor0(default_int))
+    } else if let Some(values) = node.get_attr_opt_vec::<f32>("values_floats")? {
+        let default_float: f32 = node.get_attr_opt("default_float")?.unwrap_or(-0.0);
+        (rctensor1(&values), rctensor0(default_float))
+    } else {
+        bail!("LabelEncoder requires one of values_strings, values_int64s or values_floats")
+    };
+    node.expect(from.len() == to.len(), "the same number of keys and values")?;
+    Ok((expand(CategoryMapper { from, to, fallback }), vec![]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(s: &[&str]) -> Tensor {
+        tensor1(&s.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn v1_both_ways() {
+        let op = LabelEncoderV1 {
+            classes: strings(&["a", "b", "c"]).into_arc_tensor(),
+            default_int: -1,
+            default_string: "_Unused".to_string(),
+        };
+        let encoded = expand(op.clone()).eval(tvec!(strings(&["c", "a", "z"]).into())).unwrap();
+        assert_eq!(*encoded[0], tensor1(&[2i64, 0, -1]));
+        let decoded = expand(op).eval(tvec!(tensor1(&[1i64, 3]).into())).unwrap();
+        assert_eq!(*decoded[0], strings(&["b", "_Unused"]));
+    }
+
+    #[test]
+    fn string_to_float() {
+        let op = CategoryMapper {
+            from: strings(&["x", "y"]).into_arc_tensor(),
+            to: rctensor1(&[0.5f32, 1.5]),
+            fallback: rctensor0(-0.0f32),
+        };
+        let output = expand(op).eval(tvec!(strings(&["y", "x", "w"]).into())).unwrap();
+        assert_eq!(*output[0], tensor1(&[1.5f32, 0.5, -0.0]));
+    }
+}
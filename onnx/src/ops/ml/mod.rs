@@ -1,9 +1,19 @@
 mod category_mapper;
+mod imputer;
+mod label_encoder;
+mod normalizer;
+mod scaler;
 mod tree_ensemble_classifier;
+mod zip_map;
 
 use crate::model::OnnxOpRegister;
 
 pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     category_mapper::register_all_ops(reg);
+    imputer::register_all_ops(reg);
+    label_encoder::register_all_ops(reg);
+    normalizer::register_all_ops(reg);
+    scaler::register_all_ops(reg);
     tree_ensemble_classifier::register_all_ops(reg);
+    zip_map::register_all_ops(reg);
 }
@@ -0,0 +1,128 @@
+use crate::model::{OnnxOpRegister, ParsingContext};
+use crate::pb::*;
+use tract_hir::internal::*;
+use tract_hir::ops::nn::{Reduce, Reducer};
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert("Normalizer", normalizer);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Norm {
+    Max,
+    L1,
+    L2,
+}
+
+#[derive(Debug, Clone, Hash)]
+struct Normalizer {
+    norm: Norm,
+}
+
+impl_dyn_hash!(Normalizer);
+
+impl Expansion for Normalizer {
+    fn name(&self) -> Cow<str> {
+        "Normalizer".into()
+    }
+
+    fn op_families(&self) -> &'static [&'static str] {
+        &["onnx-ml"]
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("norm: {:?}", self.norm)])
+    }
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let rank = model.outlet_fact(inputs[0])?.rank();
+        let input = model.wire_node(
+            format!("{}.cast", prefix),
+            tract_core::ops::cast::cast(f32::datum_type()),
+            inputs,
+        )?;
+        let reducer = match self.norm {
+            Norm::Max => Reducer::Max,
+            Norm::L1 => Reducer::L1,
+            Norm::L2 => Reducer::L2,
+        };
+        let norm = Reduce::new(Some(vec![rank as i64 - 1]), true, reducer).wire(
+            &format!("{}.norm", prefix),
+            model,
+            &input,
+        )?;
+        // rows with a null norm are left untouched
+        let is_zero = model.wire_node(
+            format!("{}.is_zero", prefix),
+            tract_core::ops::logic::equals::unary(
+                tensor0(0f32).broadcast_into_rank(rank)?.into_arc_tensor(),
+            ),
+            &norm,
+        )?;
+        let one = model.add_const(
+            format!("{}.one", prefix),
+            tensor0(1f32).broadcast_into_rank(rank)?.into_arc_tensor(),
+        )?;
+        let norm = model.wire_node(
+            format!("{}.safe_norm", prefix),
+            tract_core::ops::logic::Iff,
+            &[is_zero[0], one, norm[0]],
+        )?;
+        model.wire_node(
+            format!("{}.div", prefix),
+            tract_core::ops::math::div::bin_typed(),
+            &[input[0], norm[0]],
+        )
+    }
+}
+
+fn normalizer(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let norm = match node.get_attr_opt("norm")?.unwrap_or("MAX") {
+        "MAX" => Norm::Max,
+        "L1" => Norm::L1,
+        "L2" => Norm::L2,
+        other => bail!("Unsupported norm for Normalizer: {}", other),
+    };
+    Ok((expand(Normalizer { norm }), vec![]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tract_ndarray::arr2;
+
+    #[test]
+    fn l1_with_null_row() {
+        let input = arr2(&[[1f32, -3.], [0., 0.]]).into_tensor();
+        let output = expand(Normalizer { norm: Norm::L1 }).eval(tvec!(input.into())).unwrap();
+        assert_eq!(*output[0], arr2(&[[0.25f32, -0.75], [0., 0.]]).into_tensor());
+    }
+
+    #[test]
+    fn l2_on_ints() {
+        let input = arr2(&[[3i64, 4]]).into_tensor();
+        let output = expand(Normalizer { norm: Norm::L2 }).eval(tvec!(input.into())).unwrap();
+        assert_eq!(*output[0], arr2(&[[0.6f32, 0.8]]).into_tensor());
+    }
+}
@@ -0,0 +1,97 @@
+use crate::model::{OnnxOpRegister, ParsingContext};
+use crate::pb::*;
+use tract_hir::internal::*;
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert("Scaler", scaler);
+}
+
+#[derive(Debug, Clone, Hash)]
+struct Scaler {
+    offset: Arc<Tensor>,
+    scale: Arc<Tensor>,
+}
+
+impl_dyn_hash!(Scaler);
+
+impl Expansion for Scaler {
+    fn name(&self) -> Cow<str> {
+        "Scaler".into()
+    }
+
+    fn op_families(&self) -> &'static [&'static str] {
+        &["onnx-ml"]
+    }
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let rank = model.outlet_fact(inputs[0])?.rank();
+        let neg_offset: Vec<f32> = self.offset.as_slice::<f32>()?.iter().map(|x| -x).collect();
+        let mut wire = model.wire_node(
+            format!("{}.cast", prefix),
+            tract_core::ops::cast::cast(f32::datum_type()),
+            inputs,
+        )?;
+        wire = model.wire_node(
+            format!("{}.offset", prefix),
+            tract_core::ops::math::add::unary(
+                tensor1(&neg_offset).broadcast_into_rank(rank)?.into_arc_tensor(),
+            ),
+            &wire,
+        )?;
+        model.wire_node(
+            format!("{}.scale", prefix),
+            tract_core::ops::math::mul::unary(
+                self.scale.as_ref().clone().broadcast_into_rank(rank)?.into_arc_tensor(),
+            ),
+            &wire,
+        )
+    }
+}
+
+fn scaler(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let offset: Vec<f32> = node.get_attr_opt_vec("offset")?.unwrap_or_else(|| vec![0.0]);
+    let scale: Vec<f32> = node.get_attr_opt_vec("scale")?.unwrap_or_else(|| vec![1.0]);
+    Ok((expand(Scaler { offset: rctensor1(&offset), scale: rctensor1(&scale) }), vec![]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tract_ndarray::arr2;
+
+    #[test]
+    fn offset_and_scale_per_feature() {
+        let op = Scaler { offset: rctensor1(&[1f32, -2.]), scale: rctensor1(&[2f32, 0.5]) };
+        let input = arr2(&[[1f32, 2.], [3., 4.]]).into_tensor();
+        let output = expand(op).eval(tvec!(input.into())).unwrap();
+        assert_eq!(*output[0], arr2(&[[0f32, 2.], [4., 3.]]).into_tensor());
+    }
+
+    #[test]
+    fn ints_are_cast() {
+        let op = Scaler { offset: rctensor1(&[1f32]), scale: rctensor1(&[3f32]) };
+        let output = expand(op).eval(tvec!(tensor1(&[1i64, 2]).into())).unwrap();
+        assert_eq!(*output[0], tensor1(&[0f32, 3.]));
+    }
+}
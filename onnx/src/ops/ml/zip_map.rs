@@ -0,0 +1,96 @@
+use crate::model::{OnnxOpRegister, ParsingContext};
+use crate::pb::*;
+use tract_hir::internal::*;
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert("ZipMap", zip_map);
+}
+
+/// ZipMap produces a sequence of maps from class label to score. As tract has
+/// no map type, the output is kept as the dense [N, C] score tensor: the map
+/// keys are the class labels, in the order of the labels attribute, which is
+/// exposed by `info`.
+#[derive(Debug, Clone, Hash)]
+struct ZipMap {
+    labels: Arc<Tensor>,
+}
+
+impl_dyn_hash!(ZipMap);
+
+impl Expansion for ZipMap {
+    fn name(&self) -> Cow<str> {
+        "ZipMap".into()
+    }
+
+    fn op_families(&self) -> &'static [&'static str] {
+        &["onnx-ml"]
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("keys: {:?}", self.labels)])
+    }
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, f32::datum_type())?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        s.equals(&inputs[0].shape[1], self.labels.len().to_dim())?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        model.wire_node(prefix, tract_core::ops::identity::Identity, inputs)
+    }
+}
+
+fn zip_map(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let ints = node.get_attr_opt_vec::<i64>("classlabels_int64s")?;
+    let strings = node.get_attr_opt_vec::<String>("classlabels_strings")?;
+    let labels = match (ints, strings) {
+        (Some(ints), None) => rctensor1(&ints),
+        (None, Some(strings)) => rctensor1(&strings),
+        _ => bail!("ZipMap requires exactly one of classlabels_int64s and classlabels_strings"),
+    };
+    Ok((expand(ZipMap { labels }), vec![]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tract_ndarray::arr2;
+
+    #[test]
+    fn string_keys() {
+        let labels = tensor1(&["cat".to_string(), "dog".to_string()]).into_arc_tensor();
+        let op = ZipMap { labels };
+        assert_eq!(op.info().unwrap(), vec!["keys: 2,String cat, dog".to_string()]);
+        let input = arr2(&[[0.25f32, 0.75]]).into_tensor();
+        let output = expand(op).eval(tvec!(input.clone().into())).unwrap();
+        assert_eq!(*output[0], input);
+    }
+
+    #[test]
+    fn int64_keys() {
+        let op = ZipMap { labels: rctensor1(&[10i64, 20, 30]) };
+        assert_eq!(op.info().unwrap(), vec!["keys: 3,I64 10, 20, 30".to_string()]);
+        let input = arr2(&[[0.5f32, 0.25, 0.25]]).into_tensor();
+        let output = expand(op).eval(tvec!(input.clone().into())).unwrap();
+        assert_eq!(*output[0], input);
+    }
+}
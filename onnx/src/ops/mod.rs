@@ -20,6 +20,8 @@ mod nn;
 mod quant;
 pub mod rec;
 mod resize;
+mod sequence;
+mod text;
 
 pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("Cast", cast::cast);
@@ -33,6 +35,8 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     nn::register_all_ops(reg);
     quant::register_all_ops(reg);
     rec::register_all_ops(reg);
+    sequence::register_all_ops(reg);
+    text::register_all_ops(reg);
 }
 
 fn konst(
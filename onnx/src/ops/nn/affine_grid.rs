@@ -0,0 +1,165 @@
+use crate::model::ParsingContext;
+use crate::pb::*;
+use tract_hir::internal::*;
+use tract_hir::tract_core::ops::matmul::MatMulUnary;
+
+pub fn affine_grid(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let align_corners = node.get_attr_opt("align_corners")?.unwrap_or(false);
+    Ok((expand(AffineGrid { align_corners }), vec![]))
+}
+
+/// Generates a sampling grid for GridSample from a batch of affine matrices.
+///
+/// The size input must be a constant: the grid of normalized (x, y, ..., 1)
+/// coordinates is computed at wiring time, and multiplied by the transposed
+/// matrices.
+#[derive(Debug, Clone, Hash)]
+pub struct AffineGrid {
+    align_corners: bool,
+}
+
+impl_dyn_hash!(AffineGrid);
+
+impl AffineGrid {
+    fn base_grid(&self, spatial: &[usize]) -> Tensor {
+        let rank = spatial.len();
+        let points = spatial.iter().product::<usize>();
+        let mut grid = tract_ndarray::Array3::<f32>::ones((1, points, rank + 1));
+        for (point, coords) in tract_ndarray::indices(spatial).into_iter().enumerate() {
+            // coordinates are ordered from the innermost axis (x) outwards
+            for (axis, &size) in spatial.iter().enumerate() {
+                let ix = coords[axis] as f32;
+                let coord = if size == 1 {
+                    0.0
+                } else if self.align_corners {
+                    2.0 * ix / (size - 1) as f32 - 1.0
+                } else {
+                    (2.0 * ix + 1.0) / size as f32 - 1.0
+                };
+                grid[(0, point, rank - 1 - axis)] = coord;
+            }
+        }
+        grid.into_tensor()
+    }
+}
+
+impl Expansion for AffineGrid {
+    fn name(&self) -> Cow<str> {
+        "AffineGrid".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("align_corners: {}", self.align_corners)])
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 2)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].rank, 3)?;
+        s.equals(&inputs[1].rank, 1)?;
+        s.equals(&outputs[0].shape[0], &inputs[0].shape[0])?;
+        s.given(&inputs[1].value, move |s, size| {
+            let size = size.cast_to::<TDim>()?;
+            let size = size.as_slice::<TDim>()?;
+            if size.len() < 3 {
+                bail!("AffineGrid expects a size of rank at least 3, got {:?}", size);
+            }
+            let rank = size.len() - 2;
+            s.equals(&inputs[0].shape[1], rank.to_dim())?;
+            s.equals(&inputs[0].shape[2], (rank + 1).to_dim())?;
+            let mut shape: TVec<TDim> = tvec!(size[0].clone());
+            shape.extend(size[2..].iter().cloned());
+            shape.push(rank.to_dim());
+            s.equals(&outputs[0].shape, shape)
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let size = model
+            .outlet_fact(inputs[1])?
+            .konst
+            .clone()
+            .context("AffineGrid expects a constant size")?
+            .cast_to::<i64>()?
+            .as_slice::<i64>()?
+            .iter()
+            .map(|d| *d as usize)
+            .collect::<TVec<usize>>();
+        let dt = model.outlet_fact(inputs[0])?.datum_type;
+        let spatial = &size[2..];
+        let base = self.base_grid(spatial).cast_to_dt(dt)?.into_owned();
+        let points = model.wire_node(
+            format!("{}.matmul", prefix),
+            MatMulUnary::new(base.into_arc_tensor(), false, true, false),
+            &inputs[0..1],
+        )?;
+        let shape = spatial.iter().map(|d| d.to_dim()).collect();
+        model.wire_node(
+            format!("{}.reshape", prefix),
+            AxisOp::Reshape(1, tvec!(spatial.iter().product::<usize>().to_dim()), shape),
+            &points,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tract_ndarray::arr3;
+
+    fn grid(align_corners: bool, theta: [[f32; 3]; 2]) -> Tensor {
+        let op = expand(AffineGrid { align_corners });
+        let output =
+            op.eval(tvec!(arr3(&[theta]).into_arc_tensor(), rctensor1(&[1i64, 1, 2, 3]))).unwrap();
+        output[0].clone().into_tensor()
+    }
+
+    #[test]
+    fn identity() {
+        let found = grid(false, [[1., 0., 0.], [0., 1., 0.]]);
+        let x = 2. / 3.;
+        let expected = tract_ndarray::arr2(&[
+            [-x, -0.5],
+            [0., -0.5],
+            [x, -0.5],
+            [-x, 0.5],
+            [0., 0.5],
+            [x, 0.5],
+        ])
+        .into_shape((1, 2, 3, 2))
+        .unwrap();
+        found.close_enough(&expected.into_tensor(), true).unwrap();
+    }
+
+    #[test]
+    fn scale_and_translate_align_corners() {
+        let found = grid(true, [[2., 0., 1.], [0., 1., 0.]]);
+        let expected = tract_ndarray::arr2(&[
+            [-1f32, -1.],
+            [1., -1.],
+            [3., -1.],
+            [-1., 1.],
+            [1., 1.],
+            [3., 1.],
+        ])
+        .into_shape((1, 2, 3, 2))
+        .unwrap();
+        found.close_enough(&expected.into_tensor(), true).unwrap();
+    }
+}
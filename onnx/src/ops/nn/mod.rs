@@ -6,9 +6,11 @@ use crate::model::{OnnxOpRegister, ParsingContext};
 use crate::pb::NodeProto;
 use crate::pb_helpers::OptionExt;
 
+mod affine_grid;
 mod batch_norm;
 mod conv_transpose;
 mod dropout;
+mod grid_sample;
 mod instance_norm;
 mod lrn;
 
@@ -37,6 +39,7 @@ fn reduce(
 }
 
 pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert("AffineGrid", affine_grid::affine_grid);
     reg.insert("ArgMax", arg_max_min);
     reg.insert("ArgMin", arg_max_min);
     reg.insert("AveragePool", average_pool);
@@ -49,6 +52,7 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("GlobalAveragePool", |_, _| Ok((expand(ops::nn::GlobalAvgPool), vec![])));
     reg.insert("GlobalLpPool", global_lp_pool);
     reg.insert("GlobalMaxPool", |_, _| Ok((expand(ops::nn::GlobalMaxPool), vec![])));
+    reg.insert("GridSample", grid_sample::grid_sample);
     reg.insert("Hardmax", layer_hard_max);
     reg.insert("HardSigmoid", hard_sigmoid);
     reg.insert("InstanceNormalization", instance_norm::instance_normalization);
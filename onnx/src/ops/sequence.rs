@@ -0,0 +1,674 @@
+//! ONNX sequence operators.
+//!
+//! tract has no sequence value type: a sequence is represented by a single
+//! tensor, its elements being stacked along an extra leading axis. This
+//! requires all elements of a sequence to share the same shape (ragged
+//! sequences are rejected), and lets every sequence operator be expressed in
+//! terms of plain tensor operators (axis changes, Slice, Gather and
+//! TypedConcat) when the model is typed.
+//!
+//! Positions given to SequenceInsert and SequenceErase may be computed at
+//! runtime, as long as the sequence length is known.
+
+use crate::model::{OnnxOpRegister, ParsingContext};
+use crate::pb::*;
+use tract_hir::internal::*;
+use tract_hir::tract_core::ops::array::{Gather, Slice, TypedConcat};
+use tract_hir::tract_core::ops::binary::wire_with_rank_broadcast;
+use tract_hir::tract_core::ops::{cast::cast, logic, math};
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert("ConcatFromSequence", concat_from_sequence);
+    reg.insert("SequenceAt", |_, _| Ok((expand(SequenceAt), vec![])));
+    reg.insert("SequenceConstruct", |_, _| Ok((expand(SequenceConstruct), vec![])));
+    reg.insert("SequenceErase", |_, _| Ok((expand(SequenceErase), vec![])));
+    reg.insert("SequenceInsert", |_, _| Ok((expand(SequenceInsert), vec![])));
+    reg.insert("SequenceLength", |_, _| Ok((expand(SequenceLength), vec![])));
+    reg.insert("SplitToSequence", split_to_sequence);
+}
+
+/// Position of an insertion or erasure in a sequence.
+enum Position {
+    Const(TDim),
+    Wire(OutletId),
+}
+
+/// Resolves an optional position input against a sequence length, to a
+/// constant if it is known at this point.
+fn position(
+    model: &TypedModel,
+    position: Option<&OutletId>,
+    len: &TDim,
+    default: TDim,
+) -> TractResult<Position> {
+    if let Some(position) = position {
+        if let Some(konst) = &model.outlet_fact(*position)?.konst {
+            let position = konst.cast_to_scalar::<i64>()?;
+            Ok(Position::Const(if position < 0 {
+                len.clone() + position
+            } else {
+                position.to_dim()
+            }))
+        } else {
+            Ok(Position::Wire(*position))
+        }
+    } else {
+        Ok(Position::Const(default))
+    }
+}
+
+/// Wires the indices, in the sequence, of the elements of the sequence with
+/// an element erased or inserted at a runtime position. For an insertion, the
+/// inserted element is expected after the `len` elements of the sequence.
+fn wire_indices(
+    prefix: &str,
+    model: &mut TypedModel,
+    position: OutletId,
+    len: usize,
+    insert: bool,
+) -> TractResult<OutletId> {
+    let position =
+        model.wire_node(format!("{}.position", prefix), cast(i64::datum_type()), &[position])?;
+    let zero = model.add_const(format!("{}.zero", prefix), tensor0(0i64))?;
+    let negative = model.wire_node(
+        format!("{}.negative", prefix),
+        logic::lesser::bin_typed(),
+        &[position[0], zero],
+    )?;
+    let negative =
+        model.wire_node(format!("{}.negative-i64", prefix), cast(i64::datum_type()), &negative)?;
+    let len_wire = model.add_const(format!("{}.len", prefix), tensor0(len as i64))?;
+    let shift = model.wire_node(
+        format!("{}.shift", prefix),
+        math::mul::bin_typed(),
+        &[negative[0], len_wire],
+    )?;
+    let position = model.wire_node(
+        format!("{}.positive", prefix),
+        math::add::bin_typed(),
+        &[position[0], shift[0]],
+    )?[0];
+    let output_len = if insert { len + 1 } else { len - 1 };
+    let range = tensor1(&(0..output_len as i64).collect::<Vec<_>>());
+    let range = model.add_const(format!("{}.range", prefix), range)?;
+    let mut indices = if insert {
+        // elements after the position come from one step before, the element
+        // at the position is the inserted one
+        let after = wire_with_rank_broadcast(
+            &format!("{}.after", prefix),
+            model,
+            logic::greater::bin_typed(),
+            &[range, position],
+        )?;
+        let after =
+            model.wire_node(format!("{}.after-i64", prefix), cast(i64::datum_type()), &after)?;
+        let shifted = model.wire_node(
+            format!("{}.shifted", prefix),
+            math::sub::bin_typed(),
+            &[range, after[0]],
+        )?;
+        let at = wire_with_rank_broadcast(
+            &format!("{}.at", prefix),
+            model,
+            logic::equals::bin_typed(),
+            &[range, position],
+        )?;
+        let at = model.wire_node(format!("{}.at-i64", prefix), cast(i64::datum_type()), &at)?;
+        let to_inserted =
+            tensor1(&(0..output_len as i64).map(|i| len as i64 - i).collect::<Vec<_>>());
+        let to_inserted = model.add_const(format!("{}.to_inserted", prefix), to_inserted)?;
+        let inserted = model.wire_node(
+            format!("{}.inserted", prefix),
+            math::mul::bin_typed(),
+            &[at[0], to_inserted],
+        )?;
+        model.wire_node(prefix, math::add::bin_typed(), &[shifted[0], inserted[0]])?
+    } else {
+        // elements from the position on come from one step after
+        let after = wire_with_rank_broadcast(
+            &format!("{}.after", prefix),
+            model,
+            logic::greater_equal::bin_typed(),
+            &[range, position],
+        )?;
+        let after =
+            model.wire_node(format!("{}.after-i64", prefix), cast(i64::datum_type()), &after)?;
+        model.wire_node(prefix, math::add::bin_typed(), &[range, after[0]])?
+    };
+    Ok(indices.remove(0))
+}
+
+fn wire_slice(
+    prefix: &str,
+    model: &mut TypedModel,
+    seq: OutletId,
+    start: TDim,
+    end: TDim,
+) -> TractResult<OutletId> {
+    Ok(model.wire_node(prefix, Slice { axis: 0, start, end }, &[seq])?[0])
+}
+
+/// Builds a sequence from its inputs, which must all have the same shape.
+#[derive(Debug, Clone, Hash)]
+struct SequenceConstruct;
+impl_dyn_hash!(SequenceConstruct);
+
+impl Expansion for SequenceConstruct {
+    fn name(&self) -> Cow<str> {
+        "SequenceConstruct".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        if inputs.is_empty() {
+            bail!("SequenceConstruct expects at least one input")
+        }
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].rank, inputs[0].rank.bex() + 1)?;
+        s.equals(&outputs[0].shape[0], inputs.len().to_dim())?;
+        for input in inputs {
+            s.equals(&outputs[0].datum_type, &input.datum_type)?;
+            s.equals(&inputs[0].shape, &input.shape)?;
+        }
+        s.given(&inputs[0].rank, move |s, rank| {
+            for axis in 0..rank as usize {
+                s.equals(&outputs[0].shape[axis + 1], &inputs[0].shape[axis])?;
+            }
+            Ok(())
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let mut wires = tvec!();
+        for (ix, input) in inputs.iter().enumerate() {
+            wires.push(
+                model.wire_node(
+                    format!("{}.add_axis-{}", prefix, ix),
+                    AxisOp::Add(0),
+                    &[*input],
+                )?[0],
+            );
+        }
+        model.wire_node(prefix, TypedConcat::concat_vars(0, wires.len()), &wires)
+    }
+}
+
+#[derive(Debug, Clone, Hash)]
+struct SequenceAt;
+impl_dyn_hash!(SequenceAt);
+
+impl Expansion for SequenceAt {
+    fn name(&self) -> Cow<str> {
+        "SequenceAt".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[1].rank, 0)?;
+        s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        s.equals(&outputs[0].rank, inputs[0].rank.bex() - 1)?;
+        s.given(&outputs[0].rank, move |s, rank| {
+            for axis in 0..rank as usize {
+                s.equals(&outputs[0].shape[axis], &inputs[0].shape[axis + 1])?;
+            }
+            Ok(())
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        model.wire_node(prefix, Gather::new(0), inputs)
+    }
+}
+
+/// Inserts a tensor in a sequence. The tensor must have the shape of the
+/// sequence elements.
+#[derive(Debug, Clone, Hash)]
+struct SequenceInsert;
+impl_dyn_hash!(SequenceInsert);
+
+impl Expansion for SequenceInsert {
+    fn name(&self) -> Cow<str> {
+        "SequenceInsert".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        if inputs.len() != 2 && inputs.len() != 3 {
+            bail!("SequenceInsert expects 2 or 3 inputs, node has {}.", inputs.len())
+        }
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        s.equals(&outputs[0].datum_type, &inputs[1].datum_type)?;
+        s.equals(&outputs[0].rank, &inputs[0].rank)?;
+        s.equals(&outputs[0].rank, inputs[1].rank.bex() + 1)?;
+        s.equals(&outputs[0].shape[0], inputs[0].shape[0].bex() + 1.to_dim())?;
+        s.given(&inputs[1].rank, move |s, rank| {
+            for axis in 0..rank as usize {
+                s.equals(&outputs[0].shape[axis + 1], &inputs[1].shape[axis])?;
+                s.equals(&outputs[0].shape[axis + 1], &inputs[0].shape[axis + 1])?;
+            }
+            Ok(())
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let len = model.outlet_fact(inputs[0])?.shape[0].clone();
+        let tensor =
+            model.wire_node(format!("{}.add_axis", prefix), AxisOp::Add(0), &inputs[1..2])?;
+        let position = match position(model, inputs.get(2), &len, len.clone())? {
+            Position::Const(position) => position,
+            Position::Wire(position) => {
+                let len = len.to_usize().context("Runtime positions need a known length")?;
+                let appended = model.wire_node(
+                    format!("{}.append", prefix),
+                    TypedConcat::concat_vars(0, 2),
+                    &[inputs[0], tensor[0]],
+                )?;
+                let indices =
+                    wire_indices(&format!("{}.indices", prefix), model, position, len, true)?;
+                return model.wire_node(prefix, Gather::new(0), &[appended[0], indices]);
+            }
+        };
+        let before = wire_slice(
+            &format!("{}.before", prefix),
+            model,
+            inputs[0],
+            0.to_dim(),
+            position.clone(),
+        )?;
+        let after = wire_slice(&format!("{}.after", prefix), model, inputs[0], position, len)?;
+        model.wire_node(prefix, TypedConcat::concat_vars(0, 3), &[before, tensor[0], after])
+    }
+}
+
+#[derive(Debug, Clone, Hash)]
+struct SequenceErase;
+impl_dyn_hash!(SequenceErase);
+
+impl Expansion for SequenceErase {
+    fn name(&self) -> Cow<str> {
+        "SequenceErase".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        if inputs.len() != 1 && inputs.len() != 2 {
+            bail!("SequenceErase expects 1 or 2 inputs, node has {}.", inputs.len())
+        }
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        s.equals(&outputs[0].rank, &inputs[0].rank)?;
+        s.equals(&inputs[0].shape[0], outputs[0].shape[0].bex() + 1.to_dim())?;
+        s.given(&inputs[0].rank, move |s, rank| {
+            for axis in 1..rank as usize {
+                s.equals(&outputs[0].shape[axis], &inputs[0].shape[axis])?;
+            }
+            Ok(())
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let len = model.outlet_fact(inputs[0])?.shape[0].clone();
+        let position = match position(model, inputs.get(1), &len, len.clone() - 1)? {
+            Position::Const(position) => position,
+            Position::Wire(position) => {
+                let len = len.to_usize().context("Runtime positions need a known length")?;
+                let indices =
+                    wire_indices(&format!("{}.indices", prefix), model, position, len, false)?;
+                return model.wire_node(prefix, Gather::new(0), &[inputs[0], indices]);
+            }
+        };
+        let before = wire_slice(
+            &format!("{}.before", prefix),
+            model,
+            inputs[0],
+            0.to_dim(),
+            position.clone(),
+        )?;
+        let after = wire_slice(&format!("{}.after", prefix), model, inputs[0], position + 1, len)?;
+        model.wire_node(prefix, TypedConcat::concat_vars(0, 2), &[before, after])
+    }
+}
+
+#[derive(Debug, Clone, Hash)]
+struct SequenceLength;
+impl_dyn_hash!(SequenceLength);
+
+impl Expansion for SequenceLength {
+    fn name(&self) -> Cow<str> {
+        "SequenceLength".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, i64::datum_type())?;
+        s.equals(&outputs[0].rank, 0)?;
+        s.given(&inputs[0].shape[0], move |s, len| {
+            if let Ok(len) = len.to_i64() {
+                s.equals(&outputs[0].value, rctensor0(len))?;
+            }
+            Ok(())
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let len = model.outlet_fact(inputs[0])?.shape[0].to_i64()?;
+        Ok(tvec!(model.add_const(prefix, tensor0(len))?))
+    }
+}
+
+/// Splits a tensor into a sequence. As the chunks are stacked, a split input
+/// must be constant and all its chunks must have the same size: uneven splits,
+/// including a last shorter chunk, are rejected.
+#[derive(Debug, Clone, Hash)]
+struct SplitToSequence {
+    axis: i64,
+    keep_dims: bool,
+}
+impl_dyn_hash!(SplitToSequence);
+
+fn split_to_sequence(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let axis = node.get_attr_opt("axis")?.unwrap_or(0);
+    let keep_dims = node.get_attr_opt("keepdims")?.unwrap_or(true);
+    Ok((expand(SplitToSequence { axis, keep_dims }), vec![]))
+}
+
+impl SplitToSequence {
+    /// Returns the (equal) size of the chunks, None meaning chunks of one
+    /// element on the split axis.
+    fn chunk_size(split: Option<&Tensor>) -> TractResult<Option<i64>> {
+        let split =
+            if let Some(split) = split { split.cast_to::<i64>()? } else { return Ok(None) };
+        let split = split.as_slice::<i64>()?;
+        if split.iter().any(|s| *s != split[0]) {
+            bail!(
+                "SplitToSequence with uneven chunks can not be represented as a tensor ({:?})",
+                split
+            )
+        }
+        Ok(Some(split[0]))
+    }
+}
+
+impl Expansion for SplitToSequence {
+    fn name(&self) -> Cow<str> {
+        "SplitToSequence".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        if inputs.len() == 1 && !self.keep_dims {
+            s.equals(&outputs[0].rank, &inputs[0].rank)?;
+        } else {
+            s.equals(&outputs[0].rank, inputs[0].rank.bex() + 1)?;
+        }
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let fact = model.outlet_fact(inputs[0])?.clone();
+        let axis = if self.axis < 0 { self.axis + fact.rank() as i64 } else { self.axis } as usize;
+        let chunk = if let Some(split) = inputs.get(1) {
+            let split = model.outlet_fact(*split)?.konst.clone();
+            Self::chunk_size(Some(
+                split
+                    .as_deref()
+                    .context("Only constant splits are supported in SplitToSequence")?,
+            ))?
+        } else {
+            None
+        };
+        let mut wire = tvec!(inputs[0]);
+        if let Some(chunk) = chunk {
+            let dim = fact.shape[axis].clone();
+            wire = model.wire_node(
+                format!("{}.reshape", prefix),
+                AxisOp::Reshape(
+                    axis,
+                    tvec!(dim.clone()),
+                    tvec!(dim / chunk as u64, chunk.to_dim()),
+                ),
+                &wire,
+            )?;
+        } else if self.keep_dims {
+            wire = model.wire_node(format!("{}.add_axis", prefix), AxisOp::Add(axis + 1), &wire)?;
+        }
+        if axis != 0 {
+            wire =
+                model.wire_node(format!("{}.move_axis", prefix), AxisOp::Move(axis, 0), &wire)?;
+        }
+        Ok(wire)
+    }
+}
+
+#[derive(Debug, Clone, Hash)]
+struct ConcatFromSequence {
+    axis: i64,
+    new_axis: bool,
+}
+impl_dyn_hash!(ConcatFromSequence);
+
+fn concat_from_sequence(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let axis = node.get_attr("axis")?;
+    let new_axis = node.get_attr_opt("new_axis")?.unwrap_or(false);
+    Ok((expand(ConcatFromSequence { axis, new_axis }), vec![]))
+}
+
+impl Expansion for ConcatFromSequence {
+    fn name(&self) -> Cow<str> {
+        "ConcatFromSequence".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        if self.new_axis {
+            s.equals(&outputs[0].rank, &inputs[0].rank)?;
+        } else {
+            s.equals(&outputs[0].rank, inputs[0].rank.bex() - 1)?;
+        }
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let fact = model.outlet_fact(inputs[0])?.clone();
+        let output_rank = fact.rank() as i64 - !self.new_axis as i64;
+        let axis = if self.axis < 0 { self.axis + output_rank } else { self.axis } as usize;
+        let mut wire = tvec!(inputs[0]);
+        if axis != 0 {
+            wire =
+                model.wire_node(format!("{}.move_axis", prefix), AxisOp::Move(0, axis), &wire)?;
+        }
+        if !self.new_axis {
+            let len = fact.shape[0].clone();
+            let dim = fact.shape[axis + 1].clone();
+            let merged = if let Ok(d) = dim.to_i64() {
+                len.clone() * d
+            } else {
+                dim.clone() * len.to_i64()?
+            };
+            wire = model.wire_node(
+                format!("{}.reshape", prefix),
+                AxisOp::Reshape(axis, tvec!(len, dim), tvec!(merged)),
+                &wire,
+            )?;
+        }
+        Ok(wire)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tract_ndarray::arr2;
+
+    #[test]
+    fn split_then_concat() {
+        let input = arr2(&[[1i64, 2, 3, 4], [5, 6, 7, 8]]).into_arc_tensor();
+        let split = expand(SplitToSequence { axis: 1, keep_dims: true });
+        let seq = split.eval(tvec!(input.clone(), rctensor1(&[2i64, 2]))).unwrap();
+        assert_eq!(seq[0].shape(), &[2, 2, 2]);
+        let concat = expand(ConcatFromSequence { axis: 1, new_axis: false });
+        let output = concat.eval(seq).unwrap();
+        assert_eq!(output[0], input);
+    }
+
+    #[test]
+    fn construct_insert_erase() {
+        let a = rctensor1(&[1f32, 2.]);
+        let b = rctensor1(&[3f32, 4.]);
+        let c = rctensor1(&[5f32, 6.]);
+        let seq = expand(SequenceConstruct).eval(tvec!(a, b)).unwrap();
+        let seq = expand(SequenceInsert).eval(tvec!(seq[0].clone(), c, rctensor0(1i64))).unwrap();
+        assert_eq!(*seq[0], arr2(&[[1f32, 2.], [5., 6.], [3., 4.]]).into_tensor());
+        let seq = expand(SequenceErase).eval(tvec!(seq[0].clone())).unwrap();
+        let last = expand(SequenceAt).eval(tvec!(seq[0].clone(), rctensor0(-1i64))).unwrap();
+        assert_eq!(*last[0], tensor1(&[5f32, 6.]));
+        let first = expand(SequenceAt).eval(tvec!(seq[0].clone(), rctensor0(0i64))).unwrap();
+        assert_eq!(*first[0], tensor1(&[1f32, 2.]));
+    }
+
+    #[test]
+    fn uneven_split_is_rejected() {
+        let input = arr2(&[[1i64, 2, 3], [4, 5, 6]]).into_arc_tensor();
+        let split = expand(SplitToSequence { axis: 1, keep_dims: true });
+        assert!(split.eval(tvec!(input, rctensor1(&[2i64, 1]))).is_err());
+    }
+
+    #[test]
+    fn construct_without_input_is_rejected() {
+        let output = InferenceFact::default();
+        assert!(expand(SequenceConstruct).infer(tvec!(), tvec!(&output), tvec!()).is_err());
+    }
+
+    /// Wires an expansion on model sources, so that positions are only known
+    /// at runtime, and runs it.
+    fn run_with_sources(op: &dyn Expansion, inputs: TVec<Tensor>) -> Tensor {
+        let mut model = TypedModel::default();
+        let wires = inputs
+            .iter()
+            .enumerate()
+            .map(|(ix, t)| {
+                model.add_source(
+                    format!("source-{}", ix),
+                    TypedFact::dt_shape(t.datum_type(), t.shape()),
+                )
+            })
+            .collect::<TractResult<TVec<_>>>()
+            .unwrap();
+        let output = op.wire("op", &mut model, &wires).unwrap();
+        model.set_output_outlets(&output).unwrap();
+        SimplePlan::new(model).unwrap().run(inputs).unwrap().remove(0).into_tensor()
+    }
+
+    #[test]
+    fn runtime_positions() {
+        let seq = arr2(&[[1f32, 2.], [3., 4.], [5., 6.]]).into_tensor();
+        let x = tensor1(&[7f32, 8.]);
+        let inserted = [
+            (0i64, arr2(&[[7f32, 8.], [1., 2.], [3., 4.], [5., 6.]])),
+            (3, arr2(&[[1f32, 2.], [3., 4.], [5., 6.], [7., 8.]])),
+            (-1, arr2(&[[1f32, 2.], [3., 4.], [7., 8.], [5., 6.]])),
+        ];
+        for (position, expected) in inserted.iter() {
+            let inputs = tvec!(seq.clone(), x.clone(), tensor0(*position));
+            assert_eq!(run_with_sources(&SequenceInsert, inputs), expected.clone().into_tensor());
+        }
+        let erased = [(1i64, arr2(&[[1f32, 2.], [5., 6.]])), (-1, arr2(&[[1f32, 2.], [3., 4.]]))];
+        for (position, expected) in erased.iter() {
+            let inputs = tvec!(seq.clone(), tensor0(*position));
+            assert_eq!(run_with_sources(&SequenceErase, inputs), expected.clone().into_tensor());
+        }
+    }
+}
@@ -0,0 +1,9 @@
+mod string_normalizer;
+mod tfidf_vectorizer;
+
+use crate::model::OnnxOpRegister;
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert("StringNormalizer", string_normalizer::string_normalizer);
+    reg.insert("TfIdfVectorizer", tfidf_vectorizer::tfidf_vectorizer);
+}
@@ -0,0 +1,150 @@
+use crate::model::ParsingContext;
+use crate::pb::*;
+use tract_hir::internal::*;
+
+pub fn string_normalizer(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let case_change = match node.get_attr_opt("case_change_action")?.unwrap_or("NONE") {
+        "NONE" => None,
+        "LOWER" => Some(CaseChange::Lower),
+        "UPPER" => Some(CaseChange::Upper),
+        other => bail!("Unsupported case_change_action {}", other),
+    };
+    let is_case_sensitive = node.get_attr_opt("is_case_sensitive")?.unwrap_or(false);
+    let stopwords = node.get_attr_opt_vec::<String>("stopwords")?.unwrap_or_default();
+    Ok((Box::new(StringNormalizer::new(case_change, is_case_sensitive, stopwords)), vec![]))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CaseChange {
+    Lower,
+    Upper,
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct StringNormalizer {
+    case_change: Option<CaseChange>,
+    is_case_sensitive: bool,
+    stopwords: Vec<String>,
+    len: Symbol,
+}
+
+impl_dyn_hash!(StringNormalizer);
+
+impl StringNormalizer {
+    pub fn new(
+        case_change: Option<CaseChange>,
+        is_case_sensitive: bool,
+        stopwords: Vec<String>,
+    ) -> StringNormalizer {
+        let stopwords = if is_case_sensitive {
+            stopwords
+        } else {
+            stopwords.iter().map(|s| s.to_lowercase()).collect()
+        };
+        StringNormalizer { case_change, is_case_sensitive, stopwords, len: Symbol::new('w') }
+    }
+
+    fn is_stopword(&self, s: &str) -> bool {
+        if self.is_case_sensitive {
+            self.stopwords.iter().any(|w| w == s)
+        } else {
+            let s = s.to_lowercase();
+            self.stopwords.iter().any(|w| *w == s)
+        }
+    }
+
+    fn change_case(&self, s: &str) -> String {
+        match self.case_change {
+            None => s.to_string(),
+            Some(CaseChange::Lower) => s.to_lowercase(),
+            Some(CaseChange::Upper) => s.to_uppercase(),
+        }
+    }
+}
+
+impl Op for StringNormalizer {
+    fn name(&self) -> Cow<str> {
+        "StringNormalizer".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![
+            format!(
+                "case change: {:?}, case sensitive: {:?}",
+                self.case_change, self.is_case_sensitive
+            ),
+            format!("stopwords: {:?}", self.stopwords),
+        ])
+    }
+
+    op_onnx!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for StringNormalizer {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let mut output: Vec<String> = input
+            .as_slice::<String>()?
+            .iter()
+            .filter(|s| !self.is_stopword(s))
+            .map(|s| self.change_case(s))
+            .collect();
+        if output.is_empty() {
+            output.push(String::new());
+        }
+        let mut shape: TVec<usize> = input.shape().into();
+        *shape.last_mut().context("StringNormalizer expects a 1D or 2D input")? = output.len();
+        Ok(tvec!(tensor1(&output).into_shape(&shape)?.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for StringNormalizer {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, String::datum_type())?;
+        s.equals(&outputs[0].datum_type, String::datum_type())?;
+        s.equals(&inputs[0].rank, &outputs[0].rank)?;
+        s.given(&inputs[0].rank, move |s, rank| {
+            if rank == 2 {
+                s.equals(&inputs[0].shape[0], 1.to_dim())?;
+                s.equals(&outputs[0].shape[0], 1.to_dim())?;
+            } else if rank != 1 {
+                bail!("StringNormalizer expects a 1D or 2D input, got rank {}", rank);
+            }
+            if self.stopwords.is_empty() {
+                s.equals(&inputs[0].shape, &outputs[0].shape)?;
+            }
+            Ok(())
+        })
+    }
+
+    as_op!();
+    to_typed!();
+}
+
+impl TypedOp for StringNormalizer {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let mut shape = inputs[0].shape.to_tvec();
+        if !self.stopwords.is_empty() {
+            *shape.last_mut().context("StringNormalizer expects a 1D or 2D input")? =
+                self.len.to_dim();
+        }
+        Ok(tvec!(TypedFact::dt_shape(String::datum_type(), shape)))
+    }
+
+    as_op!();
+}
@@ -0,0 +1,321 @@
+use crate::model::ParsingContext;
+use crate::pb::*;
+use std::hash::Hash;
+use tract_hir::internal::*;
+
+pub fn tfidf_vectorizer(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let min_gram_length: usize = node.get_attr("min_gram_length")?;
+    let max_gram_length: usize = node.get_attr("max_gram_length")?;
+    let max_skip_count: usize = node.get_attr("max_skip_count")?;
+    node.expect_attr("min_gram_length", min_gram_length > 0, "a strictly positive value")?;
+    node.expect_attr("max_gram_length", min_gram_length <= max_gram_length, "max >= min")?;
+    let mode = match node.get_attr("mode")? {
+        "TF" => Mode::Tf,
+        "IDF" => Mode::Idf,
+        "TFIDF" => Mode::TfIdf,
+        other => bail!("Unsupported TfIdfVectorizer mode {}", other),
+    };
+    let ngram_counts: Vec<usize> = node.get_attr_vec("ngram_counts")?;
+    let ngram_indexes: Vec<usize> = node.get_attr_vec("ngram_indexes")?;
+    let pool = match (
+        node.get_attr_opt_vec::<i64>("pool_int64s")?,
+        node.get_attr_opt_vec::<String>("pool_strings")?,
+    ) {
+        (Some(ints), None) => rctensor1(&ints),
+        (None, Some(strings)) => rctensor1(&strings),
+        _ => bail!("TfIdfVectorizer requires exactly one of pool_int64s and pool_strings"),
+    };
+    let weights = node.get_attr_opt_vec::<f32>("weights")?.map(|w| rctensor1(&w));
+    if let Some(weights) = &weights {
+        node.expect_attr("weights", weights.len() == ngram_indexes.len(), "one weight per n-gram")?;
+    }
+    let op = TfIdfVectorizer::new(
+        min_gram_length,
+        max_gram_length,
+        max_skip_count,
+        mode,
+        &ngram_counts,
+        ngram_indexes,
+        pool,
+        weights,
+    )?;
+    Ok((Box::new(op), vec![]))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Mode {
+    Tf,
+    Idf,
+    TfIdf,
+}
+
+/// The n-gram pool, as a flat list of n-grams along with their length.
+#[derive(Clone, Debug, Hash)]
+pub struct Pool {
+    tokens: Arc<Tensor>,
+    ngrams: Vec<(usize, usize)>,
+}
+
+impl Pool {
+    /// `ngram_counts[i]` is the offset in tokens where the (i+1)-grams start.
+    fn new(tokens: Arc<Tensor>, ngram_counts: &[usize]) -> TractResult<Pool> {
+        let mut ngrams = vec![];
+        for (ix, &start) in ngram_counts.iter().enumerate() {
+            let n = ix + 1;
+            let end = ngram_counts.get(ix + 1).copied().unwrap_or(tokens.len());
+            if start > end || (end - start) % n != 0 {
+                bail!("Inconsistent ngram_counts {:?} for a pool of {}", ngram_counts, tokens.len())
+            }
+            ngrams.extend((start..end).step_by(n).map(|offset| (n, offset)));
+        }
+        Ok(Pool { tokens, ngrams })
+    }
+
+    fn index<T: Datum + Hash + Eq>(&self) -> TractResult<HashMap<&[T], usize>> {
+        let tokens = self.tokens.as_slice::<T>()?;
+        Ok(self
+            .ngrams
+            .iter()
+            .enumerate()
+            .map(|(ix, &(n, offset))| (&tokens[offset..][..n], ix))
+            .collect())
+    }
+}
+
+#[derive(Clone, Debug, Hash)]
+pub struct TfIdfVectorizer {
+    min_gram_length: usize,
+    max_gram_length: usize,
+    max_skip_count: usize,
+    mode: Mode,
+    ngram_indexes: Vec<usize>,
+    pool: Pool,
+    weights: Option<Arc<Tensor>>,
+}
+
+impl_dyn_hash!(TfIdfVectorizer);
+
+impl TfIdfVectorizer {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        min_gram_length: usize,
+        max_gram_length: usize,
+        max_skip_count: usize,
+        mode: Mode,
+        ngram_counts: &[usize],
+        ngram_indexes: Vec<usize>,
+        pool: Arc<Tensor>,
+        weights: Option<Arc<Tensor>>,
+    ) -> TractResult<TfIdfVectorizer> {
+        let pool = Pool::new(pool, ngram_counts)?;
+        if pool.ngrams.len() != ngram_indexes.len() {
+            bail!(
+                "Found {} n-grams in the pool, but {} n-gram indexes",
+                pool.ngrams.len(),
+                ngram_indexes.len()
+            )
+        }
+        Ok(TfIdfVectorizer {
+            min_gram_length,
+            max_gram_length,
+            max_skip_count,
+            mode,
+            ngram_indexes,
+            pool,
+            weights,
+        })
+    }
+
+    fn output_len(&self) -> usize {
+        self.ngram_indexes.iter().max().map(|m| m + 1).unwrap_or(0)
+    }
+
+    fn count_row<T: Datum + Hash + Eq>(
+        &self,
+        index: &HashMap<&[T], usize>,
+        row: &[T],
+        counts: &mut [f32],
+    ) {
+        let mut gram: Vec<T> = Vec::with_capacity(self.max_gram_length);
+        for skip in 0..=self.max_skip_count {
+            let step = skip + 1;
+            for start in 0..row.len() {
+                gram.clear();
+                for n in 1..=self.max_gram_length {
+                    if start + (n - 1) * step >= row.len() {
+                        break;
+                    }
+                    gram.push(row[start + (n - 1) * step].clone());
+                    // unigrams do not depend on the skip count, count them only once
+                    if n < self.min_gram_length || (n == 1 && skip > 0) {
+                        continue;
+                    }
+                    if let Some(&ix) = index.get(&*gram) {
+                        counts[self.ngram_indexes[ix]] += 1.0;
+                    }
+                }
+            }
+        }
+    }
+
+    fn eval_t<T: Datum + Hash + Eq>(&self, input: &Tensor) -> TractResult<Tensor> {
+        let index = self.pool.index::<T>()?;
+        let input = input.to_array_view::<T>()?;
+        let (rows, row_len) = match input.shape() {
+            &[len] => (1, len),
+            &[rows, len] => (rows, len),
+            _ => bail!("TfIdfVectorizer expects a 1D or 2D input"),
+        };
+        let mut output_shape: TVec<usize> = input.shape().into();
+        output_shape[input.ndim() - 1] = self.output_len();
+        let mut output = tract_ndarray::Array2::<f32>::zeros((rows, self.output_len()));
+        let input = input.into_shape((rows, row_len))?;
+        for (row, mut counts) in input.outer_iter().zip(output.outer_iter_mut()) {
+            self.count_row(&index, row.as_slice().unwrap(), counts.as_slice_mut().unwrap());
+        }
+        if self.mode != Mode::Tf {
+            let weights = self.weights.as_ref().map(|w| w.as_slice::<f32>()).transpose()?;
+            for mut counts in output.outer_iter_mut() {
+                for (ix, &out) in self.ngram_indexes.iter().enumerate() {
+                    let weight = weights.map(|w| w[ix]).unwrap_or(1.0);
+                    let c = &mut counts[out];
+                    *c = match self.mode {
+                        Mode::Idf if *c > 0.0 => weight,
+                        Mode::Idf => 0.0,
+                        _ => *c * weight,
+                    };
+                }
+            }
+        }
+        Ok(output.into_shape(&*output_shape)?.into_tensor())
+    }
+}
+
+impl Op for TfIdfVectorizer {
+    fn name(&self) -> Cow<str> {
+        "TfIdfVectorizer".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![
+            format!(
+                "mode: {:?}, grams: {}..={}, max skip: {}",
+                self.mode, self.min_gram_length, self.max_gram_length, self.max_skip_count
+            ),
+            format!("pool: {} n-grams, output len: {}", self.pool.ngrams.len(), self.output_len()),
+        ])
+    }
+
+    op_onnx!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for TfIdfVectorizer {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let output = if self.pool.tokens.datum_type() == String::datum_type() {
+            self.eval_t::<String>(&input)?
+        } else {
+            self.eval_t::<i64>(&*input.cast_to::<i64>()?)?
+        };
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for TfIdfVectorizer {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        s.equals(&inputs[0].rank, &outputs[0].rank)?;
+        s.given(&inputs[0].rank, move |s, rank| {
+            if rank == 2 {
+                s.equals(&inputs[0].shape[0], &outputs[0].shape[0])?;
+            } else if rank != 1 {
+                bail!("TfIdfVectorizer expects a 1D or 2D input, got rank {}", rank);
+            }
+            s.equals(&outputs[0].shape[rank as usize - 1], self.output_len().to_dim())
+        })
+    }
+
+    as_op!();
+    to_typed!();
+}
+
+impl TypedOp for TfIdfVectorizer {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let mut shape = inputs[0].shape.to_tvec();
+        *shape.last_mut().context("TfIdfVectorizer expects a 1D or 2D input")? =
+            self.output_len().to_dim();
+        Ok(tvec!(TypedFact::dt_shape(f32::datum_type(), shape)))
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tract_ndarray::arr2;
+
+    fn vectorizer(min_gram_length: usize) -> TfIdfVectorizer {
+        TfIdfVectorizer::new(
+            min_gram_length,
+            2,
+            5,
+            Mode::Tf,
+            &[0, 4],
+            (0..7).collect(),
+            rctensor1(&[2i64, 3, 5, 4, 5, 6, 7, 8, 6, 7]),
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn tf_batch_uniandbigrams_skip5() {
+        let input = arr2(&[[1i32, 1, 3, 3, 3, 7], [8, 6, 7, 5, 6, 8]]).into_arc_tensor();
+        let output = vectorizer(1).eval(tvec!(input)).unwrap();
+        let expected = arr2(&[[0f32, 3., 0., 0., 0., 0., 0.], [0., 0., 1., 0., 1., 1., 1.]]);
+        assert_eq!(*output[0], expected.into_tensor());
+    }
+
+    #[test]
+    fn tf_batch_onlybigrams_skip5() {
+        let input = arr2(&[[1i32, 1, 3, 3, 3, 7], [8, 6, 7, 5, 6, 8]]).into_arc_tensor();
+        let output = vectorizer(2).eval(tvec!(input)).unwrap();
+        let expected = arr2(&[[0f32, 0., 0., 0., 0., 0., 0.], [0., 0., 0., 0., 1., 1., 1.]]);
+        assert_eq!(*output[0], expected.into_tensor());
+    }
+
+    #[test]
+    fn tfidf_strings() {
+        let op = TfIdfVectorizer::new(
+            1,
+            1,
+            0,
+            Mode::TfIdf,
+            &[0],
+            vec![1, 0],
+            rctensor1(&["a".to_string(), "b".to_string()]),
+            Some(rctensor1(&[0.5f32, 2.0])),
+        )
+        .unwrap();
+        let input =
+            rctensor1(&["b", "a", "b", "c"].iter().map(|s| s.to_string()).collect::<Vec<_>>());
+        let output = op.eval(tvec!(input)).unwrap();
+        assert_eq!(*output[0], tensor1(&[4f32, 0.5]));
+    }
+}
@@ -58,6 +58,34 @@ impl TryFrom<type_proto::Tensor> for InferenceFact {
     }
 }
 
+/// Sequences are represented as a single tensor, their elements being stacked
+/// along an extra leading axis (see `ops::sequence`): the elements of a
+/// sequence must share a shape. Maps have no tensor counterpart, so their facts
+/// are left undetermined.
+impl<'a> TryFrom<&'a TypeProto> for InferenceFact {
+    type Error = TractError;
+    fn try_from(t: &'a TypeProto) -> TractResult<InferenceFact> {
+        match &t.value {
+            Some(type_proto::Value::TensorType(t)) => t.try_into(),
+            Some(type_proto::Value::SequenceType(s)) => {
+                let mut fact: InferenceFact = match s.elem_type.as_deref() {
+                    Some(elem) => elem.try_into()?,
+                    None => InferenceFact::default(),
+                };
+                let dims: TVec<DimFact> =
+                    std::iter::once(DimFact::default()).chain(fact.shape.dims().cloned()).collect();
+                fact.shape = if fact.shape.is_open() {
+                    ShapeFactoid::open(dims)
+                } else {
+                    ShapeFactoid::closed(dims)
+                };
+                Ok(fact)
+            }
+            Some(type_proto::Value::MapType(_)) | None => Ok(InferenceFact::default()),
+        }
+    }
+}
+
 impl<'a> TryFrom<&'a TensorProto> for Tensor {
     type Error = TractError;
     fn try_from(t: &TensorProto) -> TractResult<Tensor> {
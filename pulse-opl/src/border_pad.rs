@@ -0,0 +1,232 @@
+use tract_core::ndarray::*;
+use tract_core::ops::array::PadMode;
+use tract_nnef::internal::*;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_primitive(
+        "tract_pulse_border_pad",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Integer.named("axis"),
+            TypeName::Integer.named("before"),
+            TypeName::Integer.named("after"),
+            TypeName::Integer.named("begin_input"),
+            TypeName::Integer.tensor().named("end_input"),
+            TypeName::Integer.named("overlap"),
+            TypeName::Integer.named("delay"),
+            TypeName::String.named("border"),
+        ],
+        de_border_pad,
+    );
+}
+
+fn de_border_pad(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let wire = invocation.named_arg_as(builder, "input")?;
+    let axis = invocation.named_arg_as::<i64>(builder, "axis")? as usize;
+    let before = invocation.named_arg_as::<i64>(builder, "before")? as usize;
+    let after = invocation.named_arg_as::<i64>(builder, "after")? as usize;
+    let begin_input = invocation.named_arg_as::<i64>(builder, "begin_input")? as usize;
+    let end_input =
+        invocation.named_arg_as::<Arc<Tensor>>(builder, "end_input")?.to_scalar::<TDim>()?.clone();
+    let overlap = invocation.named_arg_as::<i64>(builder, "overlap")? as usize;
+    let delay = invocation.named_arg_as::<i64>(builder, "delay")? as usize;
+    let border = invocation.named_arg_as::<String>(builder, "border")?;
+    let mode = match &*border {
+        "replicated" => PadMode::Edge,
+        "reflect" => PadMode::Reflect,
+        "symmetric" => PadMode::Symmetric,
+        _ => bail!("Unsupported pulsed border padding mode {}", border),
+    };
+    let op = PulseBorderPad { axis, before, after, begin_input, end_input, overlap, delay, mode };
+    builder.wire(op, &[wire])
+}
+
+#[derive(Debug, Clone, Default, Hash)]
+struct PulseBorderPadOpState {
+    positions: crate::SlotPositions,
+}
+
+impl OpState for PulseBorderPadOpState {
+    fn eval(
+        &mut self,
+        session: &mut SessionState,
+        op: &dyn Op,
+        mut inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs).into_tensor();
+        let op = op.downcast_ref::<PulseBorderPad>().ok_or_else(|| format_err!("Wrong Op type"))?;
+        let pulse = input.shape()[op.axis] - op.overlap;
+        let input_len = op
+            .end_input
+            .eval(&session.resolved_symbols)
+            .to_usize()
+            .ok()
+            .map(|end| end.saturating_sub(op.begin_input));
+        let output = self.positions.run(input, pulse, |input, pulse_begin, _| unsafe {
+            dispatch_copy_by_size!(Self::pad(input.datum_type())(
+                op,
+                &input,
+                pulse_begin,
+                pulse,
+                input_len
+            ))
+        })?;
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+
+    fn save(
+        &self,
+        _op: &dyn Op,
+        prefix: &str,
+        tensors: &mut Vec<(String, Tensor)>,
+    ) -> TractResult<()> {
+        self.positions.save(tensors, prefix);
+        Ok(())
+    }
+
+    fn load(
+        &mut self,
+        _op: &dyn Op,
+        prefix: &str,
+        tensors: &mut HashMap<String, Tensor>,
+    ) -> TractResult<()> {
+        self.positions = crate::SlotPositions::load(tensors, prefix)?;
+        Ok(())
+    }
+
+    fn reset_batch_slot(
+        &mut self,
+        _op: &dyn Op,
+        inputs: &[&TypedFact],
+        batch: Symbol,
+        slot: usize,
+    ) -> TractResult<()> {
+        self.positions.reset(inputs, batch, slot)
+    }
+}
+
+impl PulseBorderPadOpState {
+    unsafe fn pad<T: Datum + Copy>(
+        op: &PulseBorderPad,
+        input: &Tensor,
+        pulse_begin: usize,
+        pulse: usize,
+        input_len: Option<usize>,
+    ) -> TractResult<Tensor> {
+        let dt = input.datum_type();
+        let input = input.to_array_view_unchecked::<T>();
+        let skip = op.overlap - op.delay;
+        let mut output = input.slice_axis(Axis(op.axis), (skip..skip + pulse).into()).to_owned();
+        // index in the padded output of the first frame of the pulse
+        let output_begin =
+            (pulse_begin + op.before) as isize - (op.begin_input + op.delay) as isize;
+        for frame in 0..pulse {
+            let j = output_begin + frame as isize;
+            if j < 0 {
+                continue;
+            }
+            let j = j as usize;
+            let source = if j < op.before {
+                match op.mode {
+                    PadMode::Edge => 0,
+                    PadMode::Reflect => op.before - j,
+                    _ => op.before - 1 - j,
+                }
+            } else if let Some(len) = input_len.filter(|&len| j >= op.before + len) {
+                let t = j - op.before - len;
+                if t >= op.after {
+                    continue;
+                }
+                let source = match op.mode {
+                    PadMode::Edge => len.checked_sub(1),
+                    PadMode::Reflect => len.checked_sub(2 + t),
+                    _ => len.checked_sub(1 + t),
+                };
+                source.ok_or_else(|| {
+                    format_err!("Padding {:?} is too big for a stream of {} frames", op.mode, len)
+                })?
+            } else {
+                continue;
+            };
+            // position of the source frame in the window
+            let ix = (op.begin_input + source + op.overlap) as isize - pulse_begin as isize;
+            if ix < 0 || ix as usize >= pulse + op.overlap {
+                bail!("Frame {} of the stream is not in the pulse window", source);
+            }
+            output
+                .index_axis_mut(Axis(op.axis), frame)
+                .assign(&input.index_axis(Axis(op.axis), ix as usize));
+        }
+        let mut output = output.into_tensor();
+        output.set_datum_type(dt);
+        Ok(output)
+    }
+}
+
+/// Pads the stream with some of its own frames (edge, reflect or symmetric
+/// modes).
+///
+/// The input is a window made of the last `overlap` frames followed by the
+/// current pulse (as output by a Delay with overlap), and the output is
+/// further delayed by `delay` frames, so the frames to copy at the beginning
+/// of the stream are already there, and the ones to copy at the end are still
+/// in the window.
+#[derive(Debug, Clone, Default, Hash)]
+pub struct PulseBorderPad {
+    pub axis: usize,
+    pub before: usize,
+    pub after: usize,
+    pub begin_input: usize,
+    pub end_input: TDim,
+    pub overlap: usize,
+    pub delay: usize,
+    pub mode: PadMode,
+}
+
+impl_dyn_hash!(PulseBorderPad);
+
+impl Op for PulseBorderPad {
+    fn name(&self) -> Cow<str> {
+        "PulseBorderPad".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![
+            format!(
+                "Mode: {:?}, axis: {} before: {} after: {}",
+                self.mode, self.axis, self.before, self.after,
+            ),
+            format!("overlap: {} delay: {}", self.overlap, self.delay),
+        ])
+    }
+
+    op_pulse!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for PulseBorderPad {
+    fn is_stateless(&self) -> bool {
+        false
+    }
+
+    fn state(
+        &self,
+        _session: &mut SessionState,
+        _node_id: usize,
+    ) -> TractResult<Option<Box<dyn OpState>>> {
+        Ok(Some(Box::new(PulseBorderPadOpState::default())))
+    }
+}
+
+impl TypedOp for PulseBorderPad {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let mut fact = inputs[0].clone();
+        fact.shape.set(self.axis, fact.shape[self.axis].clone() - self.overlap.to_dim());
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+}
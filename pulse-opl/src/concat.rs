@@ -2,14 +2,44 @@ use std::ops::Range;
 use tract_core::ndarray::*;
 use tract_nnef::internal::*;
 
+pub fn register(registry: &mut Registry) {
+    registry.register_primitive(
+        "tract_pulse_same_axis_concat",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Integer.named("axis"),
+            TypeName::Scalar.tensor().named("pre"),
+            TypeName::Scalar.tensor().named("post"),
+            TypeName::Integer.named("input_delay"),
+            TypeName::Integer.tensor().named("input_len"),
+        ],
+        de_same_axis_concat,
+    );
+}
+
+fn de_same_axis_concat(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let wire = invocation.named_arg_as(builder, "input")?;
+    let axis = invocation.named_arg_as::<i64>(builder, "axis")? as usize;
+    let pre_slice = invocation.named_arg_as::<Arc<Tensor>>(builder, "pre")?.into_tensor();
+    let post_slice = invocation.named_arg_as::<Arc<Tensor>>(builder, "post")?.into_tensor();
+    let input_delay = invocation.named_arg_as::<i64>(builder, "input_delay")? as usize;
+    let input_len =
+        invocation.named_arg_as::<Arc<Tensor>>(builder, "input_len")?.to_scalar::<TDim>()?.clone();
+    let op = PulsedSameAxisConcat { axis, pre_slice, post_slice, input_delay, input_len };
+    builder.wire(op, &[wire])
+}
+
 /// Concat with pulse along concat axis
 #[derive(Debug, Clone, Hash)]
 pub struct PulsedSameAxisConcat {
-    axis: usize,
-    pre_slice: Tensor,
-    post_slice: Tensor,
-    input_delay: usize,
-    input_len: TDim,
+    pub axis: usize,
+    pub pre_slice: Tensor,
+    pub post_slice: Tensor,
+    pub input_delay: usize,
+    pub input_len: TDim,
 }
 impl_dyn_hash!(PulsedSameAxisConcat);
 
@@ -24,7 +54,7 @@ impl Op for PulsedSameAxisConcat {
 
 impl EvalOp for PulsedSameAxisConcat {
     fn is_stateless(&self) -> bool {
-        true
+        false
     }
 
     fn state(
@@ -32,7 +62,9 @@ impl EvalOp for PulsedSameAxisConcat {
         _session: &mut SessionState,
         _node_id: usize,
     ) -> TractResult<Option<Box<dyn OpState>>> {
-        return Ok(Some(Box::new(PulsedSameAxisConcatState::default())));
+        let symbols_in_dim = self.input_len.symbols().into_iter().collect();
+        let positions = crate::SlotPositions::default();
+        return Ok(Some(Box::new(PulsedSameAxisConcatState { positions, symbols_in_dim })));
     }
 }
 
@@ -44,9 +76,10 @@ impl TypedOp for PulsedSameAxisConcat {
     }
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct PulsedSameAxisConcatState {
-    current_pos: usize,
+    positions: crate::SlotPositions,
+    symbols_in_dim: Vec<Symbol>,
 }
 
 impl OpState for PulsedSameAxisConcatState {
@@ -60,51 +93,83 @@ impl OpState for PulsedSameAxisConcatState {
             .downcast_ref::<PulsedSameAxisConcat>()
             .ok_or_else(|| format_err!("Wrong Op type"))?;
         let input = args_1!(inputs);
-        let mut data = input.into_tensor();
+        let data = input.into_tensor();
         let pulse = data.shape()[op.axis];
-        let current_pos = self.current_pos;
-        self.current_pos += pulse;
-
-        unsafe {
+        let PulsedSameAxisConcatState { positions, symbols_in_dim } = self;
+        // the constant parts can not depend on the batch size: they are the
+        // same for all the streams
+        let data = positions.run(data, pulse, |mut data, current_pos, _| {
             let pre_length = op.pre_slice.shape()[op.axis];
             let pre_offset = op.input_delay - pre_length;
-            dispatch_datum_by_size!(overwrite_part_of_pulse(data.datum_type())(
+            dispatch_datum!(overwrite_part_of_pulse(data.datum_type())(
                 op.axis,
                 &mut data,
                 current_pos,
                 &op.pre_slice,
                 pre_offset
-            ));
-            if let Ok(l) = op.input_len.eval(&session.resolved_symbols).to_usize() {
+            ))?;
+            if symbols_in_dim.iter().all(|s| session.resolved_symbols[*s].is_some()) {
+                let l = op.input_len.eval(&session.resolved_symbols).to_usize().unwrap();
                 let post_offset = op.input_delay + l as usize;
-                dispatch_datum_by_size!(overwrite_part_of_pulse(data.datum_type())(
+                dispatch_datum!(overwrite_part_of_pulse(data.datum_type())(
                     op.axis,
                     &mut data,
                     current_pos,
                     &op.post_slice,
                     post_offset
-                ));
+                ))?;
             }
-        }
+            Ok(data)
+        })?;
 
         return Ok(tvec!(data.into_arc_tensor()));
     }
+
+    fn save(
+        &self,
+        _op: &dyn Op,
+        prefix: &str,
+        tensors: &mut Vec<(String, Tensor)>,
+    ) -> TractResult<()> {
+        self.positions.save(tensors, prefix);
+        Ok(())
+    }
+
+    fn load(
+        &mut self,
+        _op: &dyn Op,
+        prefix: &str,
+        tensors: &mut HashMap<String, Tensor>,
+    ) -> TractResult<()> {
+        self.positions = crate::SlotPositions::load(tensors, prefix)?;
+        Ok(())
+    }
+
+    fn reset_batch_slot(
+        &mut self,
+        _op: &dyn Op,
+        inputs: &[&TypedFact],
+        batch: Symbol,
+        slot: usize,
+    ) -> TractResult<()> {
+        self.positions.reset(inputs, batch, slot)
+    }
 }
 
-unsafe fn overwrite_part_of_pulse<T: Datum>(
+pub fn overwrite_part_of_pulse<T: Datum>(
     axis: usize,
     pulse_data: &mut Tensor,
     current_pos: usize,
     const_data: &Tensor,
     const_offset: usize,
-) {
+) -> TractResult<()> {
     let pulse = pulse_data.shape()[axis];
     let const_length = const_data.shape()[axis];
     let const_range = const_offset..const_offset + const_length;
     let pulse_range = current_pos..current_pos + pulse;
     let axis = Axis(axis);
-    let mut pulse_data = pulse_data.to_array_view_mut_unchecked::<T>();
-    let const_data = const_data.to_array_view_unchecked::<T>();
+    let mut pulse_data = pulse_data.to_array_view_mut::<T>()?;
+    let const_data = const_data.to_array_view::<T>()?;
 
     match range_in_range(&pulse_range, &const_range) {
         RangeInRange::Before(_) | RangeInRange::After(_) => (),
@@ -131,6 +196,7 @@ unsafe fn overwrite_part_of_pulse<T: Datum>(
                 .assign(&const_data.slice_axis(axis, (offset..const_length).into()));
         }
     }
+    Ok(())
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -0,0 +1,181 @@
+use tract_core::ndarray::*;
+use tract_nnef::internal::*;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_primitive(
+        "tract_pulse_cum_sum",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Integer.named("axis"),
+            TypeName::Logical.named("exclusive"),
+            TypeName::Integer.named("begin"),
+        ],
+        de_cum_sum,
+    );
+}
+
+fn de_cum_sum(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let wire = invocation.named_arg_as(builder, "input")?;
+    let axis = invocation.named_arg_as::<i64>(builder, "axis")? as usize;
+    let exclusive = invocation.named_arg_as::<bool>(builder, "exclusive")?;
+    let begin = invocation.named_arg_as::<i64>(builder, "begin")? as usize;
+    builder.wire(PulseCumSum { axis, exclusive, begin }, &[wire])
+}
+
+#[derive(Debug, Clone, Default)]
+struct PulseCumSumState {
+    positions: crate::SlotPositions,
+    acc: Option<Tensor>,
+}
+
+impl OpState for PulseCumSumState {
+    fn eval(
+        &mut self,
+        _session: &mut SessionState,
+        op: &dyn Op,
+        mut inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let mut input = args_1!(inputs).into_tensor();
+        let op = op.downcast_ref::<PulseCumSum>().ok_or_else(|| format_err!("Wrong Op type"))?;
+        let pulse = input.shape()[op.axis];
+        let PulseCumSumState { positions, acc } = self;
+        // the position only matters for the frames before the stream begins
+        if op.begin == 0 {
+            dispatch_numbers!(Self::accumulate(input.datum_type())(op, &mut input, 0, acc))?;
+            positions.skip(pulse);
+        } else {
+            input = positions.run(input, pulse, |mut input, pos, slot| {
+                let dt = input.datum_type();
+                match slot {
+                    Some(slot) => slot.with_state(acc, |acc| {
+                        dispatch_numbers!(Self::accumulate(dt)(op, &mut input, pos, acc))
+                    })?,
+                    None => dispatch_numbers!(Self::accumulate(dt)(op, &mut input, pos, acc))?,
+                }
+                Ok(input)
+            })?;
+        }
+        Ok(tvec!(input.into_arc_tensor()))
+    }
+
+    fn save(
+        &self,
+        _op: &dyn Op,
+        prefix: &str,
+        tensors: &mut Vec<(String, Tensor)>,
+    ) -> TractResult<()> {
+        self.positions.save(tensors, prefix);
+        if let Some(acc) = &self.acc {
+            tensors.push((format!("{}acc", prefix), acc.clone()));
+        }
+        Ok(())
+    }
+
+    fn load(
+        &mut self,
+        _op: &dyn Op,
+        prefix: &str,
+        tensors: &mut HashMap<String, Tensor>,
+    ) -> TractResult<()> {
+        self.positions = crate::SlotPositions::load(tensors, prefix)?;
+        self.acc = tensors.remove(&format!("{}acc", prefix));
+        Ok(())
+    }
+
+    fn reset_batch_slot(
+        &mut self,
+        _op: &dyn Op,
+        inputs: &[&TypedFact],
+        batch: Symbol,
+        slot: usize,
+    ) -> TractResult<()> {
+        if let Some(acc) = &mut self.acc {
+            zero_batch_slot(acc, batch_axis(inputs[0], batch)?, slot)?;
+        }
+        self.positions.reset(inputs, batch, slot)
+    }
+}
+
+impl PulseCumSumState {
+    fn accumulate<T: Datum + Copy + tract_num_traits::Zero>(
+        op: &PulseCumSum,
+        input: &mut Tensor,
+        pulse_begin: usize,
+        acc: &mut Option<Tensor>,
+    ) -> TractResult<()> {
+        let pulse = input.shape()[op.axis];
+        if acc.is_none() {
+            let mut shape: TVec<usize> = input.shape().into();
+            shape[op.axis] = 1;
+            *acc = Some(Tensor::zero::<T>(&shape)?);
+        }
+        let mut acc = acc.as_mut().unwrap().to_array_view_mut::<T>()?;
+        let mut acc = acc.index_axis_mut(Axis(op.axis), 0);
+        let mut input = input.to_array_view_mut::<T>()?;
+        // frames before the beginning of the stream are left out of the sum
+        for frame in op.begin.saturating_sub(pulse_begin).min(pulse)..pulse {
+            let mut frame = input.index_axis_mut(Axis(op.axis), frame);
+            Zip::from(&mut frame).and(&mut acc).for_each(|x, acc| {
+                let value = *x;
+                if op.exclusive {
+                    *x = *acc;
+                    *acc = *acc + value;
+                } else {
+                    *acc = *acc + value;
+                    *x = *acc;
+                }
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Cumulative sum along the streaming axis, carrying the running sum from a
+/// pulse to the next. `begin` is the position of the first frame of the
+/// stream.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PulseCumSum {
+    pub axis: usize,
+    pub exclusive: bool,
+    pub begin: usize,
+}
+
+impl_dyn_hash!(PulseCumSum);
+
+impl Op for PulseCumSum {
+    fn name(&self) -> Cow<str> {
+        "PulseCumSum".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("axis: {} exclusive: {} begin: {}", self.axis, self.exclusive, self.begin)])
+    }
+
+    op_pulse!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for PulseCumSum {
+    fn is_stateless(&self) -> bool {
+        false
+    }
+
+    fn state(
+        &self,
+        _session: &mut SessionState,
+        _node_id: usize,
+    ) -> TractResult<Option<Box<dyn OpState>>> {
+        Ok(Some(Box::new(PulseCumSumState::default())))
+    }
+}
+
+impl TypedOp for PulseCumSum {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(inputs[0].without_value()))
+    }
+}
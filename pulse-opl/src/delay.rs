@@ -45,12 +45,18 @@ impl OpState for DelayState {
         let output_pulse = input_pulse + op.overlap;
         let mut output_shape: TVec<usize> = input.shape().into();
         output_shape[op.axis] = output_pulse;
+        let mut buffer_shape: TVec<usize> = input.shape().into();
+        buffer_shape[op.axis] = buffered;
+        if self.buffer.rank() > 0
+            && (self.buffer.datum_type() != input.datum_type()
+                || self.buffer.shape() != &*buffer_shape)
+        {
+            bail!("Buffer {:?} does not fit input {:?}", self.buffer, input)
+        }
         // build output
         unsafe {
             if self.buffer.rank() == 0 {
-                let mut shape = input.shape().to_owned();
-                shape[op.axis] = buffered;
-                self.buffer = Tensor::uninitialized_dt(input.datum_type(), &shape)?;
+                self.buffer = Tensor::uninitialized_dt(input.datum_type(), &buffer_shape)?;
             }
             let mut output = Tensor::uninitialized_dt(input.datum_type(), &*output_shape)?;
             if op.delay < input_pulse {
@@ -79,6 +85,46 @@ impl OpState for DelayState {
             Ok(tvec!(output))
         }
     }
+
+    fn save(
+        &self,
+        _op: &dyn Op,
+        prefix: &str,
+        tensors: &mut Vec<(String, Tensor)>,
+    ) -> TractResult<()> {
+        tensors.push((format!("{}buffer", prefix), self.buffer.clone()));
+        Ok(())
+    }
+
+    fn load(
+        &mut self,
+        op: &dyn Op,
+        prefix: &str,
+        tensors: &mut HashMap<String, Tensor>,
+    ) -> TractResult<()> {
+        let op = op.downcast_ref::<Delay>().ok_or_else(|| format_err!("Wrong Op type"))?;
+        let buffer = take_state_tensor(tensors, prefix, "buffer")?;
+        // a scalar is the placeholder of a buffer not allocated yet
+        if buffer.rank() > 0 && !op.fits_buffer(&buffer) {
+            bail!("Buffer {:?} does not fit {:?}", buffer, op)
+        }
+        self.buffer = buffer;
+        Ok(())
+    }
+
+    fn reset_batch_slot(
+        &mut self,
+        _op: &dyn Op,
+        inputs: &[&TypedFact],
+        batch: Symbol,
+        slot: usize,
+    ) -> TractResult<()> {
+        // the buffer is only allocated at the first pulse
+        if self.buffer.rank() > 0 {
+            zero_batch_slot(&mut self.buffer, batch_axis(inputs[0], batch)?, slot)?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Hash)]
@@ -110,6 +156,22 @@ impl Delay {
         buffer_shape[axis] = (delay + overlap).to_dim();
         Ok(Delay { datum_type: input_fact.datum_type, buffer_shape, axis, delay, overlap })
     }
+
+    /// Checks a buffer against the datum type and the known dimensions of
+    /// the buffer shape.
+    fn fits_buffer(&self, buffer: &Tensor) -> bool {
+        buffer.datum_type() == self.datum_type
+            && buffer.rank() == self.buffer_shape.len()
+            && buffer.shape().iter().zip(self.buffer_shape.iter()).enumerate().all(
+                |(axis, (found, expected))| {
+                    if axis == self.axis {
+                        *found == self.delay + self.overlap
+                    } else {
+                        expected.to_usize().map(|d| d == *found).unwrap_or(true)
+                    }
+                },
+            )
+    }
 }
 
 impl Op for Delay {
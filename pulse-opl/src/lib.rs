@@ -3,8 +3,12 @@ use tract_nnef::internal::*;
 #[macro_use]
 mod macros;
 
+mod border_pad;
 mod concat;
+mod cum_sum;
 mod delay;
+mod mask;
+mod overlap_add;
 mod pad;
 
 pub use tract_nnef;
@@ -16,7 +20,12 @@ pub mod prelude {
 }
 
 pub mod ops {
+    pub use super::border_pad::PulseBorderPad;
+    pub use super::concat::PulsedSameAxisConcat;
+    pub use super::cum_sum::PulseCumSum;
     pub use super::delay::Delay;
+    pub use super::mask::PulseMask;
+    pub use super::overlap_add::OverlapAdd;
     pub use super::pad::PulsePad;
 }
 
@@ -32,8 +41,140 @@ impl WithPulse for tract_nnef::framework::Nnef {
     }
 }
 
+/// Position in the stream of a pulsed op state, for each slot of a batch of
+/// streams. All the slots share the same position until one of them is reset
+/// for a new stream.
+#[derive(Debug, Clone, Default, Hash)]
+pub(crate) struct SlotPositions {
+    /// position of the slots that have never been reset
+    shared: usize,
+    /// position of each slot once one has been reset, the slots past its end
+    /// being at the shared position
+    slots: Vec<usize>,
+    /// batch axis of the op input, once a slot has been reset
+    batch_axis: Option<usize>,
+}
+
+/// One slot of a batch of streams, along `axis` of the op input.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BatchSlot {
+    pub axis: usize,
+    pub slot: usize,
+    pub slots: usize,
+}
+
+impl BatchSlot {
+    /// Runs `f` on the part of the batched `state` tensor belonging to the
+    /// slot, allocating the state of the whole batch when `f` creates it.
+    pub fn with_state<R>(
+        &self,
+        state: &mut Option<Tensor>,
+        f: impl FnOnce(&mut Option<Tensor>) -> TractResult<R>,
+    ) -> TractResult<R> {
+        let mut part =
+            state.as_ref().map(|s| s.slice(self.axis, self.slot, self.slot + 1)).transpose()?;
+        let result = f(&mut part)?;
+        if let Some(part) = part {
+            if state.is_none() {
+                let mut shape: TVec<usize> = part.shape().into();
+                shape[self.axis] = self.slots;
+                *state = Some(Tensor::zero_dt(part.datum_type(), &shape)?);
+            }
+            state.as_mut().unwrap().assign_slice(self.slot..self.slot + 1, &part, .., self.axis)?;
+        }
+        Ok(result)
+    }
+}
+
+impl SlotPositions {
+    /// Runs `f` on the frames of each stream of `input` with its position,
+    /// and concatenates the outputs along the batch axis. Streams all at the
+    /// same position are run at once, as a single stream.
+    pub fn run(
+        &mut self,
+        input: Tensor,
+        pulse: usize,
+        mut f: impl FnMut(Tensor, usize, Option<BatchSlot>) -> TractResult<Tensor>,
+    ) -> TractResult<Tensor> {
+        let output = match self.batch_axis {
+            Some(axis) => {
+                let slots = input.shape()[axis];
+                if self.slots.len() < slots {
+                    self.slots.resize(slots, self.shared);
+                }
+                let positions = &self.slots[..slots];
+                if positions.iter().all(|&pos| pos == positions[0]) {
+                    f(input, positions[0], None)?
+                } else {
+                    let outputs = positions
+                        .iter()
+                        .enumerate()
+                        .map(|(slot, &pos)| {
+                            let frames = input.slice(axis, slot, slot + 1)?;
+                            f(frames, pos, Some(BatchSlot { axis, slot, slots }))
+                        })
+                        .collect::<TractResult<Vec<_>>>()?;
+                    Tensor::stack_tensors(axis, &outputs)?
+                }
+            }
+            None => f(input, self.shared, None)?,
+        };
+        self.skip(pulse);
+        Ok(output)
+    }
+
+    /// Moves all the streams `pulse` frames forward.
+    pub fn skip(&mut self, pulse: usize) {
+        self.shared += pulse;
+        self.slots.iter_mut().for_each(|pos| *pos += pulse);
+    }
+
+    /// Restarts the stream of `slot` from the beginning.
+    pub fn reset(&mut self, inputs: &[&TypedFact], batch: Symbol, slot: usize) -> TractResult<()> {
+        self.batch_axis = Some(batch_axis(inputs[0], batch)?);
+        if self.slots.len() <= slot {
+            self.slots.resize(slot + 1, self.shared);
+        }
+        self.slots[slot] = 0;
+        Ok(())
+    }
+
+    /// Saves the positions as state tensors of `prefix`.
+    pub fn save(&self, tensors: &mut Vec<(String, Tensor)>, prefix: &str) {
+        tensors.push((format!("{}current_pos", prefix), tensor0(self.shared as i64)));
+        if let Some(axis) = self.batch_axis {
+            tensors.push((format!("{}batch_axis", prefix), tensor0(axis as i64)));
+            let slots: Vec<i64> = self.slots.iter().map(|&pos| pos as i64).collect();
+            tensors.push((format!("{}slot_pos", prefix), tensor1(&slots)));
+        }
+    }
+
+    /// Loads the positions saved by `save`.
+    pub fn load(tensors: &mut HashMap<String, Tensor>, prefix: &str) -> TractResult<SlotPositions> {
+        let shared =
+            *take_state_tensor(tensors, prefix, "current_pos")?.to_scalar::<i64>()? as usize;
+        let batch_axis = tensors
+            .remove(&format!("{}batch_axis", prefix))
+            .map(|axis| axis.to_scalar::<i64>().map(|&axis| axis as usize))
+            .transpose()?;
+        let slots = if batch_axis.is_some() {
+            let slots = take_state_tensor(tensors, prefix, "slot_pos")?;
+            slots.as_slice::<i64>()?.iter().map(|&pos| pos as usize).collect()
+        } else {
+            vec![]
+        };
+        Ok(SlotPositions { shared, slots, batch_axis })
+    }
+}
+
 pub fn tract_nnef_registry() -> Registry {
     let mut reg = Registry::new("pulse");
+    border_pad::register(&mut reg);
+    concat::register(&mut reg);
+    cum_sum::register(&mut reg);
     delay::register(&mut reg);
+    mask::register(&mut reg);
+    overlap_add::register(&mut reg);
+    pad::register(&mut reg);
     reg
 }
@@ -0,0 +1,179 @@
+use tract_core::ndarray::*;
+use tract_nnef::internal::*;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_primitive(
+        "tract_pulse_mask",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Integer.named("axis"),
+            TypeName::Integer.named("begin"),
+            TypeName::Integer.tensor().named("end"),
+            TypeName::Scalar.tensor().named("value"),
+        ],
+        de_pulse_mask,
+    );
+}
+
+fn de_pulse_mask(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let wire = invocation.named_arg_as(builder, "input")?;
+    let axis = invocation.named_arg_as::<i64>(builder, "axis")? as usize;
+    let begin = invocation.named_arg_as::<i64>(builder, "begin")? as usize;
+    let end = invocation.named_arg_as::<Arc<Tensor>>(builder, "end")?.to_scalar::<TDim>()?.clone();
+    let value = invocation.named_arg_as::<Arc<Tensor>>(builder, "value")?;
+    let op = PulseMask { axis, begin, end, value };
+    builder.wire(op, &[wire])
+}
+
+#[derive(Debug, Clone, Default, Hash)]
+struct PulseMaskOpState {
+    positions: crate::SlotPositions,
+}
+
+impl OpState for PulseMaskOpState {
+    fn eval(
+        &mut self,
+        session: &mut SessionState,
+        op: &dyn Op,
+        mut inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs).into_tensor();
+        let op = op.downcast_ref::<PulseMask>().ok_or_else(|| format_err!("Wrong Op type"))?;
+        let pulse = input.shape()[op.axis];
+        let tensor = self
+            .positions
+            .run(input, pulse, |input, pos, _| Self::mask(session, op, input, pos))?;
+        Ok(tvec!(tensor.into_arc_tensor()))
+    }
+
+    fn save(
+        &self,
+        _op: &dyn Op,
+        prefix: &str,
+        tensors: &mut Vec<(String, Tensor)>,
+    ) -> TractResult<()> {
+        self.positions.save(tensors, prefix);
+        Ok(())
+    }
+
+    fn load(
+        &mut self,
+        _op: &dyn Op,
+        prefix: &str,
+        tensors: &mut HashMap<String, Tensor>,
+    ) -> TractResult<()> {
+        self.positions = crate::SlotPositions::load(tensors, prefix)?;
+        Ok(())
+    }
+
+    fn reset_batch_slot(
+        &mut self,
+        _op: &dyn Op,
+        inputs: &[&TypedFact],
+        batch: Symbol,
+        slot: usize,
+    ) -> TractResult<()> {
+        self.positions.reset(inputs, batch, slot)
+    }
+}
+
+impl PulseMaskOpState {
+    unsafe fn fill_slice_constant<T: Datum + Copy>(
+        data: &mut Tensor,
+        constant: &Tensor,
+        axis: usize,
+        range: std::ops::Range<usize>,
+    ) {
+        let c = constant.to_scalar_unchecked::<T>();
+        data.to_array_view_mut_unchecked::<T>().slice_axis_mut(Axis(axis), range.into()).fill(*c);
+    }
+
+    fn mask(
+        session: &SessionState,
+        op: &PulseMask,
+        mut input: Tensor,
+        pulse_begin: usize,
+    ) -> TractResult<Tensor> {
+        let pulse = input.shape()[op.axis];
+        let pulse_end = pulse_begin + pulse;
+        let end = op.end.eval(&session.resolved_symbols).to_usize().unwrap_or(std::usize::MAX);
+
+        // pulse is entirely in valid input, just forward
+        if pulse_begin >= op.begin && pulse_end <= end {
+            return Ok(input);
+        }
+        if pulse_begin < op.begin {
+            let fill_up_to = (op.begin - pulse_begin).min(pulse);
+            unsafe {
+                dispatch_copy_by_size!(Self::fill_slice_constant(input.datum_type())(
+                    &mut input,
+                    &op.value,
+                    op.axis,
+                    0..fill_up_to
+                ))
+            };
+        }
+        if pulse_end > end {
+            let fill_from = pulse - (pulse_end - end).min(pulse);
+            unsafe {
+                dispatch_copy_by_size!(Self::fill_slice_constant(input.datum_type())(
+                    &mut input,
+                    &op.value,
+                    op.axis,
+                    fill_from..pulse
+                ))
+            };
+        }
+        Ok(input)
+    }
+}
+
+/// Replaces the frames outside of the [begin, end) range of the stream with
+/// a constant.
+#[derive(Debug, Clone, Hash)]
+pub struct PulseMask {
+    pub axis: usize,
+    pub begin: usize,
+    pub end: TDim,
+    pub value: Arc<Tensor>,
+}
+
+impl_dyn_hash!(PulseMask);
+
+impl Op for PulseMask {
+    fn name(&self) -> Cow<str> {
+        "PulseMask".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("axis: {} begin: {} end: {}", self.axis, self.begin, self.end)])
+    }
+
+    op_pulse!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for PulseMask {
+    fn is_stateless(&self) -> bool {
+        false
+    }
+
+    fn state(
+        &self,
+        _session: &mut SessionState,
+        _node_id: usize,
+    ) -> TractResult<Option<Box<dyn OpState>>> {
+        Ok(Some(Box::new(PulseMaskOpState::default())))
+    }
+}
+
+impl TypedOp for PulseMask {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(inputs[0].clone()))
+    }
+
+    as_op!();
+}
@@ -0,0 +1,144 @@
+use tract_core::ndarray::*;
+use tract_nnef::internal::*;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_primitive(
+        "tract_pulse_overlap_add",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Integer.named("axis"),
+            TypeName::Integer.named("overlap"),
+        ],
+        de_overlap_add,
+    );
+}
+
+fn de_overlap_add(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let wire = invocation.named_arg_as(builder, "input")?;
+    let axis = invocation.named_arg_as::<i64>(builder, "axis")? as usize;
+    let overlap = invocation.named_arg_as::<i64>(builder, "overlap")? as usize;
+    builder.wire(OverlapAdd { axis, overlap }, &[wire])
+}
+
+#[derive(Debug, Clone, Default)]
+struct OverlapAddState {
+    buffer: Option<Tensor>,
+}
+
+impl OpState for OverlapAddState {
+    fn eval(
+        &mut self,
+        _state: &mut SessionState,
+        op: &dyn Op,
+        mut inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let mut input = args_1!(inputs).into_tensor();
+        let op = op.downcast_ref::<OverlapAdd>().ok_or_else(|| format_err!("Wrong Op type"))?;
+        let output_pulse = input.shape()[op.axis] - op.overlap;
+        if let Some(buffer) = self.buffer.take() {
+            dispatch_numbers!(Self::add_buffer(input.datum_type())(op, &mut input, &buffer))?;
+        }
+        self.buffer = Some(input.slice(op.axis, output_pulse, output_pulse + op.overlap)?);
+        Ok(tvec!(input.slice(op.axis, 0, output_pulse)?.into_arc_tensor()))
+    }
+
+    fn save(
+        &self,
+        _op: &dyn Op,
+        prefix: &str,
+        tensors: &mut Vec<(String, Tensor)>,
+    ) -> TractResult<()> {
+        if let Some(buffer) = &self.buffer {
+            tensors.push((format!("{}buffer", prefix), buffer.clone()));
+        }
+        Ok(())
+    }
+
+    fn load(
+        &mut self,
+        _op: &dyn Op,
+        prefix: &str,
+        tensors: &mut HashMap<String, Tensor>,
+    ) -> TractResult<()> {
+        self.buffer = tensors.remove(&format!("{}buffer", prefix));
+        Ok(())
+    }
+
+    fn reset_batch_slot(
+        &mut self,
+        _op: &dyn Op,
+        inputs: &[&TypedFact],
+        batch: Symbol,
+        slot: usize,
+    ) -> TractResult<()> {
+        if let Some(buffer) = &mut self.buffer {
+            zero_batch_slot(buffer, batch_axis(inputs[0], batch)?, slot)?;
+        }
+        Ok(())
+    }
+}
+
+impl OverlapAddState {
+    fn add_buffer<T: Datum + Copy + std::ops::Add<T, Output = T>>(
+        op: &OverlapAdd,
+        input: &mut Tensor,
+        buffer: &Tensor,
+    ) -> TractResult<()> {
+        let mut input = input.to_array_view_mut::<T>()?;
+        input
+            .slice_axis_mut(Axis(op.axis), (0..op.overlap).into())
+            .zip_mut_with(&buffer.to_array_view::<T>()?, |i, b| *i = *i + *b);
+        Ok(())
+    }
+}
+
+/// Accumulates the tail of each pulse (the last `overlap` frames) into the
+/// head of the next one, as the outputs of a deconvolution overlap between
+/// consecutive input pulses.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct OverlapAdd {
+    pub axis: usize,
+    pub overlap: usize,
+}
+
+impl_dyn_hash!(OverlapAdd);
+
+impl Op for OverlapAdd {
+    fn name(&self) -> Cow<str> {
+        "OverlapAdd".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("axis: {} overlap: {}", self.axis, self.overlap)])
+    }
+
+    op_pulse!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for OverlapAdd {
+    fn is_stateless(&self) -> bool {
+        false
+    }
+
+    fn state(
+        &self,
+        _session: &mut SessionState,
+        _node_id: usize,
+    ) -> TractResult<Option<Box<dyn OpState>>> {
+        Ok(Some(Box::new(OverlapAddState::default())))
+    }
+}
+
+impl TypedOp for OverlapAdd {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let mut fact = inputs[0].clone();
+        fact.shape.set(self.axis, fact.shape[self.axis].clone() - self.overlap.to_dim());
+        Ok(tvec!(fact))
+    }
+}
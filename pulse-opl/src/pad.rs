@@ -2,9 +2,51 @@ use tract_core::ndarray::*;
 use tract_core::ops::array::PadMode;
 use tract_nnef::internal::*;
 
+pub fn register(registry: &mut Registry) {
+    registry.register_primitive(
+        "tract_pulse_pad",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Integer.named("axis"),
+            TypeName::Integer.named("pulse"),
+            TypeName::Integer.named("before"),
+            TypeName::Integer.tensor().named("after"),
+            TypeName::Integer.named("begin_input"),
+            TypeName::Integer.tensor().named("end_input"),
+            TypeName::String.named("border"),
+            TypeName::Scalar.tensor().named("value"),
+        ],
+        de_pulse_pad,
+    );
+}
+
+fn de_pulse_pad(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let wire = invocation.named_arg_as(builder, "input")?;
+    let axis = invocation.named_arg_as::<i64>(builder, "axis")? as usize;
+    let pulse = invocation.named_arg_as::<i64>(builder, "pulse")? as usize;
+    let before = invocation.named_arg_as::<i64>(builder, "before")? as usize;
+    let after =
+        invocation.named_arg_as::<Arc<Tensor>>(builder, "after")?.to_scalar::<TDim>()?.clone();
+    let begin_input = invocation.named_arg_as::<i64>(builder, "begin_input")? as usize;
+    let end_input =
+        invocation.named_arg_as::<Arc<Tensor>>(builder, "end_input")?.to_scalar::<TDim>()?.clone();
+    let border = invocation.named_arg_as::<String>(builder, "border")?;
+    let mode = match &*border {
+        "constant" => PadMode::Constant(invocation.named_arg_as(builder, "value")?),
+        "replicated" => PadMode::Edge,
+        _ => bail!("Unsupported pulsed padding mode {}", border),
+    };
+    let op = PulsePad { axis, pulse, before, after, begin_input, end_input, mode };
+    builder.wire(op, &[wire])
+}
+
 #[derive(Debug, Clone, Default, Hash)]
 struct PulsePadOpState {
-    current_pos: usize,
+    positions: crate::SlotPositions,
+    /// last frame of the input, keeping its streaming axis
     last_valid_frame: Option<Tensor>,
 }
 
@@ -17,18 +59,52 @@ impl OpState for PulsePadOpState {
     ) -> TractResult<TVec<Arc<Tensor>>> {
         let input = args_1!(inputs).into_tensor();
         let op = op.downcast_ref::<PulsePad>().ok_or_else(|| format_err!("Wrong Op type"))?;
-        let tensor = self.pad(session, op, input)?;
+        let PulsePadOpState { positions, last_valid_frame } = self;
+        let tensor = positions.run(input, op.pulse, |input, pos, slot| match slot {
+            Some(slot) => {
+                slot.with_state(last_valid_frame, |frame| Self::pad(session, op, input, pos, frame))
+            }
+            None => Self::pad(session, op, input, pos, last_valid_frame),
+        })?;
         Ok(tvec!(tensor.into_arc_tensor()))
     }
-}
 
-impl PulsePadOpState {
-    unsafe fn save_frame<T: Datum + Copy>(&mut self, op: &PulsePad, input: &Tensor, frame: usize) {
-        let data = input.to_array_view_unchecked::<T>();
-        self.last_valid_frame =
-            Some(data.index_axis(Axis(op.axis), frame).to_owned().into_tensor());
+    fn save(
+        &self,
+        _op: &dyn Op,
+        prefix: &str,
+        tensors: &mut Vec<(String, Tensor)>,
+    ) -> TractResult<()> {
+        self.positions.save(tensors, prefix);
+        if let Some(last_valid_frame) = &self.last_valid_frame {
+            tensors.push((format!("{}last_valid_frame", prefix), last_valid_frame.clone()));
+        }
+        Ok(())
+    }
+
+    fn load(
+        &mut self,
+        _op: &dyn Op,
+        prefix: &str,
+        tensors: &mut HashMap<String, Tensor>,
+    ) -> TractResult<()> {
+        self.positions = crate::SlotPositions::load(tensors, prefix)?;
+        self.last_valid_frame = tensors.remove(&format!("{}last_valid_frame", prefix));
+        Ok(())
+    }
+
+    fn reset_batch_slot(
+        &mut self,
+        _op: &dyn Op,
+        inputs: &[&TypedFact],
+        batch: Symbol,
+        slot: usize,
+    ) -> TractResult<()> {
+        self.positions.reset(inputs, batch, slot)
     }
+}
 
+impl PulsePadOpState {
     unsafe fn fill_slice_constant<T: Datum + Copy>(
         data: &mut Tensor,
         constant: &Tensor,
@@ -53,14 +129,13 @@ impl PulsePadOpState {
     }
 
     fn pad(
-        &mut self,
-        session: &mut SessionState,
+        session: &SessionState,
         op: &PulsePad,
         mut input: Tensor,
+        pulse_begin: usize,
+        last_valid_frame: &mut Option<Tensor>,
     ) -> TractResult<Tensor> {
-        let pulse_begin = self.current_pos;
-        let pulse_end = self.current_pos + op.pulse;
-        self.current_pos += op.pulse;
+        let pulse_end = pulse_begin + op.pulse;
         let end_input =
             op.end_input.eval(&session.resolved_symbols).to_usize().unwrap_or(std::usize::MAX);
         let after = op.after.eval(&session.resolved_symbols).to_usize().unwrap_or(std::usize::MAX);
@@ -68,14 +143,8 @@ impl PulsePadOpState {
         if let PadMode::Edge = op.mode {
             if after != 0 && pulse_begin < end_input {
                 let latest_valid_frame = (end_input - pulse_begin).min(op.pulse) - 1;
-                unsafe {
-                    dispatch_copy_by_size!(Self::save_frame(input.datum_type())(
-                        self,
-                        op,
-                        &input,
-                        latest_valid_frame
-                    ))
-                }
+                *last_valid_frame =
+                    Some(input.slice(op.axis, latest_valid_frame, latest_valid_frame + 1)?);
             }
         }
 
@@ -126,7 +195,11 @@ impl PulsePadOpState {
                     ))
                 },
                 PadMode::Edge => {
-                    let last_frame = self.last_valid_frame.as_ref().unwrap();
+                    let last_frame = last_valid_frame.as_ref().unwrap();
+                    // frames are saved and copied by element size
+                    if last_frame.datum_type().size_of() != input.datum_type().size_of() {
+                        bail!("Last valid frame {:?} does not fit input {:?}", last_frame, input)
+                    }
                     unsafe {
                         dispatch_copy_by_size!(Self::fill_slice_with_frame(input.datum_type())(
                             &mut input,
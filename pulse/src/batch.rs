@@ -0,0 +1,217 @@
+use crate::internal::*;
+
+/// Runs a pulsed model over a batch of independent streams, each stream
+/// using one slot of the batch axis.
+///
+/// The batch size of the model must be the symbol `batch`, resolved here to
+/// the number of slots. Streams join and leave between two pulses: a
+/// joining stream gets a slot with fresh op states (delay buffers, scan
+/// hidden states, ...) while the other streams go on untouched. Ops
+/// depending on the position in the stream (like padding) keep a position
+/// for each slot, and run the slots one by one while they are not aligned.
+/// Plans with op states that can not restart a single stream are rejected.
+#[derive(Debug, Clone)]
+pub struct StreamBatch {
+    state: TypedSimpleState<TypedModel, Arc<TypedSimplePlan<TypedModel>>>,
+    batch: Symbol,
+    used: Vec<bool>,
+    started: bool,
+}
+
+impl StreamBatch {
+    pub fn new(
+        plan: Arc<TypedSimplePlan<TypedModel>>,
+        batch: Symbol,
+        slots: usize,
+    ) -> TractResult<StreamBatch> {
+        let mut state = SimpleState::new(plan)?;
+        state.session_state.resolved_symbols[batch] = Some(slots as i64);
+        // fail now rather than when the first stream joins a running batch
+        state
+            .clone()
+            .reset_batch_slot(batch, 0)
+            .context("Streams of this model can not join a running batch")?;
+        Ok(StreamBatch { state, batch, used: vec![false; slots], started: false })
+    }
+
+    /// Number of slots in the batch.
+    pub fn slots(&self) -> usize {
+        self.used.len()
+    }
+
+    pub fn is_used(&self, slot: usize) -> bool {
+        self.used.get(slot).copied().unwrap_or(false)
+    }
+
+    /// Gives a free slot to a new stream, resetting its state.
+    pub fn join(&mut self) -> TractResult<usize> {
+        let slot = self
+            .used
+            .iter()
+            .position(|used| !used)
+            .ok_or_else(|| format_err!("All {} slots of the batch are used", self.slots()))?;
+        if self.started {
+            self.state.reset_batch_slot(self.batch, slot)?;
+        }
+        self.used[slot] = true;
+        Ok(slot)
+    }
+
+    /// Frees the slot of a stream that has ended.
+    pub fn leave(&mut self, slot: usize) -> TractResult<()> {
+        if !self.is_used(slot) {
+            bail!("Slot {} is not used", slot)
+        }
+        self.used[slot] = false;
+        Ok(())
+    }
+
+    /// Runs a pulse of all the streams, batched in the inputs. The content of
+    /// free slots does not matter, and their outputs are meaningless.
+    pub fn run(&mut self, inputs: TVec<Tensor>) -> TractResult<TVec<Arc<Tensor>>> {
+        self.started = true;
+        self.state.run(inputs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tract_core::ops::array::{ConstantOfShape, CumSum, Pad, PadMode};
+    use tract_core::ops::math;
+    use tract_core::ops::scan::*;
+    use tract_pulse_opl::ops::{Delay, PulseCumSum, PulseMask, PulsedSameAxisConcat};
+
+    fn pulse(stream: usize, i: usize) -> Tensor {
+        tensor1(&(0..4).map(|x| (100 * stream + 4 * i + x) as f32).collect::<Vec<_>>())
+            .into_shape(&[1, 2, 2])
+            .unwrap()
+    }
+
+    fn fact(n: Symbol, len: usize) -> TypedFact {
+        TypedFact::dt_shape(f32::datum_type(), [n.to_dim(), len.to_dim(), 2.to_dim()].as_ref())
+    }
+
+    /// Runs streams 0 and 1 together, then 2 replaces 1 after two pulses,
+    /// and checks each stream output against a run of the stream alone.
+    fn check_streams(model: TypedModel, n: Symbol, delay: usize) {
+        let plan = Arc::new(SimplePlan::new(model).unwrap());
+        let mut batch = StreamBatch::new(plan.clone(), n, 2).unwrap();
+        assert_eq!(batch.join().unwrap(), 0);
+        assert_eq!(batch.join().unwrap(), 1);
+        assert!(batch.join().is_err());
+        let mut found = vec![vec![], vec![], vec![]];
+        for i in 0..5 {
+            if i == 2 {
+                batch.leave(1).unwrap();
+                assert_eq!(batch.join().unwrap(), 1);
+            }
+            let second = if i < 2 { (1, i) } else { (2, i - 2) };
+            let input = Tensor::stack_tensors(0, &[pulse(0, i), pulse(second.0, second.1)]);
+            let output = batch.run(tvec!(input.unwrap())).unwrap().remove(0);
+            found[0].push(output.slice(0, 0, 1).unwrap());
+            found[second.0].push(output.slice(0, 1, 2).unwrap());
+        }
+
+        for (stream, found) in found.iter().enumerate() {
+            let mut state = SimpleState::new(plan.clone()).unwrap();
+            state.session_state.resolved_symbols[n] = Some(1);
+            let expected: Vec<Arc<Tensor>> = (0..found.len())
+                .map(|i| state.run(tvec!(pulse(stream, i))).unwrap().remove(0))
+                .collect();
+            let expected = Tensor::stack_tensors(1, &expected).unwrap();
+            let found = Tensor::stack_tensors(1, &found).unwrap();
+            // the first frames, before the delay, are not meaningful
+            let len = found.shape()[1];
+            assert_eq!(found.slice(1, delay, len).unwrap(), expected.slice(1, delay, len).unwrap());
+        }
+    }
+
+    #[test]
+    fn delay_and_cum_sum() {
+        let n = Symbol::new('N');
+        let mut model = TypedModel::default();
+        let a = model.add_source("a", fact(n, 2)).unwrap();
+        let sum = PulseCumSum { axis: 1, exclusive: false, begin: 0 };
+        let sum = model.wire_node("sum", sum, &[a]).unwrap();
+        let delay = Delay::new_typed(&fact(n, 2), 1, 3, 0).unwrap();
+        let delay = model.wire_node("delay", delay, &sum).unwrap();
+        model.set_output_outlets(&delay).unwrap();
+        check_streams(model, n, 3);
+    }
+
+    #[test]
+    fn scan() {
+        let n = Symbol::new('N');
+        let mut body = TypedModel::default();
+        let h = body.add_source("h", fact(n, 1)).unwrap();
+        let x = body.add_source("x", fact(n, 1)).unwrap();
+        let sum = body.wire_node("sum", math::add::bin_typed(), &[h, x]).unwrap();
+        body.set_output_outlets(&sum).unwrap();
+
+        let mut model = TypedModel::default();
+        let a = model.add_source("a", fact(n, 2)).unwrap();
+        let zero = ConstantOfShape::new(fact(n, 1).shape.to_tvec(), rctensor0(0f32));
+        let h0 = model.wire_node("h0", zero, &[]).unwrap();
+        let input_mapping = vec![
+            InputMapping::State { initializer: StateInitializer::FromInput(1) },
+            InputMapping::Scan { slot: 0, axis: 1, chunk: 1 },
+        ];
+        let output_mapping = vec![OutputMapping {
+            full_slot: Some(0),
+            axis: 1,
+            chunk: 1,
+            full_dim_hint: None,
+            last_value_slot: None,
+            state: true,
+        }];
+        let scan = Scan::new(body, input_mapping, output_mapping, None, 0).unwrap();
+        let scan = model.wire_node("scan", scan, &[a, h0[0]]).unwrap();
+        model.set_output_outlets(&scan).unwrap();
+        check_streams(model, n, 0);
+    }
+
+    #[test]
+    fn mask_concat_and_delayed_cum_sum() {
+        let n = Symbol::new('N');
+        let mut model = TypedModel::default();
+        let a = model.add_source("a", fact(n, 2)).unwrap();
+        let mask = PulseMask { axis: 1, begin: 1, end: stream_dim(), value: rctensor0(-1f32) };
+        let mask = model.wire_node("mask", mask, &[a]).unwrap();
+        let concat = PulsedSameAxisConcat {
+            axis: 1,
+            pre_slice: tensor3(&[[[5f32, 6.], [7., 8.]]]),
+            post_slice: tensor3(&[[[0f32; 2]; 0]]),
+            input_delay: 3,
+            input_len: stream_dim(),
+        };
+        let concat = model.wire_node("concat", concat, &mask).unwrap();
+        let sum = PulseCumSum { axis: 1, exclusive: false, begin: 2 };
+        let sum = model.wire_node("sum", sum, &concat).unwrap();
+        model.set_output_outlets(&sum).unwrap();
+        check_streams(model, n, 0);
+    }
+
+    fn check_padded_streams(mode: PadMode) {
+        let n = Symbol::new('N');
+        let mut model = TypedModel::default();
+        let shape = [n.to_dim(), stream_dim(), 2.to_dim()];
+        let a = model.add_source("a", TypedFact::dt_shape(f32::datum_type(), shape.as_ref()));
+        let pad = Pad { pads: vec![(0, 0), (2, 1), (0, 0)], mode };
+        let pad = model.wire_node("pad", pad, &[a.unwrap()]).unwrap();
+        let sum = model.wire_node("sum", CumSum::new(1, false, false), &pad).unwrap();
+        model.set_output_outlets(&sum).unwrap();
+        let pulsed = PulsedModel::new(&model, 2).unwrap().into_typed().unwrap();
+        check_streams(pulsed, n, 0);
+    }
+
+    #[test]
+    fn constant_padded_streams() {
+        check_padded_streams(PadMode::Constant(rctensor0(1f32)));
+    }
+
+    #[test]
+    fn edge_padded_streams() {
+        check_padded_streams(PadMode::Edge);
+    }
+}
@@ -12,6 +12,15 @@ pub fn stream_dim() -> TDim {
     (*S).into()
 }
 
+/// Rate of a stream of length `len`, as a fraction of the rate of `S`.
+pub fn stream_rate(len: &TDim) -> TractResult<(usize, usize)> {
+    let (num, den) = len.slope(stream_symbol());
+    if num <= 0 {
+        bail!("{} is not a stream length", len)
+    }
+    Ok((num as usize, den as usize))
+}
+
 pub trait StreamFact {
     fn stream_info(&self) -> Option<(usize, &TDim)>;
 }
@@ -43,14 +52,22 @@ pub struct PulsedFact {
 impl_dyn_hash!(PulsedFact);
 
 impl PulsedFact {
+    /// Pulsed fact for a streaming input, `pulse` being the pulse of the `S`
+    /// stream. Streams running at another rate have a length proportional
+    /// to `S` (like `S/4` for a stream four times slower) and get a pulse in
+    /// the same proportion.
     pub fn from_tensor_fact_pulse(tf: &TypedFact, pulse: usize) -> TractResult<PulsedFact> {
         let datum_type = tf.datum_type;
         let (axis, len) = tf
             .shape
             .stream_info()
             .ok_or_else(|| format_err!("Can not pulse a tensor with no streaming dim"))?;
+        let (num, den) = stream_rate(len)?;
+        if pulse * num % den != 0 {
+            bail!("Pulse {} does not split a stream of length {} in whole frames", pulse, len)
+        }
         let mut shape: TVec<TDim> = tf.shape.iter().collect();
-        shape[axis] = pulse.into();
+        shape[axis] = (pulse * num / den).into();
         Ok(PulsedFact { datum_type, shape, axis, dim: len.clone(), delay: 0 })
     }
 
@@ -58,6 +75,13 @@ impl PulsedFact {
         self.shape[self.axis].to_usize().expect("Pulse should be an integer. This is a tract bug.")
     }
 
+    /// Size in bytes of `frames` frames of the stream.
+    pub fn frames_bytes(&self, frames: usize) -> TractResult<TDim> {
+        let mut shape = self.shape.clone();
+        shape[self.axis] = frames.to_dim();
+        shape.iter().try_fold(self.datum_type.size_of().to_dim(), |acc, d| acc.maybe_mul(d))
+    }
+
     pub fn to_pulse_fact(&self) -> TypedFact {
         TypedFact::dt_shape(self.datum_type, &self.shape)
     }
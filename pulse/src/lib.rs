@@ -1,6 +1,7 @@
 #[macro_use]
 pub mod macros;
 
+pub mod batch;
 pub mod fact;
 pub mod model;
 pub mod ops;
@@ -12,7 +13,8 @@ pub mod internal {
 
     pub use downcast_rs::Downcast;
 
-    pub use crate::fact::{stream_dim, stream_symbol, PulsedFact};
+    pub use crate::batch::StreamBatch;
+    pub use crate::fact::{stream_dim, stream_rate, stream_symbol, PulsedFact};
     pub use crate::model::{PulsedModel, PulsedModelExt};
     pub use crate::ops::{OpPulsifier, PulsedOp};
     pub use tract_pulse_opl::op_pulse;
@@ -36,7 +38,10 @@ impl WithPulse for tract_nnef::framework::Nnef {
 
 fn tract_nnef_registry() -> Registry {
     let mut reg = tract_pulse_opl::tract_nnef_registry();
+    ops::array::register(&mut reg);
+    ops::cnn::register(&mut reg);
     ops::delay::register(&mut reg);
+    ops::mask::register(&mut reg);
     reg
 }
 
@@ -94,4 +99,286 @@ mod tests {
             TypedFact::dt_shape(DatumType::F32, &[4, 2, 3])
         );
     }
+
+    #[test]
+    fn test_nnef_round_trip() {
+        use tract_core::ops::array::{ConcatSlice, Pad, PadMode, TypedConcat};
+        let mut model = TypedModel::default();
+        let fact = TypedFact::dt_shape(f32::datum_type(), [stream_dim(), 2.to_dim()].as_ref());
+        let a = model.add_source("a", fact).unwrap();
+        let pad = Pad { pads: vec![(2, 1), (0, 0)], mode: PadMode::Constant(rctensor0(0.5f32)) };
+        let padded = model.wire_node("pad", pad, &[a]).unwrap();
+        let concat = TypedConcat {
+            axis: 0,
+            slices: tvec!(
+                ConcatSlice::Const(rctensor2(&[[9f32, 9.]])),
+                ConcatSlice::Var,
+                ConcatSlice::Const(rctensor2(&[[7f32, 7.]]))
+            ),
+        };
+        let concat = model.wire_node("concat", concat, &padded).unwrap();
+        model.set_output_outlets(&concat).unwrap();
+        let typed = PulsedModel::new(&model, 3).unwrap().into_typed().unwrap();
+
+        let nnef = tract_nnef::nnef().with_pulse();
+        let mut buffer = vec![];
+        nnef.write_to_tar(&typed, &mut buffer).unwrap();
+        let reloaded = nnef.model_for_read(&mut &*buffer).unwrap();
+        for prop in &["pulse.delay", "pulse.input_axes", "pulse.output_axes", "pulse.pulse"] {
+            assert_eq!(
+                reloaded.properties[*prop].cast_to::<i64>().unwrap(),
+                typed.properties[*prop].cast_to::<i64>().unwrap()
+            );
+        }
+
+        let delay = typed.properties["pulse.delay"].as_slice::<i64>().unwrap()[0] as usize;
+        let mut expected = SimpleState::new(SimplePlan::new(typed).unwrap()).unwrap();
+        let mut found = SimpleState::new(SimplePlan::new(reloaded).unwrap()).unwrap();
+        for i in 0..4 {
+            let input = tensor1(&(0..6).map(|x| (i * 6 + x) as f32).collect::<Vec<_>>())
+                .into_shape(&[3, 2])
+                .unwrap();
+            let expected = expected.run(tvec!(input.clone())).unwrap().remove(0);
+            let found = found.run(tvec!(input)).unwrap().remove(0);
+            // frames before the output delay are not meaningful
+            let skip = delay.saturating_sub(i * 3).min(3);
+            assert_eq!(expected.slice(0, skip, 3).unwrap(), found.slice(0, skip, 3).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_latency_and_buffers() {
+        use tract_core::ops::array::{Pad, PadMode, Slice};
+        use tract_core::ops::Downsample;
+        let mut model = TypedModel::default();
+        let fact = TypedFact::dt_shape(f32::datum_type(), [stream_dim(), 2.to_dim()].as_ref());
+        let a = model.add_source("a", fact).unwrap();
+        let pad = Pad { pads: vec![(2, 1), (0, 0)], mode: PadMode::Constant(rctensor0(0f32)) };
+        let padded = model.wire_node("pad", pad, &[a]).unwrap();
+        let down = Downsample { axis: 0, stride: 2, modulo: 0 };
+        let down = model.wire_node("down", down, &padded).unwrap();
+        let end = model.outlet_fact(down[0]).unwrap().shape[0].clone();
+        let slice = Slice { axis: 0, start: 1.to_dim(), end };
+        let sliced = model.wire_node("slice", slice, &down).unwrap();
+        model.set_output_outlets(&sliced).unwrap();
+
+        assert_eq!(PulsedModel::minimal_pulse(&model, 8).unwrap(), 2);
+        assert_eq!(PulsedModel::minimal_pulse(&model, 6).unwrap(), 2);
+        assert!(PulsedModel::minimal_pulse(&model, 3).is_err());
+        let pulsed = PulsedModel::new(&model, 4).unwrap();
+        // one frame of delay on the downsampled output is two input frames
+        assert_eq!(pulsed.latency().unwrap(), 2);
+        let delay = pulsed.node_by_name("pad.Delay").unwrap().id;
+        assert_eq!(pulsed.buffered_bytes().unwrap(), vec!((delay, 16.to_dim())));
+    }
+
+    #[test]
+    fn test_edge_padding_buffers() {
+        use tract_core::ops::array::{Pad, PadMode};
+        let buffers = |before: usize| {
+            let mut model = TypedModel::default();
+            let fact = TypedFact::dt_shape(f32::datum_type(), [stream_dim(), 2.to_dim()].as_ref());
+            let a = model.add_source("a", fact).unwrap();
+            let pad = Pad { pads: vec![(before, 1), (0, 0)], mode: PadMode::Edge };
+            let padded = model.wire_node("pad", pad, &[a]).unwrap();
+            model.set_output_outlets(&padded).unwrap();
+            let pulsed = PulsedModel::new(&model, 4).unwrap();
+            pulsed
+                .buffered_bytes()
+                .unwrap()
+                .into_iter()
+                .map(|(node, bytes)| (pulsed.node(node).name.clone(), bytes))
+                .collect::<Vec<_>>()
+        };
+        // a short edge padding is done by PulsePad, keeping the last frame
+        assert_eq!(
+            buffers(1),
+            vec!(("pad.Delay".to_string(), 8.to_dim()), ("pad".to_string(), 8.to_dim()))
+        );
+        // a longer one by PulseBorderPad, whose window is held by its delay
+        assert_eq!(buffers(5), vec!(("pad.Delay".to_string(), 48.to_dim())));
+    }
+
+    #[test]
+    fn test_streams_at_different_rates() {
+        use tract_core::ops::array::{Pad, PadMode, Slice};
+        use tract_core::ops::{math, Downsample};
+        let mut model = TypedModel::default();
+        // audio streamed along its second axis, video four times slower
+        let audio_fact =
+            TypedFact::dt_shape(f32::datum_type(), [2.to_dim(), stream_dim()].as_ref());
+        let video_len = stream_dim().div_ceil(4);
+        let video_fact =
+            TypedFact::dt_shape(f32::datum_type(), [2.to_dim(), video_len.clone()].as_ref());
+        let audio = model.add_source("audio", audio_fact).unwrap();
+        let video = model.add_source("video", video_fact).unwrap();
+        let down = Downsample { axis: 1, stride: 4, modulo: 0 };
+        let down = model.wire_node("down", down, &[audio]).unwrap();
+        // delay the video by one frame, for the sum to have to realign it
+        let pad = Pad { pads: vec![(0, 0), (1, 0)], mode: PadMode::Constant(rctensor0(0f32)) };
+        let padded = model.wire_node("pad", pad, &[video]).unwrap();
+        let slice = Slice { axis: 1, start: 1.to_dim(), end: video_len + 1 };
+        let video = model.wire_node("slice", slice, &padded).unwrap();
+        let sum = model.wire_node("sum", math::add::bin_typed(), &[down[0], video[0]]).unwrap();
+        model.set_output_outlets(&sum).unwrap();
+
+        assert!(PulsedModel::new(&model, 6).is_err());
+        let pulsed = PulsedModel::new(&model, 8).unwrap();
+        assert_eq!(pulsed.input_fact(0).unwrap().pulse(), 8);
+        assert_eq!(pulsed.input_fact(1).unwrap().pulse(), 2);
+        let output_fact = pulsed.output_fact(0).unwrap().clone();
+        assert_eq!(output_fact.pulse(), 2);
+        assert_eq!(output_fact.delay, 1);
+        assert_eq!(pulsed.latency().unwrap(), 4);
+        let typed = pulsed.clone().into_typed().unwrap();
+        assert_eq!(*typed.properties["pulse.pulse"], tensor1(&[8i64, 2]));
+
+        let audio =
+            tensor1(&(0..40).map(|x| x as f32).collect::<Vec<_>>()).into_shape(&[2, 20]).unwrap();
+        let video = tensor1(&(0..10).map(|x| 100. * x as f32).collect::<Vec<_>>())
+            .into_shape(&[2, 5])
+            .unwrap();
+        let expected = model
+            .concretize_dims(&SymbolValues::default().with(stream_symbol(), 20))
+            .unwrap()
+            .into_runnable()
+            .unwrap()
+            .run(tvec!(audio.clone(), video.clone()))
+            .unwrap()
+            .remove(0);
+
+        let plan = SimplePlan::new(pulsed.into_typed().unwrap()).unwrap();
+        let mut state = SimpleState::new(&plan).unwrap();
+        state.session_state.resolved_symbols[stream_symbol()] = Some(20);
+        let mut outputs = vec![];
+        let chunk = |input: &Tensor, pulse: usize, i: usize| {
+            let mut chunk = Tensor::zero::<f32>(&[2, pulse]).unwrap();
+            let count = pulse.min(input.shape()[1].saturating_sub(i * pulse));
+            if count > 0 {
+                chunk.assign_slice(..count, input, i * pulse..i * pulse + count, 1).unwrap();
+            }
+            chunk
+        };
+        for i in 0..3 {
+            let inputs = tvec!(chunk(&audio, 8, i), chunk(&video, 2, i));
+            outputs.push(state.run(inputs).unwrap().remove(0));
+        }
+        let found = Tensor::stack_tensors(1, &outputs).unwrap();
+        assert_eq!(found.slice(1, 1, 6).unwrap(), *expected);
+    }
+
+    #[test]
+    fn test_combining_different_rates_is_an_error() {
+        let mut source = TypedModel::default();
+        let fact = TypedFact::dt_shape(f32::datum_type(), [stream_dim()].as_ref());
+        let a = source.add_source("a", fact.clone()).unwrap();
+        let b = source.add_source("b", fact).unwrap();
+        let sum = source.wire_node("sum", tract_core::ops::math::add::bin_typed(), &[a, b]);
+        let sum = sum.unwrap()[0];
+
+        let mut target = PulsedModel::default();
+        let mut mapping = HashMap::new();
+        for (outlet, pulse) in &[(a, 4), (b, 2)] {
+            let fact = PulsedFact {
+                datum_type: f32::datum_type(),
+                shape: tvec!(pulse.to_dim()),
+                axis: 0,
+                dim: stream_dim(),
+                delay: 0,
+            };
+            let name = &source.node(outlet.node).name;
+            mapping.insert(*outlet, target.add_source(name, fact).unwrap());
+        }
+        let node = source.node(sum.node);
+        assert!(crate::ops::binary::sync_inputs(node, &mut target, &mapping).is_err());
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
+        use tract_core::ops::array::{CumSum, Pad, PadMode};
+        let mut model = TypedModel::default();
+        let fact = TypedFact::dt_shape(f32::datum_type(), [stream_dim(), 2.to_dim()].as_ref());
+        let a = model.add_source("a", fact).unwrap();
+        let pad = Pad { pads: vec![(2, 1), (0, 0)], mode: PadMode::Edge };
+        let padded = model.wire_node("pad", pad, &[a]).unwrap();
+        let sum = model.wire_node("sum", CumSum::new(0, false, false), &padded).unwrap();
+        model.set_output_outlets(&sum).unwrap();
+        let typed = PulsedModel::new(&model, 3).unwrap().into_typed().unwrap();
+        let plan = SimplePlan::new(typed).unwrap();
+
+        let pulse = |i: usize| {
+            tensor1(&(0..6).map(|x| (i * 6 + x) as f32).collect::<Vec<_>>())
+                .into_shape(&[3, 2])
+                .unwrap()
+        };
+        let mut state = SimpleState::new(&plan).unwrap();
+        for i in 0..2 {
+            state.run(tvec!(pulse(i))).unwrap();
+        }
+        let snapshot = state.snapshot().unwrap();
+        assert!(snapshot.iter().any(|(name, _)| name == "sum.acc"));
+        let mut restored = SimpleState::new(&plan).unwrap();
+        restored.restore(snapshot).unwrap();
+        for i in 2..5 {
+            let expected = state.run(tvec!(pulse(i))).unwrap();
+            let found = restored.run(tvec!(pulse(i))).unwrap();
+            assert_eq!(expected, found);
+        }
+    }
+
+    #[test]
+    fn test_snapshot_names() {
+        use tract_pulse_opl::ops::Delay;
+        let mut model = TypedModel::default();
+        let fact = TypedFact::dt_shape(f32::datum_type(), &[3, 2]);
+        let a = model.add_source("a", fact.clone()).unwrap();
+        let delay = Delay::new_typed(&fact, 0, 2, 0).unwrap();
+        let first = model.wire_node("session", delay.clone(), &[a]).unwrap();
+        let second = model.wire_node("a.b", delay, &first).unwrap();
+        model.set_output_outlets(&second).unwrap();
+        let plan = SimplePlan::new(model).unwrap();
+
+        let mut state = SimpleState::new(&plan).unwrap();
+        state.session_state.tensors.insert("x".to_string(), tensor0(1f32));
+        let snapshot = state.snapshot().unwrap();
+        let mut names: Vec<&str> = snapshot.iter().map(|(name, _)| &**name).collect();
+        names.sort();
+        assert_eq!(names, vec![".session.x", "a\\.b.buffer", "session.buffer"]);
+
+        let mut restored = SimpleState::new(&plan).unwrap();
+        restored.session_state.tensors.insert("y".to_string(), tensor0(2f32));
+        restored.restore(snapshot).unwrap();
+        let ids: Vec<&String> = restored.session_state.tensors.keys().collect();
+        assert_eq!(ids, vec!["x"]);
+    }
+
+    #[test]
+    fn test_restore_mismatched_delay_buffer() {
+        use tract_pulse_opl::ops::Delay;
+        let mut model = TypedModel::default();
+        let fact = TypedFact::dt_shape(f32::datum_type(), &[3, 2]);
+        let a = model.add_source("a", fact.clone()).unwrap();
+        let delay = model.wire_node("delay", Delay::new_typed(&fact, 0, 2, 0).unwrap(), &[a]);
+        model.set_output_outlets(&delay.unwrap()).unwrap();
+        let plan = SimplePlan::new(model).unwrap();
+
+        // a state that has not run yet only has a placeholder buffer
+        let fresh = SimpleState::new(&plan).unwrap().snapshot().unwrap();
+        SimpleState::new(&plan).unwrap().restore(fresh).unwrap();
+
+        let mut state = SimpleState::new(&plan).unwrap();
+        state.run(tvec!(Tensor::zero::<f32>(&[3, 2]).unwrap())).unwrap();
+        let snapshot = state.snapshot().unwrap();
+        SimpleState::new(&plan).unwrap().restore(snapshot.clone()).unwrap();
+        for buffer in vec![
+            Tensor::zero::<f32>(&[3, 2]).unwrap(),
+            Tensor::zero::<f32>(&[2, 3]).unwrap(),
+            Tensor::zero::<f32>(&[2]).unwrap(),
+            Tensor::zero::<i8>(&[2, 2]).unwrap(),
+        ] {
+            let mut snapshot = snapshot.clone();
+            snapshot.iter_mut().find(|(name, _)| name == "delay.buffer").unwrap().1 = buffer;
+            assert!(SimpleState::new(&plan).unwrap().restore(snapshot).is_err());
+        }
+    }
 }
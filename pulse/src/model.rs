@@ -13,6 +13,23 @@ pub trait PulsedModelExt {
     ) -> TractResult<(PulsedModel, HashMap<OutletId, OutletId>)>;
 
     fn into_typed(self) -> TractResult<TypedModel>;
+
+    /// Algorithmic latency of the model, in frames of the `S` stream: how far
+    /// behind the input streams the most delayed output is (not counting the
+    /// time to fill a pulse).
+    fn latency(&self) -> TractResult<usize>;
+
+    /// Nodes carrying some state from one pulse to the next (delay buffers,
+    /// scan states, ...) with the size of this state in bytes.
+    fn buffered_bytes(&self) -> TractResult<Vec<(usize, TDim)>>;
+
+    /// Smallest pulse for which `source` can be pulsified, `max_pulse` being
+    /// a pulse that works.
+    ///
+    /// Operators require their input pulse to be a multiple of their stride,
+    /// so the minimal pulse divides every working pulse: only the divisors of
+    /// `max_pulse` are tried, each try pulsifying the whole model.
+    fn minimal_pulse(source: &TypedModel, max_pulse: usize) -> TractResult<usize>;
 }
 
 impl PulsedModelExt for PulsedModel {
@@ -30,16 +47,62 @@ impl PulsedModelExt for PulsedModel {
 
     fn into_typed(self) -> TractResult<TypedModel> {
         let mut typed = tract_core::model::translator::IntoTranslator.translate_model(&self)?;
-        let delays = tensor1(
-            &self
-                .output_outlets()?
-                .iter()
-                .map(|oo| Ok(self.outlet_fact(*oo)?.delay as _))
-                .collect::<TractResult<TVec<i64>>>()?,
-        );
+        let output_facts = self
+            .output_outlets()?
+            .iter()
+            .map(|oo| self.outlet_fact(*oo))
+            .collect::<TractResult<TVec<_>>>()?;
+        let input_facts = self
+            .input_outlets()?
+            .iter()
+            .map(|oo| self.outlet_fact(*oo))
+            .collect::<TractResult<TVec<_>>>()?;
+        let delays = tensor1(&output_facts.iter().map(|f| f.delay as i64).collect::<TVec<_>>());
         typed.properties.insert("pulse.delay".to_string(), delays.into_arc_tensor());
+        let input_axes = tensor1(&input_facts.iter().map(|f| f.axis as i64).collect::<TVec<_>>());
+        typed.properties.insert("pulse.input_axes".to_string(), input_axes.into_arc_tensor());
+        let output_axes = tensor1(&output_facts.iter().map(|f| f.axis as i64).collect::<TVec<_>>());
+        typed.properties.insert("pulse.output_axes".to_string(), output_axes.into_arc_tensor());
+        let pulses = tensor1(&input_facts.iter().map(|f| f.pulse() as i64).collect::<TVec<_>>());
+        typed.properties.insert("pulse.pulse".to_string(), pulses.into_arc_tensor());
         Ok(typed)
     }
+
+    fn latency(&self) -> TractResult<usize> {
+        let mut latency = 0;
+        for output in self.output_outlets()? {
+            let fact = self.outlet_fact(*output)?;
+            // output delays are counted in output frames
+            let (num, den) = stream_rate(&fact.dim)?;
+            let delay = (fact.delay * den + num - 1) / num;
+            latency = latency.max(delay);
+        }
+        Ok(latency)
+    }
+
+    fn buffered_bytes(&self) -> TractResult<Vec<(usize, TDim)>> {
+        let mut buffers = vec![];
+        for node in self.eval_order()? {
+            let node = self.node(node);
+            let inputs = self.node_input_facts(node.id)?;
+            let bytes = node.op.buffered_bytes(&inputs)?;
+            if bytes != 0.to_dim() {
+                buffers.push((node.id, bytes));
+            }
+        }
+        Ok(buffers)
+    }
+
+    fn minimal_pulse(source: &TypedModel, max_pulse: usize) -> TractResult<usize> {
+        let mut error = format_err!("No pulse to try");
+        for pulse in (1..=max_pulse).filter(|p| max_pulse % p == 0) {
+            match PulsedModel::new(source, pulse) {
+                Ok(_) => return Ok(pulse),
+                Err(e) => error = e,
+            }
+        }
+        Err(error).with_context(|| format!("Can not pulsify with a pulse dividing {}", max_pulse))
+    }
 }
 
 impl SpecialOps<PulsedFact, Box<dyn PulsedOp>> for PulsedModel {
@@ -0,0 +1,99 @@
+use crate::internal::*;
+use tract_core::ops::array::CumSum;
+use tract_pulse_opl::ops::PulseCumSum;
+
+register_all!(CumSum: pulsify);
+
+fn pulsify(
+    op: &CumSum,
+    _source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    _pulse: usize,
+) -> TractResult<TVec<OutletId>> {
+    let input = mapping[&node.inputs[0]];
+    let fact = target.outlet_fact(input)?.clone();
+    if op.axis != fact.axis {
+        return target.wire_node(&*node.name, op.clone(), &[input]);
+    }
+    if op.reverse {
+        bail!("Can not pulsify a reverse cumulative sum along the streaming axis");
+    }
+    target.wire_node(
+        &*node.name,
+        PulseCumSum { axis: op.axis, exclusive: op.exclusive, begin: fact.delay },
+        &[input],
+    )
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<PulseCumSum>(), ser_pulse_cum_sum)
+}
+
+fn ser_pulse_cum_sum(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op().downcast_ref::<PulseCumSum>().unwrap();
+    let wire = ast.mapping[&node.inputs[0]].clone();
+    Ok(Some(invocation(
+        "tract_pulse_cum_sum",
+        &[wire],
+        &[
+            ("axis", numeric(op.axis)),
+            ("exclusive", logical(op.exclusive)),
+            ("begin", numeric(op.begin)),
+        ],
+    )))
+}
+
+impl PulsedOp for CumSum {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        Ok(tvec!(inputs[0].clone()))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
+
+impl PulsedOp for PulseCumSum {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        Ok(tvec!(inputs[0].clone()))
+    }
+
+    fn buffered_bytes(&self, inputs: &[&PulsedFact]) -> TractResult<TDim> {
+        inputs[0].frames_bytes(1)
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tract_core::ops::array::Slice;
+
+    #[test]
+    fn cum_sum_after_delay() {
+        let mut model = TypedModel::default();
+        let fact = TypedFact::dt_shape(f32::datum_type(), [stream_dim(), 2.to_dim()].as_ref());
+        let a = model.add_source("a", fact).unwrap();
+        let slice = Slice { axis: 0, start: 1.to_dim(), end: stream_dim() };
+        let sliced = model.wire_node("slice", slice, &[a]).unwrap();
+        let sum = model.wire_node("sum", CumSum::new(0, true, false), &sliced).unwrap();
+        model.set_output_outlets(&sum).unwrap();
+        let pulsed = PulsedModel::new(&model, 3).unwrap();
+        assert_eq!(pulsed.output_fact(0).unwrap().delay, 1);
+
+        let mut state = SimpleState::new(SimplePlan::new(pulsed).unwrap()).unwrap();
+        let mut got = vec![];
+        for i in 0..3 {
+            let input = tensor1(&(0..6).map(|x| (i * 6 + x) as f32).collect::<Vec<_>>())
+                .into_shape(&[3, 2])
+                .unwrap();
+            let output = state.run(tvec!(input)).unwrap().remove(0);
+            got.extend_from_slice(output.as_slice::<f32>().unwrap());
+        }
+        // the first frame is skipped by the slice and must not be summed
+        assert_eq!(&got[2..10], &[0., 0., 2., 3., 6., 8., 12., 15.]);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::internal::*;
+use tract_core::ops::array::Gather;
+use tract_core::ops::konst::Const;
+
+register_all!(Gather: pulsify, Const: pulsify_const);
+
+/// Constants are only accepted as the indices of a Gather, which embeds them.
+fn pulsify_const(
+    _op: &Const,
+    source: &TypedModel,
+    node: &TypedNode,
+    _target: &mut PulsedModel,
+    _mapping: &HashMap<OutletId, OutletId>,
+    _pulse: usize,
+) -> TractResult<TVec<OutletId>> {
+    let as_indices = node.outputs[0]
+        .successors
+        .iter()
+        .all(|succ| succ.slot == 1 && source.node(succ.node).op_is::<Gather>());
+    if !as_indices || source.output_outlets()?.contains(&OutletId::new(node.id, 0)) {
+        bail!("Constant {} can not be pulsified (only Gather indices can be constant)", node.name);
+    }
+    Ok(tvec!())
+}
+
+fn pulsify(
+    op: &Gather,
+    source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    _pulse: usize,
+) -> TractResult<TVec<OutletId>> {
+    let input = mapping[&node.inputs[0]];
+    let indices = source
+        .outlet_fact(node.inputs[1])?
+        .konst
+        .clone()
+        .ok_or_else(|| format_err!("Gather can only be pulsified with constant indices"))?;
+    if target.outlet_fact(input)?.axis == op.axis {
+        bail!("Can not gather along the streaming axis");
+    }
+    target.wire_node(&*node.name, GatherUnary { axis: op.axis, indices }, &[input])
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<GatherUnary>(), ser_gather_unary)
+}
+
+fn ser_gather_unary(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op().downcast_ref::<GatherUnary>().unwrap();
+    let wire = ast.mapping[&node.inputs[0]].clone();
+    let indices = ast.konst_variable(format!("{}.indices", node.name), &op.indices)?;
+    Ok(Some(invocation("tract_core_gather", &[wire, indices], &[("axis", numeric(op.axis))])))
+}
+
+/// Gather with constant indices.
+#[derive(Debug, Clone, Hash)]
+pub struct GatherUnary {
+    pub axis: usize,
+    pub indices: Arc<Tensor>,
+}
+
+impl_dyn_hash!(GatherUnary);
+
+impl Op for GatherUnary {
+    fn name(&self) -> Cow<str> {
+        "GatherUnary".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("axis: {} indices: {:?}", self.axis, self.indices)])
+    }
+
+    op_pulse!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for GatherUnary {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        Gather::new(self.axis).eval(tvec!(input, self.indices.clone()))
+    }
+}
+
+impl TypedOp for GatherUnary {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let indices = TypedFact::from(self.indices.clone());
+        Gather::new(self.axis).output_facts(&[inputs[0], &indices])
+    }
+
+    as_op!();
+}
+
+impl PulsedOp for GatherUnary {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        let indices_shape: TVec<TDim> = self.indices.shape().iter().map(|d| d.to_dim()).collect();
+        fact.shape = Gather::new(self.axis).compute_output_shape(&*fact.shape, &*indices_shape)?;
+        if fact.axis > self.axis {
+            fact.axis = fact.axis + self.indices.rank() - 1;
+        }
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
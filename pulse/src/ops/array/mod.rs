@@ -1,7 +1,16 @@
 use crate::internal::*;
 
 mod concat;
+mod cum_sum;
+mod gather;
 mod pad;
-mod slice;
+pub(crate) mod slice;
 
-register_all_mod!(concat, pad, slice);
+register_all_mod!(concat, cum_sum, gather, pad, slice);
+
+pub fn register(registry: &mut Registry) {
+    concat::register(registry);
+    cum_sum::register(registry);
+    gather::register(registry);
+    pad::register(registry);
+}
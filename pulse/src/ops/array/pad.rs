@@ -1,7 +1,6 @@
 use crate::internal::*;
-use tract_core::ndarray::*;
 use tract_core::ops::array::{Pad, PadMode};
-use tract_pulse_opl::ops::{Delay, PulsePad};
+use tract_pulse_opl::ops::{Delay, PulseBorderPad, PulsePad};
 
 register_all!(Pad: pulsify);
 
@@ -15,10 +14,18 @@ fn pulsify(
 ) -> TractResult<TVec<OutletId>> {
     let mut input = mapping[&node.inputs[0]];
     let fact = target.outlet_fact(input)?.clone();
-    if !op.pads.iter().enumerate().all(|(ax, &(a, b))| ax == fact.axis || (a == 0 && b == 0)) {
-        bail!("Pad pulse only implemented for streaming dim");
-    }
     let (before, after) = op.pads[fact.axis];
+    if op.pads.iter().enumerate().any(|(ax, &(a, b))| ax != fact.axis && (a != 0 || b != 0)) {
+        // padding the other axes does not interact with the stream
+        let mut pads = op.pads.clone();
+        pads[fact.axis] = (0, 0);
+        let other_axes = Pad { pads, mode: op.mode.clone() };
+        if before == 0 && after == 0 {
+            return target.wire_node(&*node.name, other_axes, &[input]);
+        }
+        input = target.wire_node(format!("{}.other_axes", node.name), other_axes, &[input])?[0];
+    }
+    let fact = target.outlet_fact(input)?.clone();
     let pulse = fact.pulse();
     let mut extra_delay = before.saturating_sub(fact.delay);
     match op.mode {
@@ -29,12 +36,9 @@ fn pulsify(
                 extra_delay += before - start_offset;
             }
         }
-        PadMode::Edge => bail!(
-            "Edge padding mode needs pulse strictly bigger than left padding (pulse={} padding={})",
-            pulse,
-            before
-        ),
-        PadMode::Reflect => bail!("Reflect padding mode pulsing is not supported"),
+        PadMode::Edge | PadMode::Reflect | PadMode::Symmetric => {
+            return pulsify_border(op, node, target, input)
+        }
     };
     if extra_delay > 0 {
         input = target.wire_node(
@@ -55,153 +59,144 @@ fn pulsify(
     target.wire_node(&*node.name, op, &[input])
 }
 
-#[derive(Debug, Clone, Default, Hash)]
-struct PulsePadOpState {
-    current_pos: usize,
-    last_valid_frame: Option<Tensor>,
+/// Edge, reflect and symmetric padding copy frames of the stream: the output
+/// is delayed until the frames needed at the beginning have been received,
+/// and a window of past frames is kept for the ones needed at the end.
+fn pulsify_border(
+    op: &Pad,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    input: OutletId,
+) -> TractResult<TVec<OutletId>> {
+    let fact = target.outlet_fact(input)?.clone();
+    let (before, after) = op.pads[fact.axis];
+    let (delay, overlap) = match op.mode {
+        PadMode::Edge => (before, before + after),
+        _ => (2 * before, 2 * before + 2 * after),
+    };
+    let window = target.wire_node(
+        format!("{}.Delay", node.name),
+        Delay::new(fact.axis, &(&fact).into(), 0, overlap),
+        &[input],
+    )?;
+    let op = PulseBorderPad {
+        axis: fact.axis,
+        before,
+        after,
+        begin_input: fact.delay,
+        end_input: fact.delay.to_dim() + &fact.dim,
+        overlap,
+        delay,
+        mode: op.mode.clone(),
+    };
+    target.wire_node(&*node.name, op, &window)
 }
 
-impl OpState for PulsePadOpState {
-    fn eval(
-        &mut self,
-        session: &mut SessionState,
-        op: &dyn Op,
-        mut inputs: TVec<Arc<Tensor>>,
-    ) -> TractResult<TVec<Arc<Tensor>>> {
-        let input = args_1!(inputs).into_tensor();
-        let op = op.downcast_ref::<PulsePad>().ok_or_else(|| format_err!("Wrong Op type"))?;
-        let tensor = self.pad(session, op, input)?;
-        Ok(tvec!(tensor.into_arc_tensor()))
+impl PulsedOp for Pad {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        if self.pads[fact.axis] != (0, 0) {
+            bail!("Pad along the streaming axis needs a pulsed padding op")
+        }
+        for (ax, &(a, b)) in self.pads.iter().enumerate() {
+            fact.shape[ax] = fact.shape[ax].clone() + a + b;
+        }
+        Ok(tvec!(fact))
     }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
 }
 
-impl PulsePadOpState {
-    unsafe fn save_frame<T: Datum + Copy>(&mut self, op: &PulsePad, input: &Tensor, frame: usize) {
-        let data = input.to_array_view_unchecked::<T>();
-        self.last_valid_frame =
-            Some(data.index_axis(Axis(op.axis), frame).to_owned().into_tensor());
-    }
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<PulsePad>(), ser_pulse_pad);
+    registry.register_dumper(TypeId::of::<PulseBorderPad>(), ser_pulse_border_pad)
+}
 
-    unsafe fn fill_slice_constant<T: Datum + Copy>(
-        data: &mut Tensor,
-        constant: &Tensor,
-        axis: usize,
-        range: std::ops::Range<usize>,
-    ) {
-        let c = constant.to_scalar_unchecked::<T>();
-        data.to_array_view_mut_unchecked::<T>().slice_axis_mut(Axis(axis), range.into()).fill(*c);
-    }
+fn ser_pulse_pad(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op().downcast_ref::<PulsePad>().unwrap();
+    let wire = ast.mapping[&node.inputs[0]].clone();
+    let dt = ast.model.outlet_fact(node.inputs[0])?.datum_type;
+    let (border, value) = match &op.mode {
+        PadMode::Constant(c) => ("constant", c.clone()),
+        PadMode::Edge => ("replicated", Tensor::zero_dt(dt, &[])?.into_arc_tensor()),
+        _ => bail!("Unsupported pulsed padding mode {:?}", op.mode),
+    };
+    let value = ast.konst_variable(format!("{}.value", node.name), &value)?;
+    let after = ast.konst_variable(format!("{}.after", node.name), &rctensor0(op.after.clone()))?;
+    let end_input =
+        ast.konst_variable(format!("{}.end_input", node.name), &rctensor0(op.end_input.clone()))?;
+    Ok(Some(invocation(
+        "tract_pulse_pad",
+        &[wire],
+        &[
+            ("axis", numeric(op.axis)),
+            ("pulse", numeric(op.pulse)),
+            ("before", numeric(op.before)),
+            ("after", (*after).clone()),
+            ("begin_input", numeric(op.begin_input)),
+            ("end_input", (*end_input).clone()),
+            ("border", string(border)),
+            ("value", (*value).clone()),
+        ],
+    )))
+}
 
-    unsafe fn fill_slice_with_frame<T: Datum + Copy>(
-        data: &mut Tensor,
-        axis: usize,
-        valid: &Tensor,
-        range: std::ops::Range<usize>,
-    ) {
-        let mut data = data.to_array_view_mut_unchecked::<T>();
-        let valid = valid.to_array_view_unchecked::<T>();
-        for i in range {
-            data.slice_axis_mut(Axis(axis), (i..i + 1).into()).assign(&valid);
-        }
+impl PulsedOp for PulsePad {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        fact.dim += self.before.to_dim() + &self.after;
+        fact.delay -= self.before;
+        Ok(tvec!(fact))
     }
 
-    fn pad(
-        &mut self,
-        session: &mut SessionState,
-        op: &PulsePad,
-        mut input: Tensor,
-    ) -> TractResult<Tensor> {
-        let pulse_begin = self.current_pos;
-        let pulse_end = self.current_pos + op.pulse;
-        self.current_pos += op.pulse;
-        let end_input =
-            op.end_input.eval(&session.resolved_symbols).to_usize().unwrap_or(std::usize::MAX);
-        let after = op.after.eval(&session.resolved_symbols).to_usize().unwrap_or(std::usize::MAX);
-
-        if let PadMode::Edge = op.mode {
-            if after != 0 && pulse_begin < end_input {
-                let latest_valid_frame = (end_input - pulse_begin).min(op.pulse) - 1;
-                unsafe {
-                    dispatch_copy_by_size!(Self::save_frame(input.datum_type())(
-                        self,
-                        op,
-                        &input,
-                        latest_valid_frame
-                    ))
-                }
-            }
-        }
-
-        // pulse is entirely in valid input, just forward
-        if pulse_begin >= op.begin_input && pulse_end <= end_input {
-            return Ok(input);
-        }
-        // pulse is entirely before or after output is valid, just forward
-        if pulse_end <= op.begin_input - op.before || pulse_begin >= end_input.saturating_add(after)
-        {
-            return Ok(input);
+    fn buffered_bytes(&self, inputs: &[&PulsedFact]) -> TractResult<TDim> {
+        // short edge paddings (the longer ones go to PulseBorderPad, its
+        // window being held by its input Delay) keep the last frame around
+        if let PadMode::Edge = self.mode {
+            inputs[0].frames_bytes(1)
+        } else {
+            Ok(0.to_dim())
         }
+    }
 
-        if pulse_begin < op.begin_input {
-            let fill_up_to = (op.begin_input - pulse_begin).min(op.pulse);
-            match &op.mode {
-                PadMode::Constant(c) => unsafe {
-                    dispatch_copy_by_size!(Self::fill_slice_constant(input.datum_type())(
-                        &mut input,
-                        &c,
-                        op.axis,
-                        0..fill_up_to
-                    ))
-                },
-                PadMode::Edge => {
-                    let frame = input.slice(op.axis, fill_up_to, fill_up_to + 1)?;
-                    unsafe {
-                        dispatch_copy_by_size!(Self::fill_slice_with_frame(input.datum_type())(
-                            &mut input,
-                            op.axis,
-                            &frame,
-                            0..fill_up_to
-                        ))
-                    }
-                }
-                _ => unimplemented!(),
-            }
-        }
-        if pulse_end > end_input && after > 0 {
-            let fill_from = op.pulse - (pulse_end - end_input).min(op.pulse);
-            match &op.mode {
-                PadMode::Constant(c) => unsafe {
-                    dispatch_copy_by_size!(Self::fill_slice_constant(input.datum_type())(
-                        &mut input,
-                        c,
-                        op.axis,
-                        fill_from..op.pulse
-                    ))
-                },
-                PadMode::Edge => {
-                    let last_frame = self.last_valid_frame.as_ref().unwrap();
-                    unsafe {
-                        dispatch_copy_by_size!(Self::fill_slice_with_frame(input.datum_type())(
-                            &mut input,
-                            op.axis,
-                            last_frame,
-                            fill_from..op.pulse
-                        ))
-                    }
-                }
-                _ => unimplemented!(),
-            }
-        }
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
 
-        Ok(input)
-    }
+fn ser_pulse_border_pad(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op().downcast_ref::<PulseBorderPad>().unwrap();
+    let wire = ast.mapping[&node.inputs[0]].clone();
+    let border = match &op.mode {
+        PadMode::Edge => "replicated",
+        PadMode::Reflect => "reflect",
+        PadMode::Symmetric => "symmetric",
+        _ => bail!("Unsupported pulsed border padding mode {:?}", op.mode),
+    };
+    let end_input =
+        ast.konst_variable(format!("{}.end_input", node.name), &rctensor0(op.end_input.clone()))?;
+    Ok(Some(invocation(
+        "tract_pulse_border_pad",
+        &[wire],
+        &[
+            ("axis", numeric(op.axis)),
+            ("before", numeric(op.before)),
+            ("after", numeric(op.after)),
+            ("begin_input", numeric(op.begin_input)),
+            ("end_input", (*end_input).clone()),
+            ("overlap", numeric(op.overlap)),
+            ("delay", numeric(op.delay)),
+            ("border", string(border)),
+        ],
+    )))
 }
 
-impl PulsedOp for PulsePad {
+impl PulsedOp for PulseBorderPad {
     fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
         let mut fact = inputs[0].clone();
-        fact.dim += self.before.to_dim() + &self.after;
-        fact.delay -= self.before;
+        fact.shape[self.axis] = fact.shape[self.axis].clone() - self.overlap;
+        fact.dim += (self.before + self.after).to_dim();
+        fact.delay = fact.delay + self.delay - self.overlap - self.before;
         Ok(tvec!(fact))
     }
 
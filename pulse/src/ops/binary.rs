@@ -5,11 +5,24 @@ use tract_pulse_opl::ops::Delay;
 
 register_all!(UnaryOp: pulsify_un, TypedBinOp: pulsify_bin, Iff: pulsify_iff);
 
-fn sync_inputs(
+/// Delay the inputs so they all have the delay of the most delayed one.
+///
+/// Only delays are realigned: the streams must already run at the same rate
+/// (like a stream of length S/4 and a stream of length S downsampled by 4),
+/// as a Delay can not turn a pulse into another.
+pub(crate) fn sync_inputs(
     node: &TypedNode,
     target: &mut PulsedModel,
     mapping: &HashMap<OutletId, OutletId>,
 ) -> TractResult<TVec<OutletId>> {
+    let pulses: TVec<usize> = node
+        .inputs
+        .iter()
+        .map(|input| Ok(target.outlet_fact(mapping[input])?.pulse()))
+        .collect::<TractResult<_>>()?;
+    if pulses.iter().any(|p| *p != pulses[0]) {
+        bail!("Can not combine streams running at different rates (pulses: {:?})", pulses)
+    }
     let delay = node
         .inputs
         .iter()
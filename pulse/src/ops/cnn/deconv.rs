@@ -0,0 +1,153 @@
+use crate::internal::*;
+use crate::ops::array::slice::PulsedAxisSlice;
+use tract_core::ops::cnn::{DeconvUnary, PaddingSpec};
+use tract_pulse_opl::ops::{OverlapAdd, PulseMask};
+
+register_all!(DeconvUnary: pulsify);
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<OverlapAdd>(), ser_overlap_add)
+}
+
+fn ser_overlap_add(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op().downcast_ref::<OverlapAdd>().unwrap();
+    let wire = ast.mapping[&node.inputs[0]].clone();
+    Ok(Some(invocation(
+        "tract_pulse_overlap_add",
+        &[wire],
+        &[("axis", numeric(op.axis)), ("overlap", numeric(op.overlap))],
+    )))
+}
+
+/// Each input pulse is deconvolved on its own (without padding on the
+/// streaming axis), the overlapping tails are summed into the next pulse, then
+/// the bias is added and the padding is turned into delay.
+fn pulsify(
+    op: &DeconvUnary,
+    source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    _pulse: usize,
+) -> TractResult<TVec<OutletId>> {
+    let mut wire = mapping[&node.inputs[0]];
+    let fact = target.outlet_fact(wire)?.clone();
+    let input_shape = op.pool_spec.data_format.shape(&*fact.shape)?;
+    if Some(fact.axis) == input_shape.n_axis() {
+        return target.wire_node(&node.name, op.clone(), &[wire]);
+    }
+    if fact.axis == input_shape.c_axis() {
+        bail!("Can not pulsify deconvolution along the input channel axis");
+    }
+    let geo_axis = fact.axis - input_shape.h_axis();
+    let stride = op.pool_spec.stride(geo_axis);
+    let overlap = (op.pool_spec.kernel_shape[geo_axis] - 1) * op.pool_spec.dilation(geo_axis);
+
+    let source_shape =
+        op.pool_spec.data_format.shape(source.outlet_fact(node.inputs[0])?.shape.to_tvec())?;
+    let padding = op.pool_spec.padding.compute_one_for_deconv(
+        geo_axis,
+        &source_shape.hw_dims()[geo_axis],
+        op.pool_spec.kernel_shape[geo_axis],
+        op.pool_spec.dilation(geo_axis),
+        stride,
+        op.adjustments[geo_axis],
+    );
+    let pulse_padding = match &op.pool_spec.padding {
+        PaddingSpec::Valid => PaddingSpec::Valid,
+        PaddingSpec::Explicit(before, after, ceil_mode) => {
+            let mut before = before.clone();
+            let mut after = after.clone();
+            before[geo_axis] = 0;
+            after[geo_axis] = 0;
+            PaddingSpec::Explicit(before, after, *ceil_mode)
+        }
+        _ => bail!("Can only pulsify deconvolution with valid or explicit padding"),
+    };
+
+    // frames before and after the stream would leak in the overlap
+    wire = target.wire_node(
+        format!("{}.mask", node.name),
+        PulseMask {
+            axis: fact.axis,
+            begin: fact.delay,
+            end: fact.delay.to_dim() + &fact.dim,
+            value: Tensor::zero_dt(fact.datum_type, &[])?.into_arc_tensor(),
+        },
+        &[wire],
+    )?[0];
+
+    // a pulse of P frames deconvolves to P * stride frames plus the overlap
+    let mut pulse_op = op.clone();
+    pulse_op.pool_spec.padding = pulse_padding;
+    pulse_op.adjustments[geo_axis] = stride - 1;
+    pulse_op.bias = None;
+    wire = target.wire_node(format!("{}.deconv", node.name), pulse_op, &[wire])?[0];
+    wire = target.wire_node(
+        format!("{}.overlap_add", node.name),
+        OverlapAdd { axis: fact.axis, overlap },
+        &[wire],
+    )?[0];
+
+    if let Some(bias) = &op.bias {
+        let output_shape = op.pool_spec.data_format.shape(&*target.outlet_fact(wire)?.shape)?;
+        let mut bias_shape = tvec!(1; output_shape.rank());
+        bias_shape[output_shape.c_axis()] = bias.len();
+        let bias = bias.clone().into_tensor().into_shape(&bias_shape)?;
+        wire = target.wire_node(
+            format!("{}.bias", node.name),
+            tract_core::ops::math::add::unary(bias.into_arc_tensor()),
+            &[wire],
+        )?[0];
+    }
+
+    let output_dim = source.outlet_fact(OutletId::new(node.id, 0))?.shape[fact.axis].clone();
+    target.wire_node(
+        &node.name,
+        PulsedAxisSlice { axis: fact.axis, skip: padding.pad_before.to_usize()?, take: output_dim },
+        &[wire],
+    )
+}
+
+impl PulsedOp for DeconvUnary {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        let shape = tract_core::ops::cnn::deconv::output_shape(
+            &self.pool_spec,
+            &*fact.shape,
+            &self.adjustments,
+        )?;
+        let input_shape = self.pool_spec.data_format.shape(&*fact.shape)?;
+        if Some(fact.axis) != input_shape.n_axis() {
+            let stream_shape = fact.streaming_shape();
+            let stream_shape = tract_core::ops::cnn::deconv::output_shape(
+                &self.pool_spec,
+                &*stream_shape,
+                &self.adjustments,
+            )?;
+            let stride = self.pool_spec.stride(fact.axis - input_shape.h_axis());
+            fact.dim = stream_shape[fact.axis].clone();
+            fact.delay *= stride;
+        }
+        fact.shape = shape;
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
+
+impl PulsedOp for OverlapAdd {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        fact.shape[self.axis] = fact.shape[self.axis].clone() - self.overlap.to_dim();
+        Ok(tvec!(fact))
+    }
+
+    fn buffered_bytes(&self, inputs: &[&PulsedFact]) -> TractResult<TDim> {
+        inputs[0].frames_bytes(self.overlap)
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
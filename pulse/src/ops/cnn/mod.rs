@@ -1,6 +1,11 @@
 use crate::internal::*;
 
 mod conv;
+mod deconv;
 mod pools;
 
-register_all_mod!(conv, pools);
+register_all_mod!(conv, deconv, pools);
+
+pub fn register(registry: &mut Registry) {
+    deconv::register(registry);
+}
@@ -27,6 +27,10 @@ impl PulsedOp for Delay {
         Ok(tvec!(fact))
     }
 
+    fn buffered_bytes(&self, inputs: &[&PulsedFact]) -> TractResult<TDim> {
+        inputs[0].frames_bytes(self.delay + self.overlap)
+    }
+
     as_op!();
     pulsed_op_to_typed_op!();
 }
@@ -0,0 +1,32 @@
+use crate::internal::*;
+use tract_pulse_opl::ops::PulseMask;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<PulseMask>(), ser_pulse_mask)
+}
+
+fn ser_pulse_mask(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op().downcast_ref::<PulseMask>().unwrap();
+    let wire = ast.mapping[&node.inputs[0]].clone();
+    let value = ast.konst_variable(format!("{}.value", node.name), &op.value)?;
+    let end = ast.konst_variable(format!("{}.end", node.name), &rctensor0(op.end.clone()))?;
+    Ok(Some(invocation(
+        "tract_pulse_mask",
+        &[wire],
+        &[
+            ("axis", numeric(op.axis)),
+            ("begin", numeric(op.begin)),
+            ("end", (*end).clone()),
+            ("value", (*value).clone()),
+        ],
+    )))
+}
+
+impl PulsedOp for PulseMask {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        Ok(tvec!(inputs[0].clone()))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
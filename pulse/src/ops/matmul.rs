@@ -1,7 +1,7 @@
 use crate::internal::*;
-use tract_core::ops::matmul::MatMulUnary;
+use tract_core::ops::matmul::{MatMul, MatMulUnary};
 
-register_all!(MatMulUnary: pulsify);
+register_all!(MatMulUnary: pulsify, MatMul: pulsify_bin);
 
 fn pulsify(
     op: &MatMulUnary,
@@ -36,3 +36,39 @@ impl PulsedOp for MatMulUnary {
     as_op!();
     pulsed_op_to_typed_op!();
 }
+
+fn pulsify_bin(
+    op: &MatMul,
+    _source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    _pulse: usize,
+) -> TractResult<TVec<OutletId>> {
+    let inputs = crate::ops::binary::sync_inputs(node, target, mapping)?;
+    let a = target.outlet_fact(inputs[0])?;
+    let b = target.outlet_fact(inputs[1])?;
+    if a.axis != b.axis || a.axis + 2 >= a.shape.len() {
+        bail!("Can only pulsify MatMul along a common batch dimension");
+    }
+    target.wire_node(&*node.name, op.clone(), &inputs)
+}
+
+impl PulsedOp for MatMul {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        let (_m, _k, _n, c_shape) = tract_core::ops::matmul::compute_shape(
+            &inputs[0].shape,
+            &inputs[1].shape,
+            self.a_trans,
+            self.b_trans,
+            self.c_trans,
+        )?;
+        fact.datum_type = tract_core::ops::matmul::output_type(inputs[0].datum_type);
+        fact.shape = c_shape;
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
@@ -8,6 +8,7 @@ pub mod delay;
 pub mod downsample;
 pub mod dummy;
 pub mod element_wise;
+pub mod mask;
 pub mod matmul;
 pub mod nn;
 pub mod quant;
@@ -70,6 +71,11 @@ pub trait PulsedOp:
 
     /// Deduce output facts from input facts.
     fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>>;
+
+    /// Size in bytes of the state the op carries from one pulse to the next.
+    fn buffered_bytes(&self, _inputs: &[&PulsedFact]) -> TractResult<TDim> {
+        Ok(0.to_dim())
+    }
 }
 
 tract_core::dyn_clone::clone_trait_object!(PulsedOp);
@@ -14,7 +14,7 @@ fn pulsify(
     let input = mapping[&node.inputs[0]];
     let axis = target.outlet_fact(input)?.axis;
     if op.axes.contains(&axis) {
-        bail!("Can not reduce over streaming axis");
+        bail!("Can not reduce over streaming axis (output depends on the whole stream)");
     }
     target.wire_node(&*node.name, op.clone(), &[input])
 }
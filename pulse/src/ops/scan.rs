@@ -71,6 +71,18 @@ impl PulsedOp for Scan {
         Ok(tvec!(fact))
     }
 
+    fn buffered_bytes(&self, _inputs: &[&PulsedFact]) -> TractResult<TDim> {
+        let mut bytes = 0.to_dim();
+        for (ix, mapping) in self.input_mapping.iter().enumerate() {
+            if mapping.as_state().is_some() {
+                let fact = self.body.input_fact(ix)?;
+                let volume = fact.shape.iter().try_fold(1.to_dim(), |acc, d| acc.maybe_mul(&d))?;
+                bytes += volume * fact.datum_type.size_of();
+            }
+        }
+        Ok(bytes)
+    }
+
     as_op!();
     pulsed_op_to_typed_op!();
 }
@@ -0,0 +1,110 @@
+//! Functions from a GraphDef library, as used by the TF2 functional control
+//! flow operators (While, If, PartitionedCall, ...).
+use crate::tfpb::tensorflow::attr_value::Value;
+use crate::tfpb::tensorflow::{AttrValue, FunctionDef, GraphDef, NodeDef};
+use crate::tfpb::{self, node};
+use tract_hir::internal::*;
+
+/// A function body rewritten as a plain graph, with the names of the tensors
+/// it takes as input and returns.
+#[derive(Clone, Debug)]
+pub struct FunctionGraph {
+    pub graph: GraphDef,
+    pub inputs: Vec<String>,
+    pub outputs: Vec<String>,
+}
+
+/// Rewrites a function body to a GraphDef.
+///
+/// Input arguments become Placeholders, and function-style inputs
+/// ("node:output_arg:index") are translated to graph-style inputs
+/// ("node:output_index").
+pub fn graph_for_function(func: &FunctionDef) -> TractResult<FunctionGraph> {
+    let signature = func.signature.as_ref().context("Function without signature")?;
+    let mut graph = tfpb::graph();
+    let mut inputs = vec![];
+    for (ix, arg) in signature.input_arg.iter().enumerate() {
+        if !arg.number_attr.is_empty() || !arg.type_list_attr.is_empty() {
+            bail!("Function {}: list argument {} is not supported", signature.name, arg.name);
+        }
+        if !arg.type_attr.is_empty() {
+            bail!("Function {}: generic argument {} is not supported", signature.name, arg.name);
+        }
+        let mut placeholder = node().name(&*arg.name).op("Placeholder");
+        placeholder.attr.insert("dtype".into(), AttrValue { value: Some(Value::Type(arg.r#type)) });
+        if let Some(shape) = func
+            .arg_attr
+            .get(&(ix as u32))
+            .and_then(|attrs| attrs.attr.get("_output_shapes"))
+            .and_then(|shapes| shapes.value.as_ref())
+            .and_then(|shapes| if let Value::List(l) = shapes { l.shape.first() } else { None })
+        {
+            if !shape.unknown_rank {
+                placeholder = placeholder.attr("shape", shape.clone());
+            }
+        }
+        graph.node.push(placeholder);
+        inputs.push(arg.name.clone());
+    }
+    let ops: HashMap<&str, &str> = func.node_def.iter().map(|n| (&*n.name, &*n.op)).collect();
+    let translate = |input: &str| -> TractResult<String> {
+        if input.starts_with('^') {
+            return Ok(input.to_string());
+        }
+        let splits: Vec<&str> = input.split(':').collect();
+        match splits.len() {
+            1 => Ok(input.to_string()),
+            // argument reference, with an explicit index
+            2 => Ok(if splits[1] == "0" { splits[0].to_string() } else { input.to_string() }),
+            3 => {
+                let op = ops.get(splits[0]).with_context(|| {
+                    format!("Function {}: no node named {}", signature.name, splits[0])
+                })?;
+                let index = splits[2].parse::<usize>()? + output_arg_offset(op, splits[1]);
+                Ok(format!("{}:{}", splits[0], index))
+            }
+            _ => bail!("Function {}: can not parse input {}", signature.name, input),
+        }
+    };
+    for n in &func.node_def {
+        let input = n.input.iter().map(|i| translate(i)).collect::<TractResult<_>>()?;
+        graph.node.push(NodeDef { input, ..n.clone() });
+    }
+    let outputs = signature
+        .output_arg
+        .iter()
+        .map(|arg| {
+            let ret = func.ret.get(&arg.name).with_context(|| {
+                format!("Function {}: no value returned for {}", signature.name, arg.name)
+            })?;
+            translate(ret)
+        })
+        .collect::<TractResult<_>>()?;
+    Ok(FunctionGraph { graph, inputs, outputs })
+}
+
+/// Position of the first output tensor of a named output argument, for the
+/// operators having more than one output argument.
+fn output_arg_offset(op: &str, arg: &str) -> usize {
+    let args: &[&str] = match op {
+        "Switch" | "RefSwitch" => &["output_false", "output_true"],
+        "Merge" | "RefMerge" => &["output", "value_index"],
+        "FusedBatchNorm" | "FusedBatchNormV2" | "FusedBatchNormV3" => &[
+            "y",
+            "batch_mean",
+            "batch_variance",
+            "reserve_space_1",
+            "reserve_space_2",
+            "reserve_space_3",
+        ],
+        "Unique" => &["y", "idx"],
+        "TopK" | "TopKV2" => &["values", "indices"],
+        "BlockLSTM" | "BlockLSTMV2" | "LSTMBlockCell" => &["i", "cs", "f", "o", "ci", "co", "h"],
+        "GRUBlockCell" => &["r", "u", "c", "h"],
+        "CudnnRNN" | "CudnnRNNV2" | "CudnnRNNV3" => {
+            &["output", "output_h", "output_c", "reserve_space", "host_reserved"]
+        }
+        _ => &[],
+    };
+    args.iter().position(|a| *a == arg).unwrap_or(0)
+}
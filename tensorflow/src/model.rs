@@ -1,13 +1,48 @@
-use crate::tfpb::tensorflow::{GraphDef, NodeDef, SavedModel};
+use crate::saved_model::{self, FrozenSavedModel, Signature};
+use crate::tfpb::tensorflow::{FunctionDef, FunctionDefLibrary, GraphDef, NodeDef, SavedModel};
 use prost::Message;
 use std::{fs, path};
 use tract_hir::internal::*;
 
-#[derive(Default)]
-pub struct ParsingContext {
+pub struct ParsingContext<'a> {
+    pub framework: &'a Tensorflow,
+    pub library: Option<&'a FunctionDefLibrary>,
     pub node_output_arities: HashMap<String, usize>,
 }
 
+impl<'a> ParsingContext<'a> {
+    pub fn function(&self, name: &str) -> TractResult<&'a FunctionDef> {
+        self.library
+            .and_then(|lib| {
+                lib.function.iter().find(|f| f.signature.as_ref().map(|s| &*s.name) == Some(name))
+            })
+            .with_context(|| format!("Function {} not found in graph library", name))
+    }
+
+    /// Parses a library function to a model, with inputs and outputs in the
+    /// order of the function signature.
+    pub fn parse_function(&self, name: &str) -> TractResult<InferenceModel> {
+        let func = crate::function::graph_for_function(self.function(name)?)
+            .with_context(|| format!("Translating function {}", name))?;
+        let mut model = self
+            .framework
+            .parse_graph_with_library(&func.graph, self.library)
+            .with_context(|| format!("Parsing function {}", name))?
+            .0;
+        let outlet = |model: &InferenceModel, name: &str| -> TractResult<OutletId> {
+            let (node, slot) = Tensorflow::parse_input(name)?;
+            Ok(OutletId::new(model.node_by_name(node)?.id, slot))
+        };
+        let inputs =
+            func.inputs.iter().map(|i| outlet(&model, i)).collect::<TractResult<TVec<_>>>()?;
+        let outputs =
+            func.outputs.iter().map(|o| outlet(&model, o)).collect::<TractResult<TVec<_>>>()?;
+        model.set_input_outlets(&inputs)?;
+        model.set_output_outlets(&outputs)?;
+        Ok(model)
+    }
+}
+
 #[derive(Clone, Default)]
 pub struct TfOpRegister(
     pub HashMap<String, fn(&ParsingContext, node: &NodeDef) -> TractResult<Box<dyn InferenceOp>>>,
@@ -115,12 +150,108 @@ impl Tensorflow {
         Ok(saved.meta_graphs.remove(0).graph_def.unwrap())
     }
 
+    /// Reads a SavedModel directory, freezing the variables from its
+    /// `variables/` checkpoint.
+    ///
+    /// The meta graph tagged "serve" is used, or the first one. If a
+    /// signature is picked (explicitly, or "serving_default" when it exists)
+    /// the graph is pruned to what this signature needs.
+    pub fn read_saved_model_dir(
+        &self,
+        dir: impl AsRef<path::Path>,
+        signature: Option<&str>,
+    ) -> TractResult<FrozenSavedModel> {
+        let dir = dir.as_ref();
+        let pb = dir.join("saved_model.pb");
+        let mut saved = self.open_saved_model(
+            &mut fs::File::open(&pb).with_context(|| format!("Opening {:?}", pb))?,
+        )?;
+        let ix = saved
+            .meta_graphs
+            .iter()
+            .position(|mg| {
+                mg.meta_info_def
+                    .as_ref()
+                    .map(|i| i.tags.iter().any(|t| t == "serve"))
+                    .unwrap_or(false)
+            })
+            .unwrap_or(0);
+        if ix >= saved.meta_graphs.len() {
+            bail!("SavedModel {:?} contains no meta graph", dir)
+        }
+        let mut meta = saved.meta_graphs.remove(ix);
+        let mut graph = meta.graph_def.take().context("Meta graph has no graph")?;
+        let signature = match signature {
+            Some(key) => Some(meta.signature_def.get(key).with_context(|| {
+                let mut keys = meta.signature_def.keys().collect::<Vec<_>>();
+                keys.sort();
+                format!("No signature {} in {:?} (found: {:?})", key, dir, keys)
+            })?),
+            None => meta.signature_def.get(saved_model::DEFAULT_SIGNATURE),
+        };
+        let signature = signature.map(Signature::from_def).transpose()?;
+        let prefix = dir.join("variables").join("variables");
+        if dir.join("variables").join("variables.index").exists() {
+            let variables = saved_model::read_tensor_bundle(&prefix)?;
+            saved_model::freeze_variables(&mut graph, &variables)?;
+        }
+        if let Some(signature) = &signature {
+            let inputs = signature.inputs.iter().map(|i| &*i.1).collect::<Vec<_>>();
+            let outputs = signature.outputs.iter().map(|o| &*o.1).collect::<Vec<_>>();
+            saved_model::prune(&mut graph, &inputs, &outputs)?;
+        }
+        Ok(FrozenSavedModel { graph, signature })
+    }
+
+    /// Parses a frozen SavedModel graph, using the signature (if any) to set
+    /// the model inputs and outputs.
+    pub fn parse_saved_model(&self, saved: &FrozenSavedModel) -> TractResult<TfModelAndExtensions> {
+        let mut parsed = self.parse_graph(&saved.graph)?;
+        if let Some(signature) = &saved.signature {
+            let model = &mut parsed.0;
+            let outlet = |model: &InferenceModel, name: &str| -> TractResult<OutletId> {
+                let (node, slot) = Self::parse_input(name)?;
+                Ok(OutletId::new(model.node_by_name(node)?.id, slot))
+            };
+            let inputs = signature
+                .inputs
+                .iter()
+                .map(|i| outlet(model, &i.1))
+                .collect::<TractResult<TVec<_>>>()?;
+            let outputs = signature
+                .outputs
+                .iter()
+                .map(|o| outlet(model, &o.1))
+                .collect::<TractResult<TVec<_>>>()?;
+            model.set_input_outlets(&inputs)?;
+            model.set_output_outlets(&outputs)?;
+        }
+        Ok(parsed)
+    }
+
     pub fn parse_graph(&self, graph: &GraphDef) -> TractResult<TfModelAndExtensions> {
+        let mut parsed = self.parse_graph_with_library(graph, graph.library.as_ref())?;
+        if let Some(versions) = &graph.versions {
+            let properties = &mut parsed.0.properties;
+            properties.insert("tf.producer_version".to_string(), rctensor0(versions.producer));
+            properties
+                .insert("tf.min_consumer_version".to_string(), rctensor0(versions.min_consumer));
+        }
+        Ok(parsed)
+    }
+
+    /// Parses a graph, resolving the functions it calls in `library`.
+    pub fn parse_graph_with_library(
+        &self,
+        graph: &GraphDef,
+        library: Option<&FunctionDefLibrary>,
+    ) -> TractResult<TfModelAndExtensions> {
         use crate::ops::control_flow as cf;
 
         let mut model = InferenceModel::default();
         let mut inputs = tvec!();
-        let mut context = ParsingContext::default();
+        let mut context =
+            ParsingContext { framework: self, library, node_output_arities: HashMap::new() };
         let mut control_inputs = vec![];
 
         // compute min output arity for all nodes
@@ -146,6 +277,9 @@ impl Tensorflow {
 
             let op = match self.op_register.0.get(&pbnode.op) {
                 Some(builder) => (builder)(&context, pbnode)?,
+                None if context.function(&pbnode.op).is_ok() => {
+                    Box::new(cf::FunctionCall::new(context.parse_function(&pbnode.op)?))
+                }
                 None => tract_hir::ops::unimpl::UnimplementedOp::new(
                     context.node_output_arities.get(name).cloned().unwrap_or(1),
                     &pbnode.op,
@@ -235,7 +369,11 @@ impl Tensorflow {
 
 impl Framework<GraphDef, InferenceModel> for Tensorflow {
     /// This method will try to read as frozen model, then as a saved model.
+    /// Directories are read as SavedModel with frozen variables.
     fn proto_model_for_path(&self, r: impl AsRef<path::Path>) -> TractResult<GraphDef> {
+        if r.as_ref().is_dir() {
+            return Ok(self.read_saved_model_dir(r, None)?.graph);
+        }
         self.read_frozen_model(&mut fs::File::open(r.as_ref())?)
             .or_else(|_| self.read_saved_model(&mut fs::File::open(r.as_ref())?))
     }
@@ -249,4 +387,15 @@ impl Framework<GraphDef, InferenceModel> for Tensorflow {
     fn model_for_proto_model(&self, graph: &GraphDef) -> TractResult<InferenceModel> {
         Ok(self.parse_graph(graph)?.0)
     }
+
+    /// SavedModel directories get their inputs and outputs from the default
+    /// signature.
+    fn model_for_path(&self, p: impl AsRef<path::Path>) -> TractResult<InferenceModel> {
+        if p.as_ref().is_dir() {
+            let saved = self.read_saved_model_dir(p, None)?;
+            return Ok(self.parse_saved_model(&saved)?.0);
+        }
+        let proto = self.proto_model_for_path(p)?;
+        self.model_for_proto_model(&proto)
+    }
 }
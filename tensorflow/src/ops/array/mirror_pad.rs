@@ -0,0 +1,72 @@
+use tract_hir::internal::*;
+use tract_hir::ops::array::PadMode;
+
+use crate::model::ParsingContext;
+use crate::tfpb::tensorflow::NodeDef;
+
+pub fn mirror_pad(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let mode = match pb.get_attr_str("mode")?.as_str() {
+        "REFLECT" => PadMode::Reflect,
+        "SYMMETRIC" => PadMode::Symmetric,
+        mode => bail!("Unsupported MirrorPad mode {}", mode),
+    };
+    Ok(expand(MirrorPad::new(mode)))
+}
+
+#[derive(Debug, Clone, new, Hash)]
+pub struct MirrorPad {
+    mode: PadMode,
+}
+
+impl_dyn_hash!(MirrorPad);
+
+impl MirrorPad {
+    fn pads(paddings: &Tensor) -> TractResult<Vec<(usize, usize)>> {
+        let paddings = paddings.cast_to::<i64>()?;
+        let paddings =
+            paddings.to_array_view::<i64>()?.into_dimensionality::<tract_ndarray::Ix2>()?;
+        Ok(paddings.outer_iter().map(|p| (p[0] as usize, p[1] as usize)).collect())
+    }
+}
+
+impl Expansion for MirrorPad {
+    fn name(&self) -> Cow<str> {
+        "MirrorPad".into()
+    }
+
+    op_tf!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].rank, &outputs[0].rank)?;
+        s.equals(&inputs[1].rank, 2)?;
+        s.given_2(&inputs[0].shape, &inputs[1].value, move |s, shape, paddings| {
+            let pads = Self::pads(&paddings)?;
+            let shape: TVec<TDim> =
+                shape.iter().zip(pads.iter()).map(|(d, (a, b))| d.clone() + *a + *b).collect();
+            s.equals(&outputs[0].shape, shape)
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        target: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let paddings = target
+            .outlet_fact(inputs[1])?
+            .konst
+            .clone()
+            .context("MirrorPad expects constant paddings")?;
+        let op = tract_hir::ops::array::Pad::new(Self::pads(&paddings)?, self.mode.clone());
+        target.wire_node(prefix, op, &inputs[0..1])
+    }
+}
@@ -9,11 +9,15 @@ mod expand_dims;
 mod fill;
 mod gather_nd;
 mod gather_v2;
+mod mirror_pad;
+mod one_hot;
 mod pack;
 mod pad;
 mod range;
+mod split;
 mod squeeze;
 mod transpose;
+mod unpack;
 
 pub fn register_all_ops(reg: &mut TfOpRegister) {
     reg.insert("ConcatV2", concatv2::build);
@@ -21,16 +25,21 @@ pub fn register_all_ops(reg: &mut TfOpRegister) {
     reg.insert("Fill", fill::fill);
     reg.insert("GatherNd", gather_nd::gather_nd);
     reg.insert("GatherV2", gather_v2::gather_v2);
+    reg.insert("MirrorPad", mirror_pad::mirror_pad);
+    reg.insert("OneHot", one_hot::one_hot);
     reg.insert("Pack", pack::pack);
     reg.insert("Pad", pad::pad);
     reg.insert("Range", range::range);
     reg.insert("Reshape", |_, _| Ok(expand(tract_hir::ops::array::Reshape::new())));
     reg.insert("Shape", |_, _| Ok(expand(tract_hir::ops::array::Shape::new(DatumType::I32))));
     reg.insert("Slice", slice);
+    reg.insert("Split", split::split);
+    reg.insert("SplitV", split::split_v);
     reg.insert("Squeeze", squeeze::squeeze);
     reg.insert("StridedSlice", strided_slice);
     reg.insert("Tile", |_, _| Ok(expand(::tract_hir::ops::array::Tile)));
     reg.insert("Transpose", transpose::transpose);
+    reg.insert("Unpack", unpack::unpack);
 }
 
 fn strided_slice(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
@@ -0,0 +1,77 @@
+use tract_hir::internal::*;
+
+use crate::model::ParsingContext;
+use crate::tfpb::tensorflow::NodeDef;
+
+pub fn one_hot(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let axis = pb.get_attr_opt_int("axis")?.unwrap_or(-1i64);
+    Ok(expand(OneHot::new(axis)))
+}
+
+/// Inputs are indices, depth, on_value and off_value.
+#[derive(Debug, Clone, new, Hash)]
+pub struct OneHot {
+    axis: i64,
+}
+
+impl_dyn_hash!(OneHot);
+
+impl Expansion for OneHot {
+    fn name(&self) -> Cow<str> {
+        "OneHot".into()
+    }
+
+    op_tf!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 4)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[1].rank, 0)?;
+        s.equals(&inputs[2].rank, 0)?;
+        s.equals(&inputs[3].rank, 0)?;
+        s.equals(&inputs[2].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[3].datum_type, &outputs[0].datum_type)?;
+        s.equals(inputs[0].rank.bex() + 1, &outputs[0].rank)?;
+        s.given_2(&inputs[0].shape, &inputs[1].value, move |s, shape, depth| {
+            let axis = if self.axis < 0 { self.axis + shape.len() as i64 + 1 } else { self.axis };
+            let mut shape = shape.clone();
+            shape.insert(axis as usize, depth_value(&depth)?.to_dim());
+            s.equals(&outputs[0].shape, shape)
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        target: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let rank = target.outlet_fact(inputs[0])?.rank() as i64;
+        let axis = if self.axis < 0 { self.axis + rank + 1 } else { self.axis } as usize;
+        let konst = |ix: usize, what: &str| -> TractResult<Arc<Tensor>> {
+            target
+                .outlet_fact(inputs[ix])?
+                .konst
+                .clone()
+                .with_context(|| format!("OneHot expects a constant {}", what))
+        };
+        let dim = depth_value(&*konst(1, "depth")?)?;
+        let on = konst(2, "on_value")?;
+        let off = konst(3, "off_value")?;
+        let op = tract_core::ops::array::OneHot { axis, dim, on, off };
+        target.wire_node(prefix, op, &inputs[0..1])
+    }
+}
+
+fn depth_value(depth: &Tensor) -> TractResult<usize> {
+    let depth = depth.cast_to_scalar::<i64>()?;
+    if depth < 0 {
+        bail!("OneHot depth must be non negative, got {}", depth)
+    }
+    Ok(depth as usize)
+}
@@ -0,0 +1,152 @@
+use tract_hir::internal::*;
+
+use crate::model::ParsingContext;
+use crate::tfpb::tensorflow::NodeDef;
+
+pub fn split(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let num_split = pb.get_attr_int("num_split")?;
+    Ok(expand(Split::new(num_split, false)))
+}
+
+pub fn split_v(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let num_split = pb.get_attr_int("num_split")?;
+    Ok(expand(Split::new(num_split, true)))
+}
+
+/// Split (inputs: axis, value) and SplitV (inputs: value, size_splits,
+/// axis).
+#[derive(Debug, Clone, new, Hash)]
+pub struct Split {
+    num_split: usize,
+    with_sizes: bool,
+}
+
+impl_dyn_hash!(Split);
+
+impl Split {
+    fn value_input(&self) -> usize {
+        if self.with_sizes {
+            0
+        } else {
+            1
+        }
+    }
+
+    fn axis_input(&self) -> usize {
+        if self.with_sizes {
+            2
+        } else {
+            0
+        }
+    }
+
+    fn split_dims(&self, dim: &TDim, sizes: Option<&Tensor>) -> TractResult<TVec<TDim>> {
+        if let Some(sizes) = sizes {
+            let sizes = sizes.cast_to::<i64>()?;
+            let sizes = sizes.as_slice::<i64>()?;
+            if sizes.len() != self.num_split {
+                bail!("SplitV expects {} sizes, got {:?}", self.num_split, sizes);
+            }
+            let known: i64 = sizes.iter().filter(|&&s| s >= 0).sum();
+            sizes
+                .iter()
+                .map(|&s| if s >= 0 { Ok(s.to_dim()) } else { Ok(dim.clone() - known) })
+                .collect()
+        } else {
+            Ok(tvec!(dim.clone() / self.num_split; self.num_split))
+        }
+    }
+}
+
+impl Expansion for Split {
+    fn name(&self) -> Cow<str> {
+        if self.with_sizes { "SplitV" } else { "Split" }.into()
+    }
+
+    op_tf!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2 + self.with_sizes as usize)?;
+        check_output_arity(&outputs, self.num_split)?;
+        let value = &inputs[self.value_input()];
+        let axis = &inputs[self.axis_input()];
+        s.equals(&axis.rank, 0)?;
+        for output in outputs {
+            s.equals(&value.datum_type, &output.datum_type)?;
+            s.equals(&value.rank, &output.rank)?;
+        }
+        s.given_2(&value.shape, &axis.value, move |s, shape, axis| {
+            let axis = axis.cast_to_scalar::<i64>()?;
+            let axis = if axis < 0 { axis + shape.len() as i64 } else { axis } as usize;
+            let dim = shape[axis].clone();
+            let apply = move |s: &mut Solver<'r>, dims: TVec<TDim>| -> InferenceResult {
+                for (output, dim) in outputs.iter().zip(dims) {
+                    let mut shape = shape.clone();
+                    shape[axis] = dim;
+                    s.equals(&output.shape, shape)?;
+                }
+                Ok(())
+            };
+            if self.with_sizes {
+                s.given(&inputs[1].value, move |s, sizes| {
+                    apply(s, self.split_dims(&dim, Some(&sizes))?)
+                })
+            } else {
+                apply(s, self.split_dims(&dim, None)?)
+            }
+        })
+    }
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(self.num_split)
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        target: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let value = inputs[self.value_input()];
+        let fact = target.outlet_fact(value)?.clone();
+        let axis = target
+            .outlet_fact(inputs[self.axis_input()])?
+            .konst
+            .as_ref()
+            .context("Split expects a constant axis")?
+            .cast_to_scalar::<i64>()?;
+        let axis = if axis < 0 { axis + fact.rank() as i64 } else { axis } as usize;
+        let sizes = if self.with_sizes {
+            Some(
+                target
+                    .outlet_fact(inputs[1])?
+                    .konst
+                    .clone()
+                    .context("SplitV expects constant sizes")?,
+            )
+        } else {
+            None
+        };
+        let mut outputs = tvec!();
+        let mut current = 0.to_dim();
+        for (ix, len) in
+            self.split_dims(&fact.shape[axis], sizes.as_deref())?.into_iter().enumerate()
+        {
+            let end = current.clone() + len;
+            outputs.push(
+                target.wire_node(
+                    format!("{}.{}", prefix, ix),
+                    tract_hir::ops::array::Slice::new(axis, current, end.clone()),
+                    &[value],
+                )?[0],
+            );
+            current = end;
+        }
+        Ok(outputs)
+    }
+}
@@ -0,0 +1,74 @@
+use tract_hir::internal::*;
+
+use crate::model::ParsingContext;
+use crate::tfpb::tensorflow::NodeDef;
+
+pub fn unpack(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let num = pb.get_attr_int("num")?;
+    let axis = pb.get_attr_opt_int("axis")?.unwrap_or(0i64);
+    Ok(expand(Unpack::new(num, axis)))
+}
+
+#[derive(Debug, Clone, new, Hash)]
+pub struct Unpack {
+    num: usize,
+    axis: i64,
+}
+
+impl_dyn_hash!(Unpack);
+
+impl Expansion for Unpack {
+    fn name(&self) -> Cow<str> {
+        "Unpack".into()
+    }
+
+    op_tf!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, self.num)?;
+        for output in outputs {
+            s.equals(&inputs[0].datum_type, &output.datum_type)?;
+            s.equals(inputs[0].rank.bex() - 1, &output.rank)?;
+        }
+        s.given(&inputs[0].shape, move |s, shape| {
+            let axis = if self.axis < 0 { self.axis + shape.len() as i64 } else { self.axis };
+            let mut shape = shape.clone();
+            let dim = shape.remove(axis as usize);
+            s.equals(dim, self.num.to_dim())?;
+            for output in outputs {
+                s.equals(&output.shape, shape.clone())?;
+            }
+            Ok(())
+        })
+    }
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(self.num)
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        target: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let rank = target.outlet_fact(inputs[0])?.rank() as i64;
+        let axis = if self.axis < 0 { self.axis + rank } else { self.axis } as usize;
+        (0..self.num)
+            .map(|ix| {
+                let wire = target.wire_node(
+                    format!("{}.slice-{}", prefix, ix),
+                    tract_hir::ops::array::Slice::new(axis, ix, ix + 1),
+                    &inputs[0..1],
+                )?;
+                Ok(target.wire_node(format!("{}.{}", prefix, ix), AxisOp::Rm(axis), &wire)?[0])
+            })
+            .collect()
+    }
+}
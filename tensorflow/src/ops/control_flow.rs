@@ -1,6 +1,11 @@
 use tract_hir::internal::*;
+use tract_hir::ops::scan::{InputMapping, OutputMapping, Scan, StateInitializer};
+use tract_hir::tract_core::ops::binary::{BinMiniOp, TypedBinOp, UnaryOp};
+use tract_hir::tract_core::ops::identity::Identity;
+use tract_hir::tract_core::ops::{logic, math};
 
-use crate::model::TfOpRegister;
+use crate::model::{ParsingContext, TfOpRegister};
+use crate::tfpb::tensorflow::NodeDef;
 
 pub fn register_all_ops(reg: &mut TfOpRegister) {
     reg.insert("Enter", |_, node| {
@@ -8,6 +13,29 @@ pub fn register_all_ops(reg: &mut TfOpRegister) {
     });
     reg.insert("Exit", |_, _| Ok(Box::new(LoopGate(LoopGateRole::Exit))));
     reg.insert("LoopCond", |_, _| Ok(Box::new(LoopGate(LoopGateRole::LoopCond))));
+    reg.insert("If", if_);
+    reg.insert("PartitionedCall", partitioned_call);
+    reg.insert("StatefulPartitionedCall", partitioned_call);
+    reg.insert("StatelessIf", if_);
+    reg.insert("StatelessWhile", while_);
+    reg.insert("While", while_);
+}
+
+fn partitioned_call(ctx: &ParsingContext, node: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let f = node.get_attr_func("f")?;
+    Ok(Box::new(FunctionCall::new(ctx.parse_function(&f.name)?)))
+}
+
+fn if_(ctx: &ParsingContext, node: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let then_branch = ctx.parse_function(&node.get_attr_func("then_branch")?.name)?;
+    let else_branch = ctx.parse_function(&node.get_attr_func("else_branch")?.name)?;
+    Ok(Box::new(If::new(then_branch, else_branch)))
+}
+
+fn while_(ctx: &ParsingContext, node: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let cond = ctx.parse_function(&node.get_attr_func("cond")?.name)?;
+    let body = ctx.parse_function(&node.get_attr_func("body")?.name)?;
+    Ok(Box::new(While::new(cond, body)))
 }
 
 #[derive(Debug, Clone, Hash)]
@@ -91,7 +119,10 @@ impl EvalOp for NextIteration {
         _state: &mut SessionState,
         _id: usize,
     ) -> TractResult<Option<Box<dyn OpState>>> {
-        unimplemented!();
+        bail!(
+            "{} belongs to a TensorFlow 1 dataflow loop, which can not be evaluated: only functional While loops are supported",
+            self.name()
+        )
     }
 }
 
@@ -117,3 +148,601 @@ impl InferenceRulesOp for NextIteration {
 
     as_op!();
 }
+
+/// Unifies type and shape of two facts, leaving values alone.
+fn unify_dt_shape(a: &mut InferenceFact, b: &mut InferenceFact) -> TractResult<bool> {
+    let dt = a.datum_type.unify_with_mut(&mut b.datum_type)?;
+    let shape = a.shape.unify_with_mut(&mut b.shape)?;
+    Ok(dt || shape)
+}
+
+/// Unifies outer facts with a function body interface, then analyses the
+/// body.
+fn unify_body(
+    body: &mut InferenceModel,
+    inputs: &mut [InferenceFact],
+    outputs: &mut [InferenceFact],
+) -> TractResult<bool> {
+    let mut changed = false;
+    for (ix, input) in inputs.iter_mut().enumerate() {
+        changed |= unify_dt_shape(input, body.input_fact_mut(ix)?)?;
+    }
+    for (ix, output) in outputs.iter_mut().enumerate() {
+        changed |= unify_dt_shape(output, body.output_fact_mut(ix)?)?;
+    }
+    changed |= body.analyse(false).context("analysing function body")?;
+    Ok(changed)
+}
+
+fn check_body_arity(
+    name: &str,
+    body: &InferenceModel,
+    inputs: usize,
+    outputs: usize,
+) -> TractResult<()> {
+    if body.input_outlets()?.len() != inputs || body.output_outlets()?.len() != outputs {
+        bail!(
+            "{} body has {} inputs and {} outputs, expected {} and {}",
+            name,
+            body.input_outlets()?.len(),
+            body.output_outlets()?.len(),
+            inputs,
+            outputs
+        )
+    }
+    Ok(())
+}
+
+/// Types a function body for the given input facts. Values are dropped, as
+/// bodies may be run on various inputs.
+fn typed_body(body: &InferenceModel, inputs: &[TypedFact]) -> TractResult<TypedModel> {
+    let mut body = body.clone();
+    for (ix, fact) in inputs.iter().enumerate() {
+        body.set_input_fact(ix, InferenceFact::from(&fact.without_value()))?;
+    }
+    body.into_typed()
+}
+
+/// Copies a typed body in the target model, wired to `inputs`.
+fn inline(
+    target: &mut TypedModel,
+    prefix: &str,
+    body: &TypedModel,
+    inputs: &[OutletId],
+) -> TractResult<TVec<OutletId>> {
+    let mut mapping: HashMap<OutletId, OutletId> = HashMap::new();
+    for (ix, input) in body.input_outlets()?.iter().enumerate() {
+        mapping.insert(*input, inputs[ix]);
+    }
+    for n in body.eval_order()? {
+        let node = body.node(n);
+        if mapping.contains_key(&OutletId::new(n, 0)) {
+            continue;
+        }
+        let node_inputs = node.inputs.iter().map(|i| mapping[i]).collect::<TVec<_>>();
+        let wires =
+            target.wire_node(format!("{}.{}", prefix, node.name), node.op.clone(), &node_inputs)?;
+        for (ix, wire) in wires.into_iter().enumerate() {
+            mapping.insert(OutletId::new(n, ix), wire);
+        }
+    }
+    Ok(body.output_outlets()?.iter().map(|o| mapping[o]).collect())
+}
+
+fn typed_facts(inputs: &[Arc<Tensor>]) -> TVec<TypedFact> {
+    inputs.iter().map(|t| TypedFact::from(&**t).without_value()).collect()
+}
+
+fn run(model: &TypedModel, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+    SimplePlan::new(model)?.run(inputs.into_iter().map(|t| t.into_tensor()).collect())
+}
+
+/// Truth value of a condition: non-empty tensors and non-zero scalars are
+/// true.
+fn truth(t: &Tensor) -> TractResult<bool> {
+    if t.rank() > 0 {
+        Ok(t.len() > 0)
+    } else {
+        Ok(*t.cast_to::<bool>()?.to_scalar::<bool>()?)
+    }
+}
+
+/// Call to a library function (PartitionedCall, StatefulPartitionedCall, or
+/// a node using a function as its op).
+#[derive(Debug, Clone, new, Hash)]
+pub struct FunctionCall {
+    pub body: InferenceModel,
+}
+
+impl_dyn_hash!(FunctionCall);
+
+impl Op for FunctionCall {
+    fn name(&self) -> Cow<str> {
+        "FunctionCall".into()
+    }
+
+    op_tf!();
+    not_a_typed_op!();
+}
+
+impl EvalOp for FunctionCall {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        run(&typed_body(&self.body, &typed_facts(&inputs))?, inputs)
+    }
+}
+
+impl InferenceOp for FunctionCall {
+    fn infer_facts(
+        &mut self,
+        inputs: TVec<&InferenceFact>,
+        outputs: TVec<&InferenceFact>,
+        _observed: TVec<&InferenceFact>,
+    ) -> TractResult<(TVec<InferenceFact>, TVec<InferenceFact>, TVec<InferenceFact>)> {
+        check_body_arity("Function", &self.body, inputs.len(), outputs.len())?;
+        let mut inputs: TVec<InferenceFact> = inputs.into_iter().cloned().collect();
+        let mut outputs: TVec<InferenceFact> = outputs.into_iter().cloned().collect();
+        while unify_body(&mut self.body, &mut inputs, &mut outputs)? {}
+        Ok((inputs, outputs, tvec!()))
+    }
+
+    fn to_typed(
+        &self,
+        _source: &InferenceModel,
+        node: &InferenceNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        let inputs = node.inputs.iter().map(|m| mapping[m]).collect::<TVec<_>>();
+        let facts = inputs
+            .iter()
+            .map(|o| target.outlet_fact(*o).cloned())
+            .collect::<TractResult<TVec<_>>>()?;
+        inline(target, &node.name, &typed_body(&self.body, &facts)?, &inputs)
+    }
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(self.body.output_outlets()?.len())
+    }
+
+    as_op!();
+}
+
+/// Functional conditional (If, StatelessIf). The first input is the
+/// condition, the others are passed to the selected branch.
+#[derive(Debug, Clone, new, Hash)]
+pub struct If {
+    pub then_branch: InferenceModel,
+    pub else_branch: InferenceModel,
+}
+
+impl_dyn_hash!(If);
+
+impl Op for If {
+    fn name(&self) -> Cow<str> {
+        "If".into()
+    }
+
+    op_tf!();
+    not_a_typed_op!();
+}
+
+impl EvalOp for If {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let cond = inputs.remove(0);
+        let branch = if truth(&cond)? { &self.then_branch } else { &self.else_branch };
+        run(&typed_body(branch, &typed_facts(&inputs))?, inputs)
+    }
+}
+
+impl InferenceOp for If {
+    fn infer_facts(
+        &mut self,
+        inputs: TVec<&InferenceFact>,
+        outputs: TVec<&InferenceFact>,
+        _observed: TVec<&InferenceFact>,
+    ) -> TractResult<(TVec<InferenceFact>, TVec<InferenceFact>, TVec<InferenceFact>)> {
+        if inputs.is_empty() {
+            bail!("If expects a condition input");
+        }
+        check_body_arity("If then", &self.then_branch, inputs.len() - 1, outputs.len())?;
+        check_body_arity("If else", &self.else_branch, inputs.len() - 1, outputs.len())?;
+        let mut inputs: TVec<InferenceFact> = inputs.into_iter().cloned().collect();
+        let mut outputs: TVec<InferenceFact> = outputs.into_iter().cloned().collect();
+        loop {
+            let mut changed = unify_body(&mut self.then_branch, &mut inputs[1..], &mut outputs)?;
+            changed |= unify_body(&mut self.else_branch, &mut inputs[1..], &mut outputs)?;
+            if !changed {
+                break;
+            }
+        }
+        Ok((inputs, outputs, tvec!()))
+    }
+
+    fn to_typed(
+        &self,
+        _source: &InferenceModel,
+        node: &InferenceNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        let inputs = node.inputs.iter().map(|m| mapping[m]).collect::<TVec<_>>();
+        let facts = inputs[1..]
+            .iter()
+            .map(|o| target.outlet_fact(*o).cloned())
+            .collect::<TractResult<TVec<_>>>()?;
+        if let Some(cond) = target.outlet_fact(inputs[0])?.konst.clone() {
+            let branch = if truth(&cond)? { &self.then_branch } else { &self.else_branch };
+            return inline(target, &node.name, &typed_body(branch, &facts)?, &inputs[1..]);
+        }
+        let op = TypedIf {
+            then_branch: typed_body(&self.then_branch, &facts)?,
+            else_branch: typed_body(&self.else_branch, &facts)?,
+        };
+        target.wire_node(&*node.name, op, &inputs)
+    }
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(self.then_branch.output_outlets()?.len())
+    }
+
+    as_op!();
+}
+
+/// If with a condition only known at runtime.
+#[derive(Debug, Clone, Hash)]
+pub struct TypedIf {
+    pub then_branch: TypedModel,
+    pub else_branch: TypedModel,
+}
+
+impl_dyn_hash!(TypedIf);
+
+impl Op for TypedIf {
+    fn name(&self) -> Cow<str> {
+        "TypedIf".into()
+    }
+
+    op_tf!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for TypedIf {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let cond = inputs.remove(0);
+        run(if truth(&cond)? { &self.then_branch } else { &self.else_branch }, inputs)
+    }
+}
+
+impl TypedOp for TypedIf {
+    fn output_facts(&self, _inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        (0..self.then_branch.output_outlets()?.len())
+            .map(|ix| Ok(self.then_branch.output_fact(ix)?.without_value()))
+            .collect()
+    }
+
+    as_op!();
+}
+
+/// Upper bound on a static trip count, as the loop is then turned into a scan
+/// over a constant of that length.
+const MAX_STATIC_ITERATIONS: usize = 1 << 20;
+
+/// Functional loop (While, StatelessWhile). Inputs are the initial values
+/// of the loop variables, outputs their final values.
+#[derive(Debug, Clone, new, Hash)]
+pub struct While {
+    pub cond: InferenceModel,
+    pub body: InferenceModel,
+}
+
+impl_dyn_hash!(While);
+
+impl Op for While {
+    fn name(&self) -> Cow<str> {
+        "While".into()
+    }
+
+    op_tf!();
+    not_a_typed_op!();
+}
+
+impl EvalOp for While {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let facts = typed_facts(&inputs);
+        TypedWhile::new(typed_body(&self.cond, &facts)?, typed_body(&self.body, &facts)?)?
+            .eval(inputs)
+    }
+}
+
+impl InferenceOp for While {
+    fn infer_facts(
+        &mut self,
+        inputs: TVec<&InferenceFact>,
+        outputs: TVec<&InferenceFact>,
+        _observed: TVec<&InferenceFact>,
+    ) -> TractResult<(TVec<InferenceFact>, TVec<InferenceFact>, TVec<InferenceFact>)> {
+        if inputs.len() != outputs.len() {
+            bail!("While has {} inputs and {} outputs", inputs.len(), outputs.len());
+        }
+        check_body_arity("While cond", &self.cond, inputs.len(), 1)?;
+        check_body_arity("While body", &self.body, inputs.len(), outputs.len())?;
+        let mut inputs: TVec<InferenceFact> = inputs.into_iter().cloned().collect();
+        let mut outputs: TVec<InferenceFact> = outputs.into_iter().cloned().collect();
+        loop {
+            // loop variables keep their type and shape across iterations
+            let mut changed = false;
+            for (input, output) in inputs.iter_mut().zip(outputs.iter_mut()) {
+                changed |= unify_dt_shape(input, output)?;
+            }
+            changed |= unify_body(&mut self.body, &mut inputs, &mut outputs)?;
+            changed |= unify_body(&mut self.cond, &mut inputs, &mut [])?;
+            if !changed {
+                break;
+            }
+        }
+        Ok((inputs, outputs, tvec!()))
+    }
+
+    fn to_typed(
+        &self,
+        _source: &InferenceModel,
+        node: &InferenceNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        let inputs = node.inputs.iter().map(|m| mapping[m]).collect::<TVec<_>>();
+        let facts = inputs
+            .iter()
+            .map(|o| target.outlet_fact(*o).cloned())
+            .collect::<TractResult<TVec<_>>>()?;
+        let op = TypedWhile::new(typed_body(&self.cond, &facts)?, typed_body(&self.body, &facts)?)?;
+        match op.static_trip_count(&facts)? {
+            Some(0) => Ok(inputs),
+            Some(n) => {
+                let scan = op.to_scan(&node.name)?;
+                let mut scan_inputs = inputs.clone();
+                let iterations = tensor1(&(0..n as i64).collect::<Vec<_>>());
+                scan_inputs
+                    .push(target.add_const(format!("{}.iterations", node.name), iterations)?);
+                target.wire_node(&*node.name, scan, &scan_inputs)
+            }
+            None => target.wire_node(&*node.name, op, &inputs),
+        }
+    }
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(self.body.output_outlets()?.len())
+    }
+
+    as_op!();
+}
+
+/// While loop, run as is. Used when the trip count can not be known
+/// beforehand.
+#[derive(Debug, Clone, Hash)]
+pub struct TypedWhile {
+    pub cond: Arc<TypedSimplePlan<TypedModel>>,
+    pub body: Arc<TypedSimplePlan<TypedModel>>,
+}
+
+impl_dyn_hash!(TypedWhile);
+
+impl TypedWhile {
+    pub fn new(cond: TypedModel, body: TypedModel) -> TractResult<TypedWhile> {
+        Ok(TypedWhile {
+            cond: Arc::new(SimplePlan::new(cond)?),
+            body: Arc::new(SimplePlan::new(body)?),
+        })
+    }
+
+    /// Number of iterations, when the condition compares a counter, starting
+    /// from a constant and moved by a constant step in the body, to a
+    /// constant limit.
+    fn static_trip_count(&self, facts: &[TypedFact]) -> TractResult<Option<usize>> {
+        let cond = self.cond.model();
+        let body = self.body.model();
+        let (op, a, b) = match binary(cond, cond.output_outlets()?[0])? {
+            Some(comparison) => comparison,
+            None => return Ok(None),
+        };
+        // a loop variable is a limit if it has a constant initial value and
+        // the body leaves it untouched
+        let limit = |operand: &Operand| -> TractResult<Option<i64>> {
+            match *operand {
+                Operand::Const(value) => Ok(Some(value)),
+                Operand::Var(var) => {
+                    if operand_of(body, body.output_outlets()?[var])? != Some(Operand::Var(var)) {
+                        return Ok(None);
+                    }
+                    konst_scalar(&facts[var])
+                }
+            }
+        };
+        let ((var, step), limit, flipped) =
+            if let (Some(counter), Some(limit)) = (self.counter(&a)?, limit(&b)?) {
+                (counter, limit, false)
+            } else if let (Some(counter), Some(limit)) = (self.counter(&b)?, limit(&a)?) {
+                (counter, limit, true)
+            } else {
+                return Ok(None);
+            };
+        let start = match konst_scalar(&facts[var])? {
+            Some(start) => start as i128,
+            None => return Ok(None),
+        };
+        let (step, limit) = (step as i128, limit as i128);
+        // the loop runs while the counter is below, or above, a bound
+        let lesser = op.is::<logic::Lesser>() || op.is::<logic::LesserEqual>();
+        let greater = op.is::<logic::Greater>() || op.is::<logic::GreaterEqual>();
+        if !lesser && !greater {
+            return Ok(None);
+        }
+        let strict = op.is::<logic::Lesser>() || op.is::<logic::Greater>();
+        let below = lesser != flipped;
+        let bound = match (below, strict) {
+            (_, true) => limit,
+            (true, false) => limit + 1,
+            (false, false) => limit - 1,
+        };
+        let (distance, step) = if below { (bound - start, step) } else { (start - bound, -step) };
+        let count = if distance <= 0 {
+            0
+        } else if step <= 0 {
+            // endless, or at least not ending by the counter
+            return Ok(None);
+        } else {
+            (distance + step - 1) / step
+        };
+        Ok(if count <= MAX_STATIC_ITERATIONS as i128 { Some(count as usize) } else { None })
+    }
+
+    /// Loop variable and its constant step, if the body adds (or subtracts)
+    /// a constant to the operand.
+    fn counter(&self, operand: &Operand) -> TractResult<Option<(usize, i64)>> {
+        let var = if let Operand::Var(var) = *operand { var } else { return Ok(None) };
+        let body = self.body.model();
+        let (op, a, b) = match binary(body, body.output_outlets()?[var])? {
+            Some(update) => update,
+            None => return Ok(None),
+        };
+        let step = match (a, b) {
+            (Operand::Var(v), Operand::Const(step)) if v == var && op.is::<math::Add>() => step,
+            (Operand::Const(step), Operand::Var(v)) if v == var && op.is::<math::Add>() => step,
+            (Operand::Var(v), Operand::Const(step)) if v == var && op.is::<math::Sub>() => -step,
+            _ => return Ok(None),
+        };
+        Ok(Some((var, step)))
+    }
+
+    /// Scan running the body over a dummy input of the trip count length.
+    fn to_scan(&self, name: &str) -> TractResult<Scan> {
+        let vars = self.body.model().input_outlets()?.len();
+        let mut body = self.body.model().clone();
+        body.add_source(
+            format!("{}.iteration", name),
+            TypedFact::dt_shape(i64::datum_type(), [1]),
+        )?;
+        let mut input_mapping = (0..vars)
+            .map(|ix| InputMapping::State { initializer: StateInitializer::FromInput(ix) })
+            .collect::<Vec<_>>();
+        input_mapping.push(InputMapping::Scan { slot: vars, axis: 0, chunk: 1 });
+        let output_mapping = (0..vars)
+            .map(|ix| OutputMapping {
+                full_slot: None,
+                axis: 0,
+                chunk: 1,
+                full_dim_hint: None,
+                last_value_slot: Some(ix),
+                state: true,
+            })
+            .collect();
+        Scan::new(body, input_mapping, output_mapping, None, 0)
+    }
+}
+
+/// Scalar operand in a loop condition or a counter update.
+#[derive(Clone, Debug, PartialEq)]
+enum Operand {
+    Var(usize),
+    Const(i64),
+}
+
+/// Integer value of a single element tensor.
+fn scalar(t: &Tensor) -> TractResult<Option<i64>> {
+    if t.len() == 1 && t.datum_type().is_integer() {
+        Ok(Some(t.cast_to_scalar::<i64>()?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Integer value of a single element constant fact.
+fn konst_scalar(fact: &TypedFact) -> TractResult<Option<i64>> {
+    fact.konst.as_ref().map(|k| scalar(k)).transpose().map(Option::flatten)
+}
+
+fn skip_identities(model: &TypedModel, mut outlet: OutletId) -> OutletId {
+    while model.node(outlet.node).op_is::<Identity>() {
+        outlet = model.node(outlet.node).inputs[0];
+    }
+    outlet
+}
+
+/// Loop variable or integer constant an outlet is, up to identities.
+fn operand_of(model: &TypedModel, outlet: OutletId) -> TractResult<Option<Operand>> {
+    let outlet = skip_identities(model, outlet);
+    if let Some(var) = model.input_outlets()?.iter().position(|i| *i == outlet) {
+        Ok(Some(Operand::Var(var)))
+    } else if let Some(k) = &model.outlet_fact(outlet)?.konst {
+        Ok(scalar(k)?.map(Operand::Const))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Binary op computing an outlet from two operands.
+fn binary(
+    model: &TypedModel,
+    outlet: OutletId,
+) -> TractResult<Option<(&dyn BinMiniOp, Operand, Operand)>> {
+    let node = model.node(skip_identities(model, outlet).node);
+    if let Some(op) = node.op_as::<TypedBinOp>() {
+        if let (Some(a), Some(b)) =
+            (operand_of(model, node.inputs[0])?, operand_of(model, node.inputs[1])?)
+        {
+            return Ok(Some((&*op.0, a, b)));
+        }
+    } else if let Some(op) = node.op_as::<UnaryOp>() {
+        if let (Some(a), Some(b)) = (scalar(&op.a)?, operand_of(model, node.inputs[0])?) {
+            return Ok(Some((&*op.mini_op, Operand::Const(a), b)));
+        }
+    }
+    Ok(None)
+}
+
+impl Op for TypedWhile {
+    fn name(&self) -> Cow<str> {
+        "TypedWhile".into()
+    }
+
+    op_tf!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for TypedWhile {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let mut state: TVec<Tensor> = inputs.into_iter().map(|t| t.into_tensor()).collect();
+        while truth(&self.cond.run(state.clone())?[0])? {
+            state = self.body.run(state)?.into_iter().map(|t| t.into_tensor()).collect();
+        }
+        Ok(state.into_iter().map(|t| t.into_arc_tensor()).collect())
+    }
+}
+
+impl TypedOp for TypedWhile {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(inputs.iter().map(|f| f.without_value()).collect())
+    }
+
+    as_op!();
+}
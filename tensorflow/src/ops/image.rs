@@ -0,0 +1,160 @@
+use tract_hir::internal::*;
+use tract_ndarray::prelude::*;
+
+use crate::model::ParsingContext;
+use crate::model::TfOpRegister;
+use crate::tfpb::tensorflow::NodeDef;
+
+pub fn register_all_ops(reg: &mut TfOpRegister) {
+    reg.insert("ResizeBilinear", resize_bilinear);
+}
+
+fn resize_bilinear(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let align_corners = pb.get_attr_opt_bool("align_corners")?.unwrap_or(false);
+    let half_pixel_centers = pb.get_attr_opt_bool("half_pixel_centers")?.unwrap_or(false);
+    if align_corners && half_pixel_centers {
+        bail!("ResizeBilinear: align_corners and half_pixel_centers can not be both set")
+    }
+    Ok(Box::new(ResizeBilinear::new(align_corners, half_pixel_centers)))
+}
+
+/// Bilinear resizing of the spatial axes of a NHWC tensor. The second input
+/// is the output [height, width]. Output is always f32.
+#[derive(Clone, Debug, new, Hash)]
+pub struct ResizeBilinear {
+    align_corners: bool,
+    half_pixel_centers: bool,
+}
+
+impl_dyn_hash!(ResizeBilinear);
+
+impl ResizeBilinear {
+    fn output_shape(input: &[TDim], size: &Tensor) -> TractResult<TVec<TDim>> {
+        let size = size.cast_to::<i64>()?;
+        let size = size.as_slice::<i64>()?;
+        if size.len() != 2 {
+            bail!("ResizeBilinear expects a size of length 2, got {:?}", size)
+        }
+        Ok(tvec!(input[0].clone(), size[0].to_dim(), size[1].to_dim(), input[3].clone()))
+    }
+
+    /// For each output position, the two source positions to blend, and the
+    /// weight of the second one.
+    fn sources(&self, len_in: usize, len_out: usize) -> TractResult<Vec<(usize, usize, f32)>> {
+        if len_out == 0 {
+            return Ok(vec![]);
+        }
+        if len_in == 0 {
+            bail!("ResizeBilinear can not resize an empty image to {} positions", len_out)
+        }
+        // aligning corners needs two output corners
+        let scale = if self.align_corners && len_out > 1 {
+            (len_in - 1) as f32 / (len_out - 1) as f32
+        } else {
+            len_in as f32 / len_out as f32
+        };
+        Ok((0..len_out)
+            .map(|x| {
+                let x_in = if self.half_pixel_centers {
+                    (x as f32 + 0.5) * scale - 0.5
+                } else {
+                    x as f32 * scale
+                };
+                let lower = (x_in.floor().max(0.0) as usize).min(len_in - 1);
+                let upper = (x_in.ceil() as usize).min(len_in - 1);
+                (lower, upper, x_in - x_in.floor())
+            })
+            .collect())
+    }
+}
+
+impl Op for ResizeBilinear {
+    fn name(&self) -> Cow<str> {
+        "ResizeBilinear".into()
+    }
+
+    fn validation(&self) -> Validation {
+        Validation::Rounding
+    }
+
+    op_tf!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for ResizeBilinear {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (images, size) = args_2!(inputs);
+        let shape: TVec<TDim> = images.shape().iter().map(|d| d.to_dim()).collect();
+        let output_shape = Self::output_shape(&shape, &size)?;
+        let output_shape =
+            output_shape.iter().map(|d| d.to_usize()).collect::<TractResult<TVec<usize>>>()?;
+        let images = images.cast_to::<f32>()?;
+        let images = images.to_array_view::<f32>()?;
+        let ys = self.sources(images.shape()[1], output_shape[1])?;
+        let xs = self.sources(images.shape()[2], output_shape[2])?;
+        let output = ArrayD::from_shape_fn(&*output_shape, |co| {
+            let (y0, y1, dy) = ys[co[1]];
+            let (x0, x1, dx) = xs[co[2]];
+            let at = |y: usize, x: usize| images[[co[0], y, x, co[3]]];
+            let top = at(y0, x0) + (at(y0, x1) - at(y0, x0)) * dx;
+            let bottom = at(y1, x0) + (at(y1, x1) - at(y1, x0)) * dx;
+            top + (bottom - top) * dy
+        });
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for ResizeBilinear {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].rank, 4)?;
+        s.equals(&inputs[1].rank, 1)?;
+        s.equals(&inputs[1].shape[0], 2.to_dim())?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        s.equals(&outputs[0].rank, 4)?;
+        s.equals(&inputs[0].shape[0], &outputs[0].shape[0])?;
+        s.equals(&inputs[0].shape[3], &outputs[0].shape[3])?;
+        s.given(&inputs[1].value, move |s, size| {
+            let size = size.cast_to::<i64>()?;
+            let size = size.as_slice::<i64>()?;
+            s.equals(&outputs[0].shape[1], size[0].to_dim())?;
+            s.equals(&outputs[0].shape[2], size[1].to_dim())
+        })
+    }
+
+    as_op!();
+    to_typed!();
+}
+
+impl TypedOp for ResizeBilinear {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let size = inputs[1].konst.as_ref().context("ResizeBilinear expects a constant size")?;
+        let shape: TVec<TDim> = inputs[0].shape.iter().collect();
+        Ok(tvec!(TypedFact::dt_shape(f32::datum_type(), Self::output_shape(&shape, size)?)))
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sources_of_degenerate_sizes() {
+        let op = ResizeBilinear { align_corners: true, half_pixel_centers: false };
+        assert_eq!(op.sources(2, 1).unwrap(), vec![(0, 0, 0.0)]);
+        assert!(op.sources(0, 0).unwrap().is_empty());
+        assert!(op.sources(0, 2).is_err());
+    }
+}
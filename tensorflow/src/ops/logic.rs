@@ -15,9 +15,69 @@ pub fn register_all_ops(reg: &mut TfOpRegister) {
     reg.insert("LogicalAnd", |_, _| Ok(ops::logic::And.into_hir()));
     reg.insert("LogicalOr", |_, _| Ok(ops::logic::Or.into_hir()));
     reg.insert("Merge", merge);
+    reg.insert("Select", |_, _| Ok(expand(Select)));
+    reg.insert("SelectV2", |_, _| Ok(Box::new(ops::logic::Iff)));
     reg.insert("Switch", |_, _| Ok(Box::new(Switch)));
 }
 
+/// TF1 Select: the condition is either of the same shape as the branches,
+/// or a vector selecting along their first axis.
+#[derive(Debug, Clone, new, Hash)]
+pub struct Select;
+
+impl_dyn_hash!(Select);
+
+impl Expansion for Select {
+    fn name(&self) -> Cow<str> {
+        "Select".into()
+    }
+
+    op_tf!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 3)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, DatumType::Bool)?;
+        s.equals(&inputs[1].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[2].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[1].shape, &outputs[0].shape)?;
+        s.equals(&inputs[2].shape, &outputs[0].shape)?;
+        s.given_2(&inputs[0].rank, &inputs[1].rank, move |s, cond, rank| {
+            if cond == rank {
+                s.equals(&inputs[0].shape, &outputs[0].shape)
+            } else if cond == 1 {
+                s.equals(&inputs[0].shape[0], &outputs[0].shape[0])
+            } else {
+                bail!("Select condition must be a vector or of the branches rank")
+            }
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        target: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let mut cond = inputs[0];
+        let cond_rank = target.outlet_fact(cond)?.rank();
+        let rank = target.outlet_fact(inputs[1])?.rank();
+        for axis in cond_rank..rank {
+            cond = target.wire_node(
+                format!("{}.cond_add_axis_{}", prefix, axis),
+                AxisOp::Add(axis),
+                &[cond],
+            )?[0];
+        }
+        target.wire_node(prefix, ops::logic::Iff, &[cond, inputs[1], inputs[2]])
+    }
+}
+
 #[derive(Debug, Clone, new, Hash)]
 pub struct Switch;
 
@@ -0,0 +1,58 @@
+use tract_hir::internal::*;
+
+use crate::model::ParsingContext;
+use crate::tfpb::tensorflow::NodeDef;
+
+pub fn cumsum(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let exclusive = pb.get_attr_opt_bool("exclusive")?.unwrap_or(false);
+    let reverse = pb.get_attr_opt_bool("reverse")?.unwrap_or(false);
+    Ok(expand(Cumsum::new(exclusive, reverse)))
+}
+
+#[derive(Debug, Clone, new, Hash)]
+pub struct Cumsum {
+    exclusive: bool,
+    reverse: bool,
+}
+
+impl_dyn_hash!(Cumsum);
+
+impl Expansion for Cumsum {
+    fn name(&self) -> Cow<str> {
+        "Cumsum".into()
+    }
+
+    op_tf!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        s.equals(&inputs[1].rank, 0)?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        target: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let rank = target.outlet_fact(inputs[0])?.rank() as i64;
+        let axis = target
+            .outlet_fact(inputs[1])?
+            .konst
+            .as_ref()
+            .context("Cumsum expects a constant axis")?
+            .cast_to_scalar::<i64>()?;
+        let axis = if axis < 0 { axis + rank } else { axis } as usize;
+        let op = tract_core::ops::array::CumSum::new(axis, self.exclusive, self.reverse);
+        target.wire_node(prefix, op, &inputs[0..1])
+    }
+}
@@ -0,0 +1,134 @@
+use tract_hir::internal::*;
+use tract_hir::ops::cnn::{PaddingSpec, PoolSpec};
+use tract_hir::ops::nn::DataFormat;
+use tract_hir::tract_core::ops::cnn::{deconv, DeconvUnary, KernelFormat};
+
+use crate::model::ParsingContext;
+use crate::tfpb::tensorflow::NodeDef;
+
+pub fn conv2d_backprop_input(
+    _ctx: &ParsingContext,
+    pb: &NodeDef,
+) -> TractResult<Box<dyn InferenceOp>> {
+    if super::data_format(pb)? != DataFormat::NHWC {
+        bail!("Conv2DBackpropInput only supports NHWC data format")
+    }
+    let strides = super::strides(pb)?;
+    let dilations = pb.get_attr_opt_list_int::<usize>("dilations")?.unwrap_or_else(|| vec![1; 4]);
+    if dilations.len() != 4 || dilations[0] != 1 || dilations[3] != 1 {
+        bail!("dilations must be of the form [1, h, v, 1], found {:?}", dilations)
+    };
+    Ok(expand(Conv2DBackpropInput::new(
+        super::padding(pb)?,
+        strides[1..3].into(),
+        dilations[1..3].into(),
+    )))
+}
+
+/// Transposed convolution, as emitted for Conv2DTranspose layers. Inputs are
+/// the output shape, the filter and the gradient (the actual input).
+#[derive(Debug, Clone, new, Hash)]
+pub struct Conv2DBackpropInput {
+    padding: PaddingSpec,
+    strides: TVec<usize>,
+    dilations: TVec<usize>,
+}
+
+impl_dyn_hash!(Conv2DBackpropInput);
+
+impl Expansion for Conv2DBackpropInput {
+    fn name(&self) -> Cow<str> {
+        "Conv2DBackpropInput".into()
+    }
+
+    op_tf!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 3)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].rank, 1)?;
+        s.equals(&inputs[0].shape[0], 4.to_dim())?;
+        s.equals(&inputs[1].rank, 4)?;
+        s.equals(&inputs[2].rank, 4)?;
+        s.equals(&inputs[1].datum_type, &inputs[2].datum_type)?;
+        s.equals(&inputs[2].datum_type, &outputs[0].datum_type)?;
+        s.equals(&outputs[0].rank, 4)?;
+        s.equals(&inputs[1].shape[3], &inputs[2].shape[3])?;
+        s.equals(&inputs[1].shape[2], &outputs[0].shape[3])?;
+        s.equals(&inputs[2].shape[0], &outputs[0].shape[0])?;
+        s.given(&inputs[0].value, move |s, sizes| {
+            let sizes = sizes.cast_to::<TDim>()?;
+            s.equals(
+                &outputs[0].shape,
+                sizes.as_slice::<TDim>()?.iter().cloned().collect::<TVec<_>>(),
+            )
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        target: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let sizes = target
+            .outlet_fact(inputs[0])?
+            .konst
+            .clone()
+            .context("Conv2DBackpropInput expects a constant output shape")?;
+        let sizes = sizes.cast_to::<i64>()?;
+        let y_geo: TVec<usize> =
+            sizes.as_slice::<i64>()?[1..3].iter().map(|&d| d as usize).collect();
+        let kernel = target
+            .outlet_fact(inputs[1])?
+            .konst
+            .clone()
+            .context("Conv2DBackpropInput expects a constant filter")?;
+        // TF filter is [H, W, output channels, input channels]
+        let kernel = kernel.into_tensor().permute_axes(&[0, 1, 3, 2])?;
+        let x_shape = target.outlet_fact(inputs[2])?.shape.clone();
+        let x_geo: TVec<usize> = x_shape
+            .as_concrete()
+            .context("Conv2DBackpropInput expects a concrete input shape")?[1..3]
+            .into();
+        let kernel_geo: TVec<usize> = kernel.shape()[0..2].into();
+        let padding = if self.padding == PaddingSpec::Valid {
+            PaddingSpec::Valid
+        } else {
+            let mut before = tvec!();
+            let mut after = tvec!();
+            for ax in 0..2 {
+                let total = ((x_geo[ax] - 1) * self.strides[ax]
+                    + (kernel_geo[ax] - 1) * self.dilations[ax]
+                    + 1)
+                .saturating_sub(y_geo[ax]);
+                before.push(total / 2);
+                after.push(total - total / 2);
+            }
+            PaddingSpec::Explicit(before, after, false)
+        };
+        let pool_spec = PoolSpec::new(
+            DataFormat::NHWC,
+            kernel_geo,
+            padding,
+            Some(self.dilations.clone()),
+            Some(self.strides.clone()),
+            Some(kernel.shape()[3]),
+        );
+        let adjustments = deconv::adjustments(&pool_spec, &x_geo, &y_geo)?;
+        let op = DeconvUnary::new(
+            pool_spec,
+            KernelFormat::HWIO,
+            kernel.into_arc_tensor(),
+            None,
+            adjustments,
+            1,
+        );
+        target.wire_node(prefix, op, &inputs[2..3])
+    }
+}
@@ -0,0 +1,25 @@
+use tract_hir::internal::*;
+use tract_hir::ops::cnn;
+use tract_hir::ops::nn::DataFormat;
+
+use crate::model::ParsingContext;
+use crate::tfpb::tensorflow::NodeDef;
+
+pub fn conv3d(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let strides: Vec<usize> = pb.get_attr_list_int("strides")?;
+    if strides.len() != 5 || strides[0] != 1 || strides[4] != 1 {
+        bail!("strides must be of the form [1, d, h, w, 1], found {:?}", strides)
+    };
+    let mut op =
+        cnn::Conv::default().hwio().padding(super::padding(pb)?).strides(strides[1..4].into());
+    if let Some(dilations) = pb.get_attr_opt_list_int::<usize>("dilations")? {
+        if dilations.len() != 5 || dilations[0] != 1 || dilations[4] != 1 {
+            bail!("dilations must be of the form [1, d, h, w, 1], found {:?}", dilations)
+        };
+        op = op.dilations(dilations[1..4].into());
+    }
+    if super::data_format(pb)? == DataFormat::NHWC {
+        op = op.nhwc()
+    }
+    Ok(expand(op))
+}
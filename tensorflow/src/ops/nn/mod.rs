@@ -6,6 +6,8 @@ use crate::model::TfOpRegister;
 use crate::tfpb::tensorflow::NodeDef;
 
 pub mod conv2d;
+pub mod conv2d_backprop_input;
+pub mod conv3d;
 pub mod dw_conv2d;
 pub mod fused_batch_norm;
 pub mod pools;
@@ -14,8 +16,17 @@ pub mod s2b;
 pub fn register_all_ops(reg: &mut TfOpRegister) {
     reg.insert("AvgPool", pools::avgpool);
     reg.insert("Conv2D", conv2d::conv2d);
+    reg.insert("Conv2DBackpropInput", conv2d_backprop_input::conv2d_backprop_input);
+    reg.insert("Conv3D", conv3d::conv3d);
     reg.insert("DepthwiseConv2dNative", dw_conv2d::depthwise_conv2d);
+    reg.insert("Elu", |_, _| Ok(expand(tract_hir::ops::activations::Elu(1.0))));
     reg.insert("FusedBatchNorm", fused_batch_norm::fused_batch_norm);
+    reg.insert("FusedBatchNormV2", fused_batch_norm::fused_batch_norm);
+    reg.insert("FusedBatchNormV3", fused_batch_norm::fused_batch_norm);
+    reg.insert("LeakyRelu", |_, pb| {
+        let alpha = pb.get_attr_opt_float("alpha")?.unwrap_or(0.2);
+        Ok(expand(tract_hir::ops::activations::LeakyRelu(alpha)))
+    });
     reg.insert("MaxPool", pools::maxpool);
     reg.insert("Relu", |_, _| Ok(expand(tract_hir::ops::activations::Clip::new(Some(0.0), None))));
     reg.insert("Relu6", |_, _| {
@@ -23,6 +34,7 @@ pub fn register_all_ops(reg: &mut TfOpRegister) {
     });
     reg.insert("Sigmoid", |_, _| Ok(Box::new(tract_hir::ops::nn::sigmoid())));
     reg.insert("Softmax", |_, _| Ok(expand(LayerSoftmax::new(1))));
+    reg.insert("Softplus", |_, _| Ok(expand(tract_hir::ops::activations::Softplus)));
     reg.insert("SpaceToBatchND", s2b::space_to_batch_nd);
     reg.insert("BatchToSpaceND", s2b::batch_to_space_nd);
 }
@@ -36,11 +48,13 @@ pub fn strides(pb: &NodeDef) -> TractResult<Vec<usize>> {
 }
 
 pub fn data_format(pb: &NodeDef) -> TractResult<DataFormat> {
-    let df = if pb.get_attr_opt_raw_str("data_format")?.unwrap_or(b"NHWC") == b"NHWC" {
-        DataFormat::NHWC
-    } else {
-        DataFormat::NCHW
-    };
+    let df =
+        if matches!(pb.get_attr_opt_raw_str("data_format")?.unwrap_or(b"NHWC"), b"NHWC" | b"NDHWC")
+        {
+            DataFormat::NHWC
+        } else {
+            DataFormat::NCHW
+        };
     Ok(df)
 }
 
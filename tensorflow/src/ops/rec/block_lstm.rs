@@ -1,5 +1,6 @@
 use tract_hir::internal::*;
 
+use super::lstm_block_cell::{GateLayout, LstmCell};
 use crate::model::ParsingContext;
 use crate::tfpb::tensorflow::NodeDef;
 
@@ -8,28 +9,30 @@ pub fn block_lstm(_ctx: &ParsingContext, node: &NodeDef) -> TractResult<Box<dyn
     let cell_clip = node.get_attr_opt_float("cell_clip")?.unwrap_or(3.0);
     let t = node.get_attr_datum_type("T")?;
     let use_peephole = node.get_attr_opt_bool("use_peephole")?.unwrap_or(false);
-    if use_peephole {
-        unimplemented!("Block LSTM peeplholes");
-    }
-    Ok(expand(BlockLSTM::new(forget_bias, cell_clip, t, use_peephole)))
+    let cell = LstmCell::new(forget_bias, cell_clip, use_peephole, GateLayout::ICFO);
+    Ok(expand(BlockLSTM::new(cell, t)))
+}
+
+/// BlockLSTMV2 has no forget bias, and uses the same gate layout as Keras.
+pub fn block_lstm_v2(_ctx: &ParsingContext, node: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let cell_clip = node.get_attr_opt_float("cell_clip")?.unwrap_or(0.0);
+    let t = node.get_attr_datum_type("T")?;
+    let use_peephole = node.get_attr_opt_bool("use_peephole")?.unwrap_or(false);
+    let cell = LstmCell::new(0.0, cell_clip, use_peephole, GateLayout::IFCO);
+    Ok(expand(BlockLSTM::new(cell, t)))
 }
 
-#[derive(Clone, Debug, new, Educe)]
-#[educe(Hash)]
+#[derive(Clone, Debug, new, Hash)]
 pub struct BlockLSTM {
-    #[educe(Hash(method = "hash_f32"))]
-    forget_bias: f32,
-    #[educe(Hash(method = "hash_f32"))]
-    cell_clip: f32,
+    cell: LstmCell,
     t: DatumType,
-    use_peephole: bool,
 }
 
 impl_dyn_hash!(BlockLSTM);
 
 impl Expansion for BlockLSTM {
     fn name(&self) -> Cow<str> {
-        "BlockLSTM".into()
+        if self.cell.layout == GateLayout::IFCO { "BlockLSTMV2" } else { "BlockLSTM" }.into()
     }
 
     op_tf!();
@@ -78,18 +81,28 @@ impl Expansion for BlockLSTM {
         model: &mut TypedModel,
         inputs: &[OutletId],
     ) -> TractResult<TVec<OutletId>> {
-        use tract_hir::tract_core::ops::{array, math, matmul, nn, scan};
+        use tract_hir::tract_core::ops::scan;
 
         let mut body = TypedModel::default();
         let mut outer_inputs = vec![];
         let mut input_mapping = vec![];
         let mut output_mapping = vec![];
 
-        let w = model.outlet_fact(inputs[4])?.konst.clone().context("W must be cosntant")?;
-        let b = model.outlet_fact(inputs[8])?.konst.clone().context("B must be constant")?;
-        let cell_size = w.shape()[1] / 4;
-        let mut b = b.into_tensor();
-        b.insert_axis(0)?;
+        let mut konst = |ix: usize, name: &str| -> TractResult<OutletId> {
+            let t = model
+                .outlet_fact(inputs[ix])?
+                .konst
+                .clone()
+                .with_context(|| format!("{} must be constant", name))?;
+            Ok(body.add_const(format!("{}-{}", prefix, name), t)?)
+        };
+        let w = konst(4, "w")?;
+        let b = konst(8, "b")?;
+        let peepholes = if self.cell.use_peephole {
+            Some([konst(5, "wci")?, konst(6, "wcf")?, konst(7, "wco")?])
+        } else {
+            None
+        };
 
         macro_rules! wire {
             ($name: ident = $op: expr, $($param: expr),*) => {
@@ -105,7 +118,7 @@ impl Expansion for BlockLSTM {
         // X: body input 0: X, new outside input 0 (was 1)
         outer_inputs.push(inputs[1]);
         input_mapping.push(scan::InputMapping::Scan { slot: 1, axis: 0, chunk: 1 });
-        let mut x_source_fact = model.outlet_fact(inputs[1])?.clone();
+        let mut x_source_fact = model.outlet_fact(inputs[1])?.without_value();
         x_source_fact.shape.set(0, 1.to_dim());
         let x_source = body.add_source("x_source", x_source_fact)?.into();
         wire!(x = AxisOp::Rm(0), x_source);
@@ -113,7 +126,7 @@ impl Expansion for BlockLSTM {
         // CS: body input 1
         let cs = model.wire_node(format!("{}.cs-axis", prefix), AxisOp::Add(0), &[inputs[2]])?[0];
         outer_inputs.push(cs);
-        let cs_fact = model.outlet_fact(cs)?.clone();
+        let cs_fact = model.outlet_fact(cs)?.without_value();
         let cs_source = body.add_source("cs_source", cs_fact)?;
         input_mapping
             .push(scan::InputMapping::State { initializer: scan::StateInitializer::FromInput(2) });
@@ -122,46 +135,22 @@ impl Expansion for BlockLSTM {
         // H: body input 2
         let h = model.wire_node(format!("{}.h-axis", prefix), AxisOp::Add(0), &[inputs[3]])?[0];
         outer_inputs.push(h);
-        let h_fact = model.outlet_fact(h)?.clone();
+        let h_fact = model.outlet_fact(h)?.without_value();
         let h_source = body.add_source("h_source", h_fact)?;
         input_mapping
             .push(scan::InputMapping::State { initializer: scan::StateInitializer::FromInput(3) });
         wire!(h_prev = AxisOp::Rm(0), h_source);
 
-        wire!(xh = array::TypedConcat::concat_vars(1, 2), x, h_prev);
-
-        let w = body.add_const(format!("{}-w", prefix), w)?;
-        wire!(i_ci_f_o_1 = matmul::mir::MatMul::default(), xh, w);
-        wire!(i_ci_f_o = math::add::unary(b.into_arc_tensor()), i_ci_f_o_1);
-
-        wire!(i_1 = array::Slice::new(1, 0, cell_size), i_ci_f_o);
-        wire!(i = nn::sigmoid(), i_1);
-
-        wire!(f_1 = array::Slice::new(1, 2 * cell_size, 3 * cell_size), i_ci_f_o);
-        wire!(f_2 = math::add::unary(rctensor2(&[[self.forget_bias]])), f_1);
-        wire!(f = nn::sigmoid(), f_2);
-
-        wire!(ci_1 = array::Slice::new(1, cell_size, 2 * cell_size), i_ci_f_o);
-        wire!(ci = math::tanh(), ci_1);
-
-        wire!(o_1 = array::Slice::new(1, 3 * cell_size, 4 * cell_size), i_ci_f_o);
-        wire!(o = nn::sigmoid(), o_1);
-
-        wire!(ci_i = math::mul::bin_typed(), ci, i);
-        wire!(cs_1 = math::mul::bin_typed(), cs_prev, f);
-        wire!(cs = math::add::bin_typed(), cs_1, ci_i);
-
-        wire!(co = math::tanh(), cs);
-        wire!(h = math::mul::bin_typed(), co, o);
-
-        wire!(i_ = AxisOp::Add(0), i);
-        wire!(cs_ = AxisOp::Add(0), cs);
-        wire!(f_ = AxisOp::Add(0), f);
-        wire!(o_ = AxisOp::Add(0), o);
-        wire!(ci_ = AxisOp::Add(0), ci);
-        wire!(co_ = AxisOp::Add(0), co);
-        wire!(h_ = AxisOp::Add(0), h);
-        body.set_output_outlets(&[i_, cs_, f_, o_, ci_, co_, h_])?;
+        let cell_outputs =
+            self.cell.wire(prefix, &mut body, x, cs_prev, h_prev, w, b, peepholes)?;
+        let body_outputs = cell_outputs
+            .iter()
+            .enumerate()
+            .map(|(ix, o)| {
+                Ok(body.wire_node(format!("{}-output-{}", prefix, ix), AxisOp::Add(0), &[*o])?[0])
+            })
+            .collect::<TractResult<Vec<_>>>()?;
+        body.set_output_outlets(&body_outputs)?;
         for ix in 0..7 {
             output_mapping.push(scan::OutputMapping::<TDim> {
                 state: ix == 1 || ix == 6,
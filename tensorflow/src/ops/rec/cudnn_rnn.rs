@@ -0,0 +1,417 @@
+use tract_hir::internal::*;
+
+use crate::model::ParsingContext;
+use crate::tfpb::tensorflow::NodeDef;
+
+pub fn cudnn_rnn(_ctx: &ParsingContext, node: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let mode = match node.get_attr_opt_str("rnn_mode")?.as_deref().unwrap_or("lstm") {
+        "rnn_relu" => RnnMode::Relu,
+        "rnn_tanh" => RnnMode::Tanh,
+        "lstm" => RnnMode::Lstm,
+        "gru" => RnnMode::Gru,
+        mode => bail!("Unsupported rnn_mode {}", mode),
+    };
+    match node.get_attr_opt_str("input_mode")?.as_deref().unwrap_or("linear_input") {
+        "linear_input" | "auto_select" => (),
+        mode => bail!("Unsupported input_mode {}", mode),
+    };
+    let bidirectional =
+        match node.get_attr_opt_str("direction")?.as_deref().unwrap_or("unidirectional") {
+            "unidirectional" => false,
+            "bidirectional" => true,
+            dir => bail!("Unsupported direction {}", dir),
+        };
+    if node.get_attr_opt_int::<i64>("num_proj")?.unwrap_or(0) != 0 {
+        bail!("CudnnRNN projections are not supported")
+    }
+    let time_major = node.get_attr_opt_bool("time_major")?.unwrap_or(true);
+    let with_sequence_lengths = node.op == "CudnnRNNV3";
+    // reserve_space, and host_reserved from CudnnRNNV2 on
+    let reserved_outputs = if node.op == "CudnnRNN" { 1 } else { 2 };
+    Ok(expand(CudnnRNN::new(
+        mode,
+        bidirectional,
+        time_major,
+        with_sequence_lengths,
+        reserved_outputs,
+    )))
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq)]
+pub enum RnnMode {
+    Relu,
+    Tanh,
+    Lstm,
+    Gru,
+}
+
+impl RnnMode {
+    fn gates(&self) -> usize {
+        match self {
+            RnnMode::Relu | RnnMode::Tanh => 1,
+            RnnMode::Lstm => 4,
+            RnnMode::Gru => 3,
+        }
+    }
+}
+
+/// The cuDNN recurrent layers, evaluated with the cuDNN equations.
+///
+/// The opaque params buffer is read in the canonical layout: input and
+/// recurrent weights of every layer and direction (one [units, input] matrix
+/// per gate, in cuDNN gate order), then all the biases in the same order.
+///
+/// Inputs are input, input_h, input_c, params (and sequence_lengths for
+/// CudnnRNNV3). Outputs are output, output_h and output_c (input_c for the
+/// modes without a cell state), then the reserve_space (and host_reserved
+/// for CudnnRNNV2 and V3) buffers, which are only meaningful to cuDNN
+/// training and are left empty.
+#[derive(Clone, Debug, new, Hash)]
+pub struct CudnnRNN {
+    mode: RnnMode,
+    bidirectional: bool,
+    time_major: bool,
+    with_sequence_lengths: bool,
+    reserved_outputs: usize,
+}
+
+impl_dyn_hash!(CudnnRNN);
+
+impl Expansion for CudnnRNN {
+    fn name(&self) -> Cow<str> {
+        "CudnnRNN".into()
+    }
+
+    fn validation(&self) -> Validation {
+        Validation::Rounding
+    }
+
+    op_tf!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 4 + self.with_sequence_lengths as usize)?;
+        check_output_arity(&outputs, 3 + self.reserved_outputs)?;
+        s.equals_all((0..4).map(move |i| (&inputs[i].datum_type).bex()).collect())?;
+        // input: [time, batch, input_size], or [batch, time, input_size]
+        // when not time_major, and output has the same layout
+        s.equals(&inputs[0].rank, 3)?;
+        s.equals(&inputs[1].rank, 3)?; // input_h: [layers * dirs, batch, units]
+        s.equals(&inputs[2].rank, 3)?; // input_c: [layers * dirs, batch, units]
+        s.equals(&inputs[3].rank, 1)?; // params
+        for output in outputs {
+            s.equals(&inputs[0].datum_type, &output.datum_type)?;
+        }
+        let dirs = 1 + self.bidirectional as i64;
+        s.equals(&outputs[0].rank, 3)?;
+        s.equals(&outputs[0].shape[0], &inputs[0].shape[0])?;
+        s.equals(&outputs[0].shape[1], &inputs[0].shape[1])?;
+        s.equals(&outputs[0].shape[2], dirs * inputs[1].shape[2].bex())?;
+        s.equals(&outputs[1].shape, &inputs[1].shape)?;
+        s.equals(&outputs[2].shape, &inputs[2].shape)?;
+        for reserved in &outputs[3..] {
+            s.equals(&reserved.rank, 1)?;
+            s.equals(&reserved.shape[0], 0.to_dim())?;
+        }
+        Ok(())
+    }
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(3 + self.reserved_outputs)
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        use tract_hir::tract_core::ops::array::TypedConcat;
+
+        let params = model
+            .outlet_fact(inputs[3])?
+            .konst
+            .clone()
+            .context("CudnnRNN params must be constant")?;
+        let params = params.cast_to::<f32>()?;
+        let params = params.as_slice::<f32>()?;
+
+        let mut x = inputs[0];
+        if !self.time_major {
+            x = model.wire_node(format!("{}.time_major", prefix), AxisOp::Move(1, 0), &[x])?[0];
+        }
+        let x_shape = model.outlet_fact(x)?.shape.clone();
+        let h_shape = model.outlet_fact(inputs[1])?.shape.clone();
+        let h_shape = h_shape.as_concrete().context("CudnnRNN expects a concrete input_h shape")?;
+        if self.with_sequence_lengths {
+            let lengths = model
+                .outlet_fact(inputs[4])?
+                .konst
+                .clone()
+                .context("CudnnRNNV3 sequence_lengths must be constant")?;
+            let lengths = lengths.cast_to::<i64>()?;
+            if lengths.as_slice::<i64>()?.iter().any(|&l| l.to_dim() != x_shape[0]) {
+                bail!("CudnnRNNV3 with variable sequence lengths is not supported")
+            }
+        }
+
+        let dirs = 1 + self.bidirectional as usize;
+        let layers = h_shape[0] / dirs;
+        let units = h_shape[2];
+        let gates = self.mode.gates();
+        let input_size = x_shape[2].to_usize()?;
+        let layer_input = |layer: usize| if layer == 0 { input_size } else { units * dirs };
+        let weights_len: usize =
+            (0..layers).map(|l| dirs * gates * units * (layer_input(l) + units)).sum();
+        let biases_len = layers * dirs * 2 * gates * units;
+        if params.len() != weights_len + biases_len {
+            bail!(
+                "CudnnRNN expects {} params for {} layers of {} units, got {}",
+                weights_len + biases_len,
+                layers,
+                units,
+                params.len()
+            )
+        }
+
+        let mut weights = &params[..weights_len];
+        let mut biases = &params[weights_len..];
+        let take = |buffer: &mut &[f32], shape: &[usize]| -> TractResult<Tensor> {
+            let len = shape.iter().product::<usize>();
+            let t = tensor1(&buffer[..len]).into_shape(shape)?;
+            *buffer = &buffer[len..];
+            Ok(t)
+        };
+
+        let mut h_outputs = tvec!();
+        let mut c_outputs = tvec!();
+        for layer in 0..layers {
+            let mut y = tvec!();
+            for dir in 0..dirs {
+                let ix = layer * dirs + dir;
+                let w = take(&mut weights, &[gates * units, layer_input(layer)])?;
+                let r = take(&mut weights, &[gates * units, units])?;
+                let bw = take(&mut biases, &[1, gates * units])?;
+                let br = take(&mut biases, &[1, gates * units])?;
+                let outputs = self.wire_one_side(
+                    &format!("{}.layer-{}.dir-{}", prefix, layer, dir),
+                    model,
+                    x,
+                    inputs[1],
+                    inputs[2],
+                    ix,
+                    dir == 1,
+                    [w, r, bw, br],
+                )?;
+                y.push(outputs[0]);
+                h_outputs.push(outputs[1]);
+                if self.mode == RnnMode::Lstm {
+                    c_outputs.push(outputs[2]);
+                }
+            }
+            x = if dirs == 1 {
+                y[0]
+            } else {
+                model.wire_node(
+                    format!("{}.layer-{}.y", prefix, layer),
+                    TypedConcat::concat_vars(2, dirs),
+                    &y,
+                )?[0]
+            };
+        }
+        if !self.time_major {
+            x = model.wire_node(format!("{}.output", prefix), AxisOp::Move(0, 1), &[x])?[0];
+        }
+        let mut concat = |name: &str, outlets: &[OutletId]| -> TractResult<OutletId> {
+            if outlets.len() == 1 {
+                Ok(outlets[0])
+            } else {
+                Ok(model.wire_node(
+                    format!("{}.{}", prefix, name),
+                    TypedConcat::concat_vars(0, outlets.len()),
+                    outlets,
+                )?[0])
+            }
+        };
+        let h = concat("output_h", &h_outputs)?;
+        let c =
+            if self.mode == RnnMode::Lstm { concat("output_c", &c_outputs)? } else { inputs[2] };
+        let mut outputs = tvec!(x, h, c);
+        let dt = model.outlet_fact(inputs[0])?.datum_type;
+        for name in ["reserve_space", "host_reserved"].iter().take(self.reserved_outputs) {
+            outputs
+                .push(model.add_const(format!("{}.{}", prefix, name), Tensor::zero_dt(dt, &[0])?)?);
+        }
+        Ok(outputs)
+    }
+}
+
+impl CudnnRNN {
+    /// Wires a Scan for one layer in one direction. Returns the full output,
+    /// the last h and the last c (for Lstm).
+    #[allow(clippy::too_many_arguments)]
+    fn wire_one_side(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        x: OutletId,
+        input_h: OutletId,
+        input_c: OutletId,
+        ix: usize,
+        backward: bool,
+        params: [Tensor; 4],
+    ) -> TractResult<TVec<OutletId>> {
+        use tract_hir::tract_core::ops::{array, math, matmul, nn, scan};
+
+        let [w, r, bw, br] = params;
+        let units = r.shape()[1];
+        let chunk = if backward { -1 } else { 1 };
+
+        let mut body = TypedModel::default();
+        let mut outer_inputs = vec![];
+        let mut input_mapping = vec![];
+
+        macro_rules! wire {
+            ($name: ident = $op: expr, $($param: expr),*) => {
+                let $name = body.wire_node(
+                    format!("{}.{}", prefix, stringify!($name)),
+                    $op, [$($param),*].as_ref())?[0];
+            }
+        }
+        macro_rules! gate {
+            ($name: ident = $gates: ident[$ix: expr]) => {
+                wire!($name = array::Slice::new(1, $ix * units, ($ix + 1) * units), $gates);
+            };
+        }
+
+        // x: [chunk=1, batch, input_size]
+        outer_inputs.push(x);
+        input_mapping.push(scan::InputMapping::Scan { slot: 0, axis: 0, chunk });
+        let mut x_fact = model.outlet_fact(x)?.without_value();
+        x_fact.shape.set(0, 1.to_dim());
+        let x_source = body.add_source("x_source", x_fact)?;
+        wire!(x_t = AxisOp::Rm(0), x_source);
+
+        // h and c states: [1, batch, units]
+        let mut states = vec![];
+        let with_c = self.mode == RnnMode::Lstm;
+        for (name, outer) in [("h", input_h), ("c", input_c)].iter().take(1 + with_c as usize) {
+            let init = model.wire_node(
+                format!("{}.{}_init", prefix, name),
+                array::Slice::new(0, ix, ix + 1),
+                &[*outer],
+            )?[0];
+            outer_inputs.push(init);
+            let slot = outer_inputs.len() - 1;
+            input_mapping.push(scan::InputMapping::State {
+                initializer: scan::StateInitializer::FromInput(slot),
+            });
+            let source = body
+                .add_source(format!("{}_source", name), model.outlet_fact(init)?.without_value())?;
+            states.push(
+                body.wire_node(format!("{}.{}_prev", prefix, name), AxisOp::Rm(0), &[source])?[0],
+            );
+        }
+        let h_prev = states[0];
+
+        let w = body.add_const(format!("{}.w_kernel", prefix), w.permute_axes(&[1, 0])?)?;
+        let r = body.add_const(format!("{}.r_kernel", prefix), r.permute_axes(&[1, 0])?)?;
+        let bw = body.add_const(format!("{}.w_bias", prefix), bw)?;
+        let br = body.add_const(format!("{}.r_bias", prefix), br)?;
+        wire!(gx_1 = matmul::mir::MatMul::default(), x_t, w);
+        wire!(gx = math::add::bin_typed(), gx_1, bw);
+        wire!(gh_1 = matmul::mir::MatMul::default(), h_prev, r);
+        wire!(gh = math::add::bin_typed(), gh_1, br);
+
+        let mut body_outputs = vec![];
+        match self.mode {
+            RnnMode::Relu | RnnMode::Tanh => {
+                wire!(s = math::add::bin_typed(), gx, gh);
+                if self.mode == RnnMode::Relu {
+                    wire!(h = math::max::unary(rctensor2(&[[0f32]])), s);
+                    body_outputs.push(h);
+                } else {
+                    wire!(h = math::tanh(), s);
+                    body_outputs.push(h);
+                }
+            }
+            RnnMode::Lstm => {
+                // gates are in i, f, c, o order
+                let c_prev = states[1];
+                wire!(s = math::add::bin_typed(), gx, gh);
+                gate!(i_1 = s[0]);
+                gate!(f_1 = s[1]);
+                gate!(c_1 = s[2]);
+                gate!(o_1 = s[3]);
+                wire!(i = nn::sigmoid(), i_1);
+                wire!(f = nn::sigmoid(), f_1);
+                wire!(c_tilde = math::tanh(), c_1);
+                wire!(o = nn::sigmoid(), o_1);
+                wire!(f_c_prev = math::mul::bin_typed(), f, c_prev);
+                wire!(i_c_tilde = math::mul::bin_typed(), i, c_tilde);
+                wire!(c = math::add::bin_typed(), f_c_prev, i_c_tilde);
+                wire!(c_tanh = math::tanh(), c);
+                wire!(h = math::mul::bin_typed(), o, c_tanh);
+                body_outputs.push(h);
+                body_outputs.push(c);
+            }
+            RnnMode::Gru => {
+                // gates are in r, z, n order, the reset gate applies after
+                // the recurrent matmul
+                gate!(gx_r = gx[0]);
+                gate!(gx_z = gx[1]);
+                gate!(gx_n = gx[2]);
+                gate!(gh_r = gh[0]);
+                gate!(gh_z = gh[1]);
+                gate!(gh_n = gh[2]);
+                wire!(r_1 = math::add::bin_typed(), gx_r, gh_r);
+                wire!(r = nn::sigmoid(), r_1);
+                wire!(z_1 = math::add::bin_typed(), gx_z, gh_z);
+                wire!(z = nn::sigmoid(), z_1);
+                wire!(r_gh_n = math::mul::bin_typed(), r, gh_n);
+                wire!(n_1 = math::add::bin_typed(), gx_n, r_gh_n);
+                wire!(n = math::tanh(), n_1);
+                // h = (1 - z) * n + z * h_prev = n + z * (h_prev - n)
+                wire!(h_prev_sub_n = math::sub::bin_typed(), h_prev, n);
+                wire!(z_h_prev_sub_n = math::mul::bin_typed(), z, h_prev_sub_n);
+                wire!(h = math::add::bin_typed(), n, z_h_prev_sub_n);
+                body_outputs.push(h);
+            }
+        }
+
+        let body_outputs = body_outputs
+            .iter()
+            .enumerate()
+            .map(|(ix, o)| {
+                Ok(body.wire_node(format!("{}.output-{}", prefix, ix), AxisOp::Add(0), &[*o])?[0])
+            })
+            .collect::<TractResult<Vec<_>>>()?;
+        body.set_output_outlets(&body_outputs)?;
+
+        let mut output_mapping = vec![scan::OutputMapping {
+            state: true,
+            axis: 0,
+            chunk,
+            full_dim_hint: None,
+            last_value_slot: Some(1),
+            full_slot: Some(0),
+        }];
+        if with_c {
+            output_mapping.push(scan::OutputMapping {
+                state: true,
+                axis: 0,
+                chunk,
+                full_dim_hint: None,
+                last_value_slot: Some(2),
+                full_slot: None,
+            });
+        }
+
+        let scan = scan::Scan::new(body, input_mapping, output_mapping, None, 0)?;
+        model.wire_node(prefix, scan, &outer_inputs)
+    }
+}
@@ -0,0 +1,107 @@
+use tract_hir::internal::*;
+
+use crate::model::ParsingContext;
+use crate::tfpb::tensorflow::NodeDef;
+
+pub fn gru_block_cell(_ctx: &ParsingContext, _node: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    Ok(expand(GRUBlockCell))
+}
+
+/// One GRU step. Inputs are x, h_prev, w_ru, w_c, b_ru and b_c, outputs are
+/// r, u, c and h.
+#[derive(Clone, Debug, new, Hash)]
+pub struct GRUBlockCell;
+
+impl_dyn_hash!(GRUBlockCell);
+
+impl Expansion for GRUBlockCell {
+    fn name(&self) -> Cow<str> {
+        "GRUBlockCell".into()
+    }
+
+    fn validation(&self) -> Validation {
+        Validation::Rounding
+    }
+
+    op_tf!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 6)?;
+        check_output_arity(&outputs, 4)?;
+        s.equals_all((0..6).map(move |i| (&inputs[i].datum_type).bex()).collect())?;
+
+        s.equals(&inputs[0].rank, 2)?; // x: [batch, input_size]
+        s.equals(&inputs[1].rank, 2)?; // h_prev: [batch, cell_size]
+        s.equals(&inputs[2].rank, 2)?; // w_ru: [input_size + cell_size, 2 * cell_size]
+        s.equals(&inputs[3].rank, 2)?; // w_c: [input_size + cell_size, cell_size]
+        s.equals(&inputs[4].rank, 1)?; // b_ru: [2 * cell_size]
+        s.equals(&inputs[5].rank, 1)?; // b_c: [cell_size]
+        s.equals(&inputs[2].shape[1], 2 * inputs[1].shape[1].bex())?;
+        s.equals(&inputs[3].shape[1], &inputs[1].shape[1])?;
+        s.equals(&inputs[4].shape[0], &inputs[2].shape[1])?;
+        s.equals(&inputs[5].shape[0], &inputs[1].shape[1])?;
+
+        // r, u, c, h
+        for output in outputs {
+            s.equals(&inputs[0].datum_type, &output.datum_type)?;
+            s.equals(&output.shape, &inputs[1].shape)?;
+        }
+        Ok(())
+    }
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(4)
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        use tract_hir::tract_core::ops::{array, math, matmul, nn};
+
+        let cell_size = model.outlet_fact(inputs[1])?.shape[1].to_usize()?;
+
+        macro_rules! wire {
+            ($name: ident = $op: expr, $($param: expr),*) => {
+                let $name = model.wire_node(
+                    format!("{}.{}", prefix, stringify!($name)),
+                    $op, [$($param),*].as_ref())?[0];
+            }
+        }
+
+        let (x, h_prev) = (inputs[0], inputs[1]);
+
+        // r, u = sigmoid([x, h_prev] * w_ru + b_ru)
+        wire!(x_h_prev = array::TypedConcat::concat_vars(1, 2), x, h_prev);
+        wire!(r_u_1 = matmul::mir::MatMul::default(), x_h_prev, inputs[2]);
+        wire!(b_ru = AxisOp::Add(0), inputs[4]);
+        wire!(r_u_2 = math::add::bin_typed(), r_u_1, b_ru);
+        wire!(r_u = nn::sigmoid(), r_u_2);
+        wire!(r = array::Slice::new(1, 0, cell_size), r_u);
+        wire!(u = array::Slice::new(1, cell_size, 2 * cell_size), r_u);
+
+        // c = tanh([x, r * h_prev] * w_c + b_c)
+        wire!(r_h_prev = math::mul::bin_typed(), r, h_prev);
+        wire!(x_r_h_prev = array::TypedConcat::concat_vars(1, 2), x, r_h_prev);
+        wire!(c_1 = matmul::mir::MatMul::default(), x_r_h_prev, inputs[3]);
+        wire!(b_c = AxisOp::Add(0), inputs[5]);
+        wire!(c_2 = math::add::bin_typed(), c_1, b_c);
+        wire!(c = math::tanh(), c_2);
+
+        // h = u * h_prev + (1 - u) * c
+        wire!(u_h_prev = math::mul::bin_typed(), u, h_prev);
+        let one = model.add_const(format!("{}.one", prefix), rctensor2(&[[1f32]]))?;
+        wire!(one_sub_u = math::sub::bin_typed(), one, u);
+        wire!(one_sub_u_c = math::mul::bin_typed(), one_sub_u, c);
+        wire!(h = math::add::bin_typed(), u_h_prev, one_sub_u_c);
+
+        Ok(tvec!(r, u, c, h))
+    }
+}
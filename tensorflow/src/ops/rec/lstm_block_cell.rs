@@ -0,0 +1,195 @@
+use tract_hir::internal::*;
+
+use crate::model::ParsingContext;
+use crate::tfpb::tensorflow::NodeDef;
+
+pub fn lstm_block_cell(_ctx: &ParsingContext, node: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let forget_bias = node.get_attr_opt_float("forget_bias")?.unwrap_or(1.0);
+    let cell_clip = node.get_attr_opt_float("cell_clip")?.unwrap_or(3.0);
+    let use_peephole = node.get_attr_opt_bool("use_peephole")?.unwrap_or(false);
+    Ok(expand(LSTMBlockCell::new(LstmCell::new(
+        forget_bias,
+        cell_clip,
+        use_peephole,
+        GateLayout::ICFO,
+    ))))
+}
+
+/// Order of the gates in the weight matrix columns.
+#[derive(Clone, Copy, Debug, Hash, PartialEq)]
+pub enum GateLayout {
+    /// input, cell input, forget, output (BlockLSTM, LSTMBlockCell)
+    ICFO,
+    /// input, forget, cell input, output (BlockLSTMV2)
+    IFCO,
+}
+
+/// One step of the LSTM computation shared by the TensorFlow LSTM operators.
+#[derive(Clone, Debug, new, Educe)]
+#[educe(Hash)]
+pub struct LstmCell {
+    #[educe(Hash(method = "hash_f32"))]
+    pub forget_bias: f32,
+    #[educe(Hash(method = "hash_f32"))]
+    pub cell_clip: f32,
+    pub use_peephole: bool,
+    pub layout: GateLayout,
+}
+
+impl LstmCell {
+    /// Wires the cell in `model`. x is [batch, input_size], cs_prev and h_prev
+    /// are [batch, cell_size], w is [input_size + cell_size, 4 * cell_size],
+    /// b and the optional peepholes (wci, wcf, wco) are vectors.
+    ///
+    /// Returns the seven outputs of LSTMBlockCell: i, cs, f, o, ci, co, h.
+    #[allow(clippy::too_many_arguments)]
+    pub fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        x: OutletId,
+        cs_prev: OutletId,
+        h_prev: OutletId,
+        w: OutletId,
+        b: OutletId,
+        peepholes: Option<[OutletId; 3]>,
+    ) -> TractResult<TVec<OutletId>> {
+        use tract_hir::tract_core::ops::{array, math, matmul, nn};
+
+        let cell_size = model.outlet_fact(w)?.shape[1].to_usize()? / 4;
+        let (i_ix, ci_ix, f_ix, o_ix) = match self.layout {
+            GateLayout::ICFO => (0, 1, 2, 3),
+            GateLayout::IFCO => (0, 2, 1, 3),
+        };
+
+        macro_rules! wire {
+            ($name: ident = $op: expr, $($param: expr),*) => {
+                let $name = model.wire_node(
+                    format!("{}.{}", prefix, stringify!($name)),
+                    $op, [$($param),*].as_ref())?[0];
+            }
+        }
+        macro_rules! gate {
+            ($name: ident = $gates: ident[$ix: expr]) => {
+                wire!($name = array::Slice::new(1, $ix * cell_size, ($ix + 1) * cell_size), $gates);
+            };
+        }
+
+        wire!(xh = array::TypedConcat::concat_vars(1, 2), x, h_prev);
+        wire!(gates_1 = matmul::mir::MatMul::default(), xh, w);
+        wire!(b_ = AxisOp::Add(0), b);
+        wire!(gates = math::add::bin_typed(), gates_1, b_);
+
+        gate!(i_1 = gates[i_ix]);
+        gate!(ci_1 = gates[ci_ix]);
+        gate!(f_1 = gates[f_ix]);
+        gate!(o_1 = gates[o_ix]);
+
+        let (mut i_1, mut f_1) = (i_1, f_1);
+        if let Some([wci, wcf, _]) = peepholes {
+            wire!(wci_ = AxisOp::Add(0), wci);
+            wire!(wcf_ = AxisOp::Add(0), wcf);
+            wire!(i_peep = math::mul::bin_typed(), cs_prev, wci_);
+            wire!(f_peep = math::mul::bin_typed(), cs_prev, wcf_);
+            wire!(i_2 = math::add::bin_typed(), i_1, i_peep);
+            wire!(f_2 = math::add::bin_typed(), f_1, f_peep);
+            i_1 = i_2;
+            f_1 = f_2;
+        }
+        if self.forget_bias != 0.0 {
+            wire!(f_biased = math::add::unary(rctensor2(&[[self.forget_bias]])), f_1);
+            f_1 = f_biased;
+        }
+        wire!(i = nn::sigmoid(), i_1);
+        wire!(f = nn::sigmoid(), f_1);
+        wire!(ci = math::tanh(), ci_1);
+
+        wire!(ci_i = math::mul::bin_typed(), ci, i);
+        wire!(cs_1 = math::mul::bin_typed(), cs_prev, f);
+        wire!(cs = math::add::bin_typed(), cs_1, ci_i);
+        let mut cs = cs;
+        if self.cell_clip > 0.0 {
+            wire!(cs_clip_low = math::max::unary(rctensor2(&[[-self.cell_clip]])), cs);
+            wire!(cs_clipped = math::min::unary(rctensor2(&[[self.cell_clip]])), cs_clip_low);
+            cs = cs_clipped;
+        }
+
+        let mut o_1 = o_1;
+        if let Some([_, _, wco]) = peepholes {
+            wire!(wco_ = AxisOp::Add(0), wco);
+            wire!(o_peep = math::mul::bin_typed(), cs, wco_);
+            wire!(o_2 = math::add::bin_typed(), o_1, o_peep);
+            o_1 = o_2;
+        }
+        wire!(o = nn::sigmoid(), o_1);
+
+        wire!(co = math::tanh(), cs);
+        wire!(h = math::mul::bin_typed(), co, o);
+
+        Ok(tvec!(i, cs, f, o, ci, co, h))
+    }
+}
+
+#[derive(Clone, Debug, new, Hash)]
+pub struct LSTMBlockCell {
+    cell: LstmCell,
+}
+
+impl_dyn_hash!(LSTMBlockCell);
+
+impl Expansion for LSTMBlockCell {
+    fn name(&self) -> Cow<str> {
+        "LSTMBlockCell".into()
+    }
+
+    fn validation(&self) -> Validation {
+        Validation::Rounding
+    }
+
+    op_tf!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 8)?;
+        check_output_arity(&outputs, 7)?;
+        s.equals_all((0..8).map(move |i| (&inputs[i].datum_type).bex()).collect())?;
+
+        s.equals(&inputs[0].rank, 2)?; // x: [batch, input_size]
+        s.equals(&inputs[1].rank, 2)?; // cs_prev: [batch, cell_size]
+        s.equals(&inputs[2].rank, 2)?; // h_prev: [batch, cell_size]
+        s.equals(&inputs[3].rank, 2)?; // w: [input_size + cell_size, 4 * cell_size]
+        s.equals(&inputs[4].rank, 1)?; // peephole input
+        s.equals(&inputs[5].rank, 1)?; // peephole forget
+        s.equals(&inputs[6].rank, 1)?; // peephole output
+        s.equals(&inputs[7].rank, 1)?; // bias: [4 * cell_size]
+        s.equals(&inputs[7].shape[0], 4 * inputs[1].shape[1].bex())?;
+        s.equals(&inputs[3].shape[1], &inputs[7].shape[0])?;
+
+        // i, cs, f, o, ci, co, h
+        for output in outputs {
+            s.equals(&inputs[0].datum_type, &output.datum_type)?;
+            s.equals(&output.shape, &inputs[1].shape)?;
+        }
+        Ok(())
+    }
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(7)
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let peepholes =
+            if self.cell.use_peephole { Some([inputs[4], inputs[5], inputs[6]]) } else { None };
+        self.cell
+            .wire(prefix, model, inputs[0], inputs[1], inputs[2], inputs[3], inputs[7], peepholes)
+    }
+}
@@ -1,7 +1,16 @@
 use crate::model::TfOpRegister;
 
 pub mod block_lstm;
+pub mod cudnn_rnn;
+pub mod gru_block_cell;
+pub mod lstm_block_cell;
 
 pub fn register_all_ops(reg: &mut TfOpRegister) {
     reg.insert("BlockLSTM", block_lstm::block_lstm);
+    reg.insert("BlockLSTMV2", block_lstm::block_lstm_v2);
+    reg.insert("CudnnRNN", cudnn_rnn::cudnn_rnn);
+    reg.insert("CudnnRNNV2", cudnn_rnn::cudnn_rnn);
+    reg.insert("CudnnRNNV3", cudnn_rnn::cudnn_rnn);
+    reg.insert("GRUBlockCell", gru_block_cell::gru_block_cell);
+    reg.insert("LSTMBlockCell", lstm_block_cell::lstm_block_cell);
 }
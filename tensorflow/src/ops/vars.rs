@@ -47,6 +47,9 @@ impl OpState for VariableV2State {
             .with_context(|| format!("Could not find state for variable {}", op.id))?;
         Ok(tvec!(tensor.clone().into()))
     }
+
+    // the value of the variable is in the session tensors
+    op_state_without_data!();
 }
 
 #[derive(Clone, Debug, new, Hash)]
@@ -173,6 +176,8 @@ impl OpState for AssignState {
         *store = new.clone().into_tensor();
         Ok(tvec!(new))
     }
+
+    op_state_without_data!();
 }
 
 impl EvalOp for Assign {
@@ -0,0 +1,323 @@
+//! SavedModel directories: variables checkpoint and signatures.
+//!
+//! A SavedModel directory contains a `saved_model.pb` and a `variables/`
+//! tensor bundle (`variables.index` and `variables.data-?????-of-?????`
+//! shards). The variables are read from the bundle and frozen into `Const`
+//! nodes, so the resulting graph can be handled as a frozen model.
+
+use crate::tfpb::tensorflow::bundle_header_proto::Endianness;
+use crate::tfpb::tensorflow::tensor_info::Encoding;
+use crate::tfpb::tensorflow::{
+    BundleEntryProto, BundleHeaderProto, DataType, GraphDef, NodeDef, SignatureDef, TensorProto,
+    TrackableObjectGraph,
+};
+use prost::Message;
+use std::collections::HashSet;
+use std::convert::{TryFrom, TryInto};
+use std::{fs, path};
+use tract_hir::internal::*;
+
+/// Signature picked by default when loading a SavedModel directory.
+pub const DEFAULT_SIGNATURE: &str = "serving_default";
+
+/// Checkpoint key of the object graph in TF2 object-based checkpoints.
+const OBJECT_GRAPH_KEY: &str = "_CHECKPOINTABLE_OBJECT_GRAPH";
+
+/// Magic number ending a LevelDB-style table (the bundle index format).
+const TABLE_MAGIC: u64 = 0xdb4775248b80fb57;
+const TABLE_FOOTER_LEN: usize = 48;
+
+/// Inputs and outputs of a SignatureDef, as (signature key, graph tensor
+/// name) pairs, ordered by key.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Signature {
+    pub inputs: Vec<(String, String)>,
+    pub outputs: Vec<(String, String)>,
+}
+
+impl Signature {
+    pub fn from_def(def: &SignatureDef) -> TractResult<Signature> {
+        fn tensors(
+            map: &HashMap<String, crate::tfpb::tensorflow::TensorInfo>,
+        ) -> TractResult<Vec<(String, String)>> {
+            let mut tensors = map
+                .iter()
+                .map(|(key, info)| match &info.encoding {
+                    Some(Encoding::Name(name)) => Ok((key.clone(), name.clone())),
+                    _ => bail!("Signature tensor {} is not a dense tensor", key),
+                })
+                .collect::<TractResult<Vec<_>>>()?;
+            tensors.sort();
+            Ok(tensors)
+        }
+        Ok(Signature { inputs: tensors(&def.inputs)?, outputs: tensors(&def.outputs)? })
+    }
+}
+
+/// A SavedModel graph with its variables frozen, along with the signature
+/// selected to define its inputs and outputs.
+#[derive(Clone, Debug)]
+pub struct FrozenSavedModel {
+    pub graph: GraphDef,
+    pub signature: Option<Signature>,
+}
+
+fn varint(bytes: &[u8], pos: &mut usize) -> TractResult<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes.get(*pos).context("Truncated varint")?;
+        *pos += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    bail!("Invalid varint")
+}
+
+fn block<'t>(table: &'t [u8], handle: &[u8]) -> TractResult<&'t [u8]> {
+    let mut pos = 0;
+    let offset = varint(handle, &mut pos)? as usize;
+    let size = varint(handle, &mut pos)? as usize;
+    // block content is followed by a compression type byte and a crc
+    if offset + size + 5 > table.len() {
+        bail!("Block at {}+{} overflows table ({} bytes)", offset, size, table.len())
+    }
+    if table[offset + size] != 0 {
+        bail!("Compressed tensor bundle blocks are not supported")
+    }
+    Ok(&table[offset..offset + size])
+}
+
+fn block_entries(block: &[u8]) -> TractResult<Vec<(Vec<u8>, &[u8])>> {
+    if block.len() < 4 {
+        bail!("Truncated block")
+    }
+    let restarts = u32::from_le_bytes(block[block.len() - 4..].try_into()?) as usize;
+    let end =
+        block.len().checked_sub(4 * (restarts + 1)).context("Invalid restart count in block")?;
+    let mut entries: Vec<(Vec<u8>, &[u8])> = vec![];
+    let mut pos = 0;
+    while pos < end {
+        let shared = varint(block, &mut pos)? as usize;
+        let non_shared = varint(block, &mut pos)? as usize;
+        let value_len = varint(block, &mut pos)? as usize;
+        if pos + non_shared + value_len > end {
+            bail!("Block entry overflows block")
+        }
+        let mut key = entries.last().map(|e| e.0[..shared].to_vec()).unwrap_or_default();
+        key.extend_from_slice(&block[pos..pos + non_shared]);
+        pos += non_shared;
+        entries.push((key, &block[pos..pos + value_len]));
+        pos += value_len;
+    }
+    Ok(entries)
+}
+
+/// Reads all (key, value) pairs of a LevelDB-style table.
+fn table_entries(table: &[u8]) -> TractResult<Vec<(Vec<u8>, Vec<u8>)>> {
+    if table.len() < TABLE_FOOTER_LEN {
+        bail!("Truncated table")
+    }
+    let footer = &table[table.len() - TABLE_FOOTER_LEN..];
+    if u64::from_le_bytes(footer[40..].try_into()?) != TABLE_MAGIC {
+        bail!("Not a tensor bundle index (wrong magic number)")
+    }
+    // footer starts with the metaindex handle, then the index handle
+    let mut pos = 0;
+    varint(footer, &mut pos)?;
+    varint(footer, &mut pos)?;
+    let index = block(table, &footer[pos..])?;
+    let mut entries = vec![];
+    for (_, handle) in block_entries(index)? {
+        for (key, value) in block_entries(block(table, handle)?)? {
+            entries.push((key, value.to_vec()));
+        }
+    }
+    Ok(entries)
+}
+
+/// String tensors are stored as varint lengths, a checksum of the lengths,
+/// then the concatenated bytes.
+fn blobs(data: &[u8], len: usize) -> TractResult<Vec<Blob>> {
+    let mut pos = 0;
+    let lengths =
+        (0..len).map(|_| Ok(varint(data, &mut pos)? as usize)).collect::<TractResult<Vec<_>>>()?;
+    pos += 4;
+    let mut blobs = vec![];
+    for l in lengths {
+        let bytes = data.get(pos..pos + l).context("Truncated string tensor")?;
+        blobs.push(Blob(bytes.to_vec()));
+        pos += l;
+    }
+    Ok(blobs)
+}
+
+/// Reads the tensors of a bundle given its prefix (like
+/// `saved_model/variables/variables`).
+///
+/// Keys of TF2 object-based checkpoints (like
+/// `layer/kernel/.ATTRIBUTES/VARIABLE_VALUE`) are mapped back to variable
+/// names using the checkpoint object graph. Checksums are not verified.
+pub fn read_tensor_bundle(prefix: impl AsRef<path::Path>) -> TractResult<HashMap<String, Tensor>> {
+    let prefix = prefix.as_ref().to_string_lossy().to_string();
+    let index_path = format!("{}.index", prefix);
+    let index = fs::read(&index_path).with_context(|| format!("Could not read {}", index_path))?;
+    let mut entries = table_entries(&index).with_context(|| format!("Reading {}", index_path))?;
+    if entries.first().map(|e| e.0.is_empty()) != Some(true) {
+        bail!("Tensor bundle has no header")
+    }
+    let header = BundleHeaderProto::decode(&*entries.remove(0).1)?;
+    if header.endianness != Endianness::Little as i32 {
+        bail!("Big endian tensor bundles are not supported")
+    }
+    let shards = (0..header.num_shards)
+        .map(|ix| {
+            let path = format!("{}.data-{:05}-of-{:05}", prefix, ix, header.num_shards);
+            fs::read(&path).with_context(|| format!("Could not read {}", path))
+        })
+        .collect::<TractResult<Vec<_>>>()?;
+
+    let mut tensors = HashMap::default();
+    for (key, value) in entries {
+        let key = String::from_utf8(key)?;
+        let entry = BundleEntryProto::decode(&*value)?;
+        if !entry.slices.is_empty() {
+            bail!("Partitioned variable {} is not supported", key)
+        }
+        let dt = DataType::from_i32(entry.dtype)
+            .with_context(|| format!("Unknown data type {} for {}", entry.dtype, key))?;
+        let dt = DatumType::try_from(dt)?;
+        let shape: TVec<usize> =
+            entry.shape.as_ref().map(TVec::<usize>::try_from).transpose()?.unwrap_or_default();
+        let data = shards
+            .get(entry.shard_id as usize)
+            .and_then(|s| s.get(entry.offset as usize..(entry.offset + entry.size) as usize))
+            .with_context(|| format!("Data for {} is out of its shard", key))?;
+        let len = shape.iter().product::<usize>();
+        let tensor = if dt == DatumType::Blob {
+            tract_ndarray::ArrayD::from_shape_vec(&*shape, blobs(data, len)?)?.into_tensor()
+        } else if dt.is_copy() && data.len() == len * dt.size_of() {
+            unsafe { Tensor::from_raw_dt(dt, &shape, data)? }
+        } else {
+            bail!("Inconsistent data for {}: {:?} {:?}, {} bytes", key, dt, shape, data.len())
+        };
+        tensors.insert(key, tensor);
+    }
+
+    if let Some(graph) = tensors.remove(OBJECT_GRAPH_KEY) {
+        let graph = graph.to_scalar::<Blob>()?;
+        let graph = TrackableObjectGraph::decode(&*graph.0)?;
+        for attribute in graph.nodes.iter().flat_map(|n| n.attributes.iter()) {
+            if let Some(tensor) = tensors.remove(&attribute.checkpoint_key) {
+                tensors.insert(attribute.full_name.clone(), tensor);
+            }
+        }
+    }
+    Ok(tensors)
+}
+
+fn node_name(input: &str) -> &str {
+    input.trim_start_matches('^').split(':').next().unwrap()
+}
+
+/// Replaces variables found in `variables` by `Const` nodes.
+///
+/// Variables are looked up by shared name, or node name. Reads of resource
+/// variables become `Identity`, while assignments and initialization checks
+/// are removed, along with anything depending on them.
+pub fn freeze_variables(
+    graph: &mut GraphDef,
+    variables: &HashMap<String, Tensor>,
+) -> TractResult<()> {
+    let mut frozen = HashSet::new();
+    for node in &mut graph.node {
+        if !["Variable", "VariableV2", "VarHandleOp"].contains(&&*node.op) {
+            continue;
+        }
+        let shared_name = node.get_attr_opt_str("shared_name")?.filter(|s| !s.is_empty());
+        let value = if let Some(value) = variables.get(shared_name.as_ref().unwrap_or(&node.name)) {
+            value
+        } else {
+            continue;
+        };
+        let dt = node.get_attr_datum_type("dtype")?;
+        if dt != value.datum_type() {
+            bail!("Variable {} is {:?}, checkpoint has {:?}", node.name, dt, value.datum_type())
+        }
+        node.op = "Const".to_string();
+        node.input.clear();
+        node.attr.clear();
+        node.attr.insert("dtype".to_string(), DataType::try_from(dt)?.into());
+        node.attr.insert("value".to_string(), TensorProto::try_from(value)?.into());
+        frozen.insert(node.name.clone());
+    }
+    let targets_frozen =
+        |node: &NodeDef| node.input.first().map(|i| frozen.contains(node_name(i))).unwrap_or(false);
+    let mut removed: HashSet<String> = HashSet::new();
+    for node in &mut graph.node {
+        if node.op == "ReadVariableOp" && targets_frozen(node) {
+            node.op = "Identity".to_string();
+            if let Some(dt) = node.attr.remove("dtype") {
+                node.attr.insert("T".to_string(), dt);
+            }
+        } else if [
+            "Assign",
+            "AssignAdd",
+            "AssignSub",
+            "AssignVariableOp",
+            "AssignAddVariableOp",
+            "AssignSubVariableOp",
+            "IsVariableInitialized",
+            "VarIsInitializedOp",
+        ]
+        .contains(&&*node.op)
+            && targets_frozen(node)
+        {
+            removed.insert(node.name.clone());
+        }
+    }
+    loop {
+        let before = removed.len();
+        for node in &graph.node {
+            if node.input.iter().any(|i| !i.starts_with('^') && removed.contains(node_name(i))) {
+                removed.insert(node.name.clone());
+            }
+        }
+        if removed.len() == before {
+            break;
+        }
+    }
+    graph.node.retain(|n| !removed.contains(&n.name));
+    for node in &mut graph.node {
+        node.input.retain(|i| !removed.contains(node_name(i)));
+    }
+    Ok(())
+}
+
+/// Keeps only the nodes needed to compute `outputs`, and the `inputs`.
+///
+/// Control dependencies are not followed, and dropped when they refer to a
+/// removed node.
+pub fn prune(graph: &mut GraphDef, inputs: &[&str], outputs: &[&str]) -> TractResult<()> {
+    let by_name: HashMap<&str, &NodeDef> = graph.node.iter().map(|n| (&*n.name, n)).collect();
+    let inputs: HashSet<&str> = inputs.iter().map(|i| node_name(i)).collect();
+    let mut kept: HashSet<String> = HashSet::new();
+    let mut todo: Vec<&str> =
+        outputs.iter().map(|o| node_name(o)).chain(inputs.iter().cloned()).collect();
+    while let Some(name) = todo.pop() {
+        if !kept.insert(name.to_string()) {
+            continue;
+        }
+        let node = by_name.get(name).with_context(|| format!("No node named {}", name))?;
+        // inputs of the model are fed, their own inputs are not needed
+        if !inputs.contains(name) {
+            todo.extend(node.input.iter().filter(|i| !i.starts_with('^')).map(|i| node_name(i)))
+        }
+    }
+    graph.node.retain(|n| kept.contains(&n.name));
+    for node in &mut graph.node {
+        node.input.retain(|i| kept.contains(node_name(i)));
+    }
+    Ok(())
+}
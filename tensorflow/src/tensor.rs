@@ -92,7 +92,13 @@ impl<'a> TryFrom<&'a TensorProto> for Tensor {
                     DataType::DtDouble => Self::from_raw::<f64>(&dims, content)?,
                     DataType::DtInt32 => Self::from_raw::<i32>(&dims, content)?,
                     DataType::DtInt64 => Self::from_raw::<i64>(&dims, content)?,
-                    _ => unimplemented!("missing type (for get_tensor_content) {:?}", dtype),
+                    _ => {
+                        let dt = DatumType::try_from(dtype)?;
+                        if !dt.is_copy() {
+                            bail!("missing type (for get_tensor_content) {:?}", dtype)
+                        }
+                        Self::from_raw_dt(dt, &dims, content)?
+                    }
                 }
             }
         } else {
@@ -160,6 +166,12 @@ impl<'a> TryFrom<&'a Tensor> for TensorProto {
             DatumType::I64 => {
                 tensor.int64_val = from.to_array_view::<i64>()?.iter().cloned().collect();
             }
+            DatumType::Blob => {
+                tensor.string_val = from.as_slice::<Blob>()?.iter().map(|b| b.0.clone()).collect();
+            }
+            dt if dt.is_copy() => {
+                tensor.tensor_content = unsafe { from.as_bytes() }.to_vec();
+            }
             _ => unimplemented!("missing type {:?}", from.datum_type()),
         }
         Ok(tensor)
@@ -14,7 +14,9 @@ pub mod tensorflow {
 
 use self::tensorflow::attr_value::ListValue;
 use self::tensorflow::attr_value::Value;
-use self::tensorflow::{AttrValue, DataType, GraphDef, NodeDef, TensorProto, TensorShapeProto};
+use self::tensorflow::{
+    AttrValue, DataType, GraphDef, NameAttrList, NodeDef, TensorProto, TensorShapeProto,
+};
 
 use std::convert::TryInto;
 
@@ -222,6 +224,45 @@ impl NodeDef {
         };
         Ok(None)
     }
+
+    pub fn get_attr_list_datum_type(&self, name: &str) -> TractResult<Vec<DatumType>> {
+        Ok(self.get_attr_opt_list_datum_type(name)?.with_context(|| {
+            format!("Node {} ({}) expected list<type> attribute '{}'", self.name, self.op, name)
+        })?)
+    }
+
+    pub fn get_attr_opt_list_datum_type(&self, name: &str) -> TractResult<Option<Vec<DatumType>>> {
+        if let Some(a) = self.attr.get(name) {
+            if let Value::List(list) = a.value.as_ref().unwrap() {
+                return Ok(Some(
+                    list.r#type
+                        .iter()
+                        .map(|&t| {
+                            DataType::from_i32(t)
+                                .with_context(|| format!("Unknown DataType {}", t))?
+                                .try_into()
+                        })
+                        .collect::<TractResult<_>>()?,
+                ));
+            }
+        };
+        Ok(None)
+    }
+
+    pub fn get_attr_func(&self, name: &str) -> TractResult<&NameAttrList> {
+        Ok(self.get_attr_opt_func(name)?.with_context(|| {
+            format!("Node {} ({}) expected func attribute '{}'", self.name, self.op, name)
+        })?)
+    }
+
+    pub fn get_attr_opt_func(&self, name: &str) -> TractResult<Option<&NameAttrList>> {
+        if let Some(a) = self.attr.get(name) {
+            if let Value::Func(f) = a.value.as_ref().unwrap() {
+                return Ok(Some(f));
+            }
+        };
+        Ok(None)
+    }
 }
 
 impl From<DataType> for AttrValue {
@@ -283,6 +324,29 @@ impl From<TensorShapeProto> for AttrValue {
     }
 }
 
+impl From<Vec<DataType>> for AttrValue {
+    fn from(t: Vec<DataType>) -> AttrValue {
+        AttrValue {
+            value: Some(Value::List(ListValue {
+                s: vec![],
+                i: vec![],
+                f: vec![],
+                b: vec![],
+                r#type: t.into_iter().map(|t| t.into()).collect(),
+                shape: vec![],
+                tensor: vec![],
+                func: vec![],
+            })),
+        }
+    }
+}
+
+impl From<NameAttrList> for AttrValue {
+    fn from(t: NameAttrList) -> AttrValue {
+        AttrValue { value: Some(Value::Func(t)) }
+    }
+}
+
 impl From<bool> for AttrValue {
     fn from(t: bool) -> AttrValue {
         AttrValue { value: Some(Value::B(t)) }
@@ -0,0 +1,274 @@
+extern crate tract_tensorflow;
+
+use std::convert::TryFrom;
+use tract_tensorflow::prelude::*;
+use tract_tensorflow::tfpb::tensorflow::op_def::ArgDef;
+use tract_tensorflow::tfpb::tensorflow::*;
+use tract_tensorflow::tfpb::{self, node};
+
+fn arg(name: &str, dt: DataType) -> ArgDef {
+    ArgDef { name: name.to_string(), r#type: dt.into(), ..Default::default() }
+}
+
+fn function(
+    name: &str,
+    inputs: &[(&str, DataType)],
+    outputs: &[(&str, DataType, &str)],
+    nodes: Vec<NodeDef>,
+) -> FunctionDef {
+    FunctionDef {
+        signature: Some(OpDef {
+            name: name.to_string(),
+            input_arg: inputs.iter().map(|(n, dt)| arg(n, *dt)).collect(),
+            output_arg: outputs.iter().map(|(n, dt, _)| arg(n, *dt)).collect(),
+            ..Default::default()
+        }),
+        node_def: nodes,
+        ret: outputs.iter().map(|(n, _, r)| (n.to_string(), r.to_string())).collect(),
+        ..Default::default()
+    }
+}
+
+fn func(name: &str) -> NameAttrList {
+    NameAttrList { name: name.to_string(), attr: Default::default() }
+}
+
+fn konst(name: &str, dt: DataType, t: Tensor) -> NodeDef {
+    node()
+        .name(name)
+        .op("Const")
+        .attr("dtype", dt)
+        .attr("value", TensorProto::try_from(&t).unwrap())
+}
+
+fn binary(name: &str, op: &str, a: &str, b: &str, dt: DataType) -> NodeDef {
+    node().name(name).op(op).input(a).input(b).attr("T", dt)
+}
+
+fn placeholder(name: &str, dt: DataType, dims: &[i64]) -> NodeDef {
+    let shape = TensorShapeProto {
+        dim: dims
+            .iter()
+            .map(|&d| tensor_shape_proto::Dim { size: d, name: String::new() })
+            .collect(),
+        unknown_rank: false,
+    };
+    node().name(name).op("Placeholder").attr("dtype", dt).attr("shape", shape)
+}
+
+/// cond: i < n, body: (i + 1, n, x * 2)
+fn doubling_loop() -> Vec<FunctionDef> {
+    use DataType::*;
+    let vars = [("i", DtInt32), ("n", DtInt32), ("x", DtFloat)];
+    let cond = function(
+        "cond",
+        &vars,
+        &[("less", DtBool, "less:z:0")],
+        vec![binary("less", "Less", "i", "n", DtInt32)],
+    );
+    let body = function(
+        "body",
+        &vars,
+        &[("i", DtInt32, "next_i:z:0"), ("n", DtInt32, "n"), ("x", DtFloat, "next_x:z:0")],
+        vec![
+            konst("one", DtInt32, tensor0(1i32)),
+            konst("two", DtFloat, tensor0(2f32)),
+            binary("next_i", "AddV2", "i", "one:output:0", DtInt32),
+            binary("next_x", "Mul", "x", "two:output:0", DtFloat),
+        ],
+    );
+    vec![cond, body]
+}
+
+fn while_node(n: &str) -> NodeDef {
+    node()
+        .name("while")
+        .op("StatelessWhile")
+        .input("i0")
+        .input(n)
+        .input("x")
+        .attr("T", vec![DataType::DtInt32, DataType::DtInt32, DataType::DtFloat])
+        .attr("cond", func("cond"))
+        .attr("body", func("body"))
+}
+
+fn run(graph: GraphDef, inputs: TVec<Tensor>) -> TractResult<(TypedModel, TVec<Arc<Tensor>>)> {
+    let mut model = tensorflow().model_for_proto_model(&graph)?;
+    let outputs = model.node_by_name("result")?.id;
+    model.set_output_outlets(&[OutletId::new(outputs, 0)])?;
+    let model = model.into_optimized()?;
+    let result = SimplePlan::new(&model)?.run(inputs)?;
+    Ok((model, result))
+}
+
+#[test]
+fn while_static_trip_count() -> TractResult<()> {
+    let mut graph = tfpb::graph()
+        .node(placeholder("x", DataType::DtFloat, &[2]))
+        .node(konst("i0", DataType::DtInt32, tensor0(0i32)))
+        .node(konst("n", DataType::DtInt32, tensor0(3i32)))
+        .node(while_node("n"))
+        .node(node().name("result").op("Identity").input("while:2").attr("T", DataType::DtFloat));
+    graph.library = Some(FunctionDefLibrary { function: doubling_loop(), gradient: vec![] });
+    let (model, result) = run(graph, tvec!(tensor1(&[1f32, -2.])))?;
+    assert!(model.nodes().iter().any(|n| n.op().name() == "Scan"));
+    assert_eq!(*result[0], tensor1(&[8f32, -16.]));
+    Ok(())
+}
+
+#[test]
+fn while_static_trip_count_with_step() -> TractResult<()> {
+    use DataType::*;
+    // cond: n > i, body: (i + 2, n, x * 2), with i from 0 to 5: 3 iterations
+    let vars = [("i", DtInt32), ("n", DtInt32), ("x", DtFloat)];
+    let cond = function(
+        "cond",
+        &vars,
+        &[("greater", DtBool, "greater:z:0")],
+        vec![binary("greater", "Greater", "n", "i", DtInt32)],
+    );
+    let body = function(
+        "body",
+        &vars,
+        &[("i", DtInt32, "next_i:z:0"), ("n", DtInt32, "n"), ("x", DtFloat, "next_x:z:0")],
+        vec![
+            konst("step", DtInt32, tensor0(2i32)),
+            konst("two", DtFloat, tensor0(2f32)),
+            binary("next_i", "AddV2", "i", "step:output:0", DtInt32),
+            binary("next_x", "Mul", "x", "two:output:0", DtFloat),
+        ],
+    );
+    let mut graph = tfpb::graph()
+        .node(placeholder("x", DtFloat, &[2]))
+        .node(konst("i0", DtInt32, tensor0(0i32)))
+        .node(konst("n", DtInt32, tensor0(5i32)))
+        .node(while_node("n"))
+        .node(node().name("result").op("Identity").input("while:2").attr("T", DtFloat));
+    graph.library = Some(FunctionDefLibrary { function: vec![cond, body], gradient: vec![] });
+    let (model, result) = run(graph, tvec!(tensor1(&[1f32, -2.])))?;
+    assert!(model.nodes().iter().any(|n| n.op().name() == "Scan"));
+    assert_eq!(*result[0], tensor1(&[8f32, -16.]));
+    Ok(())
+}
+
+#[test]
+fn while_data_dependent_trip_count() -> TractResult<()> {
+    use DataType::*;
+    // cond: x < 100, body: x * 2: not a counter, even with a constant start
+    let cond = function(
+        "cond",
+        &[("x", DtFloat)],
+        &[("less", DtBool, "less:z:0")],
+        vec![
+            konst("limit", DtFloat, tensor0(100f32)),
+            binary("less", "Less", "x", "limit:output:0", DtFloat),
+        ],
+    );
+    let body = function(
+        "body",
+        &[("x", DtFloat)],
+        &[("x", DtFloat, "next_x:z:0")],
+        vec![
+            konst("two", DtFloat, tensor0(2f32)),
+            binary("next_x", "Mul", "x", "two:output:0", DtFloat),
+        ],
+    );
+    let mut graph = tfpb::graph()
+        .node(konst("x", DtFloat, tensor0(1f32)))
+        .node(
+            node()
+                .name("while")
+                .op("StatelessWhile")
+                .input("x")
+                .attr("T", vec![DtFloat])
+                .attr("cond", func("cond"))
+                .attr("body", func("body")),
+        )
+        .node(node().name("result").op("Identity").input("while").attr("T", DtFloat));
+    graph.library = Some(FunctionDefLibrary { function: vec![cond, body], gradient: vec![] });
+    let (model, result) = run(graph, tvec!())?;
+    assert!(model.nodes().iter().all(|n| n.op().name() != "Scan"));
+    assert_eq!(*result[0], tensor0(128f32));
+    Ok(())
+}
+
+#[test]
+fn while_dynamic_trip_count() -> TractResult<()> {
+    let mut graph = tfpb::graph()
+        .node(placeholder("x", DataType::DtFloat, &[2]))
+        .node(placeholder("n", DataType::DtInt32, &[]))
+        .node(konst("i0", DataType::DtInt32, tensor0(0i32)))
+        .node(while_node("n"))
+        .node(node().name("result").op("Identity").input("while:2").attr("T", DataType::DtFloat));
+    graph.library = Some(FunctionDefLibrary { function: doubling_loop(), gradient: vec![] });
+    let (model, result) = run(graph.clone(), tvec!(tensor1(&[1f32, -2.]), tensor0(4i32)))?;
+    assert!(model.nodes().iter().any(|n| n.op().name() == "TypedWhile"));
+    assert_eq!(*result[0], tensor1(&[16f32, -32.]));
+    let (_, result) = run(graph, tvec!(tensor1(&[1f32, -2.]), tensor0(0i32)))?;
+    assert_eq!(*result[0], tensor1(&[1f32, -2.]));
+    Ok(())
+}
+
+#[test]
+fn if_runtime_condition() -> TractResult<()> {
+    use DataType::*;
+    let branch = |name: &str, op: &str| {
+        function(
+            name,
+            &[("x", DtFloat)],
+            &[("y", DtFloat, "y:z:0")],
+            vec![
+                konst("one", DtFloat, tensor0(1f32)),
+                binary("y", op, "x", "one:output:0", DtFloat),
+            ],
+        )
+    };
+    let mut graph = tfpb::graph()
+        .node(placeholder("c", DtBool, &[]))
+        .node(placeholder("x", DtFloat, &[2]))
+        .node(
+            node()
+                .name("result")
+                .op("StatelessIf")
+                .input("c")
+                .input("x")
+                .attr("then_branch", func("plus_one"))
+                .attr("else_branch", func("minus_one")),
+        );
+    graph.library = Some(FunctionDefLibrary {
+        function: vec![branch("plus_one", "AddV2"), branch("minus_one", "Sub")],
+        gradient: vec![],
+    });
+    let (_, result) = run(graph.clone(), tvec!(tensor0(true), tensor1(&[1f32, 2.])))?;
+    assert_eq!(*result[0], tensor1(&[2f32, 3.]));
+    let (_, result) = run(graph, tvec!(tensor0(false), tensor1(&[1f32, 2.])))?;
+    assert_eq!(*result[0], tensor1(&[0f32, 1.]));
+    Ok(())
+}
+
+#[test]
+fn partitioned_call_is_inlined() -> TractResult<()> {
+    use DataType::*;
+    let square = function(
+        "square",
+        &[("x", DtFloat)],
+        &[("y", DtFloat, "y:z:0")],
+        vec![binary("y", "Mul", "x", "x", DtFloat)],
+    );
+    let mut graph = tfpb::graph().node(placeholder("x", DtFloat, &[3])).node(
+        node().name("result").op("StatefulPartitionedCall").input("x").attr("f", func("square")),
+    );
+    graph.library = Some(FunctionDefLibrary { function: vec![square], gradient: vec![] });
+    let (model, result) = run(graph, tvec!(tensor1(&[1f32, 2., -3.])))?;
+    assert!(model.nodes().iter().all(|n| n.op().name() != "FunctionCall"));
+    assert_eq!(*result[0], tensor1(&[1f32, 4., 9.]));
+    Ok(())
+}
+
+#[test]
+fn dataflow_loop_is_an_error() {
+    use tract_tensorflow::ops::control_flow::{NextIteration, NextIterationRole};
+    use tract_tensorflow::tract_hir::internal::*;
+    let op = NextIteration::new("loop".to_string(), NextIterationRole::Source);
+    assert!(op.state(&mut SessionState::default(), 0).is_err());
+}
@@ -0,0 +1,333 @@
+extern crate tract_tensorflow;
+
+use std::convert::TryFrom;
+use tract_tensorflow::prelude::*;
+use tract_tensorflow::tfpb::tensorflow::*;
+use tract_tensorflow::tfpb::{self, node};
+
+fn konst(name: &str, t: Tensor) -> NodeDef {
+    let proto = TensorProto::try_from(&t).unwrap();
+    node().name(name).op("Const").attr("dtype", proto.dtype()).attr("value", proto)
+}
+
+fn placeholder(name: &str, dt: DataType, dims: &[i64]) -> NodeDef {
+    let shape = TensorShapeProto {
+        dim: dims
+            .iter()
+            .map(|&d| tensor_shape_proto::Dim { size: d, name: String::new() })
+            .collect(),
+        unknown_rank: false,
+    };
+    node().name(name).op("Placeholder").attr("dtype", dt).attr("shape", shape)
+}
+
+fn input(name: &str, t: &Tensor) -> NodeDef {
+    let dt = TensorProto::try_from(t).unwrap().dtype();
+    placeholder(name, dt, &t.shape().iter().map(|&d| d as i64).collect::<Vec<_>>())
+}
+
+/// Runs the optimized model, outputs are given as "node" or "node:slot".
+fn run(graph: GraphDef, inputs: TVec<Tensor>, outputs: &[&str]) -> TVec<Arc<Tensor>> {
+    let mut model = tensorflow().model_for_proto_model(&graph).unwrap();
+    let outlets = outputs
+        .iter()
+        .map(|o| {
+            let mut splits = o.split(':');
+            let node = model.node_by_name(splits.next().unwrap()).unwrap().id;
+            let slot = splits.next().map(|s| s.parse().unwrap()).unwrap_or(0);
+            OutletId::new(node, slot)
+        })
+        .collect::<Vec<_>>();
+    model.set_output_outlets(&outlets).unwrap();
+    let model = model.into_optimized().unwrap();
+    SimplePlan::new(&model).unwrap().run(inputs).unwrap()
+}
+
+fn run_unary(op: &str, attrs: &[(&str, f32)], x: Tensor) -> Arc<Tensor> {
+    let mut op = node().name("op").op(op).input("x").attr("T", DataType::DtFloat);
+    for (k, v) in attrs {
+        op = op.attr(*k, *v);
+    }
+    let graph = tfpb::graph().node(input("x", &x)).node(op);
+    run(graph, tvec!(x), &["op"]).remove(0)
+}
+
+#[test]
+fn batch_mat_mul_v2_adj_y() {
+    let a = tensor3(&[[[1f32, 2.]], [[3., 4.]]]);
+    let graph = tfpb::graph()
+        .node(input("a", &a))
+        .node(konst("b", tensor3(&[[[1f32, 0.], [0., 1.]], [[1., 2.], [3., 4.]]])))
+        .node(node().name("mm").op("BatchMatMulV2").input("a").input("b").attr("adj_y", true));
+    let result = run(graph, tvec!(a), &["mm"]);
+    assert_eq!(*result[0], tensor3(&[[[1f32, 2.]], [[11., 25.]]]));
+}
+
+#[test]
+fn split_and_split_v() {
+    let x = tensor2(&[[0i32, 1, 2, 3], [4, 5, 6, 7]]);
+    let graph = tfpb::graph()
+        .node(input("x", &x))
+        .node(konst("axis", tensor0(1i32)))
+        .node(konst("sizes", tensor1(&[1i32, -1])))
+        .node(node().name("split").op("Split").input("axis").input("x").attr("num_split", 2))
+        .node(
+            node()
+                .name("split_v")
+                .op("SplitV")
+                .input("x")
+                .input("sizes")
+                .input("axis")
+                .attr("num_split", 2),
+        );
+    let result = run(graph, tvec!(x), &["split:0", "split:1", "split_v:0", "split_v:1"]);
+    assert_eq!(*result[0], tensor2(&[[0i32, 1], [4, 5]]));
+    assert_eq!(*result[1], tensor2(&[[2i32, 3], [6, 7]]));
+    assert_eq!(*result[2], tensor2(&[[0i32], [4]]));
+    assert_eq!(*result[3], tensor2(&[[1i32, 2, 3], [5, 6, 7]]));
+}
+
+#[test]
+fn unpack() {
+    let x = tensor2(&[[1f32, 2., 3.], [4., 5., 6.]]);
+    let graph = tfpb::graph()
+        .node(input("x", &x))
+        .node(node().name("unpack").op("Unpack").input("x").attr("num", 3).attr("axis", -1));
+    let result = run(graph, tvec!(x), &["unpack:0", "unpack:2"]);
+    assert_eq!(*result[0], tensor1(&[1f32, 4.]));
+    assert_eq!(*result[1], tensor1(&[3f32, 6.]));
+}
+
+#[test]
+fn one_hot() {
+    let x = tensor1(&[0i32, 2, 1]);
+    let graph = tfpb::graph()
+        .node(input("x", &x))
+        .node(konst("depth", tensor0(3i32)))
+        .node(konst("on", tensor0(5f32)))
+        .node(konst("off", tensor0(0f32)))
+        .node(node().name("oh").op("OneHot").input("x").input("depth").input("on").input("off"));
+    let result = run(graph, tvec!(x), &["oh"]);
+    assert_eq!(*result[0], tensor2(&[[5f32, 0., 0.], [0., 0., 5.], [0., 5., 0.]]));
+}
+
+#[test]
+fn one_hot_negative_depth_is_rejected() {
+    let x = tensor1(&[0i32, 2, 1]);
+    let graph = tfpb::graph()
+        .node(input("x", &x))
+        .node(konst("depth", tensor0(-3i32)))
+        .node(konst("on", tensor0(5f32)))
+        .node(konst("off", tensor0(0f32)))
+        .node(node().name("oh").op("OneHot").input("x").input("depth").input("on").input("off"));
+    let mut model = tensorflow().model_for_proto_model(&graph).unwrap();
+    model.auto_outputs().unwrap();
+    assert!(model.into_typed().is_err());
+}
+
+#[test]
+fn arg_max() {
+    let x = tensor2(&[[1f32, 5., 3.], [7., 2., 0.]]);
+    let graph = tfpb::graph()
+        .node(input("x", &x))
+        .node(konst("axis", tensor0(1i32)))
+        .node(node().name("am").op("ArgMax").input("x").input("axis"))
+        .node(
+            node()
+                .name("am32")
+                .op("ArgMax")
+                .input("x")
+                .input("axis")
+                .attr("output_type", DataType::DtInt32),
+        );
+    let result = run(graph, tvec!(x), &["am", "am32"]);
+    assert_eq!(*result[0], tensor1(&[1i64, 0]));
+    assert_eq!(*result[1], tensor1(&[1i32, 0]));
+}
+
+#[test]
+fn select_and_select_v2() {
+    let x = tensor2(&[[1f32, 2.], [3., 4.]]);
+    let graph = tfpb::graph()
+        .node(input("x", &x))
+        .node(konst("y", tensor2(&[[10f32, 20.], [30., 40.]])))
+        .node(konst("c", tensor1(&[true, false])))
+        .node(node().name("select").op("Select").input("c").input("x").input("y"))
+        .node(node().name("select_v2").op("SelectV2").input("c").input("x").input("y"));
+    let result = run(graph, tvec!(x), &["select", "select_v2"]);
+    assert_eq!(*result[0], tensor2(&[[1f32, 2.], [30., 40.]]));
+    assert_eq!(*result[1], tensor2(&[[1f32, 20.], [3., 40.]]));
+}
+
+#[test]
+fn unary_activations() {
+    let x = || tensor1(&[-1f32, 0., 2.]);
+    let e = std::f32::consts::E;
+    let check = |op: &str, attrs: &[(&str, f32)], expected: &[f32]| {
+        run_unary(op, attrs, x()).close_enough(&tensor1(expected), true).unwrap()
+    };
+    check("Exp", &[], &[1. / e, 1., e * e]);
+    check("Square", &[], &[1., 0., 4.]);
+    check("LeakyRelu", &[("alpha", 0.1)], &[-0.1, 0., 2.]);
+    check("Elu", &[], &[1. / e - 1., 0., 2.]);
+    check("Softplus", &[], &[0.313_261_7, std::f32::consts::LN_2, 2.126_928]);
+    check("Erf", &[], &[-0.842_700_8, 0., 0.995_322_3]);
+}
+
+#[test]
+fn cumsum() {
+    let x = tensor1(&[1f32, 2., 3.]);
+    let graph = tfpb::graph()
+        .node(input("x", &x))
+        .node(konst("axis", tensor0(0i32)))
+        .node(node().name("excl").op("Cumsum").input("x").input("axis").attr("exclusive", true))
+        .node(node().name("rev").op("Cumsum").input("x").input("axis").attr("reverse", true));
+    let result = run(graph, tvec!(x), &["excl", "rev"]);
+    assert_eq!(*result[0], tensor1(&[0f32, 1., 3.]));
+    assert_eq!(*result[1], tensor1(&[6f32, 5., 3.]));
+}
+
+#[test]
+fn mirror_pad() {
+    let x = tensor1(&[1f32, 2., 3.]);
+    let graph = tfpb::graph()
+        .node(input("x", &x))
+        .node(konst("pads", tensor2(&[[2i32, 2]])))
+        .node(
+            node().name("reflect").op("MirrorPad").input("x").input("pads").attr("mode", "REFLECT"),
+        )
+        .node(
+            node()
+                .name("symmetric")
+                .op("MirrorPad")
+                .input("x")
+                .input("pads")
+                .attr("mode", "SYMMETRIC"),
+        );
+    let result = run(graph, tvec!(x), &["reflect", "symmetric"]);
+    assert_eq!(*result[0], tensor1(&[3f32, 2., 1., 2., 3., 2., 1.]));
+    assert_eq!(*result[1], tensor1(&[2f32, 1., 1., 2., 3., 3., 2.]));
+}
+
+#[test]
+fn fused_batch_norm_v3() {
+    let x = tensor4(&[[[[1f32, 2.]]]]);
+    let graph = tfpb::graph()
+        .node(input("x", &x))
+        .node(konst("scale", tensor1(&[2f32, 1.])))
+        .node(konst("offset", tensor1(&[0f32, 1.])))
+        .node(konst("mean", tensor1(&[1f32, 0.])))
+        .node(konst("variance", tensor1(&[1f32, 4.])))
+        .node(
+            node()
+                .name("bn")
+                .op("FusedBatchNormV3")
+                .input("x")
+                .input("scale")
+                .input("offset")
+                .input("mean")
+                .input("variance")
+                .attr("epsilon", 0f32),
+        );
+    let result = run(graph, tvec!(x), &["bn"]);
+    assert_eq!(*result[0], tensor4(&[[[[0f32, 2.]]]]));
+}
+
+fn conv2d_backprop_input(padding: &str, strides: i64) -> NodeDef {
+    node()
+        .name("deconv")
+        .op("Conv2DBackpropInput")
+        .input("sizes")
+        .input("filter")
+        .input("x")
+        .attr("T", DataType::DtFloat)
+        .attr("padding", padding)
+        .attr("strides", vec![1, strides, strides, 1])
+        .attr("data_format", "NHWC")
+        .attr("dilations", vec![1i64, 1, 1, 1])
+}
+
+#[test]
+fn conv2d_backprop_input_valid() {
+    let x = tensor4(&[[[[1f32], [2.]], [[3.], [4.]]]]);
+    let graph = tfpb::graph()
+        .node(input("x", &x))
+        .node(konst("sizes", tensor1(&[1i32, 4, 4, 1])))
+        .node(konst("filter", Tensor::from(tract_ndarray::Array4::<f32>::ones((2, 2, 1, 1)))))
+        .node(conv2d_backprop_input("VALID", 2));
+    let result = run(graph, tvec!(x), &["deconv"]);
+    let expected = tract_ndarray::arr2(&[
+        [1f32, 1., 2., 2.],
+        [1., 1., 2., 2.],
+        [3., 3., 4., 4.],
+        [3., 3., 4., 4.],
+    ])
+    .into_shape((1, 4, 4, 1))
+    .unwrap();
+    assert_eq!(*result[0], expected.into_tensor());
+}
+
+#[test]
+fn conv2d_backprop_input_same() {
+    let x = tensor4(&[[[[1f32], [2.]], [[3.], [4.]]]]);
+    let graph = tfpb::graph()
+        .node(input("x", &x))
+        .node(konst("sizes", tensor1(&[1i32, 2, 2, 1])))
+        .node(konst("filter", Tensor::from(tract_ndarray::Array4::<f32>::ones((2, 2, 1, 1)))))
+        .node(conv2d_backprop_input("SAME", 1));
+    let result = run(graph, tvec!(x), &["deconv"]);
+    assert_eq!(*result[0], tensor4(&[[[[1f32], [3.]], [[4.], [10.]]]]));
+}
+
+#[test]
+fn conv3d() {
+    let x = tract_ndarray::Array::range(1f32, 9., 1.).into_shape((1, 2, 2, 2, 1)).unwrap();
+    let x = x.into_tensor();
+    let graph = tfpb::graph()
+        .node(input("x", &x))
+        .node(konst("filter", Tensor::from(tract_ndarray::Array5::<f32>::ones((2, 2, 2, 1, 1)))))
+        .node(
+            node()
+                .name("conv")
+                .op("Conv3D")
+                .input("x")
+                .input("filter")
+                .attr("T", DataType::DtFloat)
+                .attr("padding", "VALID")
+                .attr("strides", vec![1i64, 1, 1, 1, 1])
+                .attr("data_format", "NDHWC"),
+        );
+    let result = run(graph, tvec!(x), &["conv"]);
+    assert_eq!(result[0].shape(), &[1, 1, 1, 1, 1]);
+    assert_eq!(result[0].as_slice::<f32>().unwrap(), &[36f32]);
+}
+
+#[test]
+fn resize_bilinear() {
+    let x = tensor4(&[[[[1f32], [2.]], [[3.], [4.]]]]);
+    let resize = |name: &str, size: &str, align_corners: bool| {
+        node()
+            .name(name)
+            .op("ResizeBilinear")
+            .input("x")
+            .input(size)
+            .attr("align_corners", align_corners)
+            .attr("half_pixel_centers", false)
+    };
+    let graph = tfpb::graph()
+        .node(input("x", &x))
+        .node(konst("size3", tensor1(&[3i32, 3])))
+        .node(konst("size4", tensor1(&[4i32, 4])))
+        .node(resize("aligned", "size3", true))
+        .node(resize("default", "size4", false));
+    let result = run(graph, tvec!(x), &["aligned", "default"]);
+    let aligned = tract_ndarray::arr2(&[[1f32, 1.5, 2.], [2., 2.5, 3.], [3., 3.5, 4.]]);
+    assert_eq!(*result[0], aligned.into_shape((1, 3, 3, 1)).unwrap().into_tensor());
+    let default = tract_ndarray::arr2(&[
+        [1f32, 1.5, 2., 2.],
+        [2., 2.5, 3., 3.],
+        [3., 3.5, 4., 4.],
+        [3., 3.5, 4., 4.],
+    ]);
+    assert_eq!(*result[1], default.into_shape((1, 4, 4, 1)).unwrap().into_tensor());
+}
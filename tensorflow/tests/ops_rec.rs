@@ -0,0 +1,310 @@
+extern crate tract_tensorflow;
+
+use std::convert::TryFrom;
+use tract_tensorflow::prelude::*;
+use tract_tensorflow::tfpb::tensorflow::*;
+use tract_tensorflow::tfpb::{self, node};
+
+fn konst(name: &str, t: Tensor) -> NodeDef {
+    let proto = TensorProto::try_from(&t).unwrap();
+    node().name(name).op("Const").attr("dtype", proto.dtype()).attr("value", proto)
+}
+
+fn input(name: &str, t: &Tensor) -> NodeDef {
+    let shape = TensorShapeProto {
+        dim: t
+            .shape()
+            .iter()
+            .map(|&d| tensor_shape_proto::Dim { size: d as i64, name: String::new() })
+            .collect(),
+        unknown_rank: false,
+    };
+    node().name(name).op("Placeholder").attr("dtype", DataType::DtFloat).attr("shape", shape)
+}
+
+/// Runs the optimized model, outputs are given as "node:slot".
+fn run(graph: GraphDef, inputs: TVec<Tensor>, outputs: &[&str]) -> TVec<Arc<Tensor>> {
+    let mut model = tensorflow().model_for_proto_model(&graph).unwrap();
+    let outlets = outputs
+        .iter()
+        .map(|o| {
+            let mut splits = o.split(':');
+            let node = model.node_by_name(splits.next().unwrap()).unwrap().id;
+            OutletId::new(node, splits.next().unwrap().parse().unwrap())
+        })
+        .collect::<Vec<_>>();
+    model.set_output_outlets(&outlets).unwrap();
+    let model = model.into_optimized().unwrap();
+    SimplePlan::new(&model).unwrap().run(inputs).unwrap()
+}
+
+fn sigmoid(x: f32) -> f32 {
+    1. / (1. + (-x).exp())
+}
+
+fn assert_close(found: &Tensor, expected: &[f32]) {
+    let found = found.as_slice::<f32>().unwrap();
+    assert_eq!(found.len(), expected.len());
+    for (f, e) in found.iter().zip(expected) {
+        assert!((f - e).abs() < 1e-5, "found {:?}, expected {:?}", found, expected);
+    }
+}
+
+/// Scalar TF LSTM step, with w = [x weights ; h weights] and gates in the
+/// given (i, ci, f, o) positions.
+#[allow(clippy::too_many_arguments)]
+fn lstm_step(
+    x: f32,
+    cs_prev: f32,
+    h_prev: f32,
+    w: &[f32; 8],
+    b: &[f32; 4],
+    (ii, ci, fi, oi): (usize, usize, usize, usize),
+    forget_bias: f32,
+    cell_clip: f32,
+    peep: [f32; 3],
+) -> (f32, f32) {
+    let g = |k: usize| x * w[k] + h_prev * w[4 + k] + b[k];
+    let i = sigmoid(g(ii) + cs_prev * peep[0]);
+    let f = sigmoid(g(fi) + cs_prev * peep[1] + forget_bias);
+    let mut cs = g(ci).tanh() * i + cs_prev * f;
+    if cell_clip > 0. {
+        cs = cs.max(-cell_clip).min(cell_clip);
+    }
+    let o = sigmoid(g(oi) + cs * peep[2]);
+    (cs, cs.tanh() * o)
+}
+
+const W: [f32; 8] = [0.5, -0.3, 0.8, 0.1, 0.2, 0.4, -0.6, 0.7];
+const B: [f32; 4] = [0.1, -0.2, 0.3, 0.05];
+
+#[test]
+fn lstm_block_cell_with_peephole_and_clip() {
+    let x = tensor2(&[[0.5f32]]);
+    let graph = tfpb::graph()
+        .node(input("x", &x))
+        .node(konst("cs_prev", tensor2(&[[2f32]])))
+        .node(konst("h_prev", tensor2(&[[0.1f32]])))
+        .node(konst("w", tensor1(&W).into_shape(&[2, 4]).unwrap()))
+        .node(konst("wci", tensor1(&[0.3f32])))
+        .node(konst("wcf", tensor1(&[-0.2f32])))
+        .node(konst("wco", tensor1(&[0.4f32])))
+        .node(konst("b", tensor1(&B)))
+        .node(
+            node()
+                .name("cell")
+                .op("LSTMBlockCell")
+                .input("x")
+                .input("cs_prev")
+                .input("h_prev")
+                .input("w")
+                .input("wci")
+                .input("wcf")
+                .input("wco")
+                .input("b")
+                .attr("forget_bias", 1f32)
+                .attr("cell_clip", 0.5f32)
+                .attr("use_peephole", true),
+        );
+    let result = run(graph, tvec!(x), &["cell:1", "cell:6"]);
+    let (cs, h) = lstm_step(0.5, 2., 0.1, &W, &B, (0, 1, 2, 3), 1., 0.5, [0.3, -0.2, 0.4]);
+    assert_eq!(cs, 0.5);
+    assert_close(&result[0], &[cs]);
+    assert_close(&result[1], &[h]);
+}
+
+fn block_lstm(op: &str) -> GraphDef {
+    tfpb::graph()
+        .node(input("x", &Tensor::zero::<f32>(&[3, 1, 1]).unwrap()))
+        .node(konst("seq_len_max", tensor0(3i64)))
+        .node(konst("cs_prev", tensor2(&[[0.2f32]])))
+        .node(konst("h_prev", tensor2(&[[-0.1f32]])))
+        .node(konst("w", tensor1(&W).into_shape(&[2, 4]).unwrap()))
+        .node(konst("wci", tensor1(&[0f32])))
+        .node(konst("wcf", tensor1(&[0f32])))
+        .node(konst("wco", tensor1(&[0f32])))
+        .node(konst("b", tensor1(&B)))
+        .node(
+            node()
+                .name("lstm")
+                .op(op)
+                .input("seq_len_max")
+                .input("x")
+                .input("cs_prev")
+                .input("h_prev")
+                .input("w")
+                .input("wci")
+                .input("wcf")
+                .input("wco")
+                .input("b")
+                .attr("T", DataType::DtFloat),
+        )
+}
+
+#[test]
+fn block_lstm_v1_and_v2() {
+    let xs = [0.5f32, -1., 2.];
+    let x = tensor1(&xs).into_shape(&[3, 1, 1]).unwrap();
+    for (op, layout, forget_bias, clip) in
+        &[("BlockLSTM", (0, 1, 2, 3), 1f32, 3f32), ("BlockLSTMV2", (0, 2, 1, 3), 0., 0.)]
+    {
+        let result = run(block_lstm(op), tvec!(x.clone()), &["lstm:1", "lstm:6"]);
+        let (mut cs, mut h) = (0.2, -0.1);
+        let (mut all_cs, mut all_h) = (vec![], vec![]);
+        for x in &xs {
+            let (cs_, h_) = lstm_step(*x, cs, h, &W, &B, *layout, *forget_bias, *clip, [0.; 3]);
+            cs = cs_;
+            h = h_;
+            all_cs.push(cs);
+            all_h.push(h);
+        }
+        assert_close(&result[0], &all_cs);
+        assert_close(&result[1], &all_h);
+    }
+}
+
+#[test]
+fn gru_block_cell() {
+    let x = tensor2(&[[0.5f32]]);
+    let w_ru = [0.3f32, -0.5, 0.7, 0.2];
+    let w_c = [0.6f32, -0.4];
+    let graph = tfpb::graph()
+        .node(input("x", &x))
+        .node(konst("h_prev", tensor2(&[[0.3f32]])))
+        .node(konst("w_ru", tensor1(&w_ru).into_shape(&[2, 2]).unwrap()))
+        .node(konst("w_c", tensor1(&w_c).into_shape(&[2, 1]).unwrap()))
+        .node(konst("b_ru", tensor1(&[0.1f32, -0.1])))
+        .node(konst("b_c", tensor1(&[0.2f32])))
+        .node(
+            node()
+                .name("cell")
+                .op("GRUBlockCell")
+                .input("x")
+                .input("h_prev")
+                .input("w_ru")
+                .input("w_c")
+                .input("b_ru")
+                .input("b_c")
+                .attr("T", DataType::DtFloat),
+        );
+    let result = run(graph, tvec!(x), &["cell:0", "cell:1", "cell:2", "cell:3"]);
+    let (x, h_prev) = (0.5f32, 0.3f32);
+    let r = sigmoid(x * w_ru[0] + h_prev * w_ru[2] + 0.1);
+    let u = sigmoid(x * w_ru[1] + h_prev * w_ru[3] - 0.1);
+    let c = (x * w_c[0] + r * h_prev * w_c[1] + 0.2).tanh();
+    let h = u * h_prev + (1. - u) * c;
+    assert_close(&result[0], &[r]);
+    assert_close(&result[1], &[u]);
+    assert_close(&result[2], &[c]);
+    assert_close(&result[3], &[h]);
+}
+
+fn cudnn_rnn(mode: &str, direction: &str, dirs: usize, params: &[f32]) -> GraphDef {
+    cudnn_rnn_op("CudnnRNN", mode, direction, dirs, params)
+}
+
+fn cudnn_rnn_op(op: &str, mode: &str, direction: &str, dirs: usize, params: &[f32]) -> GraphDef {
+    tfpb::graph()
+        .node(input("x", &Tensor::zero::<f32>(&[3, 1, 1]).unwrap()))
+        .node(konst("input_h", tensor1(&vec![0.2f32; dirs]).into_shape(&[dirs, 1, 1]).unwrap()))
+        .node(konst("input_c", tensor1(&vec![-0.3f32; dirs]).into_shape(&[dirs, 1, 1]).unwrap()))
+        .node(konst("params", tensor1(params)))
+        .node(
+            node()
+                .name("rnn")
+                .op(op)
+                .input("x")
+                .input("input_h")
+                .input("input_c")
+                .input("params")
+                .attr("T", DataType::DtFloat)
+                .attr("rnn_mode", mode)
+                .attr("direction", direction),
+        )
+}
+
+const XS: [f32; 3] = [0.5, -1., 2.];
+
+fn x() -> Tensor {
+    tensor1(&XS).into_shape(&[3, 1, 1]).unwrap()
+}
+
+#[test]
+fn cudnn_lstm() {
+    // W (i, f, c, o), R (i, f, c, o), bW, bR
+    let p: Vec<f32> = (0..16).map(|i| ((i * 7 % 11) as f32 - 5.) / 10.).collect();
+    let result =
+        run(cudnn_rnn("lstm", "unidirectional", 1, &p), tvec!(x()), &["rnn:0", "rnn:1", "rnn:2"]);
+    let (mut h, mut c) = (0.2f32, -0.3f32);
+    let mut ys = vec![];
+    for x in &XS {
+        let g = |k: usize| p[k] * x + p[4 + k] * h + p[8 + k] + p[12 + k];
+        c = sigmoid(g(1)) * c + sigmoid(g(0)) * g(2).tanh();
+        h = sigmoid(g(3)) * c.tanh();
+        ys.push(h);
+    }
+    assert_close(&result[0], &ys);
+    assert_close(&result[1], &[h]);
+    assert_close(&result[2], &[c]);
+}
+
+#[test]
+fn cudnn_gru() {
+    // W (r, z, n), R (r, z, n), bW, bR
+    let p: Vec<f32> = (0..12).map(|i| ((i * 5 % 7) as f32 - 3.) / 5.).collect();
+    let result =
+        run(cudnn_rnn("gru", "unidirectional", 1, &p), tvec!(x()), &["rnn:0", "rnn:1", "rnn:2"]);
+    let mut h = 0.2f32;
+    let mut ys = vec![];
+    for x in &XS {
+        let gx = |k: usize| p[k] * x + p[6 + k];
+        let gh = |k: usize| p[3 + k] * h + p[9 + k];
+        let r = sigmoid(gx(0) + gh(0));
+        let z = sigmoid(gx(1) + gh(1));
+        let n = (gx(2) + r * gh(2)).tanh();
+        h = (1. - z) * n + z * h;
+        ys.push(h);
+    }
+    assert_close(&result[0], &ys);
+    assert_close(&result[1], &[h]);
+    assert_close(&result[2], &[-0.3]);
+}
+
+#[test]
+fn cudnn_tanh_bidirectional() {
+    // weights: forward W, R, backward W, R, then biases: forward bW, bR,
+    // backward bW, bR
+    let p = [0.5f32, -0.4, 0.3, 0.8, 0.1, 0.05, -0.2, 0.1];
+    let result =
+        run(cudnn_rnn("rnn_tanh", "bidirectional", 2, &p), tvec!(x()), &["rnn:0", "rnn:1"]);
+    let step = |x: f32, h: f32, d: usize| {
+        (p[2 * d] * x + p[2 * d + 1] * h + p[4 + 2 * d] + p[5 + 2 * d]).tanh()
+    };
+    let mut fw = vec![];
+    let mut h = 0.2;
+    for x in &XS {
+        h = step(*x, h, 0);
+        fw.push(h);
+    }
+    let mut bw = vec![0f32; 3];
+    let mut h = 0.2;
+    for t in (0..3).rev() {
+        h = step(XS[t], h, 1);
+        bw[t] = h;
+    }
+    let ys: Vec<f32> = (0..3).flat_map(|t| vec![fw[t], bw[t]]).collect();
+    assert_close(&result[0], &ys);
+    assert_close(&result[1], &[fw[2], bw[0]]);
+}
+
+#[test]
+fn cudnn_reserved_outputs_are_empty() {
+    let p = [0.5f32, -0.4, 0.3, 0.8];
+    let result = run(cudnn_rnn("rnn_tanh", "unidirectional", 1, &p), tvec!(x()), &["rnn:3"]);
+    assert_eq!(result[0].shape(), &[0]);
+    let graph = cudnn_rnn_op("CudnnRNNV2", "rnn_tanh", "unidirectional", 1, &p);
+    let result = run(graph, tvec!(x()), &["rnn:0", "rnn:3", "rnn:4"]);
+    assert_eq!(result[0].shape(), &[3, 1, 1]);
+    assert_eq!(result[1].shape(), &[0]);
+    assert_eq!(result[2].shape(), &[0]);
+}
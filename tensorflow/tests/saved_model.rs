@@ -0,0 +1,290 @@
+extern crate tract_tensorflow;
+
+use prost::Message;
+use std::convert::TryFrom;
+use std::path::{Path, PathBuf};
+use tract_tensorflow::prelude::*;
+use tract_tensorflow::saved_model;
+use tract_tensorflow::tfpb::tensorflow::meta_graph_def::MetaInfoDef;
+use tract_tensorflow::tfpb::tensorflow::tensor_info::Encoding;
+use tract_tensorflow::tfpb::tensorflow::trackable_object_graph::trackable_object::SerializedTensor;
+use tract_tensorflow::tfpb::tensorflow::trackable_object_graph::TrackableObject;
+use tract_tensorflow::tfpb::tensorflow::*;
+use tract_tensorflow::tfpb::{self, node};
+
+fn encode(message: &impl Message) -> Vec<u8> {
+    let mut buf = vec![];
+    message.encode(&mut buf).unwrap();
+    buf
+}
+
+fn varint(buf: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 {
+        buf.push((v as u8) | 0x80);
+        v >>= 7;
+    }
+    buf.push(v as u8);
+}
+
+/// Single restart point, no prefix compression, no checksum.
+fn block(table: &mut Vec<u8>, entries: &[(Vec<u8>, Vec<u8>)]) -> Vec<u8> {
+    let offset = table.len();
+    for (k, v) in entries {
+        varint(table, 0);
+        varint(table, k.len() as u64);
+        varint(table, v.len() as u64);
+        table.extend_from_slice(k);
+        table.extend_from_slice(v);
+    }
+    table.extend_from_slice(&0u32.to_le_bytes());
+    table.extend_from_slice(&1u32.to_le_bytes());
+    let mut handle = vec![];
+    varint(&mut handle, offset as u64);
+    varint(&mut handle, (table.len() - offset) as u64);
+    table.extend_from_slice(&[0; 5]);
+    handle
+}
+
+fn write_bundle(prefix: &Path, tensors: &[(&str, DataType, &[usize], Vec<u8>)]) {
+    let mut data = vec![];
+    let mut entries = vec![];
+    let header = BundleHeaderProto { num_shards: 1, endianness: 0, version: None };
+    entries.push((vec![], encode(&header)));
+    let mut tensors = tensors.to_vec();
+    tensors.sort_by_key(|t| t.0);
+    for (name, dt, shape, bytes) in tensors {
+        let dims = shape.iter().map(|&d| d as i64).collect::<Vec<_>>();
+        let entry = BundleEntryProto {
+            dtype: dt.into(),
+            shape: Some(self::shape(&dims)),
+            shard_id: 0,
+            offset: data.len() as i64,
+            size: bytes.len() as i64,
+            crc32c: 0,
+            slices: vec![],
+        };
+        data.extend_from_slice(&bytes);
+        entries.push((name.as_bytes().to_vec(), encode(&entry)));
+    }
+    let mut table = vec![];
+    let last_key = entries.last().unwrap().0.clone();
+    let data_handle = block(&mut table, &entries);
+    let metaindex = block(&mut table, &[]);
+    let index = block(&mut table, &[(last_key, data_handle)]);
+    let mut footer = [metaindex, index].concat();
+    footer.resize(40, 0);
+    footer.extend_from_slice(&0xdb4775248b80fb57u64.to_le_bytes());
+    table.extend_from_slice(&footer);
+    let prefix = prefix.to_string_lossy();
+    std::fs::write(format!("{}.index", prefix), table).unwrap();
+    std::fs::write(format!("{}.data-00000-of-00001", prefix), data).unwrap();
+}
+
+fn f32_bytes(values: &[f32]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_le_bytes().to_vec()).collect()
+}
+
+fn tensor_info(name: &str) -> TensorInfo {
+    TensorInfo { dtype: 0, tensor_shape: None, encoding: Some(Encoding::Name(name.to_string())) }
+}
+
+/// (key, inputs, outputs), with (signature key, tensor name) pairs.
+type SignatureSpec<'a> = (&'a str, &'a [(&'a str, &'a str)], &'a [(&'a str, &'a str)]);
+
+fn write_saved_model(
+    name: &str,
+    graph: GraphDef,
+    signatures: &[SignatureSpec],
+    variables: &[(&str, DataType, &[usize], Vec<u8>)],
+) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("tract-saved-model-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("variables")).unwrap();
+    let signature_def = signatures
+        .iter()
+        .map(|(key, inputs, outputs)| {
+            let map = |tensors: &[(&str, &str)]| {
+                tensors.iter().map(|(k, t)| (k.to_string(), tensor_info(t))).collect()
+            };
+            let def = SignatureDef {
+                inputs: map(inputs),
+                outputs: map(outputs),
+                method_name: "tensorflow/serving/predict".to_string(),
+            };
+            (key.to_string(), def)
+        })
+        .collect();
+    let meta = MetaGraphDef {
+        meta_info_def: Some(MetaInfoDef { tags: vec!["serve".to_string()], ..Default::default() }),
+        graph_def: Some(graph),
+        signature_def,
+        ..Default::default()
+    };
+    let saved = SavedModel { saved_model_schema_version: 1, meta_graphs: vec![meta] };
+    std::fs::write(dir.join("saved_model.pb"), encode(&saved)).unwrap();
+    write_bundle(&dir.join("variables").join("variables"), variables);
+    dir
+}
+
+fn shape(dims: &[i64]) -> TensorShapeProto {
+    TensorShapeProto {
+        dim: dims
+            .iter()
+            .map(|&d| tensor_shape_proto::Dim { size: d, name: String::new() })
+            .collect(),
+        unknown_rank: false,
+    }
+}
+
+fn placeholder(name: &str, dims: &[i64]) -> NodeDef {
+    node().name(name).op("Placeholder").attr("dtype", DataType::DtFloat).attr("shape", shape(dims))
+}
+
+fn const_f32(name: &str, t: Tensor) -> NodeDef {
+    node()
+        .name(name)
+        .op("Const")
+        .attr("dtype", DataType::DtFloat)
+        .attr("value", TensorProto::try_from(&t).unwrap())
+}
+
+/// TF1 style: a VariableV2 with an Assign initializer, and a Saver key
+/// matching the variable name.
+fn v1_graph() -> GraphDef {
+    tfpb::graph()
+        .node(placeholder("x", &[2, 2]))
+        .node(
+            node()
+                .name("w")
+                .op("VariableV2")
+                .attr("dtype", DataType::DtFloat)
+                .attr("shape", Vec::<i64>::new())
+                .attr("container", "")
+                .attr("shared_name", ""),
+        )
+        .node(node().name("w/read").op("Identity").input("w").attr("T", DataType::DtFloat))
+        .node(const_f32("w/init", tensor2(&[[0f32], [0.]])))
+        .node(node().name("w/Assign").op("Assign").input("w").input("w/init"))
+        .node(node().name("init").op("NoOp").input("^w/Assign"))
+        .node(
+            node()
+                .name("y")
+                .op("MatMul")
+                .input("x")
+                .input("w/read")
+                .attr("T", DataType::DtFloat)
+                .attr("transpose_a", false)
+                .attr("transpose_b", false),
+        )
+        .node(const_f32("two", tensor0(2f32)))
+        .node(node().name("z").op("Mul").input("y").input("two").attr("T", DataType::DtFloat))
+}
+
+#[test]
+fn saved_model_v1_variables() -> TractResult<()> {
+    let dir = write_saved_model(
+        "v1",
+        v1_graph(),
+        &[
+            ("serving_default", &[("input", "x:0")], &[("output", "y:0")]),
+            ("doubled", &[("input", "x")], &[("doubled", "z:0"), ("output", "y:0")]),
+        ],
+        &[("w", DataType::DtFloat, &[2, 1], f32_bytes(&[1., 2.]))],
+    );
+    let tf = tensorflow();
+    let saved = tf.read_saved_model_dir(&dir, None)?;
+    assert!(saved.graph.node.iter().all(|n| n.name != "w/Assign" && n.name != "z"));
+    let model = tf.model_for_path(&dir)?.into_optimized()?;
+    let result = model.into_runnable()?.run(tvec!(tensor2(&[[1f32, 1.], [3., -1.]])))?;
+    assert_eq!(*result[0], tensor2(&[[3f32], [1.]]));
+
+    let saved = tf.read_saved_model_dir(&dir, Some("doubled"))?;
+    let model = tf.parse_saved_model(&saved)?.0.into_optimized()?;
+    assert_eq!(model.output_outlets()?.len(), 2);
+    let result = model.into_runnable()?.run(tvec!(tensor2(&[[1f32, 1.], [3., -1.]])))?;
+    assert_eq!(*result[0], tensor2(&[[6f32], [2.]]));
+    assert_eq!(*result[1], tensor2(&[[3f32], [1.]]));
+
+    let err = tf.read_saved_model_dir(&dir, Some("missing")).unwrap_err();
+    assert!(format!("{:?}", err).contains("doubled"));
+    std::fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
+/// TF2 style: resource variables, and object-based checkpoint keys.
+#[test]
+fn saved_model_v2_resource_variables() -> TractResult<()> {
+    let graph = tfpb::graph()
+        .node(placeholder("x", &[3]))
+        .node(
+            node()
+                .name("dense/kernel")
+                .op("VarHandleOp")
+                .attr("dtype", DataType::DtFloat)
+                .attr("shape", vec![3i64])
+                .attr("shared_name", "dense/kernel"),
+        )
+        .node(
+            node()
+                .name("ReadVariableOp")
+                .op("ReadVariableOp")
+                .input("dense/kernel")
+                .attr("dtype", DataType::DtFloat),
+        )
+        .node(const_f32("init_value", tensor1(&[0f32, 0., 0.])))
+        .node(
+            node()
+                .name("AssignVariableOp")
+                .op("AssignVariableOp")
+                .input("dense/kernel")
+                .input("init_value"),
+        )
+        .node(node().name("IsInitialized").op("VarIsInitializedOp").input("dense/kernel"))
+        .node(
+            node()
+                .name("add")
+                .op("AddV2")
+                .input("x")
+                .input("ReadVariableOp")
+                .attr("T", DataType::DtFloat),
+        );
+    let object_graph = encode(&TrackableObjectGraph {
+        nodes: vec![TrackableObject {
+            attributes: vec![SerializedTensor {
+                name: "VARIABLE_VALUE".to_string(),
+                full_name: "dense/kernel".to_string(),
+                checkpoint_key: "layer/kernel/.ATTRIBUTES/VARIABLE_VALUE".to_string(),
+                optional_restore: false,
+            }],
+            ..Default::default()
+        }],
+    });
+    // string tensor: lengths, lengths checksum, bytes
+    let mut blob = vec![];
+    varint(&mut blob, object_graph.len() as u64);
+    blob.extend_from_slice(&[0; 4]);
+    blob.extend_from_slice(&object_graph);
+    let dir = write_saved_model(
+        "v2",
+        graph,
+        &[("serving_default", &[("x", "x:0")], &[("output_0", "add:0")])],
+        &[
+            (
+                "layer/kernel/.ATTRIBUTES/VARIABLE_VALUE",
+                DataType::DtFloat,
+                &[3],
+                f32_bytes(&[1., 2., 3.]),
+            ),
+            ("_CHECKPOINTABLE_OBJECT_GRAPH", DataType::DtString, &[], blob),
+        ],
+    );
+    let variables = saved_model::read_tensor_bundle(dir.join("variables").join("variables"))?;
+    assert_eq!(variables.keys().collect::<Vec<_>>(), vec!["dense/kernel"]);
+
+    let model = tensorflow().model_for_path(&dir)?.into_optimized()?;
+    let result = model.into_runnable()?.run(tvec!(tensor1(&[1f32, 1., 1.])))?;
+    assert_eq!(*result[0], tensor1(&[2f32, 3., 4.]));
+    std::fs::remove_dir_all(dir)?;
+    Ok(())
+}
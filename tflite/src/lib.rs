@@ -0,0 +1,44 @@
+//! # Tract TensorFlow Lite module
+//!
+//! Tiny, no-nonsense, self contained, portable inference.
+//!
+//! Loads TensorFlow Lite flatbuffer models, including the ones quantized with
+//! per-tensor or per-channel parameters. TensorFlow Lite models have static
+//! shapes, so they are translated straight to a TypedModel.
+//!
+//! ## Example
+//!
+//! ```no_run
+//! # extern crate tract_tflite;
+//! # fn main() {
+//! use tract_tflite::prelude::*;
+//!
+//! let model = tflite().model_for_path("mobilenet_v2.tflite").unwrap();
+//! let plan = SimplePlan::new(model.into_optimized().unwrap()).unwrap();
+//! # }
+//! ```
+
+#[allow(unused_imports)]
+#[macro_use]
+extern crate log;
+pub extern crate tract_hir;
+
+pub mod model;
+pub mod ops;
+pub mod schema;
+pub mod tensors;
+
+pub use model::Tflite;
+
+pub fn tflite() -> Tflite {
+    let mut ops = crate::model::TfliteOpRegister::default();
+    ops::register_all_ops(&mut ops);
+    Tflite { op_register: ops }
+}
+
+pub use tract_hir::tract_core;
+pub mod prelude {
+    pub use crate::tflite;
+    pub use tract_hir::prelude::*;
+    pub use tract_hir::tract_core;
+}
@@ -0,0 +1,217 @@
+use crate::schema;
+use crate::tensors::{self, QuantInfo};
+use flatbuffers::{ForwardsUOffset, Vector};
+use std::fmt;
+use tract_hir::internal::*;
+
+pub type OpBuilder = fn(&mut DeserOp) -> TractResult<TVec<OutletId>>;
+
+#[derive(Clone, Default)]
+pub struct TfliteOpRegister(pub HashMap<i32, OpBuilder>);
+
+impl TfliteOpRegister {
+    pub fn insert(&mut self, code: i32, builder: OpBuilder) {
+        self.0.insert(code, builder);
+    }
+}
+
+pub struct Tflite {
+    pub op_register: TfliteOpRegister,
+}
+
+/// A TFLite flatbuffer, verified at construction time.
+pub struct TfliteProtoModel(Vec<u8>);
+
+impl fmt::Debug for TfliteProtoModel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TfliteProtoModel ({} bytes)", self.0.len())
+    }
+}
+
+impl TfliteProtoModel {
+    pub fn new(buffer: Vec<u8>) -> TractResult<TfliteProtoModel> {
+        if !flatbuffers::buffer_has_identifier(&buffer, "TFL3", false) {
+            bail!("Not a TFLite model (missing TFL3 file identifier)")
+        }
+        flatbuffers::root::<schema::Model>(&buffer)
+            .map_err(|e| format_err!("Invalid TFLite flatbuffer: {}", e))?;
+        Ok(TfliteProtoModel(buffer))
+    }
+
+    pub fn root(&self) -> schema::Model<'_> {
+        // verified in new()
+        unsafe { flatbuffers::root_unchecked::<schema::Model>(&self.0) }
+    }
+}
+
+/// An operator being translated, along with the model being built.
+pub struct DeserOp<'m, 'f> {
+    pub model: &'m mut TypedModel,
+    pub prefix: String,
+    pub flat: schema::Operator<'f>,
+    pub tensors: Vector<'f, ForwardsUOffset<schema::Tensor<'f>>>,
+    /// Wires of the operator inputs. Omitted optional inputs are skipped.
+    pub inputs: TVec<OutletId>,
+    input_ids: TVec<usize>,
+}
+
+impl<'m, 'f> DeserOp<'m, 'f> {
+    pub fn input(&self, ix: usize) -> TractResult<schema::Tensor<'f>> {
+        let id = self.input_ids.get(ix).with_context(|| format!("Missing input #{}", ix))?;
+        Ok(self.tensors.get(*id))
+    }
+
+    pub fn output(&self, ix: usize) -> TractResult<schema::Tensor<'f>> {
+        let id = self
+            .flat
+            .outputs()
+            .and_then(|o| if ix < o.len() { Some(o.get(ix)) } else { None })
+            .with_context(|| format!("Missing output #{}", ix))?;
+        Ok(self.tensors.get(id as usize))
+    }
+
+    pub fn input_fact(&self, ix: usize) -> TractResult<&TypedFact> {
+        self.model.outlet_fact(self.inputs[ix])
+    }
+
+    /// Value of a constant input, like weights or shapes.
+    pub fn konst(&self, ix: usize) -> TractResult<Arc<Tensor>> {
+        self.input_fact(ix)?
+            .konst
+            .clone()
+            .with_context(|| format!("Input #{} of {} must be a constant", ix, self.prefix))
+    }
+
+    pub fn input_quant(&self, ix: usize) -> TractResult<Option<QuantInfo>> {
+        tensors::quantization(&self.input(ix)?)
+    }
+
+    pub fn output_quant(&self, ix: usize) -> TractResult<Option<QuantInfo>> {
+        tensors::quantization(&self.output(ix)?)
+    }
+
+    pub fn output_datum_type(&self, ix: usize) -> TractResult<DatumType> {
+        tensors::datum_type(self.output(ix)?.type_().unwrap_or(0))
+    }
+
+    pub fn options<T: schema::Follow<'f, Inner = T> + 'f>(&self, kind: u8) -> TractResult<T> {
+        self.flat
+            .builtin_options(kind)
+            .with_context(|| format!("Missing or unexpected options for {}", self.prefix))
+    }
+
+    pub fn wire_node(
+        &mut self,
+        suffix: &str,
+        op: impl Into<Box<dyn TypedOp>>,
+        inputs: &[OutletId],
+    ) -> TractResult<OutletId> {
+        Ok(self.model.wire_node(format!("{}.{}", self.prefix, suffix), op, inputs)?[0])
+    }
+}
+
+impl Framework<TfliteProtoModel, TypedModel> for Tflite {
+    fn proto_model_for_read(&self, r: &mut dyn std::io::Read) -> TractResult<TfliteProtoModel> {
+        let mut buffer = vec![];
+        r.read_to_end(&mut buffer)?;
+        TfliteProtoModel::new(buffer)
+    }
+
+    fn model_for_proto_model(&self, proto: &TfliteProtoModel) -> TractResult<TypedModel> {
+        let root = proto.root();
+        let codes = root.operator_codes().context("Model has no operator codes")?;
+        let buffers = root.buffers().context("Model has no buffers")?;
+        let graph =
+            root.subgraphs().filter(|s| !s.is_empty()).context("Model has no subgraph")?.get(0);
+        let tensors = graph.tensors().context("Subgraph has no tensors")?;
+        let name = |id: usize| {
+            tensors
+                .get(id)
+                .name()
+                .map(|n| n.to_string())
+                .unwrap_or_else(|| format!("tensor_{}", id))
+        };
+
+        let mut model = TypedModel::default();
+        let mut wires: HashMap<usize, OutletId> = HashMap::default();
+        for id in graph.inputs().iter().flat_map(|i| i.iter()) {
+            let id = id as usize;
+            let fact = tensors::fact(&tensors.get(id))?;
+            wires.insert(id, model.add_source(name(id), fact)?);
+        }
+        for (id, tensor) in tensors.iter().enumerate() {
+            if wires.contains_key(&id) {
+                continue;
+            }
+            let buffer = tensor.buffer().unwrap_or(0) as usize;
+            if buffer >= buffers.len() {
+                bail!(
+                    "Tensor {} refers to buffer {}, model has {}",
+                    name(id),
+                    buffer,
+                    buffers.len()
+                )
+            }
+            if let Some(data) = buffers.get(buffer).data().filter(|d| !d.is_empty()) {
+                let value = tensors::tensor(&tensor, data.bytes())
+                    .with_context(|| format!("Loading tensor {}", name(id)))?;
+                wires.insert(id, model.add_const(name(id), value)?);
+            }
+        }
+
+        for op in graph.operators().iter().flat_map(|ops| ops.iter()) {
+            let outputs: TVec<usize> =
+                op.outputs().iter().flat_map(|o| o.iter()).map(|o| o as usize).collect();
+            let prefix = name(*outputs.first().context("Operator without outputs")?);
+            let opcode = op.opcode_index() as usize;
+            if opcode >= codes.len() {
+                bail!("Operator {} has opcode {}, model has {}", prefix, opcode, codes.len())
+            }
+            let code = codes.get(opcode).code();
+            let builder =
+                self.op_register.0.get(&code).with_context(|| {
+                    format!("Unsupported TFLite operator {} ({})", code, prefix)
+                })?;
+            let input_ids: TVec<usize> = op
+                .inputs()
+                .iter()
+                .flat_map(|i| i.iter())
+                .filter(|&i| i >= 0)
+                .map(|i| i as usize)
+                .collect();
+            let inputs = input_ids
+                .iter()
+                .map(|i| wires.get(i).copied().with_context(|| format!("Undefined {}", name(*i))))
+                .collect::<TractResult<TVec<OutletId>>>()?;
+            let mut deser =
+                DeserOp { model: &mut model, prefix, flat: op, tensors, inputs, input_ids };
+            let results = (builder)(&mut deser)
+                .with_context(|| format!("Translating TFLite operator {}", deser.prefix))?;
+            if results.len() != outputs.len() {
+                bail!(
+                    "{} built {} outputs, expected {}",
+                    deser.prefix,
+                    results.len(),
+                    outputs.len()
+                )
+            }
+            for (result, id) in results.into_iter().zip(outputs) {
+                let expected = tensors::fact(&tensors.get(id))?;
+                let found = model.outlet_fact(result)?;
+                if found.datum_type != expected.datum_type || found.shape != expected.shape {
+                    bail!("{} should be {:?}, got {:?}", name(id), expected, found)
+                }
+                wires.insert(id, result);
+            }
+        }
+
+        let outputs = graph
+            .outputs()
+            .iter()
+            .flat_map(|o| o.iter())
+            .map(|o| wires.get(&(o as usize)).copied().with_context(|| format!("Undefined {}", o)))
+            .collect::<TractResult<TVec<OutletId>>>()?;
+        model.set_output_outlets(&outputs)?;
+        Ok(model)
+    }
+}
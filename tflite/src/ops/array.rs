@@ -0,0 +1,124 @@
+use crate::model::{DeserOp, TfliteOpRegister};
+use crate::schema::{activation, builtin_operator, builtin_options};
+use crate::schema::{ConcatenationOptions, ReducerOptions};
+use crate::tensors;
+use tract_hir::internal::*;
+use tract_hir::ops;
+use tract_hir::tract_core::ops::array::{Pad, PadMode, TypedConcat};
+use tract_hir::tract_core::ops::nn::{Reduce, Reducer};
+
+use super::quant::{dequantized_input, quantized_output};
+use super::wire_fused_activation;
+
+pub fn register_all_ops(reg: &mut TfliteOpRegister) {
+    reg.insert(builtin_operator::CONCATENATION, concatenation);
+    reg.insert(builtin_operator::MEAN, mean);
+    reg.insert(builtin_operator::PAD, pad);
+    reg.insert(builtin_operator::RESHAPE, reshape);
+    reg.insert(builtin_operator::SQUEEZE, reshape);
+    reg.insert(builtin_operator::TRANSPOSE, transpose);
+}
+
+pub fn wire_reshape(
+    op: &mut DeserOp,
+    name: &str,
+    wire: OutletId,
+    shape: &[usize],
+) -> TractResult<OutletId> {
+    let from = op.model.outlet_fact(wire)?.shape.to_tvec();
+    let to = shape.iter().map(|d| d.to_dim()).collect();
+    op.wire_node(name, AxisOp::Reshape(0, from, to), &[wire])
+}
+
+fn axis(op: &DeserOp, axis: i64) -> TractResult<usize> {
+    let rank = op.input_fact(0)?.rank() as i64;
+    if axis < -rank || axis >= rank {
+        bail!("Invalid axis {} for rank {}", axis, rank)
+    }
+    Ok(((axis + rank) % rank) as usize)
+}
+
+/// Quantized inputs are concatenated as is if they share the output
+/// parameters, otherwise the concatenation is done on dequantized values.
+fn concatenation(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    let options: ConcatenationOptions = op.options(builtin_options::CONCATENATION)?;
+    let axis = axis(op, options.axis().unwrap_or(0) as i64)?;
+    let output_quant = op.output_quant(0)?;
+    let same_quant = (0..op.inputs.len())
+        .map(|ix| op.input_quant(ix))
+        .collect::<TractResult<Vec<_>>>()?
+        .iter()
+        .all(|q| q == &output_quant);
+    let concat = TypedConcat::concat_vars(axis, op.inputs.len());
+    let wire = if same_quant {
+        op.wire_node("concat", concat, &op.inputs.clone())?
+    } else {
+        let inputs = (0..op.inputs.len())
+            .map(|ix| dequantized_input(op, ix))
+            .collect::<TractResult<TVec<OutletId>>>()?;
+        let wire = op.wire_node("concat", concat, &inputs)?;
+        quantized_output(op, wire)?
+    };
+    let activation = options.fused_activation_function().unwrap_or(activation::NONE);
+    Ok(tvec!(wire_fused_activation(op, wire, activation)?))
+}
+
+fn mean(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    let options: ReducerOptions = op.options(builtin_options::REDUCER)?;
+    let mut axes = op
+        .konst(1)?
+        .cast_to::<i64>()?
+        .as_slice::<i64>()?
+        .iter()
+        .map(|&a| axis(op, a))
+        .collect::<TractResult<TVec<usize>>>()?;
+    axes.sort();
+    axes.dedup();
+    let input_shape = op.input_fact(0)?.shape.to_tvec();
+    let count = axes.iter().map(|&a| input_shape[a].to_usize()).product::<TractResult<usize>>()?;
+    let wire = dequantized_input(op, 0)?;
+    let wire = op.wire_node("sum", Reduce::new(axes.clone(), Reducer::Sum), &[wire])?;
+    let recip =
+        tensor0(1.0 / count as f32).broadcast_into_rank(input_shape.len())?.into_arc_tensor();
+    let mut wire = op.wire_node("mean", ops::math::mul::unary(recip), &[wire])?;
+    if !options.keep_dims().unwrap_or(false) {
+        for axis in axes.iter().rev() {
+            wire = op.wire_node(&format!("rm_axis_{}", axis), AxisOp::Rm(*axis), &[wire])?;
+        }
+    }
+    Ok(tvec!(quantized_output(op, wire)?))
+}
+
+fn pad(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    let pads = op.konst(1)?.cast_to::<i64>()?.into_owned();
+    let pads = pads.as_slice::<i64>()?;
+    let pads = pads.chunks(2).map(|p| (p[0] as usize, p[1] as usize)).collect();
+    let dt = op.input_fact(0)?.datum_type;
+    // quantized zeros are the zero point
+    let value = match op.input_quant(0)? {
+        Some(q) => q.zero_point_tensor(dt)?,
+        None => Tensor::zero_scalar_dt(dt)?.into_arc_tensor(),
+    };
+    Ok(tvec!(op.wire_node("pad", Pad::new(pads, PadMode::Constant(value)), &[op.inputs[0]])?))
+}
+
+/// Reshape and Squeeze: the output shape is known statically.
+fn reshape(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    let shape = tensors::shape(&op.output(0)?);
+    Ok(tvec!(wire_reshape(op, "reshape", op.inputs[0], &shape)?))
+}
+
+fn transpose(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    let perm = op
+        .konst(1)?
+        .cast_to::<i64>()?
+        .as_slice::<i64>()?
+        .iter()
+        .map(|&a| a as usize)
+        .collect::<TVec<usize>>();
+    let mut wire = op.inputs[0];
+    for (ix, axis_op) in perm_to_ops(&perm).into_iter().enumerate() {
+        wire = op.wire_node(&format!("transpose_{}", ix), axis_op, &[wire])?;
+    }
+    Ok(tvec!(wire))
+}
@@ -0,0 +1,180 @@
+use crate::model::{DeserOp, TfliteOpRegister};
+use crate::schema::{builtin_operator, builtin_options, padding};
+use crate::schema::{Conv2DOptions, DepthwiseConv2DOptions, Pool2DOptions};
+use tract_hir::internal::*;
+use tract_hir::ops;
+use tract_hir::tract_core::ops::cnn::{
+    ConvUnary, KernelFormat, MaxPool, PaddingSpec, PoolSpec, SumPool,
+};
+use tract_hir::tract_core::ops::matmul::QParams;
+use tract_hir::tract_core::ops::nn::DataFormat;
+
+use super::quant::{dequantized_input, quantized_output, requantize};
+use super::wire_fused_activation;
+
+pub fn register_all_ops(reg: &mut TfliteOpRegister) {
+    reg.insert(builtin_operator::AVERAGE_POOL_2D, average_pool_2d);
+    reg.insert(builtin_operator::CONV_2D, conv_2d);
+    reg.insert(builtin_operator::DEPTHWISE_CONV_2D, depthwise_conv_2d);
+    reg.insert(builtin_operator::MAX_POOL_2D, max_pool_2d);
+}
+
+fn padding_spec(padding: Option<i8>) -> TractResult<PaddingSpec> {
+    match padding.unwrap_or(padding::SAME) {
+        padding::SAME => Ok(PaddingSpec::SameUpper),
+        padding::VALID => Ok(PaddingSpec::Valid),
+        p => bail!("Unsupported padding {}", p),
+    }
+}
+
+struct ConvParams {
+    padding: PaddingSpec,
+    strides: TVec<usize>,
+    dilations: TVec<usize>,
+    activation: i8,
+}
+
+fn conv_2d(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    let options: Conv2DOptions = op.options(builtin_options::CONV_2D)?;
+    let params = ConvParams {
+        padding: padding_spec(options.padding())?,
+        strides: tvec!(
+            options.stride_h().unwrap_or(1) as usize,
+            options.stride_w().unwrap_or(1) as usize
+        ),
+        dilations: tvec!(
+            options.dilation_h_factor().unwrap_or(1) as usize,
+            options.dilation_w_factor().unwrap_or(1) as usize
+        ),
+        activation: options.fused_activation_function().unwrap_or(0),
+    };
+    // OHWI to HWIO
+    let kernel = op.konst(1)?.into_tensor().permute_axes(&[1, 2, 3, 0])?;
+    wire_conv(op, params, kernel, 1)
+}
+
+fn depthwise_conv_2d(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    let options: DepthwiseConv2DOptions = op.options(builtin_options::DEPTHWISE_CONV_2D)?;
+    let params = ConvParams {
+        padding: padding_spec(options.padding())?,
+        strides: tvec!(
+            options.stride_h().unwrap_or(1) as usize,
+            options.stride_w().unwrap_or(1) as usize
+        ),
+        dilations: tvec!(
+            options.dilation_h_factor().unwrap_or(1) as usize,
+            options.dilation_w_factor().unwrap_or(1) as usize
+        ),
+        activation: options.fused_activation_function().unwrap_or(0),
+    };
+    let group = op.input_fact(0)?.shape[3].to_usize()?;
+    // 1HW(C*M) to HWCM, tract HWIO format with M outputs per group
+    let kernel = op.konst(1)?;
+    let (h, w, o) = (kernel.shape()[1], kernel.shape()[2], kernel.shape()[3]);
+    let kernel = kernel.into_tensor().into_shape(&[h, w, group, o / group])?;
+    wire_conv(op, params, kernel, group)
+}
+
+fn wire_conv(
+    op: &mut DeserOp,
+    params: ConvParams,
+    kernel: Tensor,
+    group: usize,
+) -> TractResult<TVec<OutletId>> {
+    let output_channels = kernel.shape()[3] * group;
+    let pool_spec = PoolSpec::new(
+        DataFormat::NHWC,
+        kernel.shape()[0..2].into(),
+        params.padding,
+        Some(params.dilations),
+        Some(params.strides),
+        Some(output_channels),
+    );
+    let bias = if op.inputs.len() > 2 { Some(op.konst(2)?) } else { None };
+    let input_quant = op.input_quant(0)?;
+    let kernel_quant = op.input_quant(1)?;
+    let output_quant = op.output_quant(0)?;
+    let wire = if let (Some(iq), Some(kq), Some(oq)) = (input_quant, kernel_quant, output_quant) {
+        // The convolution computes the raw i32 accumulator. The bias is in
+        // the same domain, and must be added before requantization.
+        let input_dt = op.input_fact(0)?.datum_type;
+        let q_params = QParams {
+            a0: AttrOrInput::Attr(kq.zero_point_tensor(kernel.datum_type())?),
+            a_scale: AttrOrInput::Attr(rctensor0(1f32)),
+            b0: AttrOrInput::Attr(iq.zero_point_tensor(input_dt)?),
+            b_scale: AttrOrInput::Attr(rctensor0(1f32)),
+            c0: AttrOrInput::Attr(rctensor0(0i32)),
+            c_scale: AttrOrInput::Attr(rctensor0(1f32)),
+        };
+        let conv = ConvUnary::new(
+            pool_spec,
+            KernelFormat::HWIO,
+            kernel.into_arc_tensor(),
+            group,
+            None,
+            Some((i32::datum_type(), q_params)),
+        );
+        let mut wire = op.wire_node("conv", conv, &[op.inputs[0]])?;
+        if let Some(bias) = bias {
+            let bias = bias.cast_to::<i32>()?.into_owned().broadcast_into_rank(4)?;
+            wire = op.wire_node("bias", ops::math::add::unary(bias.into_arc_tensor()), &[wire])?;
+        }
+        let multiplier: Vec<f32> =
+            kq.scale.iter().map(|ks| iq.scale[0] * ks / oq.scale[0]).collect();
+        let multiplier =
+            if multiplier.len() == 1 { tensor0(multiplier[0]) } else { tensor1(&multiplier) };
+        let dt = op.output_datum_type(0)?;
+        requantize(op, wire, multiplier, oq.zero_point()?, dt)?
+    } else {
+        let conv = ConvUnary::new(
+            pool_spec,
+            KernelFormat::HWIO,
+            kernel.into_arc_tensor(),
+            group,
+            bias,
+            None,
+        );
+        op.wire_node("conv", conv, &[op.inputs[0]])?
+    };
+    Ok(tvec!(wire_fused_activation(op, wire, params.activation)?))
+}
+
+fn pool_spec(options: &Pool2DOptions) -> TractResult<PoolSpec> {
+    Ok(PoolSpec::new(
+        DataFormat::NHWC,
+        tvec!(
+            options.filter_height().unwrap_or(1) as usize,
+            options.filter_width().unwrap_or(1) as usize
+        ),
+        padding_spec(options.padding())?,
+        None,
+        Some(tvec!(
+            options.stride_h().unwrap_or(1) as usize,
+            options.stride_w().unwrap_or(1) as usize
+        )),
+        None,
+    ))
+}
+
+fn average_pool_2d(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    let options: Pool2DOptions = op.options(builtin_options::POOL_2D)?;
+    let pool = SumPool::new(pool_spec(&options)?, false, true);
+    wire_pool(op, pool, options.fused_activation_function().unwrap_or(0))
+}
+
+fn max_pool_2d(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    let options: Pool2DOptions = op.options(builtin_options::POOL_2D)?;
+    let pool = MaxPool::new(pool_spec(&options)?, None);
+    wire_pool(op, pool, options.fused_activation_function().unwrap_or(0))
+}
+
+fn wire_pool(
+    op: &mut DeserOp,
+    pool: impl Into<Box<dyn TypedOp>>,
+    activation: i8,
+) -> TractResult<TVec<OutletId>> {
+    let input = dequantized_input(op, 0)?;
+    let wire = op.wire_node("pool", pool, &[input])?;
+    let wire = quantized_output(op, wire)?;
+    Ok(tvec!(wire_fused_activation(op, wire, activation)?))
+}
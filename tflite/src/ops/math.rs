@@ -0,0 +1,63 @@
+use crate::model::{DeserOp, TfliteOpRegister};
+use crate::schema::{activation, builtin_operator, builtin_options, BinaryOptions};
+use tract_hir::internal::*;
+use tract_hir::ops;
+use tract_hir::tract_core::ops::binary::{wire_with_rank_broadcast, TypedBinOp};
+
+use super::quant::{dequantized_input, quantized_output};
+use super::wire_fused_activation;
+
+pub fn register_all_ops(reg: &mut TfliteOpRegister) {
+    reg.insert(builtin_operator::ADD, add);
+    reg.insert(builtin_operator::DIV, div);
+    reg.insert(builtin_operator::MAXIMUM, maximum);
+    reg.insert(builtin_operator::MINIMUM, minimum);
+    reg.insert(builtin_operator::MUL, mul);
+    reg.insert(builtin_operator::SUB, sub);
+}
+
+/// Binary operators work on f32 values: quantized operands are dequantized,
+/// and the result quantized again with the output parameters.
+fn wire_binary(
+    op: &mut DeserOp,
+    name: &str,
+    bin_op: TypedBinOp,
+    options: Option<u8>,
+) -> TractResult<TVec<OutletId>> {
+    let activation = if let Some(kind) = options {
+        let options: BinaryOptions = op.options(kind)?;
+        options.fused_activation_function().unwrap_or(activation::NONE)
+    } else {
+        activation::NONE
+    };
+    let a = dequantized_input(op, 0)?;
+    let b = dequantized_input(op, 1)?;
+    let prefix = format!("{}.{}", op.prefix, name);
+    let wire = wire_with_rank_broadcast(&prefix, op.model, bin_op, &[a, b])?[0];
+    let wire = quantized_output(op, wire)?;
+    Ok(tvec!(wire_fused_activation(op, wire, activation)?))
+}
+
+fn add(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    wire_binary(op, "add", ops::math::add::bin_typed(), Some(builtin_options::ADD))
+}
+
+fn div(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    wire_binary(op, "div", ops::math::div::bin_typed(), Some(builtin_options::DIV))
+}
+
+fn maximum(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    wire_binary(op, "max", ops::math::max::bin_typed(), None)
+}
+
+fn minimum(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    wire_binary(op, "min", ops::math::min::bin_typed(), None)
+}
+
+fn mul(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    wire_binary(op, "mul", ops::math::mul::bin_typed(), Some(builtin_options::MUL))
+}
+
+fn sub(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    wire_binary(op, "sub", ops::math::sub::bin_typed(), Some(builtin_options::SUB))
+}
@@ -0,0 +1,72 @@
+use crate::model::{DeserOp, TfliteOpRegister};
+use crate::schema::activation;
+use crate::tensors::quantized_range;
+use tract_hir::internal::*;
+use tract_hir::ops;
+
+pub mod array;
+pub mod cnn;
+pub mod math;
+pub mod nn;
+pub mod quant;
+
+pub fn register_all_ops(reg: &mut TfliteOpRegister) {
+    array::register_all_ops(reg);
+    cnn::register_all_ops(reg);
+    math::register_all_ops(reg);
+    nn::register_all_ops(reg);
+    quant::register_all_ops(reg);
+}
+
+/// Wires the activation fused in conv, matmul or binary operators. On
+/// quantized outputs, the clipping activations are computed in the quantized
+/// domain.
+pub fn wire_fused_activation(
+    op: &mut DeserOp,
+    wire: OutletId,
+    activation: i8,
+) -> TractResult<OutletId> {
+    let (low, high) = match activation {
+        activation::NONE => return Ok(wire),
+        activation::TANH => {
+            if op.output_quant(0)?.is_some() {
+                bail!("Fused tanh activation is not supported on quantized outputs")
+            }
+            return op.wire_node("tanh", ops::math::tanh(), &[wire]);
+        }
+        activation::RELU => (Some(0.0), None),
+        activation::RELU_N1_TO_1 => (Some(-1.0), Some(1.0)),
+        activation::RELU6 => (Some(0.0), Some(6.0)),
+        _ => bail!("Unsupported fused activation function {}", activation),
+    };
+    let fact = op.model.outlet_fact(wire)?.clone();
+    let bound = |x: f32| -> TractResult<Arc<Tensor>> {
+        let t = if let Some(q) = op.output_quant(0)? {
+            tensor0(q.quantize(x, fact.datum_type)?).cast_to_dt(fact.datum_type)?.into_owned()
+        } else {
+            tensor0(x).cast_to_dt(fact.datum_type)?.into_owned()
+        };
+        Ok(t.broadcast_into_rank(fact.rank())?.into_arc_tensor())
+    };
+    let low = low.map(bound).transpose()?;
+    let high = high.map(bound).transpose()?;
+    let mut wire = wire;
+    if let Some(low) = low {
+        wire = op.wire_node("low", ops::math::max::unary(low), &[wire])?;
+    }
+    if let Some(high) = high {
+        wire = op.wire_node("high", ops::math::min::unary(high), &[wire])?;
+    }
+    Ok(wire)
+}
+
+/// Clamps an i32 wire to the range of `dt`, and casts it.
+pub fn clamp_and_cast(op: &mut DeserOp, wire: OutletId, dt: DatumType) -> TractResult<OutletId> {
+    let rank = op.model.outlet_fact(wire)?.rank();
+    let (min, max) = quantized_range(dt)?;
+    let min = tensor0(min).broadcast_into_rank(rank)?.into_arc_tensor();
+    let max = tensor0(max).broadcast_into_rank(rank)?.into_arc_tensor();
+    let wire = op.wire_node("min", ops::math::min::unary(max), &[wire])?;
+    let wire = op.wire_node("max", ops::math::max::unary(min), &[wire])?;
+    op.wire_node("cast", ops::cast(dt), &[wire])
+}
@@ -0,0 +1,124 @@
+use crate::model::{DeserOp, TfliteOpRegister};
+use crate::schema::{activation, builtin_operator, builtin_options};
+use crate::schema::{FullyConnectedOptions, SoftmaxOptions};
+use crate::tensors;
+use tract_hir::internal::*;
+use tract_hir::ops;
+use tract_hir::ops::nn::LayerSoftmax;
+use tract_hir::tract_core::ops::matmul::{MatMulUnary, QMatMul, QParams};
+
+use super::array::wire_reshape;
+use super::quant::{dequantized_input, quantized_output};
+use super::wire_fused_activation;
+
+pub fn register_all_ops(reg: &mut TfliteOpRegister) {
+    reg.insert(builtin_operator::FULLY_CONNECTED, fully_connected);
+    reg.insert(builtin_operator::LOGISTIC, logistic);
+    reg.insert(builtin_operator::RELU, relu);
+    reg.insert(builtin_operator::RELU6, relu6);
+    reg.insert(builtin_operator::SOFTMAX, softmax);
+    reg.insert(builtin_operator::TANH, tanh);
+}
+
+fn fully_connected(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    let options: FullyConnectedOptions = op.options(builtin_options::FULLY_CONNECTED)?;
+    if options.weights_format().unwrap_or(0) != 0 {
+        bail!("Only default weights format is supported")
+    }
+    // weights are [units, depth]
+    let weights = op.konst(1)?;
+    let (units, depth) = (weights.shape()[0], weights.shape()[1]);
+    let input_shape =
+        op.input_fact(0)?.shape.as_concrete().context("Expect static shape")?.to_vec();
+    let mut wire = op.inputs[0];
+    if input_shape.len() != 2 || input_shape[1] != depth {
+        let batch = input_shape.iter().product::<usize>() / depth;
+        wire = wire_reshape(op, "reshape_input", wire, &[batch, depth])?;
+    }
+    let bias = if op.inputs.len() > 2 { Some(op.konst(2)?) } else { None };
+    let input_quant = op.input_quant(0)?;
+    let weights_quant = op.input_quant(1)?;
+    let output_quant = op.output_quant(0)?;
+    let wire = if let (Some(iq), Some(wq), Some(oq)) = (input_quant, weights_quant, output_quant) {
+        let input_dt = op.input_fact(0)?.datum_type;
+        let output_dt = op.output_datum_type(0)?;
+        let params = QParams {
+            a0: AttrOrInput::Attr(iq.zero_point_tensor(input_dt)?),
+            a_scale: AttrOrInput::Attr(iq.scale_tensor()),
+            b0: AttrOrInput::Attr(wq.zero_point_tensor(weights.datum_type())?),
+            b_scale: AttrOrInput::Attr(wq.scale_tensor()),
+            c0: AttrOrInput::Attr(oq.zero_point_tensor(output_dt)?),
+            c_scale: AttrOrInput::Attr(oq.scale_tensor()),
+        };
+        let bias = match bias {
+            Some(bias) => bias.cast_to::<i32>()?.into_owned().into_shape(&[1, units])?,
+            None => tensor0(0i32),
+        };
+        let bias = op.model.add_const(format!("{}.bias", op.prefix), bias)?;
+        let weights = op.model.add_const(format!("{}.weights", op.prefix), weights)?;
+        let qmm = QMatMul::new(false, true, false, output_dt, params);
+        op.wire_node("matmul", qmm, &[wire, weights, bias])?
+    } else {
+        let wire = op.wire_node("matmul", MatMulUnary::new(weights, false, true, true), &[wire])?;
+        if let Some(bias) = bias {
+            let bias = bias.into_tensor().into_shape(&[1, units])?.into_arc_tensor();
+            op.wire_node("bias", ops::math::add::unary(bias), &[wire])?
+        } else {
+            wire
+        }
+    };
+    let activation = options.fused_activation_function().unwrap_or(activation::NONE);
+    let mut wire = wire_fused_activation(op, wire, activation)?;
+    let output_shape = tensors::shape(&op.output(0)?);
+    if op.model.outlet_fact(wire)?.shape.as_concrete() != Some(&*output_shape) {
+        wire = wire_reshape(op, "reshape_output", wire, &output_shape)?;
+    }
+    Ok(tvec!(wire))
+}
+
+fn softmax(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    let options: SoftmaxOptions = op.options(builtin_options::SOFTMAX)?;
+    let mut wire = dequantized_input(op, 0)?;
+    let beta = options.beta().unwrap_or(1.0);
+    if beta != 1.0 {
+        let rank = op.model.outlet_fact(wire)?.rank();
+        let beta = tensor0(beta).broadcast_into_rank(rank)?.into_arc_tensor();
+        wire = op.wire_node("beta", ops::math::mul::unary(beta), &[wire])?;
+    }
+    let prefix = format!("{}.softmax", op.prefix);
+    let wire = LayerSoftmax::new(-1).wire(&prefix, op.model, &[wire])?[0];
+    Ok(tvec!(quantized_output(op, wire)?))
+}
+
+/// Requantizes the input to the output parameters, if needed, and clips it.
+fn wire_clip(op: &mut DeserOp, activation: i8) -> TractResult<TVec<OutletId>> {
+    let wire = dequantized_input(op, 0)?;
+    let wire = quantized_output(op, wire)?;
+    Ok(tvec!(wire_fused_activation(op, wire, activation)?))
+}
+
+fn wire_element_wise(
+    op: &mut DeserOp,
+    name: &str,
+    mini_op: impl Into<Box<dyn TypedOp>>,
+) -> TractResult<TVec<OutletId>> {
+    let wire = dequantized_input(op, 0)?;
+    let wire = op.wire_node(name, mini_op, &[wire])?;
+    Ok(tvec!(quantized_output(op, wire)?))
+}
+
+fn logistic(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    wire_element_wise(op, "logistic", ops::nn::sigmoid())
+}
+
+fn relu(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    wire_clip(op, activation::RELU)
+}
+
+fn relu6(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    wire_clip(op, activation::RELU6)
+}
+
+fn tanh(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    wire_element_wise(op, "tanh", ops::math::tanh())
+}
@@ -0,0 +1,67 @@
+use crate::model::{DeserOp, TfliteOpRegister};
+use crate::schema::builtin_operator;
+use tract_hir::internal::*;
+use tract_hir::ops;
+use tract_hir::ops::quant::DequantizeLinearF32;
+
+pub fn register_all_ops(reg: &mut TfliteOpRegister) {
+    reg.insert(builtin_operator::DEQUANTIZE, dequantize);
+    reg.insert(builtin_operator::QUANTIZE, quantize);
+}
+
+fn dequantize(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    if op.input_fact(0)?.datum_type.is_float() {
+        // float16 weights
+        let cast = ops::cast(f32::datum_type());
+        return Ok(tvec!(op.wire_node("cast", cast, &[op.inputs[0]])?));
+    }
+    Ok(tvec!(dequantized_input(op, 0)?))
+}
+
+fn quantize(op: &mut DeserOp) -> TractResult<TVec<OutletId>> {
+    let wire = dequantized_input(op, 0)?;
+    Ok(tvec!(quantized_output(op, wire)?))
+}
+
+/// The f32 value of an input, dequantized if it is quantized.
+pub fn dequantized_input(op: &mut DeserOp, ix: usize) -> TractResult<OutletId> {
+    let q = if let Some(q) = op.input_quant(ix)? { q } else { return Ok(op.inputs[ix]) };
+    if !q.is_per_tensor() {
+        bail!("Per-channel quantization is only supported on weights")
+    }
+    let dequant = DequantizeLinearF32::new(q.scale[0], q.zero_point()?);
+    op.wire_node(&format!("dequant_{}", ix), dequant, &[op.inputs[ix]])
+}
+
+/// Quantizes a f32 result, if the operator output is quantized.
+pub fn quantized_output(op: &mut DeserOp, wire: OutletId) -> TractResult<OutletId> {
+    let q = if let Some(q) = op.output_quant(0)? { q } else { return Ok(wire) };
+    if !q.is_per_tensor() {
+        bail!("Per-channel quantization is only supported on weights")
+    }
+    let scale = q.scale[0].recip();
+    let zero_point = q.zero_point()?;
+    let quant = match op.output_datum_type(0)? {
+        DatumType::U8 => ops::quant::quantize_linear_u8(scale, zero_point as u8),
+        DatumType::I8 => ops::quant::quantize_linear_i8(scale, zero_point as i8),
+        dt => bail!("Unsupported quantized type {:?}", dt),
+    };
+    op.wire_node("quant", quant, &[wire])
+}
+
+/// Scales an i32 accumulator by a per-tensor or per-channel multiplier (on
+/// the last axis), and converts it to the quantized output type.
+pub fn requantize(
+    op: &mut DeserOp,
+    wire: OutletId,
+    multiplier: Tensor,
+    zero_point: i32,
+    dt: DatumType,
+) -> TractResult<OutletId> {
+    let rank = op.model.outlet_fact(wire)?.rank();
+    let multiplier = multiplier.broadcast_into_rank(rank)?.into_arc_tensor();
+    let wire = op.wire_node("requant", ops::quant::scale::unary(multiplier), &[wire])?;
+    let zero_point = tensor0(zero_point).broadcast_into_rank(rank)?.into_arc_tensor();
+    let wire = op.wire_node("zero_point", ops::math::add::unary(zero_point), &[wire])?;
+    super::clamp_and_cast(op, wire, dt)
+}
@@ -0,0 +1,319 @@
+//! Read-only accessors for the subset of the TensorFlow Lite flatbuffer
+//! schema (tensorflow/lite/schema/schema.fbs) used by the loader.
+//!
+//! Field ids are the declaration order of the fields in the schema, and must
+//! be kept in sync with it.
+use flatbuffers::{
+    field_index_to_field_offset, ForwardsUOffset, InvalidFlatbuffer, Table, Vector, Verifiable,
+    Verifier,
+};
+
+pub use flatbuffers::Follow;
+
+macro_rules! table {
+    ($name: ident { $($id: literal $field: ident: $ty: ty),* $(,)? }) => {
+        #[derive(Copy, Clone)]
+        pub struct $name<'a>(Table<'a>);
+
+        impl<'a> Follow<'a> for $name<'a> {
+            type Inner = $name<'a>;
+            unsafe fn follow(buf: &'a [u8], loc: usize) -> $name<'a> {
+                $name(Table::new(buf, loc))
+            }
+        }
+
+        impl<'a> Verifiable for $name<'a> {
+            fn run_verifier(v: &mut Verifier, pos: usize) -> Result<(), InvalidFlatbuffer> {
+                v.visit_table(pos)?
+                    $(.visit_field::<$ty>(stringify!($field), field_index_to_field_offset($id), false)?)*
+                    .finish();
+                Ok(())
+            }
+        }
+
+        #[allow(dead_code)]
+        impl<'a> $name<'a> {
+            $(
+                pub fn $field(&self) -> Option<<$ty as Follow<'a>>::Inner> {
+                    // tables are only reachable from a verified root
+                    unsafe { self.0.get::<$ty>(field_index_to_field_offset($id), None) }
+                }
+            )*
+        }
+    };
+}
+
+table!(Model {
+    0 version: u32,
+    1 operator_codes: ForwardsUOffset<Vector<'a, ForwardsUOffset<OperatorCode<'a>>>>,
+    2 subgraphs: ForwardsUOffset<Vector<'a, ForwardsUOffset<SubGraph<'a>>>>,
+    3 description: ForwardsUOffset<&'a str>,
+    4 buffers: ForwardsUOffset<Vector<'a, ForwardsUOffset<Buffer<'a>>>>,
+});
+
+table!(OperatorCode {
+    0 deprecated_builtin_code: i8,
+    1 custom_code: ForwardsUOffset<&'a str>,
+    2 version: i32,
+    3 builtin_code: i32,
+});
+
+impl<'a> OperatorCode<'a> {
+    /// Builtin codes above 127 only live in the newer `builtin_code` field,
+    /// older models only fill the deprecated one.
+    pub fn code(&self) -> i32 {
+        (self.deprecated_builtin_code().unwrap_or(0) as i32).max(self.builtin_code().unwrap_or(0))
+    }
+}
+
+table!(SubGraph {
+    0 tensors: ForwardsUOffset<Vector<'a, ForwardsUOffset<Tensor<'a>>>>,
+    1 inputs: ForwardsUOffset<Vector<'a, i32>>,
+    2 outputs: ForwardsUOffset<Vector<'a, i32>>,
+    3 operators: ForwardsUOffset<Vector<'a, ForwardsUOffset<Operator<'a>>>>,
+    4 name: ForwardsUOffset<&'a str>,
+});
+
+table!(Tensor {
+    0 shape: ForwardsUOffset<Vector<'a, i32>>,
+    1 type_: i8,
+    2 buffer: u32,
+    3 name: ForwardsUOffset<&'a str>,
+    4 quantization: ForwardsUOffset<QuantizationParameters<'a>>,
+});
+
+table!(QuantizationParameters {
+    0 min: ForwardsUOffset<Vector<'a, f32>>,
+    1 max: ForwardsUOffset<Vector<'a, f32>>,
+    2 scale: ForwardsUOffset<Vector<'a, f32>>,
+    3 zero_point: ForwardsUOffset<Vector<'a, i64>>,
+    6 quantized_dimension: i32,
+});
+
+table!(Buffer {
+    0 data: ForwardsUOffset<Vector<'a, u8>>,
+});
+
+table!(Conv2DOptions {
+    0 padding: i8,
+    1 stride_w: i32,
+    2 stride_h: i32,
+    3 fused_activation_function: i8,
+    4 dilation_w_factor: i32,
+    5 dilation_h_factor: i32,
+});
+
+table!(DepthwiseConv2DOptions {
+    0 padding: i8,
+    1 stride_w: i32,
+    2 stride_h: i32,
+    3 depth_multiplier: i32,
+    4 fused_activation_function: i8,
+    5 dilation_w_factor: i32,
+    6 dilation_h_factor: i32,
+});
+
+table!(Pool2DOptions {
+    0 padding: i8,
+    1 stride_w: i32,
+    2 stride_h: i32,
+    3 filter_width: i32,
+    4 filter_height: i32,
+    5 fused_activation_function: i8,
+});
+
+table!(FullyConnectedOptions {
+    0 fused_activation_function: i8,
+    1 weights_format: i8,
+    2 keep_num_dims: bool,
+});
+
+table!(SoftmaxOptions {
+    0 beta: f32,
+});
+
+table!(ConcatenationOptions {
+    0 axis: i32,
+    1 fused_activation_function: i8,
+});
+
+// Shared by Add, Sub, Mul and Div options.
+table!(BinaryOptions {
+    0 fused_activation_function: i8,
+});
+
+table!(ReshapeOptions {
+    0 new_shape: ForwardsUOffset<Vector<'a, i32>>,
+});
+
+table!(ReducerOptions {
+    0 keep_dims: bool,
+});
+
+table!(SqueezeOptions {
+    0 squeeze_dims: ForwardsUOffset<Vector<'a, i32>>,
+});
+
+/// Values of the TensorType enum.
+pub mod tensor_type {
+    pub const FLOAT32: i8 = 0;
+    pub const FLOAT16: i8 = 1;
+    pub const INT32: i8 = 2;
+    pub const UINT8: i8 = 3;
+    pub const INT64: i8 = 4;
+    pub const STRING: i8 = 5;
+    pub const BOOL: i8 = 6;
+    pub const INT16: i8 = 7;
+    pub const INT8: i8 = 9;
+    pub const FLOAT64: i8 = 10;
+}
+
+/// Values of the BuiltinOperator enum.
+pub mod builtin_operator {
+    pub const ADD: i32 = 0;
+    pub const AVERAGE_POOL_2D: i32 = 1;
+    pub const CONCATENATION: i32 = 2;
+    pub const CONV_2D: i32 = 3;
+    pub const DEPTHWISE_CONV_2D: i32 = 4;
+    pub const DEQUANTIZE: i32 = 6;
+    pub const FULLY_CONNECTED: i32 = 9;
+    pub const LOGISTIC: i32 = 14;
+    pub const MAX_POOL_2D: i32 = 17;
+    pub const MUL: i32 = 18;
+    pub const RELU: i32 = 19;
+    pub const RELU6: i32 = 21;
+    pub const RESHAPE: i32 = 22;
+    pub const SOFTMAX: i32 = 25;
+    pub const TANH: i32 = 28;
+    pub const PAD: i32 = 34;
+    pub const TRANSPOSE: i32 = 39;
+    pub const MEAN: i32 = 40;
+    pub const SUB: i32 = 41;
+    pub const DIV: i32 = 42;
+    pub const SQUEEZE: i32 = 43;
+    pub const MAXIMUM: i32 = 55;
+    pub const MINIMUM: i32 = 57;
+    pub const QUANTIZE: i32 = 114;
+}
+
+/// Values of the ActivationFunctionType enum.
+pub mod activation {
+    pub const NONE: i8 = 0;
+    pub const RELU: i8 = 1;
+    pub const RELU_N1_TO_1: i8 = 2;
+    pub const RELU6: i8 = 3;
+    pub const TANH: i8 = 4;
+}
+
+/// Values of the Padding enum.
+pub mod padding {
+    pub const SAME: i8 = 0;
+    pub const VALID: i8 = 1;
+}
+
+/// Values of the BuiltinOptions union, for the options tables above.
+pub mod builtin_options {
+    pub const NONE: u8 = 0;
+    pub const CONV_2D: u8 = 1;
+    pub const DEPTHWISE_CONV_2D: u8 = 2;
+    pub const POOL_2D: u8 = 5;
+    pub const FULLY_CONNECTED: u8 = 8;
+    pub const SOFTMAX: u8 = 9;
+    pub const CONCATENATION: u8 = 10;
+    pub const ADD: u8 = 11;
+    pub const RESHAPE: u8 = 17;
+    pub const MUL: u8 = 21;
+    pub const REDUCER: u8 = 27;
+    pub const SUB: u8 = 28;
+    pub const DIV: u8 = 29;
+    pub const SQUEEZE: u8 = 30;
+}
+
+#[derive(Copy, Clone)]
+pub struct Operator<'a>(Table<'a>);
+
+impl<'a> Follow<'a> for Operator<'a> {
+    type Inner = Operator<'a>;
+    unsafe fn follow(buf: &'a [u8], loc: usize) -> Operator<'a> {
+        Operator(Table::new(buf, loc))
+    }
+}
+
+fn verify_options(kind: u8, v: &mut Verifier, pos: usize) -> Result<(), InvalidFlatbuffer> {
+    use builtin_options::*;
+    type F<T> = ForwardsUOffset<T>;
+    match kind {
+        CONV_2D => v.verify_union_variant::<F<Conv2DOptions>>("Conv2DOptions", pos),
+        DEPTHWISE_CONV_2D => {
+            v.verify_union_variant::<F<DepthwiseConv2DOptions>>("DepthwiseConv2DOptions", pos)
+        }
+        POOL_2D => v.verify_union_variant::<F<Pool2DOptions>>("Pool2DOptions", pos),
+        FULLY_CONNECTED => {
+            v.verify_union_variant::<F<FullyConnectedOptions>>("FullyConnectedOptions", pos)
+        }
+        SOFTMAX => v.verify_union_variant::<F<SoftmaxOptions>>("SoftmaxOptions", pos),
+        CONCATENATION => {
+            v.verify_union_variant::<F<ConcatenationOptions>>("ConcatenationOptions", pos)
+        }
+        ADD | MUL | SUB | DIV => v.verify_union_variant::<F<BinaryOptions>>("BinaryOptions", pos),
+        RESHAPE => v.verify_union_variant::<F<ReshapeOptions>>("ReshapeOptions", pos),
+        REDUCER => v.verify_union_variant::<F<ReducerOptions>>("ReducerOptions", pos),
+        SQUEEZE => v.verify_union_variant::<F<SqueezeOptions>>("SqueezeOptions", pos),
+        // options of unsupported operators are never read
+        _ => Ok(()),
+    }
+}
+
+impl<'a> Verifiable for Operator<'a> {
+    fn run_verifier(v: &mut Verifier, pos: usize) -> Result<(), InvalidFlatbuffer> {
+        v.visit_table(pos)?
+            .visit_field::<u32>("opcode_index", field_index_to_field_offset(0), false)?
+            .visit_field::<ForwardsUOffset<Vector<i32>>>(
+                "inputs",
+                field_index_to_field_offset(1),
+                false,
+            )?
+            .visit_field::<ForwardsUOffset<Vector<i32>>>(
+                "outputs",
+                field_index_to_field_offset(2),
+                false,
+            )?
+            .visit_union::<u8, _>(
+                "builtin_options_type",
+                field_index_to_field_offset(3),
+                "builtin_options",
+                field_index_to_field_offset(4),
+                false,
+                verify_options,
+            )?
+            .finish();
+        Ok(())
+    }
+}
+
+impl<'a> Operator<'a> {
+    pub fn opcode_index(&self) -> u32 {
+        unsafe { self.0.get::<u32>(field_index_to_field_offset(0), Some(0)).unwrap() }
+    }
+
+    pub fn inputs(&self) -> Option<Vector<'a, i32>> {
+        unsafe { self.0.get::<ForwardsUOffset<Vector<i32>>>(field_index_to_field_offset(1), None) }
+    }
+
+    pub fn outputs(&self) -> Option<Vector<'a, i32>> {
+        unsafe { self.0.get::<ForwardsUOffset<Vector<i32>>>(field_index_to_field_offset(2), None) }
+    }
+
+    pub fn builtin_options_type(&self) -> u8 {
+        unsafe { self.0.get::<u8>(field_index_to_field_offset(3), Some(0)).unwrap() }
+    }
+
+    /// Options of the operator, if they are of the `kind` variant of the
+    /// BuiltinOptions union.
+    pub fn builtin_options<T: Follow<'a, Inner = T> + 'a>(&self, kind: u8) -> Option<T> {
+        if self.builtin_options_type() != kind {
+            return None;
+        }
+        unsafe { self.0.get::<ForwardsUOffset<T>>(field_index_to_field_offset(4), None) }
+    }
+}
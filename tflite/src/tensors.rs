@@ -0,0 +1,113 @@
+use crate::schema::{self, tensor_type};
+use tract_hir::internal::*;
+
+pub fn datum_type(t: i8) -> TractResult<DatumType> {
+    match t {
+        tensor_type::FLOAT32 => Ok(f32::datum_type()),
+        tensor_type::FLOAT16 => Ok(f16::datum_type()),
+        tensor_type::FLOAT64 => Ok(f64::datum_type()),
+        tensor_type::INT8 => Ok(i8::datum_type()),
+        tensor_type::INT16 => Ok(i16::datum_type()),
+        tensor_type::INT32 => Ok(i32::datum_type()),
+        tensor_type::INT64 => Ok(i64::datum_type()),
+        tensor_type::UINT8 => Ok(u8::datum_type()),
+        tensor_type::BOOL => Ok(bool::datum_type()),
+        _ => bail!("Unsupported TFLite tensor type {}", t),
+    }
+}
+
+pub fn shape(t: &schema::Tensor) -> TVec<usize> {
+    t.shape().map(|s| s.iter().map(|d| d as usize).collect()).unwrap_or_default()
+}
+
+pub fn fact(t: &schema::Tensor) -> TractResult<TypedFact> {
+    Ok(TypedFact::dt_shape(datum_type(t.type_().unwrap_or(0))?, &*shape(t)))
+}
+
+pub fn tensor(t: &schema::Tensor, data: &[u8]) -> TractResult<Tensor> {
+    let shape = shape(t);
+    let dt = datum_type(t.type_().unwrap_or(0))?;
+    unsafe {
+        match dt {
+            DatumType::Bool => Ok(Tensor::from_raw::<u8>(&shape, data)?
+                .into_array::<u8>()?
+                .mapv(|x| x != 0)
+                .into()),
+            _ => Tensor::from_raw_dt(dt, &shape, data),
+        }
+    }
+}
+
+/// Quantization parameters of a tensor.
+///
+/// Per-tensor parameters are stored as single-element vectors, per-channel
+/// ones have one element per slice along `axis`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QuantInfo {
+    pub scale: Vec<f32>,
+    pub zero_point: Vec<i32>,
+    pub axis: usize,
+}
+
+impl QuantInfo {
+    pub fn is_per_tensor(&self) -> bool {
+        self.scale.len() == 1
+    }
+
+    pub fn scale_tensor(&self) -> Arc<Tensor> {
+        if self.is_per_tensor() {
+            rctensor0(self.scale[0])
+        } else {
+            rctensor1(&self.scale)
+        }
+    }
+
+    pub fn zero_point(&self) -> TractResult<i32> {
+        if self.zero_point.iter().any(|&z| z != self.zero_point[0]) {
+            bail!("Per-channel zero points are not supported, got {:?}", self.zero_point)
+        }
+        Ok(self.zero_point[0])
+    }
+
+    pub fn zero_point_tensor(&self, dt: DatumType) -> TractResult<Arc<Tensor>> {
+        Ok(tensor0(self.zero_point()?).cast_to_dt(dt)?.into_owned().into_arc_tensor())
+    }
+
+    /// Quantizes a float value, clamping it to the `dt` range.
+    pub fn quantize(&self, x: f32, dt: DatumType) -> TractResult<i32> {
+        let (min, max) = quantized_range(dt)?;
+        Ok(((x / self.scale[0]).round() as i32 + self.zero_point()?).max(min).min(max))
+    }
+}
+
+pub fn quantized_range(dt: DatumType) -> TractResult<(i32, i32)> {
+    match dt {
+        DatumType::I8 => Ok((i8::MIN as i32, i8::MAX as i32)),
+        DatumType::U8 => Ok((u8::MIN as i32, u8::MAX as i32)),
+        DatumType::I16 => Ok((i16::MIN as i32, i16::MAX as i32)),
+        DatumType::I32 => Ok((i32::MIN, i32::MAX)),
+        _ => bail!("{:?} is not a quantized type", dt),
+    }
+}
+
+/// Quantization parameters of a quantized integer tensor. Float tensors
+/// sometimes carry min/max statistics, they are ignored.
+pub fn quantization(t: &schema::Tensor) -> TractResult<Option<QuantInfo>> {
+    if !datum_type(t.type_().unwrap_or(0))?.is_integer() {
+        return Ok(None);
+    }
+    let q = if let Some(q) = t.quantization() { q } else { return Ok(None) };
+    let scale: Vec<f32> = q.scale().map(|s| s.iter().collect()).unwrap_or_default();
+    if scale.is_empty() {
+        return Ok(None);
+    }
+    let mut zero_point: Vec<i32> =
+        q.zero_point().map(|z| z.iter().map(|z| z as i32).collect()).unwrap_or_default();
+    if zero_point.is_empty() {
+        zero_point = vec![0; scale.len()];
+    }
+    if zero_point.len() != scale.len() {
+        bail!("Inconsistent quantization: {} scales, {} zero points", scale.len(), zero_point.len())
+    }
+    Ok(Some(QuantInfo { scale, zero_point, axis: q.quantized_dimension().unwrap_or(0) as usize }))
+}
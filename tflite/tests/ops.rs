@@ -0,0 +1,256 @@
+extern crate tract_tflite;
+
+mod utils;
+
+use crate::utils::*;
+use tract_tflite::prelude::*;
+use tract_tflite::schema::{builtin_operator as op, builtin_options as opt, tensor_type as tt};
+use tract_tflite::tract_hir::tract_ndarray::{arr2, Array4, Ix4};
+
+fn run(spec: &ModelSpec, inputs: TVec<Tensor>) -> TractResult<TVec<Arc<Tensor>>> {
+    let model = tflite().model_for_read(&mut &*spec.build())?;
+    let result = model.clone().into_runnable()?.run(inputs.clone())?;
+    let optimized = model.into_optimized()?.into_runnable()?.run(inputs)?;
+    assert_eq!(result, optimized);
+    Ok(result)
+}
+
+#[test]
+fn conv_2d_bias_relu() -> TractResult<()> {
+    let mut spec = ModelSpec::default();
+    let input = spec.input(&[1, 3, 3, 1], tt::FLOAT32);
+    let kernel = spec.f32_const(&[1, 2, 2, 1], &[1., 1., 1., -1.]);
+    let bias = spec.f32_const(&[1], &[-1.]);
+    let output = spec.tensor(&[1, 2, 2, 1], tt::FLOAT32);
+    spec.outputs.push(output);
+    spec.op(
+        op::CONV_2D,
+        &[input, kernel, bias],
+        &[output],
+        opt::CONV_2D,
+        &[(0, Value::I8(1)), (1, Value::I32(1)), (2, Value::I32(1)), (3, Value::I8(1))],
+    );
+    let input = Array4::from_shape_vec((1, 3, 3, 1), (0..9).map(|x| x as f32).collect())?;
+    let result = run(&spec, tvec!(input.into_tensor()))?;
+    // 0+1+3-4-1, 1+2+4-5-1, 3+4+6-7-1, 4+5+7-8-1
+    assert_eq!(*result[0], tensor4(&[[[[0f32], [1.]], [[5.], [7.]]]]));
+    Ok(())
+}
+
+#[test]
+fn depthwise_conv_2d_same() -> TractResult<()> {
+    let mut spec = ModelSpec::default();
+    let input = spec.input(&[1, 2, 2, 2], tt::FLOAT32);
+    let kernel = spec.f32_const(&[1, 2, 1, 2], &[1., 10., 2., 20.]);
+    let output = spec.tensor(&[1, 2, 2, 2], tt::FLOAT32);
+    spec.outputs.push(output);
+    spec.op(
+        op::DEPTHWISE_CONV_2D,
+        &[input, kernel, -1],
+        &[output],
+        opt::DEPTHWISE_CONV_2D,
+        &[(0, Value::I8(0)), (1, Value::I32(1)), (2, Value::I32(1)), (3, Value::I32(1))],
+    );
+    let input = tensor4(&[[[[1f32, 1.], [2., 2.]], [[3., 3.], [4., 4.]]]]);
+    let result = run(&spec, tvec!(input))?;
+    // SAME padding adds the extra row at the bottom
+    let expected = tensor4(&[[[[7f32, 70.], [10., 100.]], [[3., 30.], [4., 40.]]]]);
+    assert_eq!(*result[0], expected);
+    Ok(())
+}
+
+/// Naive NHWC quantized convolution, with per output channel kernel scales.
+#[allow(clippy::too_many_arguments)]
+fn qconv_reference(
+    input: &Array4<i8>,
+    input_q: (f32, i32),
+    kernel: &Array4<i8>,
+    kernel_scales: &[f32],
+    bias: &[i32],
+    output_q: (f32, i32),
+) -> Array4<i8> {
+    let (_, h, w, c) = input.dim();
+    let (co, kh, kw, _) = kernel.dim();
+    Array4::from_shape_fn((1, h - kh + 1, w - kw + 1, co), |(_, y, x, o)| {
+        let mut acc = bias[o];
+        for dy in 0..kh {
+            for dx in 0..kw {
+                for i in 0..c {
+                    acc += (input[(0, y + dy, x + dx, i)] as i32 - input_q.1)
+                        * kernel[(o, dy, dx, i)] as i32;
+                }
+            }
+        }
+        let scaled = (acc as f32 * (input_q.0 * kernel_scales[o] / output_q.0)).round() as i32;
+        (scaled + output_q.1).clamp(-128, 127) as i8
+    })
+}
+
+fn qconv(kernel: Array4<i8>, input: Array4<i8>) -> TractResult<()> {
+    let kernel_scales = [0.1f32, 0.2];
+    let bias = [10i32, -20];
+    let (input_q, output_q) = ((0.5f32, -1), (0.25f32, 3));
+    let mut spec = ModelSpec::default();
+    let shape = |a: &Array4<i8>| a.shape().iter().map(|&d| d as i32).collect::<Vec<_>>();
+    let input_id = spec.input(&shape(&input), tt::INT8);
+    spec.quantize(input_id, &[input_q.0], &[input_q.1 as i64], 0);
+    let kernel_id = spec.konst(&shape(&kernel), tt::INT8, kernel.as_slice().unwrap());
+    spec.quantize(kernel_id, &kernel_scales, &[0, 0], 0);
+    let bias_id = spec.konst(&[2], tt::INT32, &bias);
+    spec.quantize(bias_id, &[input_q.0 * 0.1, input_q.0 * 0.2], &[0, 0], 0);
+    let expected =
+        qconv_reference(&input, input_q, &kernel, &kernel_scales, &bias, output_q).into_tensor();
+    let output_shape = expected.shape().iter().map(|&d| d as i32).collect::<Vec<_>>();
+    let output_id = spec.tensor(&output_shape, tt::INT8);
+    spec.quantize(output_id, &[output_q.0], &[output_q.1 as i64], 0);
+    spec.outputs.push(output_id);
+    spec.op(
+        op::CONV_2D,
+        &[input_id, kernel_id, bias_id],
+        &[output_id],
+        opt::CONV_2D,
+        &[(0, Value::I8(1)), (1, Value::I32(1)), (2, Value::I32(1))],
+    );
+    let result = run(&spec, tvec!(input.into_tensor()))?;
+    assert_eq!(*result[0], expected);
+    Ok(())
+}
+
+#[test]
+fn conv_2d_per_channel_1x1() -> TractResult<()> {
+    let kernel =
+        tensor4(&[[[[2i8]]], [[[-3]]]]).into_array::<i8>()?.into_dimensionality::<Ix4>()?;
+    let input = tensor4(&[[[[1i8], [-1]], [[4], [10]]]])
+        .into_array::<i8>()?
+        .into_dimensionality::<Ix4>()?;
+    qconv(kernel, input)
+}
+
+#[test]
+fn conv_2d_per_channel_2x2() -> TractResult<()> {
+    let kernel = Array4::from_shape_vec(
+        (2, 2, 2, 2),
+        vec![1i8, -2, 3, 4, -5, 6, 7, -8, 9, 10, -11, 12, 13, -14, 15, 16],
+    )?;
+    let input =
+        Array4::from_shape_fn((1, 3, 4, 2), |(_, y, x, c)| (y * 17 + x * 5 + c * 3) as i8 - 20);
+    qconv(kernel, input)
+}
+
+#[test]
+fn fully_connected_quantized() -> TractResult<()> {
+    let mut spec = ModelSpec::default();
+    let input = spec.input(&[1, 3], tt::INT8);
+    spec.quantize(input, &[0.5], &[1], 0);
+    let weights = spec.konst(&[2, 3], tt::INT8, &[1i8, 2, 3, -1, 0, 2]);
+    spec.quantize(weights, &[0.25], &[0], 0);
+    let bias = spec.konst(&[2], tt::INT32, &[4i32, -10]);
+    spec.quantize(bias, &[0.125], &[0], 0);
+    let output = spec.tensor(&[1, 2], tt::INT8);
+    spec.quantize(output, &[0.5], &[-2], 0);
+    spec.outputs.push(output);
+    spec.op(op::FULLY_CONNECTED, &[input, weights, bias], &[output], opt::FULLY_CONNECTED, &[]);
+    let result = run(&spec, tvec!(tensor2(&[[3i8, -2, 5]])))?;
+    // (2*1 - 3*2 + 4*3 + 4) / 4 - 2, (-2*1 + 4*2 - 10) / 4 - 2
+    assert_eq!(*result[0], tensor2(&[[1i8, -3]]));
+    Ok(())
+}
+
+#[test]
+fn fully_connected_keep_num_dims() -> TractResult<()> {
+    let mut spec = ModelSpec::default();
+    let input = spec.input(&[1, 2, 3], tt::FLOAT32);
+    let weights = spec.f32_const(&[1, 3], &[1., 2., 3.]);
+    let bias = spec.f32_const(&[1], &[0.5]);
+    let output = spec.tensor(&[1, 2, 1], tt::FLOAT32);
+    spec.outputs.push(output);
+    spec.op(
+        op::FULLY_CONNECTED,
+        &[input, weights, bias],
+        &[output],
+        opt::FULLY_CONNECTED,
+        &[(2, Value::Bool(true))],
+    );
+    let result = run(&spec, tvec!(tensor3(&[[[1f32, 1., 1.], [0., 1., -1.]]])))?;
+    assert_eq!(*result[0], tensor3(&[[[6.5f32], [-0.5]]]));
+    Ok(())
+}
+
+#[test]
+fn quantized_add_through_float() -> TractResult<()> {
+    let mut spec = ModelSpec::default();
+    let a = spec.input(&[2, 2], tt::FLOAT32);
+    let qa = spec.tensor(&[2, 2], tt::UINT8);
+    spec.quantize(qa, &[0.5], &[128], 0);
+    let b = spec.konst(&[1, 2], tt::UINT8, &[130u8, 126]);
+    spec.quantize(b, &[0.5], &[128], 0);
+    let sum = spec.tensor(&[2, 2], tt::UINT8);
+    spec.quantize(sum, &[1.0], &[128], 0);
+    let output = spec.tensor(&[2, 2], tt::FLOAT32);
+    spec.outputs.push(output);
+    spec.op(op::QUANTIZE, &[a], &[qa], opt::NONE, &[]);
+    spec.op(op::ADD, &[qa, b], &[sum], opt::ADD, &[(0, Value::I8(1))]);
+    spec.op(op::DEQUANTIZE, &[sum], &[output], opt::NONE, &[]);
+    let result = run(&spec, tvec!(tensor2(&[[1f32, 2.], [-4., -3.]])))?;
+    // b is [1, -1], the fused relu clips negative sums
+    assert_eq!(*result[0], tensor2(&[[2f32, 1.], [0., 0.]]));
+    Ok(())
+}
+
+#[test]
+fn reshape_softmax() -> TractResult<()> {
+    let mut spec = ModelSpec::default();
+    let input = spec.input(&[1, 4], tt::FLOAT32);
+    let shape = spec.konst(&[2], tt::INT32, &[2i32, 2]);
+    let reshaped = spec.tensor(&[2, 2], tt::FLOAT32);
+    let output = spec.tensor(&[2, 2], tt::FLOAT32);
+    spec.outputs.push(output);
+    spec.op(
+        op::RESHAPE,
+        &[input, shape],
+        &[reshaped],
+        opt::RESHAPE,
+        &[(0, Value::I32s(vec![2, 2]))],
+    );
+    spec.op(op::SOFTMAX, &[reshaped], &[output], opt::SOFTMAX, &[(0, Value::F32(1.0))]);
+    let result = run(&spec, tvec!(tensor2(&[[0f32, 0., 1., 1.]])))?;
+    assert_eq!(*result[0], arr2(&[[0.5f32, 0.5], [0.5, 0.5]]).into_tensor());
+    Ok(())
+}
+
+#[test]
+fn mean_and_pad() -> TractResult<()> {
+    let mut spec = ModelSpec::default();
+    let input = spec.input(&[1, 2, 2, 1], tt::FLOAT32);
+    let pads = spec.konst(&[4, 2], tt::INT32, &[0i32, 0, 1, 1, 0, 0, 0, 0]);
+    let padded = spec.tensor(&[1, 4, 2, 1], tt::FLOAT32);
+    let axes = spec.konst(&[2], tt::INT32, &[1i32, 2]);
+    let output = spec.tensor(&[1, 1], tt::FLOAT32);
+    spec.outputs.push(output);
+    spec.op(op::PAD, &[input, pads], &[padded], opt::NONE, &[]);
+    spec.op(op::MEAN, &[padded, axes], &[output], opt::REDUCER, &[(0, Value::Bool(false))]);
+    let result = run(&spec, tvec!(tensor4(&[[[[1f32], [2.]], [[3.], [6.]]]])))?;
+    assert_eq!(*result[0], tensor2(&[[1.5f32]]));
+    Ok(())
+}
+
+#[test]
+fn unsupported_operator() {
+    let mut spec = ModelSpec::default();
+    let input = spec.input(&[1], tt::FLOAT32);
+    let output = spec.tensor(&[1], tt::FLOAT32);
+    spec.outputs.push(output);
+    spec.op(1000, &[input], &[output], opt::NONE, &[]);
+    let err = tflite().model_for_read(&mut &*spec.build()).unwrap_err();
+    assert!(format!("{:?}", err).contains("Unsupported TFLite operator 1000"));
+}
+
+#[test]
+fn reject_invalid_flatbuffer() {
+    let mut model = ModelSpec::default().build();
+    assert!(tflite().model_for_read(&mut &*model).is_ok());
+    let len = model.len();
+    model.truncate(len / 2);
+    assert!(tflite().model_for_read(&mut &*model).is_err());
+    assert!(tflite().model_for_read(&mut &b"not a model at all"[..]).is_err());
+}
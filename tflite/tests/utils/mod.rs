@@ -0,0 +1,219 @@
+//! Minimal TFLite flatbuffer writer, to build test models in-repo.
+#![allow(dead_code)]
+
+use flatbuffers::{field_index_to_field_offset as slot, FlatBufferBuilder, WIPOffset};
+use tract_tflite::schema::tensor_type;
+
+#[derive(Clone, Debug)]
+pub enum Value {
+    I8(i8),
+    I32(i32),
+    F32(f32),
+    Bool(bool),
+    I32s(Vec<i32>),
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TensorSpec {
+    pub shape: Vec<i32>,
+    pub dt: i8,
+    pub data: Option<Vec<u8>>,
+    pub scale: Vec<f32>,
+    pub zero_point: Vec<i64>,
+    pub quantized_dimension: i32,
+}
+
+#[derive(Clone, Debug)]
+pub struct OpSpec {
+    pub code: i32,
+    pub inputs: Vec<i32>,
+    pub outputs: Vec<i32>,
+    pub options_type: u8,
+    pub options: Vec<(u16, Value)>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ModelSpec {
+    pub tensors: Vec<TensorSpec>,
+    pub ops: Vec<OpSpec>,
+    pub inputs: Vec<i32>,
+    pub outputs: Vec<i32>,
+}
+
+fn bytes<T: Copy>(data: &[T]) -> Vec<u8> {
+    unsafe {
+        std::slice::from_raw_parts(data.as_ptr() as *const u8, std::mem::size_of_val(data)).to_vec()
+    }
+}
+
+impl ModelSpec {
+    fn push(&mut self, tensor: TensorSpec) -> i32 {
+        self.tensors.push(tensor);
+        self.tensors.len() as i32 - 1
+    }
+
+    pub fn input(&mut self, shape: &[i32], dt: i8) -> i32 {
+        let id = self.tensor(shape, dt);
+        self.inputs.push(id);
+        id
+    }
+
+    pub fn tensor(&mut self, shape: &[i32], dt: i8) -> i32 {
+        self.push(TensorSpec { shape: shape.to_vec(), dt, ..TensorSpec::default() })
+    }
+
+    pub fn konst<T: Copy>(&mut self, shape: &[i32], dt: i8, data: &[T]) -> i32 {
+        self.push(TensorSpec {
+            shape: shape.to_vec(),
+            dt,
+            data: Some(bytes(data)),
+            ..TensorSpec::default()
+        })
+    }
+
+    pub fn f32_const(&mut self, shape: &[i32], data: &[f32]) -> i32 {
+        self.konst(shape, tensor_type::FLOAT32, data)
+    }
+
+    pub fn quantize(&mut self, tensor: i32, scale: &[f32], zero_point: &[i64], axis: i32) {
+        let t = &mut self.tensors[tensor as usize];
+        t.scale = scale.to_vec();
+        t.zero_point = zero_point.to_vec();
+        t.quantized_dimension = axis;
+    }
+
+    pub fn op(
+        &mut self,
+        code: i32,
+        inputs: &[i32],
+        outputs: &[i32],
+        options_type: u8,
+        options: &[(u16, Value)],
+    ) {
+        self.ops.push(OpSpec {
+            code,
+            inputs: inputs.to_vec(),
+            outputs: outputs.to_vec(),
+            options_type,
+            options: options.to_vec(),
+        })
+    }
+
+    pub fn build(&self) -> Vec<u8> {
+        let mut fbb = FlatBufferBuilder::new();
+
+        let mut buffers = vec![];
+        let empty = fbb.start_table();
+        buffers.push(fbb.end_table(empty));
+        let mut tensors = vec![];
+        for (ix, t) in self.tensors.iter().enumerate() {
+            let buffer = if let Some(data) = &t.data {
+                let data = fbb.create_vector(data);
+                let table = fbb.start_table();
+                fbb.push_slot_always(slot(0), data);
+                buffers.push(fbb.end_table(table));
+                buffers.len() as u32 - 1
+            } else {
+                0
+            };
+            let quant = if !t.scale.is_empty() {
+                let scale = fbb.create_vector(&t.scale);
+                let zero_point = fbb.create_vector(&t.zero_point);
+                let table = fbb.start_table();
+                fbb.push_slot_always(slot(2), scale);
+                fbb.push_slot_always(slot(3), zero_point);
+                fbb.push_slot_always(slot(6), t.quantized_dimension);
+                Some(fbb.end_table(table))
+            } else {
+                None
+            };
+            let shape = fbb.create_vector(&t.shape);
+            let name = fbb.create_string(&format!("tensor_{}", ix));
+            let table = fbb.start_table();
+            fbb.push_slot_always(slot(0), shape);
+            fbb.push_slot_always(slot(1), t.dt);
+            fbb.push_slot_always(slot(2), buffer);
+            fbb.push_slot_always(slot(3), name);
+            if let Some(quant) = quant {
+                fbb.push_slot_always(slot(4), quant);
+            }
+            tensors.push(fbb.end_table(table));
+        }
+
+        let mut codes: Vec<i32> = vec![];
+        let mut operators = vec![];
+        for op in &self.ops {
+            let opcode = codes.iter().position(|&c| c == op.code).unwrap_or_else(|| {
+                codes.push(op.code);
+                codes.len() - 1
+            });
+            let options = if op.options_type != 0 {
+                let vectors: Vec<Option<WIPOffset<_>>> = op
+                    .options
+                    .iter()
+                    .map(|(_, v)| match v {
+                        Value::I32s(v) => Some(fbb.create_vector(v)),
+                        _ => None,
+                    })
+                    .collect();
+                let table = fbb.start_table();
+                for ((id, value), vector) in op.options.iter().zip(vectors) {
+                    match value {
+                        Value::I8(v) => fbb.push_slot_always(slot(*id), *v),
+                        Value::I32(v) => fbb.push_slot_always(slot(*id), *v),
+                        Value::F32(v) => fbb.push_slot_always(slot(*id), *v),
+                        Value::Bool(v) => fbb.push_slot_always(slot(*id), *v),
+                        Value::I32s(_) => fbb.push_slot_always(slot(*id), vector.unwrap()),
+                    }
+                }
+                Some(fbb.end_table(table))
+            } else {
+                None
+            };
+            let inputs = fbb.create_vector(&op.inputs);
+            let outputs = fbb.create_vector(&op.outputs);
+            let table = fbb.start_table();
+            fbb.push_slot_always(slot(0), opcode as u32);
+            fbb.push_slot_always(slot(1), inputs);
+            fbb.push_slot_always(slot(2), outputs);
+            if let Some(options) = options {
+                fbb.push_slot_always(slot(3), op.options_type);
+                fbb.push_slot_always(slot(4), options);
+            }
+            operators.push(fbb.end_table(table));
+        }
+
+        let tensors = fbb.create_vector(&tensors);
+        let inputs = fbb.create_vector(&self.inputs);
+        let outputs = fbb.create_vector(&self.outputs);
+        let operators = fbb.create_vector(&operators);
+        let table = fbb.start_table();
+        fbb.push_slot_always(slot(0), tensors);
+        fbb.push_slot_always(slot(1), inputs);
+        fbb.push_slot_always(slot(2), outputs);
+        fbb.push_slot_always(slot(3), operators);
+        let subgraph = fbb.end_table(table);
+
+        let codes: Vec<_> = codes
+            .iter()
+            .map(|&code| {
+                let table = fbb.start_table();
+                fbb.push_slot_always(slot(0), code.min(127) as i8);
+                fbb.push_slot_always(slot(2), 1i32);
+                fbb.push_slot_always(slot(3), code);
+                fbb.end_table(table)
+            })
+            .collect();
+        let codes = fbb.create_vector(&codes);
+        let subgraphs = fbb.create_vector(&[subgraph]);
+        let buffers = fbb.create_vector(&buffers);
+        let table = fbb.start_table();
+        fbb.push_slot_always(slot(0), 3u32);
+        fbb.push_slot_always(slot(1), codes);
+        fbb.push_slot_always(slot(2), subgraphs);
+        fbb.push_slot_always(slot(4), buffers);
+        let model = fbb.end_table(table);
+        fbb.finish(model, Some("TFL3"));
+        fbb.finished_data().to_vec()
+    }
+}